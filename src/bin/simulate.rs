@@ -0,0 +1,98 @@
+//! Headless auto-battle simulator, used to balance the scenarios.
+//!
+//! ```text
+//! cargo run --release --bin simulate -- --scenario "Patrouille Gobeline" \
+//!     --party "Thalia,Thraïn" [--policy random|greedy_damage] [--script script.json] \
+//!     [--runs 100] [--seed 0] [--max-turns 100] [--root ./offlines] [--json]
+//! ```
+//!
+//! `--script` reads a JSON object `{ "<hero name>": ["<atk name>", ...] }` and plays the
+//! scripted policy.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, bail};
+use lib_rpg::{
+    common::constants::paths_const::OFFLINE_ROOT,
+    server::{
+        data_manager::DataManager,
+        simulator::{HeroPolicy, SimulationConfig, SimulationReport, simulate},
+    },
+    utils,
+};
+
+fn parse_args() -> Result<(SimulationConfig, String, bool)> {
+    let mut config = SimulationConfig::default();
+    let mut root = OFFLINE_ROOT.to_string_lossy().to_string();
+    let mut is_json = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--json" {
+            is_json = true;
+            continue;
+        }
+        let value = args
+            .next()
+            .with_context(|| format!("missing value for {}", arg))?;
+        match arg.as_str() {
+            "--scenario" => config.scenario = value,
+            "--party" => config.party = value.split(',').map(|s| s.trim().to_owned()).collect(),
+            "--policy" => {
+                config.policy = match value.as_str() {
+                    "random" => HeroPolicy::Random,
+                    "greedy_damage" => HeroPolicy::GreedyDamage,
+                    _ => bail!("unknown policy {}", value),
+                }
+            }
+            "--script" => {
+                let script: HashMap<String, Vec<String>> = utils::read_from_json(&value)?;
+                config.policy = HeroPolicy::Scripted(script);
+            }
+            "--runs" => config.nb_runs = value.parse()?,
+            "--seed" => config.seed = value.parse()?,
+            "--max-turns" => config.max_turns = value.parse()?,
+            "--root" => root = value,
+            _ => bail!("unknown argument {}", arg),
+        }
+    }
+    Ok((config, root, is_json))
+}
+
+fn print_report(report: &SimulationReport) {
+    println!("Scenario: {}", report.scenario);
+    println!(
+        "Win rate: {:.1}% ({}/{} runs, {} timeouts)",
+        report.win_rate * 100.0,
+        report.nb_wins,
+        report.nb_runs,
+        report.nb_timeouts
+    );
+    println!("Average turns: {:.2}", report.average_turns);
+    println!("\nDamage per attack:");
+    for s in &report.atk_stats {
+        println!(
+            "  {:<30} {:<30} uses: {:>6}  avg dmg: {:>8.1}  total heal: {:>8}",
+            s.launcher_id_name,
+            s.atk_name,
+            s.nb_use,
+            s.average_dmg(),
+            s.total_real_heal
+        );
+    }
+    println!("\nDeaths:");
+    for (id_name, nb) in &report.deaths {
+        println!("  {:<30} {:>6}", id_name, nb);
+    }
+}
+
+fn main() -> Result<()> {
+    let (config, root, is_json) = parse_args()?;
+    let dm = DataManager::try_new(&root)?;
+    let report = simulate(&dm, &config)?;
+    if is_json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! Lint the offline data tree.
+//!
+//! ```text
+//! cargo run --bin validate -- [--root ./offlines] [--json]
+//! ```
+//!
+//! Exits with a non-zero code when an error is found.
+
+use anyhow::{Result, bail};
+use lib_rpg::{
+    common::constants::paths_const::OFFLINE_ROOT,
+    server::validator::{Severity, validate_offline_root},
+};
+
+fn main() -> Result<()> {
+    let mut root = OFFLINE_ROOT.to_path_buf();
+    let mut is_json = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => is_json = true,
+            "--root" => match args.next() {
+                Some(value) => root = value.into(),
+                None => bail!("missing value for --root"),
+            },
+            _ => bail!("unknown argument {}", arg),
+        }
+    }
+    let diagnostics = validate_offline_root(&root);
+    if is_json {
+        println!("{}", serde_json::to_string_pretty(&diagnostics)?);
+    } else {
+        for d in &diagnostics {
+            println!("{}", d);
+        }
+        println!(
+            "{} problem(s) found in {}",
+            diagnostics.len(),
+            root.display()
+        );
+    }
+    let nb_errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    if nb_errors > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
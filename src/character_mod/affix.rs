@@ -0,0 +1,221 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::{
+    character_mod::{
+        rank::Rank,
+        stats::{StatKind, Stats},
+    },
+    common::game_rng::GameRng,
+};
+
+/// Rarity tier of a rolled equipment, each tier above `Common` rolls one more affix
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, EnumIter,
+)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Rarity {
+    pub fn to_str(&self) -> &str {
+        match self {
+            Rarity::Common => "Common",
+            Rarity::Uncommon => "Uncommon",
+            Rarity::Rare => "Rare",
+            Rarity::Epic => "Epic",
+            Rarity::Legendary => "Legendary",
+        }
+    }
+
+    /// Number of affixes rolled on an equipment of that rarity
+    pub fn nb_affixes(&self) -> usize {
+        match self {
+            Rarity::Common => 0,
+            Rarity::Uncommon => 1,
+            Rarity::Rare => 2,
+            Rarity::Epic => 3,
+            Rarity::Legendary => 4,
+        }
+    }
+
+    /// Price of an equipment of that rarity, in percent of its base price
+    pub fn price_percent(&self) -> u64 {
+        match self {
+            Rarity::Common => 100,
+            Rarity::Uncommon => 150,
+            Rarity::Rare => 200,
+            Rarity::Epic => 300,
+            Rarity::Legendary => 500,
+        }
+    }
+
+    /// Roll a rarity on a d100, the rank of the equipment shifts the roll toward the rare tiers
+    pub fn roll(rank: &Rank, rng: &mut GameRng) -> Rarity {
+        let bonus = match rank {
+            Rank::Common => 0,
+            Rank::Intermediate => 10,
+            Rank::Advanced => 20,
+        };
+        match rng.random_nb(1, 100) + bonus {
+            ..=60 => Rarity::Common,
+            61..=85 => Rarity::Uncommon,
+            86..=95 => Rarity::Rare,
+            96..=100 => Rarity::Epic,
+            _ => Rarity::Legendary,
+        }
+    }
+}
+
+impl fmt::Display for Rarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_str())
+    }
+}
+
+/// Kind of bonus brought by an affix
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AffixKind {
+    /// Bonus on a stat, counted like the stats of the equipment
+    Stat { stat: StatKind, is_percent: bool },
+    /// Percent of the damages dealt healed back on the wearer
+    Lifesteal,
+}
+
+impl Default for AffixKind {
+    fn default() -> Self {
+        AffixKind::Stat {
+            stat: StatKind::Hp,
+            is_percent: false,
+        }
+    }
+}
+
+/// Random bonus rolled on an equipment instance
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Affix {
+    pub kind: AffixKind,
+    pub value: i64,
+}
+
+impl fmt::Display for Affix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            AffixKind::Stat {
+                stat,
+                is_percent: true,
+            } => write!(f, "+{}% {}", self.value, stat.as_str()),
+            AffixKind::Stat {
+                stat,
+                is_percent: false,
+            } => write!(f, "+{} {}", self.value, stat.as_str()),
+            AffixKind::Lifesteal => write!(f, "{}% lifesteal", self.value),
+        }
+    }
+}
+
+impl Affix {
+    /// Add the bonus of the affix to the stats of an equipment
+    pub fn apply_on_stats(&self, stats: &mut Stats) {
+        if let AffixKind::Stat { stat, is_percent } = self.kind {
+            if is_percent {
+                stats[stat].buf_equip_percent += self.value;
+            } else {
+                stats[stat].buf_equip_value += self.value;
+            }
+        }
+    }
+}
+
+/// Affixes which can be rolled, with the range of their value
+const AFFIX_POOL: [(AffixKind, i64, i64); 10] = [
+    (stat_affix(StatKind::CriticalStrike, false), 2, 5),
+    (stat_affix(StatKind::HpRegen, false), 5, 12),
+    (stat_affix(StatKind::Hp, true), 5, 10),
+    (stat_affix(StatKind::PhysicalPower, false), 3, 8),
+    (stat_affix(StatKind::MagicalPower, false), 3, 8),
+    (stat_affix(StatKind::PhysicalArmor, false), 3, 8),
+    (stat_affix(StatKind::MagicalArmor, false), 3, 8),
+    (stat_affix(StatKind::Dodge, false), 2, 5),
+    (stat_affix(StatKind::Speed, false), 2, 6),
+    (AffixKind::Lifesteal, 3, 8),
+];
+
+const fn stat_affix(stat: StatKind, is_percent: bool) -> AffixKind {
+    AffixKind::Stat { stat, is_percent }
+}
+
+/// Roll the affixes of a rarity, each affix kind is rolled at most once
+pub fn roll_affixes(rarity: &Rarity, rng: &mut GameRng) -> Vec<Affix> {
+    let mut pool = AFFIX_POOL.to_vec();
+    let mut affixes = Vec::new();
+    for _ in 0..rarity.nb_affixes().min(pool.len()) {
+        let idx = rng.random_nb(0, pool.len() as i64 - 1) as usize;
+        let (kind, min, max) = pool.remove(idx);
+        affixes.push(Affix {
+            kind,
+            value: rng.random_nb(min, max),
+        });
+    }
+    affixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn unit_roll_affixes() {
+        let mut rng = GameRng::new(11);
+        for rarity in Rarity::iter() {
+            let affixes = roll_affixes(&rarity, &mut rng);
+            assert_eq!(affixes.len(), rarity.nb_affixes());
+            for (i, affix) in affixes.iter().enumerate() {
+                assert!(affixes[i + 1..].iter().all(|a| a.kind != affix.kind));
+                let (_, min, max) = AFFIX_POOL.iter().find(|p| p.0 == affix.kind).unwrap();
+                assert!((*min..=*max).contains(&affix.value));
+            }
+        }
+
+        let crit = Affix {
+            kind: stat_affix(StatKind::CriticalStrike, false),
+            value: 5,
+        };
+        assert_eq!(crit.to_string(), "+5 Critical strike");
+        let mut stats = Stats::default();
+        crit.apply_on_stats(&mut stats);
+        assert_eq!(stats[StatKind::CriticalStrike].buf_equip_value, 5);
+        let lifesteal = Affix {
+            kind: AffixKind::Lifesteal,
+            value: 4,
+        };
+        assert_eq!(lifesteal.to_string(), "4% lifesteal");
+    }
+
+    #[test]
+    fn unit_rarity_roll() {
+        let mut rng = GameRng::new(5);
+        let commons = (0..200)
+            .filter(|_| Rarity::roll(&Rank::Common, &mut rng) == Rarity::Common)
+            .count();
+        let mut rng = GameRng::new(5);
+        let advanced_commons = (0..200)
+            .filter(|_| Rarity::roll(&Rank::Advanced, &mut rng) == Rarity::Common)
+            .count();
+        assert!(advanced_commons < commons);
+        assert!(
+            (0..200)
+                .map(|_| Rarity::roll(&Rank::Common, &mut rng))
+                .all(|r| r != Rarity::Legendary)
+        );
+    }
+}
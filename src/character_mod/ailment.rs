@@ -0,0 +1,132 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Status ailment inflicted by an effect
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AilmentKind {
+    /// The character skips its rounds
+    #[default]
+    Stun,
+    /// The character skips its rounds until it receives damages
+    Sleep,
+    /// The magical attacks cannot be launched
+    Silence,
+    /// The individual attacks on enemies hit the character who inflicted the taunt
+    Taunt,
+    Poison,
+    Burn,
+    /// The individual attacks on enemies hit a random enemy
+    Confusion,
+}
+
+impl fmt::Display for AilmentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            AilmentKind::Stun => "Stun",
+            AilmentKind::Sleep => "Sleep",
+            AilmentKind::Silence => "Silence",
+            AilmentKind::Taunt => "Taunt",
+            AilmentKind::Poison => "Poison",
+            AilmentKind::Burn => "Burn",
+            AilmentKind::Confusion => "Confusion",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl AilmentKind {
+    /// Stunned and sleeping characters skip their rounds
+    pub fn skips_round(&self) -> bool {
+        matches!(self, AilmentKind::Stun | AilmentKind::Sleep)
+    }
+
+    /// Poison and burn remove HP at each turn
+    pub fn is_dot(&self) -> bool {
+        matches!(self, AilmentKind::Poison | AilmentKind::Burn)
+    }
+
+    /// State of the afflicted character, used in the logs
+    pub fn adjective(&self) -> &'static str {
+        match self {
+            AilmentKind::Stun => "stunned",
+            AilmentKind::Sleep => "asleep",
+            AilmentKind::Silence => "silenced",
+            AilmentKind::Taunt => "taunted",
+            AilmentKind::Poison => "poisoned",
+            AilmentKind::Burn => "burned",
+            AilmentKind::Confusion => "confused",
+        }
+    }
+}
+
+/// Status ailment active on a character
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ailment {
+    pub kind: AilmentKind,
+    /// Remaining turns, decreased at the first round of each turn of the afflicted character
+    pub turns_left: i64,
+    /// Poison and burn: HP change at each turn (negative)
+    pub dot_value: i64,
+    /// id_name of the character who inflicted the ailment
+    pub source_id_name: String,
+    /// Name of the atk which inflicted the ailment
+    pub atk_name: String,
+}
+
+impl Ailment {
+    /// Text displayed with the hots and bufs of the character
+    pub fn txt(&self) -> String {
+        if self.kind.is_dot() {
+            format!(
+                "{}: {} {} HP × {} turns",
+                self.atk_name,
+                self.kind,
+                self.dot_value.abs(),
+                self.turns_left
+            )
+        } else {
+            format!(
+                "{}: {} × {} turns",
+                self.atk_name, self.kind, self.turns_left
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_ailment_kind() {
+        assert!(AilmentKind::Stun.skips_round());
+        assert!(AilmentKind::Sleep.skips_round());
+        assert!(!AilmentKind::Silence.skips_round());
+        assert!(AilmentKind::Poison.is_dot());
+        assert!(AilmentKind::Burn.is_dot());
+        assert!(!AilmentKind::Taunt.is_dot());
+        assert_eq!(AilmentKind::Sleep.adjective(), "asleep");
+        assert_eq!(format!("{}", AilmentKind::Confusion), "Confusion");
+    }
+
+    #[test]
+    fn unit_ailment_txt() {
+        let poison = Ailment {
+            kind: AilmentKind::Poison,
+            turns_left: 2,
+            dot_value: -12,
+            atk_name: "Toxic".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(poison.txt(), "Toxic: Poison 12 HP × 2 turns");
+        let stun = Ailment {
+            kind: AilmentKind::Stun,
+            turns_left: 1,
+            atk_name: "Bash".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(stun.txt(), "Bash: Stun × 1 turns");
+    }
+}
@@ -6,14 +6,14 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     character_mod::character::CharacterKind,
+    character_mod::class::Class,
     character_mod::effect::EffectParam,
+    character_mod::element::{Element, TypeChart},
+    character_mod::stats::StatKind,
     character_mod::stats::Stats,
-    common::{
-        constants::all_target_const::{TARGET_ALLY, TARGET_ENNEMY},
-        constants::reach_const::INDIVIDUAL,
-        constants::stats_const::*,
-    },
-    utils::{self, get_random_nb},
+    character_mod::target::{Reach, Target},
+    common::game_rng::GameRng,
+    utils,
 };
 
 #[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -37,6 +37,7 @@ pub struct LauncherAtkInfo {
     pub kind: CharacterKind,
     pub stats: Stats,
     pub atk_type: AttackType,
+    pub type_chart: TypeChart,
 }
 
 /// Defines the parameters of an attack.
@@ -58,12 +59,12 @@ pub struct AttackType {
     /// Berserk cost of the attack
     #[serde(rename = "Coût de rage")]
     pub berseck_cost: u64,
-    /// Target of the attack, can be TARGET_ALLY, TARGET_ENNEMY, TARGET_HIMSELF, TARGET_ONLY_ALLY
+    /// Target of the attack
     #[serde(rename = "Cible")]
-    pub target: String,
-    /// Reach of the attack, can be INDIVIDUAL, ZONE, ALL
+    pub target: Target,
+    /// Reach of the attack
     #[serde(rename = "Portée")]
-    pub reach: String,
+    pub reach: Reach,
     /// Photo name of the attack
     #[serde(rename = "Photo")]
     pub name_photo: String,
@@ -82,6 +83,12 @@ pub struct AttackType {
     /// Optional mechanical summary of the attack's effects, shown as a tooltip in the UI
     #[serde(rename = "DescriptionEffects", default)]
     pub effects_description: String,
+    /// Element of the attack, its damages depend on the elements of the target
+    #[serde(rename = "Element", default)]
+    pub element: Element,
+    /// Specialisation required to learn the attack, if any
+    #[serde(rename = "Specialisation", default)]
+    pub specialisation: Option<Class>,
 }
 
 impl Default for AttackType {
@@ -92,14 +99,16 @@ impl Default for AttackType {
             mana_cost: 0,
             vigor_cost: 0,
             berseck_cost: 0,
-            target: TARGET_ALLY.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target: Target::Ally,
+            reach: Reach::Individual,
             name_photo: "".to_owned(),
             all_effects: vec![],
             aggro: 0,
             can_be_launched: true,
             description: "".to_owned(),
             effects_description: "".to_owned(),
+            element: Element::default(),
+            specialisation: None,
         }
     }
 }
@@ -108,32 +117,45 @@ impl AttackType {
     /// Try to create an AttackType from a JSON file
     pub fn try_new_from_json<P: AsRef<Path>>(path: P) -> Result<AttackType> {
         utils::read_from_json::<_, AttackType>(&path)
-            .map_err(|_| anyhow!("Unknown file: {:?}", path.as_ref()))
+            .map_err(|e| anyhow!("{:?} cannot be decoded: {}", path.as_ref(), e))
+    }
+
+    /// True if a hero of the class can learn the attack
+    pub fn is_available_for(&self, class: &Class) -> bool {
+        self.specialisation.as_ref().is_none_or(|s| s == class)
+    }
+
+    /// Check if the attack has a magical effect
+    pub fn is_magic_atk(&self) -> bool {
+        self.all_effects.iter().any(|e| e.is_magic_atk)
     }
 
     /// Check if the attack has only heal effects
     pub fn has_only_heal_effect(&self) -> bool {
         let mut is_only_heal_effect = false;
         for e in &self.all_effects {
-            if e.buffer.stats_name == HP && e.buffer.value < 0 {
+            if e.buffer.stats_name == Some(StatKind::Hp) && e.buffer.value < 0 {
                 return false;
             }
-            if e.buffer.stats_name == HP && e.buffer.value > 0 {
+            if e.buffer.stats_name == Some(StatKind::Hp) && e.buffer.value > 0 {
                 is_only_heal_effect = true;
             }
         }
-        if self.target != TARGET_ENNEMY && is_only_heal_effect {
+        if self.target != Target::Enemy && is_only_heal_effect {
             return true;
         }
         false
     }
 
     /// Get one random attack name from a list of attacks
-    pub fn get_one_random_atk_name(launchable_atks: &[AttackType]) -> Option<String> {
+    pub fn get_one_random_atk_name(
+        launchable_atks: &[AttackType],
+        rng: &mut GameRng,
+    ) -> Option<String> {
         if launchable_atks.is_empty() {
             return None;
         }
-        let nb = get_random_nb(0, launchable_atks.len() as i64 - 1);
+        let nb = rng.random_nb(0, launchable_atks.len() as i64 - 1);
         launchable_atks.get(nb as usize).map(|atk| atk.name.clone())
     }
 
@@ -149,10 +171,11 @@ impl AttackType {
     pub const DEFENSE_DIVISOR: f64 = 4.0;
 
     /// Returns `(raw_damage, effective_damage)`:
-    /// - `raw_damage`: damage before armor (attack value scaled by launcher power)
+    /// - `raw_damage`: damage before armor (attack value scaled by launcher power and type effectiveness)
     /// - `effective_damage`: damage after armor mitigation (diminishing-returns formula)
     ///
     /// Both values are negative for damage, positive for healing.
+    /// `type_multiplier` is the effectiveness of the atk element on the target (see `TypeChart`).
     /// Returns `(0, 0)` if `nb_of_turns <= 0` to avoid division by zero.
     pub fn damage_by_atk(
         target_stats: &Stats,
@@ -160,6 +183,7 @@ impl AttackType {
         is_magic: bool,
         atk_value: i64,
         nb_of_turns: i64,
+        type_multiplier: f64,
     ) -> (i64, i64) {
         if nb_of_turns <= 0 {
             return (0, 0);
@@ -170,7 +194,7 @@ impl AttackType {
 
         // Multiplicative power scaling: higher launcher power amplifies the base attack value.
         let power_factor = 1.0 + launcher_pow as f64 / Self::POWER_SCALE;
-        let raw_damage = (atk_value as f64 * power_factor).round() as i64;
+        let raw_damage = (atk_value as f64 * power_factor * type_multiplier).round() as i64;
 
         // Defense = armor + target power contribution (physical resistance from strength).
         let defense = target_armor as f64 + target_power as f64 / Self::DEFENSE_DIVISOR;
@@ -186,10 +210,13 @@ mod tests {
     use indexmap::IndexMap;
 
     use crate::{
-        character_mod::{attack_type::AttackType, buffers::BufKinds, stats::Stats},
-        common::constants::{
-            all_target_const::TARGET_ENNEMY, reach_const::INDIVIDUAL, stats_const::*,
+        character_mod::{
+            attack_type::AttackType,
+            buffers::BufKinds,
+            stats::{StatKind, Stats},
+            target::{Reach, Target},
         },
+        common::game_rng::GameRng,
         testing::testing_atk::{build_atk_damage_indiv, build_atk_heal1_indiv},
     };
 
@@ -209,16 +236,19 @@ mod tests {
         assert_eq!(atk_type.mana_cost, 0);
         assert_eq!(atk_type.vigor_cost, 9);
         assert_eq!(atk_type.berseck_cost, 0);
-        assert_eq!(atk_type.target, TARGET_ENNEMY);
-        assert_eq!(atk_type.reach, INDIVIDUAL);
+        assert_eq!(atk_type.target, Target::Enemy);
+        assert_eq!(atk_type.reach, Reach::Individual);
         assert_eq!(atk_type.name_photo, "SimpleAtk.png");
         assert_eq!(atk_type.aggro, 0);
         // decode the effect
         assert_eq!(atk_type.all_effects.len(), 1);
-        assert_eq!(atk_type.all_effects[0].buffer.stats_name, HP);
+        assert_eq!(
+            atk_type.all_effects[0].buffer.stats_name,
+            Some(StatKind::Hp)
+        );
         assert_eq!(atk_type.all_effects[0].buffer.value, -35);
-        assert_eq!(atk_type.all_effects[0].target_kind, TARGET_ENNEMY);
-        assert_eq!(atk_type.all_effects[0].reach, INDIVIDUAL);
+        assert_eq!(atk_type.all_effects[0].target_kind, Target::Enemy);
+        assert_eq!(atk_type.all_effects[0].reach, Reach::Individual);
         assert_eq!(
             atk_type.all_effects[0].buffer.kind,
             BufKinds::ChangeCurrentStatByValue
@@ -226,6 +256,19 @@ mod tests {
         assert_eq!(atk_type.all_effects[0].sub_value_effect, 0);
     }
 
+    #[test]
+    fn unit_unknown_target_or_reach_is_load_error() {
+        let atk: Result<AttackType, _> = serde_json::from_str(r#"{"Cible": "Foe"}"#);
+        assert!(
+            atk.unwrap_err()
+                .to_string()
+                .contains("unknown variant `Foe`")
+        );
+        let atk: Result<AttackType, _> =
+            serde_json::from_str(r#"{"Effet": [{"Portée": "Aleatoire Individuel"}]}"#);
+        assert!(atk.is_err());
+    }
+
     #[test]
     fn unit_has_only_heal_effect() {
         let atk_dmg = build_atk_damage_indiv();
@@ -234,7 +277,7 @@ mod tests {
         let mut atk_heal = build_atk_heal1_indiv();
         assert!(atk_heal.has_only_heal_effect());
 
-        atk_heal.target = TARGET_ENNEMY.to_owned();
+        atk_heal.target = Target::Enemy;
         assert!(!atk_heal.has_only_heal_effect());
     }
 
@@ -246,15 +289,19 @@ mod tests {
         all_atks.insert(atk1.name.clone(), atk1.clone());
         all_atks.insert(atk2.name.clone(), atk2.clone());
 
-        let random_atk_name =
-            AttackType::get_one_random_atk_name(&all_atks.values().cloned().collect::<Vec<_>>());
+        let random_atk_name = AttackType::get_one_random_atk_name(
+            &all_atks.values().cloned().collect::<Vec<_>>(),
+            &mut GameRng::default(),
+        );
         assert!(random_atk_name.is_some());
         let atk_name = random_atk_name.unwrap();
         assert!(atk_name == atk1.name || atk_name == atk2.name);
 
         let empty_atks: IndexMap<String, AttackType> = IndexMap::new();
-        let random_atk_name =
-            AttackType::get_one_random_atk_name(&empty_atks.values().cloned().collect::<Vec<_>>());
+        let random_atk_name = AttackType::get_one_random_atk_name(
+            &empty_atks.values().cloned().collect::<Vec<_>>(),
+            &mut GameRng::default(),
+        );
         assert!(random_atk_name.is_none());
     }
 
@@ -262,31 +309,42 @@ mod tests {
     fn unit_damage_by_atk() {
         let mut target_stats = Stats::default();
         target_stats.init();
-        target_stats.get_mut_value(MAGICAL_ARMOR).current = 10;
+        target_stats[StatKind::MagicalArmor].current = 10;
         // target magical power contributes to defense
-        target_stats.get_mut_value(MAGICAL_POWER).current = 20;
+        target_stats[StatKind::MagicalPower].current = 20;
 
         let mut launcher_stats = Stats::default();
         launcher_stats.init();
-        launcher_stats.get_mut_value(MAGICAL_POWER).current = 100;
+        launcher_stats[StatKind::MagicalPower].current = 100;
 
         // power_factor = 1 + 100/100 = 2.0; raw = round(-35 * 2.0) = -70
         // defense = 10 + 20/4 = 15; protection = 100/115 ≈ 0.8696
         // effective = round(-70 * 0.8696) = round(-60.87) = -61
         let (raw, effective) =
-            AttackType::damage_by_atk(&target_stats, &launcher_stats, true, -35, 1);
+            AttackType::damage_by_atk(&target_stats, &launcher_stats, true, -35, 1, 1.0);
         assert_eq!(raw, -70);
         assert_eq!(effective, -61);
 
         // nb_of_turns = 0 → (0, 0) to avoid division by zero
-        let (raw0, eff0) = AttackType::damage_by_atk(&target_stats, &launcher_stats, true, -35, 0);
+        let (raw0, eff0) =
+            AttackType::damage_by_atk(&target_stats, &launcher_stats, true, -35, 0, 1.0);
         assert_eq!(raw0, 0);
         assert_eq!(eff0, 0);
 
         // launcher_pow = 0: power_factor = 1.0; raw = atk_value unchanged
         let mut launcher_zero = Stats::default();
         launcher_zero.init();
-        let (raw_z, _) = AttackType::damage_by_atk(&target_stats, &launcher_zero, true, -50, 1);
+        let (raw_z, _) =
+            AttackType::damage_by_atk(&target_stats, &launcher_zero, true, -50, 1, 1.0);
         assert_eq!(raw_z, -50);
+
+        // type effectiveness scales the raw damage before armor
+        let (raw_x2, eff_x2) =
+            AttackType::damage_by_atk(&target_stats, &launcher_stats, true, -35, 1, 2.0);
+        assert_eq!(raw_x2, -140);
+        assert_eq!(eff_x2, -122);
+        let (raw_immune, eff_immune) =
+            AttackType::damage_by_atk(&target_stats, &launcher_stats, true, -35, 1, 0.0);
+        assert_eq!((raw_immune, eff_immune), (0, 0));
     }
 }
@@ -2,6 +2,8 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
+use crate::character_mod::stats::{StatKind, opt_stat_kind};
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BufKinds {
     #[default]
@@ -53,6 +55,21 @@ pub enum BufKinds {
     RepeatIfHeal,
     /// Revive a dead character and restore a fixed amount of HP.
     Resurrect,
+    /// Inflict the status ailment of the effect (`EffectParam::ailment`) for its number of turns.
+    /// Poison and burn remove `value` HP at each turn.
+    InflictAilment,
+    /// Absorb the next `value` damages on HP for the number of turns of the effect.
+    /// The shields of different launchers stack, a new shield of the same launcher replaces the old one.
+    AbsorbShield,
+    /// Passive: change the damages received by `value` while the HP are below
+    /// `LOW_HP_THRESHOLD_PERCENT` of the max HP.
+    LowHpDamageRxPercent,
+    /// Passive: `value`% of the overheal of the heals launched becomes a shield on the healed target.
+    OverHealToShield,
+    /// Passive: `value`% of the mana cost of an attack is refunded on a critical strike.
+    CritManaRefundPercent,
+    /// Passive: `value`% of the HP damages received are gained as berserk.
+    BerserkOnDamageRx,
     EnumSize,
 }
 
@@ -92,6 +109,12 @@ impl fmt::Display for BufKinds {
             BufKinds::ConditionDamagePrevTurn => "Condition: damage last turn",
             BufKinds::RepeatIfHeal => "Repeat if heal",
             BufKinds::Resurrect => "Resurrect",
+            BufKinds::InflictAilment => "Ailment",
+            BufKinds::AbsorbShield => "Absorb shield",
+            BufKinds::LowHpDamageRxPercent => "Damage received at low HP %",
+            BufKinds::OverHealToShield => "Overheal converts to shield",
+            BufKinds::CritManaRefundPercent => "Mana refund on crit %",
+            BufKinds::BerserkOnDamageRx => "Berserk on damage received",
             BufKinds::EnumSize => "—",
         };
         write!(f, "{}", s)
@@ -132,9 +155,9 @@ pub struct Buffer {
     /// Buf can be in percentage or in value
     #[serde(rename = "is-percent")]
     pub is_percent: bool,
-    /// Potentially, a buffer can be applied on a stat, otherwise empty
-    #[serde(rename = "stats-name")]
-    pub stats_name: String,
+    /// Potentially, a buffer can be applied on a stat
+    #[serde(rename = "stats-name", with = "opt_stat_kind")]
+    pub stats_name: Option<StatKind>,
     /// buf-type
     #[serde(rename = "kind")]
     pub kind: BufKinds,
@@ -146,16 +169,22 @@ impl Buffer {
         self.is_percent = is_percent;
     }
 
-    pub fn update_buf(&mut self, value: i64, is_percent: bool, stat: &str) {
+    /// Name of the stat of the buffer, empty without stat
+    pub fn stats_label(&self) -> &'static str {
+        self.stats_name.map(|k| k.as_str()).unwrap_or_default()
+    }
+
+    pub fn update_buf(&mut self, value: i64, is_percent: bool, stat: Option<StatKind>) {
         self.value += value;
         self.is_percent = is_percent;
-        self.stats_name = stat.to_owned();
+        self.stats_name = stat;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::character_mod::buffers::{BufKinds, Buffer, update_heal_by_multi};
+    use crate::character_mod::stats::StatKind;
 
     use super::update_damage_by_buf;
 
@@ -254,17 +283,24 @@ mod tests {
         );
         assert_eq!(format!("{}", BufKinds::RepeatIfHeal), "Repeat if heal");
         assert_eq!(format!("{}", BufKinds::Resurrect), "Resurrect");
+        assert_eq!(format!("{}", BufKinds::InflictAilment), "Ailment");
+        assert_eq!(format!("{}", BufKinds::AbsorbShield), "Absorb shield");
+        assert_eq!(
+            format!("{}", BufKinds::OverHealToShield),
+            "Overheal converts to shield"
+        );
         assert_eq!(format!("{}", BufKinds::EnumSize), "—");
     }
 
     #[test]
     fn unit_update_buf() {
         let mut buff = Buffer::default();
-        buff.update_buf(5, true, "HP");
+        buff.update_buf(5, true, Some(StatKind::Hp));
         assert_eq!(buff.value, 5);
         assert!(buff.is_percent);
-        assert_eq!(buff.stats_name, "HP");
-        buff.update_buf(10, false, "Mana");
+        assert_eq!(buff.stats_name, Some(StatKind::Hp));
+        assert_eq!(buff.stats_label(), "HP");
+        buff.update_buf(10, false, Some(StatKind::Mana));
         assert_eq!(buff.value, 15);
     }
 
@@ -312,14 +348,14 @@ mod tests {
         buff.set_buffers(10, false);
         assert!(!buff.is_percent);
         assert_eq!(buff.kind, BufKinds::DefaultBuf);
-        assert!(buff.stats_name.is_empty());
+        assert!(buff.stats_name.is_none());
         assert!(!buff.is_passive_enabled);
         assert_eq!(buff.value, 10);
 
         buff.set_buffers(20, true);
         assert!(buff.is_percent);
         assert_eq!(buff.kind, BufKinds::DefaultBuf);
-        assert!(buff.stats_name.is_empty());
+        assert!(buff.stats_name.is_none());
         assert!(!buff.is_passive_enabled);
         assert_eq!(buff.value, 20);
     }
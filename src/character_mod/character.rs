@@ -5,34 +5,40 @@ use std::{collections::HashMap, path::Path, vec};
 
 use crate::{
     character_mod::{
+        ailment::{Ailment, AilmentKind},
         attack_type::{AttackType, LauncherAtkInfo},
-        buffers::{BufKinds, Buffer},
-        class::Class,
+        buffers::{BufKinds, Buffer, update_damage_by_buf},
+        class::{Class, ClassPassive},
         effect::{EffectOutcome, EffectParam, ProcessedEffectParam, is_debuf_effect, is_hot},
+        element::{Effectiveness, Element, TypeChart},
         energy::{Energy, EnergyKind},
         equipment::{Equipment, EquipmentJsonKey},
         experience::build_exp_to_next_level,
         inventory::{Consumable, Inventory},
+        loadout::Loadout,
         rank::Rank,
         rounds_information::{AmountType, CharacterRoundsInfo},
-        stats::Stats,
-        target::TargetData,
+        stats::StatKind,
+        stats::{Stats, absorb_by_shields},
+        talent::TalentProgress,
+        target::{Target, TargetData, TargetingStrategy},
     },
     common::{
         constants::{
-            all_target_const::*,
+            character_const::{AGGRO_NORM, LOW_HP_THRESHOLD_PERCENT, SPECIALISATION_LEVEL},
             paths_const::*,
-            stats_const::*,
             streak_breaker_const::{
                 STREAK_BREAKER_ADVANCED, STREAK_BREAKER_BERSERKER, STREAK_BREAKER_INTERMEDIATE,
             },
         },
+        game_rng::GameRng,
         log_data::{
             LogData,
             const_colors::{DARK_RED, LIGHT_GREEN, MUTED_GREY},
         },
     },
     server::{
+        difficulty::Difficulty,
         game_state::GameState,
         players_manager::{DodgeInfo, GameAtkEffect},
     },
@@ -87,9 +93,21 @@ pub struct Character {
     /// Universe/theme the character belongs to (e.g. "lotr", "pokemon").
     #[serde(default)]
     pub universe: String,
-    /// Name of the last attack this character launched (not persisted in JSON).
-    #[serde(skip)]
+    /// Name of the last attack this character launched (only persisted in saves).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub last_atk_name: String,
+    /// Boss only: how the hero targeted by the boss is chosen
+    #[serde(rename = "TargetingStrategy", default)]
+    pub targeting_strategy: TargetingStrategy,
+    /// Elements of the character, giving the effectiveness of the attacks it receives
+    #[serde(rename = "Elements", default)]
+    pub elements: Vec<Element>,
+    /// Hero only: talent points and learnt talents of the class tree
+    #[serde(default)]
+    pub talents: TalentProgress,
+    /// Hero only: known attacks and the ones equipped in its slots
+    #[serde(rename = "Loadout", default)]
+    pub loadout: Loadout,
 }
 
 impl Default for Character {
@@ -112,6 +130,10 @@ impl Default for Character {
             description: String::new(),
             universe: String::new(),
             last_atk_name: String::new(),
+            targeting_strategy: TargetingStrategy::default(),
+            elements: Vec::new(),
+            talents: TalentProgress::default(),
+            loadout: Loadout::default(),
         }
     }
 }
@@ -145,7 +167,7 @@ fn drought_threshold_crit(
     {
         return Some(buf.value as u32);
     }
-    if *class == Class::Berserker {
+    if class.is_a(&Class::Berserker) {
         return Some(STREAK_BREAKER_BERSERKER);
     }
     match rank {
@@ -174,7 +196,7 @@ fn drought_threshold_dodge(
         return Some(buf.value as u32);
     }
     // Berserker blocks instead of dodging — no dodge drought counter applies
-    if *class == Class::Berserker {
+    if class.is_a(&Class::Berserker) {
         return None;
     }
     match rank {
@@ -202,6 +224,8 @@ impl Character {
             for _ in txrxlen..AmountType::EnumSize as usize {
                 value.character_rounds_info.tx_rx.push(HashMap::new());
             }
+            // each equipment instance needs its own id
+            value.inventory.assign_equipment_ids();
             // read atk only if it is new game
             if !load_from_saved_game {
                 // attack loading
@@ -220,6 +244,14 @@ impl Character {
                     }),
                     Err(e) => bail!("Files cannot be listed in {:#?}: {}", attack_path_dir, e),
                 };
+                // a new hero with a loadout knows the attacks of its level
+                if value.loadout.known_atks.is_empty() {
+                    value.loadout.learn_atks_of_level(
+                        &value.attacks_list,
+                        value.level,
+                        &value.class,
+                    );
+                }
                 let equipment_on: HashMap<EquipmentJsonKey, Vec<Equipment>> =
                     value.inventory.get_all_equipments(
                         all_equipments
@@ -265,11 +297,12 @@ impl Character {
     }
 
     pub fn remove_malus_effect(&mut self, ep: &EffectParam) -> Result<()> {
-        if ep.buffer.kind == BufKinds::ChangeMaxStatByPercentage
-            || ep.buffer.kind == BufKinds::ChangeMaxStatByValue
+        if let Some(kind) = ep.buffer.stats_name
+            && (ep.buffer.kind == BufKinds::ChangeMaxStatByPercentage
+                || ep.buffer.kind == BufKinds::ChangeMaxStatByValue)
         {
             self.stats.set_stats_on_effect(
-                &ep.buffer.stats_name,
+                kind,
                 -ep.buffer.value,
                 ep.buffer.kind == BufKinds::ChangeMaxStatByPercentage,
                 true,
@@ -306,45 +339,61 @@ impl Character {
 
     pub fn process_atk_cost(&mut self, atk_name: &str) {
         if let Some(atk) = self.attacks_list.get(atk_name) {
-            self.stats.apply_cost_on_stats(atk.mana_cost, MANA);
-            self.stats.apply_cost_on_stats(atk.berseck_cost, BERSERK);
-            self.stats.apply_cost_on_stats(atk.vigor_cost, VIGOR);
+            self.stats
+                .apply_cost_on_stats(atk.mana_cost, StatKind::Mana);
+            self.stats
+                .apply_cost_on_stats(atk.berseck_cost, StatKind::Berserk);
+            self.stats
+                .apply_cost_on_stats(atk.vigor_cost, StatKind::Vigor);
         }
     }
 
-    pub fn process_dodging(&mut self, atk_level: u64) {
-        let drought_threshold = drought_threshold_dodge(
-            &self.rank,
-            &self.class,
-            self.level,
-            &self.character_rounds_info,
+    pub fn process_dodging(&mut self, atk_level: u64, difficulty: Difficulty, rng: &mut GameRng) {
+        let drought_threshold = difficulty.streak_breaker_threshold(
+            drought_threshold_dodge(
+                &self.rank,
+                &self.class,
+                self.level,
+                &self.character_rounds_info,
+            ),
+            &self.kind,
         );
         self.character_rounds_info.process_dodging(
             atk_level,
             &self.class,
-            self.stats.all_stats[DODGE].current,
+            self.stats[StatKind::Dodge].current,
             &self.id_name,
             drought_threshold,
+            rng,
         );
     }
 
-    pub fn process_critical_strike(&mut self, atk_name: &str) -> Result<bool> {
+    pub fn process_critical_strike(
+        &mut self,
+        atk_name: &str,
+        difficulty: Difficulty,
+        rng: &mut GameRng,
+    ) -> Result<bool> {
         let atk = if let Some(atk) = self.attacks_list.get(atk_name) {
             atk
         } else {
             return Ok(false);
         };
 
-        let drought_threshold = drought_threshold_crit(
-            &self.rank,
-            &self.class,
-            self.level,
-            &self.character_rounds_info,
+        let drought_threshold = difficulty.streak_breaker_threshold(
+            drought_threshold_crit(
+                &self.rank,
+                &self.class,
+                self.level,
+                &self.character_rounds_info,
+            ),
+            &self.kind,
         );
         self.character_rounds_info.process_critical_strike(
             atk,
-            self.stats.all_stats[CRITICAL_STRIKE].current as i64,
+            self.stats[StatKind::CriticalStrike].current as i64,
             drought_threshold,
+            rng,
         )
     }
 
@@ -355,9 +404,13 @@ impl Character {
         is_crit: bool,
         current_turn: usize,
     ) -> EffectOutcome {
-        // RemoveOneDebuf: remove the oldest debuff from this character's active effects
+        // RemoveOneDebuf: remove the oldest ailment, otherwise the oldest debuff from this
+        // character's active effects
         if processed_ep.input_effect_param.buffer.kind == BufKinds::RemoveOneDebuf {
-            let debuff_removed = if let Some(pos) = self
+            let debuff_removed = if !self.character_rounds_info.ailments.is_empty() {
+                self.character_rounds_info.ailments.remove(0);
+                true
+            } else if let Some(pos) = self
                 .character_rounds_info
                 .all_effects
                 .iter()
@@ -384,7 +437,7 @@ impl Character {
             for gae in self.character_rounds_info.all_effects.iter_mut() {
                 if is_hot(
                     &gae.processed_effect_param.input_effect_param.buffer.kind,
-                    &gae.processed_effect_param
+                    gae.processed_effect_param
                         .input_effect_param
                         .buffer
                         .stats_name,
@@ -407,32 +460,40 @@ impl Character {
             };
         }
 
+        // AbsorbShield: the outcome of the effect stores the remaining amount of the shield
+        if processed_ep.input_effect_param.buffer.kind == BufKinds::AbsorbShield {
+            let amount =
+                processed_ep.number_of_applies * processed_ep.input_effect_param.buffer.value;
+            return EffectOutcome {
+                target_id_name: self.id_name.clone(),
+                full_amount_tx: amount,
+                real_amount_tx: amount,
+                shield_remaining: amount,
+                ..Default::default()
+            };
+        }
+
         // eval if the effect can be applied on the target
-        if processed_ep.input_effect_param.buffer.stats_name.is_empty()
-            || !self
-                .stats
-                .all_stats
-                .contains_key(&processed_ep.input_effect_param.buffer.stats_name)
-        {
+        let Some(stat_kind) = processed_ep.input_effect_param.buffer.stats_name else {
             tracing::debug!(
-                "Effect {} cannot be applied on {} because the stat {} does not exist.",
+                "Effect {} cannot be applied on {} because it has no stat.",
                 processed_ep.input_effect_param.buffer.kind,
                 self.id_name,
-                processed_ep.input_effect_param.buffer.stats_name
             );
             return EffectOutcome {
                 target_id_name: self.id_name.clone(),
                 ..Default::default()
             };
-        }
+        };
 
         // eval `full_amount`
         let mut full_amount;
         let mut pre_armor_amount_tx = 0i64;
+        let mut effectiveness = Effectiveness::default();
         let mut processed_effect_param = processed_ep.clone();
         let pow_current =
             launcher_stats.get_power_stat(processed_ep.input_effect_param.is_magic_atk);
-        if processed_ep.input_effect_param.buffer.stats_name == HP
+        if processed_ep.input_effect_param.buffer.stats_name == Some(StatKind::Hp)
             && processed_ep.input_effect_param.buffer.kind == BufKinds::DecreasingRateOnTurn
         {
             // prepare for HOT
@@ -443,7 +504,7 @@ impl Character {
             processed_effect_param.input_effect_param.buffer.kind =
                 BufKinds::ChangeCurrentStatByValue;
             processed_effect_param.input_effect_param.buffer.value = full_amount;
-        } else if processed_ep.input_effect_param.buffer.stats_name == HP
+        } else if processed_ep.input_effect_param.buffer.stats_name == Some(StatKind::Hp)
             && processed_ep.input_effect_param.buffer.kind == BufKinds::ChangeCurrentStatByValue
         {
             if processed_ep.input_effect_param.buffer.value > 0 {
@@ -459,25 +520,22 @@ impl Character {
                     processed_ep.input_effect_param.is_magic_atk,
                     processed_ep.input_effect_param.buffer.value,
                     processed_ep.input_effect_param.nb_turns,
+                    processed_ep.type_multiplier,
                 );
+                effectiveness = Effectiveness::from_multiplier(processed_ep.type_multiplier);
                 full_amount = processed_ep.number_of_applies * eff;
                 pre_armor_amount_tx = processed_ep.number_of_applies * raw;
             }
         } else if processed_ep.input_effect_param.buffer.kind
             == BufKinds::ChangeCurrentStatByPercentage
-            && Stats::is_energy_stat(&processed_ep.input_effect_param.buffer.stats_name)
+            && stat_kind.is_energy()
         {
             full_amount = processed_ep.number_of_applies
-                * self
-                    .stats
-                    .all_stats
-                    .get(&processed_ep.input_effect_param.buffer.stats_name)
-                    .unwrap()
-                    .max as i64
+                * self.stats[stat_kind].max as i64
                 * processed_ep.input_effect_param.buffer.value
                 / 100;
         } else if processed_ep.input_effect_param.buffer.kind == BufKinds::Resurrect
-            && processed_ep.input_effect_param.buffer.stats_name == HP
+            && processed_ep.input_effect_param.buffer.stats_name == Some(StatKind::Hp)
         {
             // Flat HP restore for resurrection — no power scaling, bypasses dead check
             full_amount =
@@ -496,12 +554,17 @@ impl Character {
         let is_max_stat_effect = processed_ep.input_effect_param.buffer.kind
             == BufKinds::ChangeMaxStatByPercentage
             || processed_ep.input_effect_param.buffer.kind == BufKinds::ChangeMaxStatByValue;
-        if processed_ep.input_effect_param.buffer.stats_name == HP && !is_max_stat_effect {
+        if processed_ep.input_effect_param.buffer.stats_name == Some(StatKind::Hp)
+            && !is_max_stat_effect
+        {
             full_amount = self.character_rounds_info.apply_buf_debuf(
                 full_amount,
                 &processed_ep.input_effect_param.target_kind,
                 is_crit,
             );
+            if full_amount < 0 && !processed_ep.input_effect_param.target_kind.is_ally() {
+                full_amount = self.apply_low_hp_damage_rx(full_amount);
+            }
             processed_effect_param.input_effect_param.buffer.value = full_amount;
         }
         // blocking the atk
@@ -515,16 +578,23 @@ impl Character {
         // Process stats `HP`
         // Calculation of the real amount of the value of the effect and update the energy stats
         // ChangeMaxStat* on HP updates max, not current — skip the current-HP update path
-        let real_hp_amount = if is_max_stat_effect {
-            0
+        let (real_hp_amount, absorbed_amount) = if is_max_stat_effect {
+            (0, 0)
         } else {
-            self.stats
-                .update_hp_process_real_amount(&processed_ep.input_effect_param, full_amount)
+            let mut shields = self.character_rounds_info.shields_mut();
+            self.stats.update_hp_process_real_amount(
+                &processed_ep.input_effect_param,
+                full_amount,
+                &mut shields,
+            )
         };
+        if absorbed_amount > 0 {
+            self.character_rounds_info.remove_depleted_shields();
+        }
 
         // Track overheal: any HP heal that exceeds the remaining HP room is overheal.
         // This covers regular attack heals; HOT overheal is tracked separately in apply_hot_or_dot.
-        if processed_ep.input_effect_param.buffer.stats_name == HP
+        if processed_ep.input_effect_param.buffer.stats_name == Some(StatKind::Hp)
             && full_amount > 0
             && real_hp_amount < full_amount
         {
@@ -547,10 +617,10 @@ impl Character {
         // be negative (headroom remaining) or positive (overflow). The actual amount added is
         // min(full_amount, apply_result) which handles both cases correctly.
         let real_dmg_amount = if apply_result < 0 {
-            apply_result
+            apply_result + absorbed_amount
         } else if is_max_stat_effect {
             0
-        } else if processed_ep.input_effect_param.buffer.stats_name == HP {
+        } else if processed_ep.input_effect_param.buffer.stats_name == Some(StatKind::Hp) {
             real_hp_amount
         } else {
             full_amount.min(apply_result)
@@ -564,10 +634,9 @@ impl Character {
         if processed_ep.input_effect_param.buffer.kind != BufKinds::ChangeMaxStatByValue
             && processed_ep.input_effect_param.buffer.kind != BufKinds::ChangeMaxStatByPercentage
         {
-            let aggro_norm = 20.0;
-            if processed_ep.input_effect_param.buffer.stats_name == HP {
-                aggro_generated = (real_hp_amount.abs() as f64 / aggro_norm).round() as u64;
-            } else if processed_ep.input_effect_param.buffer.stats_name == AGGRO
+            if processed_ep.input_effect_param.buffer.stats_name == Some(StatKind::Hp) {
+                aggro_generated = (real_hp_amount.abs() as f64 / AGGRO_NORM).round() as u64;
+            } else if processed_ep.input_effect_param.buffer.stats_name == Some(StatKind::Aggro)
                 && processed_ep.input_effect_param.buffer.kind == BufKinds::ChangeCurrentStatByValue
             {
                 // Explicit aggro effects bypass the /20 normalisation so the full value
@@ -587,6 +656,9 @@ impl Character {
             is_critical: is_crit,
             aggro_generated,
             debuff_removed: false,
+            effectiveness,
+            absorbed_amount,
+            shield_remaining: 0,
         }
     }
 
@@ -597,11 +669,12 @@ impl Character {
         full_amount: i64,
     ) -> i64 {
         // Update the max value of the stat (applies to HP and non-HP alike)
-        if processed_ep.input_effect_param.buffer.kind == BufKinds::ChangeMaxStatByPercentage
-            || processed_ep.input_effect_param.buffer.kind == BufKinds::ChangeMaxStatByValue
+        if let Some(kind) = processed_ep.input_effect_param.buffer.stats_name
+            && (processed_ep.input_effect_param.buffer.kind == BufKinds::ChangeMaxStatByPercentage
+                || processed_ep.input_effect_param.buffer.kind == BufKinds::ChangeMaxStatByValue)
         {
             self.stats.set_stats_on_effect(
-                &processed_ep.input_effect_param.buffer.stats_name,
+                kind,
                 full_amount,
                 processed_ep.input_effect_param.buffer.kind == BufKinds::ChangeMaxStatByPercentage,
                 true,
@@ -612,16 +685,14 @@ impl Character {
         // ChangeCurrentStatByPercentage on energy stats (Vigor, Mana, Berserk) uses the same
         // path: full_amount is already computed as max * value / 100, just needs to be applied.
         let mut overhead_dmg = 0;
-        if processed_ep.input_effect_param.buffer.stats_name != HP
-            && processed_ep.input_effect_param.buffer.stats_name != AGGRO
+        if let Some(kind) = processed_ep.input_effect_param.buffer.stats_name
+            && kind != StatKind::Hp
+            && kind != StatKind::Aggro
             && (processed_ep.input_effect_param.buffer.kind == BufKinds::ChangeCurrentStatByValue
                 || processed_ep.input_effect_param.buffer.kind
                     == BufKinds::ChangeCurrentStatByPercentage)
         {
-            overhead_dmg = self.stats.modify_stat_current(
-                &processed_ep.input_effect_param.buffer.stats_name,
-                full_amount,
-            );
+            overhead_dmg = self.stats.modify_stat_current(kind, full_amount);
         }
         full_amount - overhead_dmg
     }
@@ -631,6 +702,7 @@ impl Character {
         game_state: &GameState,
         is_crit: bool,
         atk: &AttackType,
+        rng: &mut GameRng,
     ) -> Result<Vec<ProcessedEffectParam>> {
         // Reset ApplyEffectInit so a value left by a previous attack (e.g. DecreasingRateOnTurn)
         // does not bleed into unrelated attacks and inflate their number_of_applies.
@@ -653,23 +725,20 @@ impl Character {
                 // apply_cost_on_stats deducts raw_cost * stat_max / 100 (not raw_cost itself),
                 // so compute actual_cost using the stat's max to get the right repeat count.
                 let (remaining, stat_max) = if atk.berseck_cost > 0 {
-                    self.stats
-                        .all_stats
-                        .get(BERSERK)
-                        .map(|s| (s.current as i64, s.max))
-                        .unwrap_or((0, 100))
+                    (
+                        self.stats[StatKind::Berserk].current as i64,
+                        self.stats[StatKind::Berserk].max,
+                    )
                 } else if atk.vigor_cost > 0 {
-                    self.stats
-                        .all_stats
-                        .get(VIGOR)
-                        .map(|s| (s.current as i64, s.max))
-                        .unwrap_or((0, 100))
+                    (
+                        self.stats[StatKind::Vigor].current as i64,
+                        self.stats[StatKind::Vigor].max,
+                    )
                 } else {
-                    self.stats
-                        .all_stats
-                        .get(MANA)
-                        .map(|s| (s.current as i64, s.max))
-                        .unwrap_or((0, 100))
+                    (
+                        self.stats[StatKind::Mana].current as i64,
+                        self.stats[StatKind::Mana].max,
+                    )
                 };
                 let actual_cost = ((raw_cost * stat_max / 100) as i64).max(1);
                 // nb_applies = total times the effect fires; remaining is post-first-deduction,
@@ -680,12 +749,13 @@ impl Character {
                     let extra = (nb_applies - 1) as u64;
                     if atk.berseck_cost > 0 {
                         self.stats
-                            .apply_cost_on_stats(extra * atk.berseck_cost, BERSERK);
+                            .apply_cost_on_stats(extra * atk.berseck_cost, StatKind::Berserk);
                     } else if atk.vigor_cost > 0 {
                         self.stats
-                            .apply_cost_on_stats(extra * atk.vigor_cost, VIGOR);
+                            .apply_cost_on_stats(extra * atk.vigor_cost, StatKind::Vigor);
                     } else {
-                        self.stats.apply_cost_on_stats(extra * atk.mana_cost, MANA);
+                        self.stats
+                            .apply_cost_on_stats(extra * atk.mana_cost, StatKind::Mana);
                     }
                 }
                 self.character_rounds_info.update_buffer(&Buffer {
@@ -696,7 +766,7 @@ impl Character {
                 });
             }
         }
-        self.process_all_effects(game_state, is_crit, &atk.name, &atk.all_effects)
+        self.process_all_effects(game_state, is_crit, &atk.name, &atk.all_effects, rng)
     }
 
     fn process_all_effects(
@@ -705,9 +775,8 @@ impl Character {
         is_crit: bool,
         action_name: &str,
         all_effects: &[EffectParam],
+        rng: &mut GameRng,
     ) -> Result<Vec<ProcessedEffectParam>> {
-        use crate::character_mod::target::is_target_ally;
-
         let mut processed_effect_param_list: Vec<ProcessedEffectParam> = vec![];
         let mut skip_next_multi = false;
         // MultiValue is stored on the launcher's CRI; apply_buf_debuf runs on the target's CRI
@@ -729,15 +798,16 @@ impl Character {
                 action_name,
                 game_state,
                 is_crit,
+                rng,
             )?;
 
             if processed.input_effect_param.buffer.kind == BufKinds::MultiValue {
                 pending_multi = processed.input_effect_param.buffer.value;
             } else if pending_multi > 1
                 && processed.input_effect_param.buffer.kind == BufKinds::ChangeCurrentStatByValue
-                && processed.input_effect_param.buffer.stats_name == HP
+                && processed.input_effect_param.buffer.stats_name == Some(StatKind::Hp)
                 && processed.input_effect_param.buffer.value > 0
-                && is_target_ally(&processed.input_effect_param.target_kind)
+                && processed.input_effect_param.target_kind.is_ally()
             {
                 // Carry the multiplier to is_receiving_atk so it is applied AFTER the
                 // power-scaled formula (value + pow) / nb_turns, not to the raw buffer.value.
@@ -778,11 +848,11 @@ impl Character {
         }
 
         // Update aggro
-        if let Some(aggro_stat) = self.stats.all_stats.get_mut(AGGRO)
-            && let Some(tx_map) = self
-                .character_rounds_info
-                .tx_rx
-                .get_mut(AmountType::Aggro as usize)
+        let aggro_stat = &mut self.stats[StatKind::Aggro];
+        if let Some(tx_map) = self
+            .character_rounds_info
+            .tx_rx
+            .get_mut(AmountType::Aggro as usize)
             && let Some(aggro) = tx_map.get_mut(&(turn_nb as u64))
         {
             // update txrx current turn nb
@@ -803,13 +873,21 @@ impl Character {
     ) -> (Option<GameAtkEffect>, Option<Vec<DodgeInfo>>) {
         let mut option_gae: Option<GameAtkEffect> = None;
         let mut di: Vec<DodgeInfo> = Vec::new();
-        if self.stats.is_dead() == Some(true)
+        if self.stats.is_dead()
             && processed_ep.input_effect_param.buffer.kind != BufKinds::Resurrect
         {
             tracing::info!("is_receiving_atk: {} is already dead.", self.id_name);
             return (None, None);
         }
 
+        // effectiveness of the atk element on this target
+        let processed_ep = &ProcessedEffectParam {
+            type_multiplier: launcher_info
+                .type_chart
+                .multiplier(launcher_info.atk_type.element, &self.elements),
+            ..processed_ep.clone()
+        };
+
         let target_data = TargetData {
             launcher_id_name: launcher_info.id_name.to_string(),
             target_id_name: self.id_name.clone(),
@@ -820,12 +898,27 @@ impl Character {
 
         // check if the effect is applied on the target
         if self.character_rounds_info.is_effect_applied(&target_data) {
-            let effect_outcome = self.apply_processed_effect_param(
-                processed_ep,
-                &launcher_info.stats,
-                is_crit,
-                game_state.current_turn_nb,
-            );
+            let is_ailment =
+                processed_ep.input_effect_param.buffer.kind == BufKinds::InflictAilment;
+            let effect_outcome = if is_ailment {
+                self.inflict_ailment(processed_ep, launcher_info)
+            } else {
+                self.apply_processed_effect_param(
+                    processed_ep,
+                    &launcher_info.stats,
+                    is_crit,
+                    game_state.current_turn_nb,
+                )
+            };
+            // a sleeping character wakes up when it receives damages
+            if effect_outcome.real_amount_tx < 0
+                && processed_ep.input_effect_param.buffer.stats_name == Some(StatKind::Hp)
+                && self
+                    .character_rounds_info
+                    .remove_ailment(AilmentKind::Sleep)
+            {
+                tracing::info!("is_receiving_atk: {} wakes up.", self.id_name);
+            }
             // assess the blocking
             if self
                 .character_rounds_info
@@ -837,12 +930,18 @@ impl Character {
             let gae = GameAtkEffect {
                 processed_effect_param: processed_ep.clone(),
                 atk_type: launcher_info.atk_type.clone(),
+                launcher_id_name: launcher_info.id_name.clone(),
                 launching_turn: game_state.current_turn_nb,
                 launching_round: game_state.current_round,
                 effect_outcome: effect_outcome.clone(),
             };
             // update character table of effects when the effect takes place
-            self.character_rounds_info.all_effects.push(gae.clone());
+            // the ailments are stored apart, with their own duration
+            if processed_ep.input_effect_param.buffer.kind == BufKinds::AbsorbShield {
+                self.character_rounds_info.add_shield(gae.clone());
+            } else if !is_ailment {
+                self.character_rounds_info.all_effects.push(gae.clone());
+            }
             // update stats table
             option_gae = Some(gae.clone());
         } else {
@@ -856,7 +955,7 @@ impl Character {
                 processed_ep.input_effect_param.target_kind,
                 launcher_info.kind,
                 processed_ep.input_effect_param.buffer.kind,
-                processed_ep.input_effect_param.buffer.stats_name
+                processed_ep.input_effect_param.buffer.stats_label()
             );
         }
         // assess the dodging
@@ -874,12 +973,170 @@ impl Character {
         (option_gae, all_dodging)
     }
 
+    /// Inflict the ailment of the effect on the character.
+    /// Poison and burn damages are assessed once, like the damages of a DOT.
+    fn inflict_ailment(
+        &mut self,
+        processed_ep: &ProcessedEffectParam,
+        launcher_info: &LauncherAtkInfo,
+    ) -> EffectOutcome {
+        let ep = &processed_ep.input_effect_param;
+        let Some(kind) = ep.ailment else {
+            tracing::warn!(
+                "inflict_ailment: atk {} has no ailment to inflict.",
+                launcher_info.atk_type.name
+            );
+            return EffectOutcome {
+                target_id_name: self.id_name.clone(),
+                ..Default::default()
+            };
+        };
+        let mut effectiveness = Effectiveness::default();
+        let mut dot_value = 0;
+        if kind.is_dot() {
+            let (_raw, eff) = AttackType::damage_by_atk(
+                &self.stats,
+                &launcher_info.stats,
+                ep.is_magic_atk,
+                ep.buffer.value,
+                ep.nb_turns,
+                processed_ep.type_multiplier,
+            );
+            effectiveness = Effectiveness::from_multiplier(processed_ep.type_multiplier);
+            dot_value = processed_ep.number_of_applies * eff;
+        }
+        self.character_rounds_info.add_ailment(Ailment {
+            kind,
+            turns_left: ep.nb_turns.max(1),
+            dot_value,
+            source_id_name: launcher_info.id_name.clone(),
+            atk_name: launcher_info.atk_type.name.clone(),
+        });
+        EffectOutcome {
+            full_amount_tx: dot_value,
+            target_id_name: self.id_name.clone(),
+            effectiveness,
+            ..Default::default()
+        }
+    }
+
+    /// With a loadout, the attack must be learnt, otherwise the level of the character must be high enough
+    pub fn is_atk_known(&self, atk_type: &AttackType) -> bool {
+        if !atk_type.is_available_for(&self.class) {
+            false
+        } else if self.loadout.is_enabled() {
+            self.loadout.is_known(&atk_type.name)
+        } else {
+            self.level >= atk_type.level
+        }
+    }
+
+    /// Learn the attacks reached by a level-up from `old_level`, and return their names
+    pub fn learn_atks_of_level(&mut self, old_level: u64) -> Vec<String> {
+        if self.loadout.is_enabled() {
+            return self
+                .loadout
+                .learn_atks_of_level(&self.attacks_list, self.level, &self.class);
+        }
+        self.attacks_list
+            .values()
+            .filter(|atk| atk.level > old_level && atk.level <= self.level)
+            .filter(|atk| atk.is_available_for(&self.class))
+            .map(|atk| atk.name.clone())
+            .collect()
+    }
+
+    /// Specialisations the hero can choose: its level reached `SPECIALISATION_LEVEL` with a base class
+    pub fn available_specialisations(&self) -> Vec<Class> {
+        if self.level < SPECIALISATION_LEVEL {
+            return Vec::new();
+        }
+        self.class.specialisations()
+    }
+
+    /// Choose a specialisation of the class of the hero, which changes its exp factor,
+    /// its loots and its attacks.
+    /// Return the names of the attacks of the specialisation learnt at once.
+    pub fn specialise(&mut self, class: &Class) -> Result<Vec<String>> {
+        if !self.available_specialisations().contains(class) {
+            bail!(
+                "{} ({}, level {}) cannot be specialised as {}",
+                self.id_name,
+                self.class.to_str(),
+                self.level,
+                class.to_str()
+            );
+        }
+        self.class = class.clone();
+        self.character_rounds_info.exp_to_next_level =
+            build_exp_to_next_level(&self.rank, &self.class, self.level);
+        let new_atks: Vec<String> = self
+            .attacks_list
+            .values()
+            .filter(|atk| atk.specialisation.as_ref() == Some(class) && atk.level <= self.level)
+            .map(|atk| atk.name.clone())
+            .collect();
+        if self.loadout.is_enabled() {
+            for atk_name in &new_atks {
+                self.loadout.learn(atk_name);
+            }
+        }
+        Ok(new_atks)
+    }
+
+    /// Enable the buffers of the innate passive of the class as passive buffers
+    pub fn apply_class_passive(&mut self, passive: &ClassPassive) {
+        for ep in &passive.effects {
+            self.character_rounds_info.update_buffer(&Buffer {
+                is_passive: true,
+                is_passive_enabled: true,
+                ..ep.buffer.clone()
+            });
+        }
+    }
+
+    /// Change the damages received with the `LowHpDamageRxPercent` passive while the HP are low
+    fn apply_low_hp_damage_rx(&self, amount: i64) -> i64 {
+        let Some(buf) = self
+            .character_rounds_info
+            .get_passive_buffer(&BufKinds::LowHpDamageRxPercent)
+        else {
+            return amount;
+        };
+        let hp = &self.stats[StatKind::Hp];
+        if hp.current * 100 >= hp.max * LOW_HP_THRESHOLD_PERCENT {
+            return amount;
+        }
+        amount + update_damage_by_buf(buf.value, buf.is_percent, amount)
+    }
+
+    /// Learn an attack of the attacks list from a trainer or a scroll, whatever its level
+    pub fn learn_attack(&mut self, atk_name: &str) -> Result<()> {
+        if !self.loadout.is_enabled() {
+            bail!("{} has no attack loadout", self.id_name);
+        }
+        if !self.attacks_list.contains_key(atk_name) {
+            bail!("{} has no attack '{}'", self.id_name, atk_name);
+        }
+        if !self.loadout.learn(atk_name) {
+            bail!("{} already knows the attack '{}'", self.id_name, atk_name);
+        }
+        Ok(())
+    }
+
     /// The attak can be launched if the character has enough mana, vigor and
     /// berserk and if the atk is not under a cooldown.
     /// If the atk can be launched, true is returned, otherwise false is returned.
     pub fn can_be_launched(&self, atk_type: &AttackType, current_turn_nb: usize) -> bool {
-        // needed level too high
-        if self.level < atk_type.level {
+        // with a loadout, only the equipped attacks can be launched
+        if !self.is_atk_known(atk_type)
+            || (self.loadout.is_enabled() && !self.loadout.is_equipped(&atk_type.name))
+        {
+            return false;
+        }
+
+        // silenced characters cannot launch magical atks
+        if atk_type.is_magic_atk() && self.character_rounds_info.has_ailment(AilmentKind::Silence) {
             return false;
         }
 
@@ -897,10 +1154,10 @@ impl Character {
                 }
             }
 
-            if atk_effect.buffer.stats_name == HP
-                && (atk_effect.target_kind == TARGET_ALLY
-                    || atk_effect.target_kind == TARGET_ONLY_ALLY
-                    || atk_effect.target_kind == TARGET_ALL_ALLIES)
+            if atk_effect.buffer.stats_name == Some(StatKind::Hp)
+                && (atk_effect.target_kind == Target::Ally
+                    || atk_effect.target_kind == Target::OnlyAllies
+                    || atk_effect.target_kind == Target::AllAllies)
                 && self.character_rounds_info.is_heal_atk_blocked
             {
                 return false;
@@ -933,9 +1190,9 @@ impl Character {
         }
 
         // atk cost enough ?
-        let mana = &self.stats.all_stats[MANA];
-        let vigor = &self.stats.all_stats[VIGOR];
-        let berserk = &self.stats.all_stats[BERSERK];
+        let mana = &self.stats[StatKind::Mana];
+        let vigor = &self.stats[StatKind::Vigor];
+        let berserk = &self.stats[StatKind::Berserk];
 
         if (atk_type.mana_cost > 0 && !self.has_energy_kind(&EnergyKind::Mana))
             || (atk_type.vigor_cost > 0 && !self.has_energy_kind(&EnergyKind::Vigor))
@@ -951,8 +1208,15 @@ impl Character {
 
     pub fn apply_hot_or_dot(&mut self, current_turn_nb: usize, hot_or_dot: i64) -> String {
         let mut log = String::new();
+        // the dots are absorbed by the shields before HP
+        let (hot_or_dot, absorbed) =
+            absorb_by_shields(hot_or_dot, &mut self.character_rounds_info.shields_mut());
+        if absorbed > 0 {
+            self.character_rounds_info.remove_depleted_shields();
+            log = format!("DOT absorbed: {}", absorbed);
+        }
         if hot_or_dot != 0 {
-            let overhead = self.stats.modify_stat_current(HP, hot_or_dot);
+            let overhead = self.stats.modify_stat_current(StatKind::Hp, hot_or_dot);
 
             // TODO output log
             // localLog.append(QString("HOT et DOT totaux: %1").arg(hotAndDot));
@@ -976,6 +1240,7 @@ impl Character {
         &mut self,
         current_turn_nb: usize,
         launchable_atks: Vec<AttackType>,
+        rng: &mut GameRng,
     ) -> Vec<LogData> {
         let mut output_logs_data: Vec<LogData> = Vec::new();
         self.character_rounds_info.actions_done_in_round = 0;
@@ -989,13 +1254,12 @@ impl Character {
                 Ok(effects_param_removed) => effects_param_removed.iter().for_each(|e| {
                     let buf = &e.processed_effect_param.input_effect_param.buffer;
                     let atk = &e.atk_type.name;
-                    let msg = if buf.stats_name.is_empty() {
-                        format!("\u{1f550} Effect expired: {} ({})", buf.kind, atk)
-                    } else {
-                        format!(
+                    let msg = match buf.stats_name {
+                        None => format!("\u{1f550} Effect expired: {} ({})", buf.kind, atk),
+                        Some(stat) => format!(
                             "\u{1f550} Effect expired: {} on {} ({})",
-                            buf.kind, buf.stats_name, atk
-                        )
+                            buf.kind, stat, atk
+                        ),
                     };
                     output_logs_data.push(LogData {
                         message: msg,
@@ -1008,19 +1272,22 @@ impl Character {
                 }),
             }
 
+            // Process the ailments
+            let (mut ailment_logs, ailment_dot) = self.character_rounds_info.process_ailments();
+            output_logs_data.append(&mut ailment_logs);
+
             // Apply passive powers
             if let Some(buf) = self
                 .character_rounds_info
                 .get_buffer_by_type(&BufKinds::OverHealBoostStat)
                 .cloned()
                 && buf.is_passive_enabled
-                && !buf.stats_name.is_empty()
+                && let Some(kind) = buf.stats_name
             {
                 // Reset the bonus from the previous turn: cap current to max so the
                 // stale overheal boost doesn't compound across turns.
-                if let Some(stat) = self.stats.all_stats.get_mut(&buf.stats_name) {
-                    stat.current = stat.current.min(stat.max);
-                }
+                let stat = &mut self.stats[kind];
+                stat.current = stat.current.min(stat.max);
                 let prev_turn = current_turn_nb.saturating_sub(1) as u64;
                 let overheal = self
                     .character_rounds_info
@@ -1031,13 +1298,12 @@ impl Character {
                     .unwrap_or(0);
                 if overheal > 0 {
                     // Bypass the max-cap: the passive bonus is an uncapped temporary boost.
-                    if let Some(stat) = self.stats.all_stats.get_mut(&buf.stats_name) {
-                        stat.current = stat.current.saturating_add(overheal as u64);
-                    }
+                    let stat = &mut self.stats[kind];
+                    stat.current = stat.current.saturating_add(overheal as u64);
                     output_logs_data.push(LogData {
                         message: format!(
                             "\u{26a1} Passive({}): {} +{} from overheal",
-                            self.id_name, buf.stats_name, overheal
+                            self.id_name, kind, overheal
                         ),
                         color: LIGHT_GREEN.to_string(),
                     });
@@ -1051,9 +1317,9 @@ impl Character {
             // apply hot and dot
             let (mut process_logs, hot_or_dot) = self
                 .character_rounds_info
-                .process_hot_and_dot(current_turn_nb);
+                .process_hot_and_dot(current_turn_nb, rng);
             output_logs_data.append(&mut process_logs);
-            let hot_dot_logs = self.apply_hot_or_dot(current_turn_nb, hot_or_dot);
+            let hot_dot_logs = self.apply_hot_or_dot(current_turn_nb, hot_or_dot + ailment_dot);
             if !hot_dot_logs.is_empty() {
                 output_logs_data.push(LogData {
                     message: hot_dot_logs,
@@ -1073,11 +1339,18 @@ impl Character {
         consumable: Consumable,
         game_state: &GameState,
         launcher_stats: &Stats,
+        rng: &mut GameRng,
     ) -> Result<Vec<EffectOutcome>> {
         if !self.inventory.contains_potion(&consumable.name) {
             bail!("no {} is in the inventory", consumable.name)
         }
-        match self.process_all_effects(game_state, false, &consumable.name, &consumable.effects) {
+        match self.process_all_effects(
+            game_state,
+            false,
+            &consumable.name,
+            &consumable.effects,
+            rng,
+        ) {
             Ok(all_processed_ep) => {
                 let mut all_eo: Vec<EffectOutcome> = vec![];
                 for processed_ep in all_processed_ep {
@@ -1102,8 +1375,15 @@ impl Character {
         consumable: &Consumable,
         game_state: &GameState,
         launcher_stats: &Stats,
+        rng: &mut GameRng,
     ) -> Result<Vec<EffectOutcome>> {
-        match self.process_all_effects(game_state, false, &consumable.name, &consumable.effects) {
+        match self.process_all_effects(
+            game_state,
+            false,
+            &consumable.name,
+            &consumable.effects,
+            rng,
+        ) {
             Ok(all_processed_ep) => {
                 let mut all_eo: Vec<EffectOutcome> = vec![];
                 for processed_ep in all_processed_ep {
@@ -1120,11 +1400,64 @@ impl Character {
         }
     }
 
+    /// Apply effects launched by the character on itself outside of an atk (e.g. the buffs of a boss phase).
+    /// The effects are stored like the ones of an atk and expire with their number of turns.
+    pub fn receive_self_effects(
+        &mut self,
+        action_name: &str,
+        effects: &[EffectParam],
+        game_state: &GameState,
+        rng: &mut GameRng,
+    ) -> Result<Vec<GameAtkEffect>> {
+        let all_processed_ep =
+            self.process_all_effects(game_state, false, action_name, effects, rng)?;
+        let launcher_info = LauncherAtkInfo {
+            id_name: self.id_name.clone(),
+            kind: self.kind.clone(),
+            stats: self.stats.clone(),
+            atk_type: AttackType {
+                name: action_name.to_owned(),
+                ..Default::default()
+            },
+            type_chart: TypeChart::default(),
+        };
+        Ok(all_processed_ep
+            .iter()
+            .filter_map(|processed_ep| {
+                self.is_receiving_atk(processed_ep, game_state, false, &launcher_info)
+                    .0
+            })
+            .collect())
+    }
+
     pub fn toggle_equipment(
         &mut self,
         new_equipment_unique_name: &str,
         all_equipments: &HashMap<EquipmentJsonKey, Vec<Equipment>>,
     ) {
+        let _ = self.update_equipment(all_equipments, |inventory| {
+            inventory.toggle_equipment(new_equipment_unique_name);
+            Ok(())
+        });
+    }
+
+    /// Equip or unequip the equipment instance `id` of the inventory
+    pub fn toggle_equipment_by_id(
+        &mut self,
+        id: u64,
+        all_equipments: &HashMap<EquipmentJsonKey, Vec<Equipment>>,
+    ) -> Result<()> {
+        self.update_equipment(all_equipments, |inventory| {
+            inventory.toggle_equipment_by_id(id)
+        })
+    }
+
+    /// Remove the stats of the equipped equipments, update the inventory and apply the stats back
+    fn update_equipment(
+        &mut self,
+        all_equipments: &HashMap<EquipmentJsonKey, Vec<Equipment>>,
+        update: impl FnOnce(&mut Inventory) -> Result<()>,
+    ) -> Result<()> {
         // downdate stats of previous equipment if exist
         let equipment_off: HashMap<EquipmentJsonKey, Vec<Equipment>> =
             self.inventory.get_all_equipments(
@@ -1145,7 +1478,7 @@ impl Character {
         );
 
         // toggle equipment
-        self.inventory.toggle_equipment(new_equipment_unique_name);
+        let result = update(&mut self.inventory);
 
         // update stats of new equipment
         let equipment_on: HashMap<EquipmentJsonKey, Vec<Equipment>> =
@@ -1167,6 +1500,7 @@ impl Character {
         );
         // apply the effects
         self.apply_effects_on_stats(false);
+        result
     }
 
     fn apply_effects_on_stats(&mut self, update_effect_stats: bool) {
@@ -1174,16 +1508,18 @@ impl Character {
             .all_effects
             .iter_mut()
             .for_each(|gae| {
-                if gae.processed_effect_param.input_effect_param.buffer.kind
-                    == BufKinds::ChangeMaxStatByPercentage
-                    || gae.processed_effect_param.input_effect_param.buffer.kind
-                        == BufKinds::ChangeMaxStatByValue
+                if let Some(kind) = gae
+                    .processed_effect_param
+                    .input_effect_param
+                    .buffer
+                    .stats_name
+                    && (gae.processed_effect_param.input_effect_param.buffer.kind
+                        == BufKinds::ChangeMaxStatByPercentage
+                        || gae.processed_effect_param.input_effect_param.buffer.kind
+                            == BufKinds::ChangeMaxStatByValue)
                 {
                     self.stats.set_stats_on_effect(
-                        &gae.processed_effect_param
-                            .input_effect_param
-                            .buffer
-                            .stats_name,
+                        kind,
                         gae.effect_outcome.full_amount_tx,
                         gae.processed_effect_param.input_effect_param.buffer.kind
                             == BufKinds::ChangeMaxStatByPercentage,
@@ -1205,23 +1541,29 @@ mod tests {
     use strum::IntoEnumIterator;
 
     use super::Character;
+    use crate::character_mod::ailment::{Ailment, AilmentKind};
     use crate::character_mod::attack_type::AttackType;
     use crate::character_mod::buffers::Buffer;
     use crate::character_mod::character::AmountType;
     use crate::character_mod::effect::EffectOutcome;
+    use crate::character_mod::effect::ProcessedEffectParam;
     use crate::character_mod::effect::{Condition, ConditionKind};
+    use crate::character_mod::element::Effectiveness;
     use crate::character_mod::energy::EnergyKind;
     use crate::character_mod::equipment::{Equipment, EquipmentJsonKey};
     use crate::character_mod::rank::Rank;
+    use crate::character_mod::stats::StatKind;
+    use crate::character_mod::target::{Reach, Target};
     use crate::common::constants::paths_const::TEST_OFFLINE_ROOT;
     use crate::common::constants::streak_breaker_const::STREAK_BREAKER_ADVANCED;
+    use crate::common::game_rng::GameRng;
+    use crate::server::difficulty::Difficulty;
     use crate::server::players_manager::GameAtkEffect;
     use crate::testing::testing_all_characters::{self, testing_all_equipment, testing_character};
     use crate::{
         character_mod::buffers::BufKinds,
         character_mod::character::{CharacterKind, Class},
         character_mod::effect::EffectParam,
-        common::constants::stats_const::*,
         testing::testing_effect::*,
     };
 
@@ -1276,67 +1618,67 @@ mod tests {
         assert_eq!("phototest", c.photo_name);
         // stats
         // stats - aggro
-        assert_eq!(0, c.stats.all_stats[AGGRO].current);
-        assert_eq!(10009, c.stats.all_stats[AGGRO].max); // buffer on aggro, 9999 -> 10009
+        assert_eq!(0, c.stats[StatKind::Aggro].current);
+        assert_eq!(10009, c.stats[StatKind::Aggro].max); // buffer on aggro, 9999 -> 10009
         // stats - aggro test init stats only on aggro
         assert_eq!(
-            c.stats.all_stats[AGGRO].current_raw,
-            c.stats.all_stats[AGGRO].current
+            c.stats[StatKind::Aggro].current_raw,
+            c.stats[StatKind::Aggro].current
         );
-        assert_eq!(c.stats.all_stats[AGGRO].max_raw, 9999);
+        assert_eq!(c.stats[StatKind::Aggro].max_raw, 9999);
         // stats - aggro rate
-        assert_eq!(1, c.stats.all_stats[AGGRO_RATE].current);
-        assert_eq!(1, c.stats.all_stats[AGGRO_RATE].max);
+        assert_eq!(1, c.stats[StatKind::AggroRate].current);
+        assert_eq!(1, c.stats[StatKind::AggroRate].max);
         // stats - berseck
-        assert_eq!(105, c.stats.all_stats[BERSERK].current);
-        assert_eq!(210, c.stats.all_stats[BERSERK].max); // right ring + 10 to max berseck (ratio -> update current 100 -> 105)
+        assert_eq!(105, c.stats[StatKind::Berserk].current);
+        assert_eq!(210, c.stats[StatKind::Berserk].max); // right ring + 10 to max berseck (ratio -> update current 100 -> 105)
         // stats - berseck_rate
-        assert_eq!(1, c.stats.all_stats[BERSECK_RATE].current); // +4 right ring
-        assert_eq!(1, c.stats.all_stats[BERSECK_RATE].max);
+        assert_eq!(1, c.stats[StatKind::BerserkRate].current); // +4 right ring
+        assert_eq!(1, c.stats[StatKind::BerserkRate].max);
         // stats - critical_strike
-        assert_eq!(10, c.stats.all_stats[CRITICAL_STRIKE].current);
-        assert_eq!(10, c.stats.all_stats[CRITICAL_STRIKE].max);
+        assert_eq!(10, c.stats[StatKind::CriticalStrike].current);
+        assert_eq!(10, c.stats[StatKind::CriticalStrike].max);
         // stats - dodge
-        assert_eq!(29, c.stats.all_stats[DODGE].current);
-        assert_eq!(29, c.stats.all_stats[DODGE].max);
+        assert_eq!(29, c.stats[StatKind::Dodge].current);
+        assert_eq!(29, c.stats[StatKind::Dodge].max);
         // stats - hp
-        assert_eq!(1, c.stats.all_stats[HP].current);
-        assert_eq!(135, c.stats.all_stats[HP].max);
-        assert_eq!(135, c.stats.all_stats[HP].max_raw);
-        assert_eq!(1, c.stats.all_stats[HP].current_raw);
+        assert_eq!(1, c.stats[StatKind::Hp].current);
+        assert_eq!(135, c.stats[StatKind::Hp].max);
+        assert_eq!(135, c.stats[StatKind::Hp].max_raw);
+        assert_eq!(1, c.stats[StatKind::Hp].current_raw);
         // stats - hp_regeneration
-        assert_eq!(7, c.stats.all_stats[HP_REGEN].current);
-        assert_eq!(7, c.stats.all_stats[HP_REGEN].max);
+        assert_eq!(7, c.stats[StatKind::HpRegen].current);
+        assert_eq!(7, c.stats[StatKind::HpRegen].max);
         // stats - magic_armor
-        assert_eq!(15, c.stats.all_stats[MAGICAL_ARMOR].current);
-        assert_eq!(15, c.stats.all_stats[MAGICAL_ARMOR].max);
+        assert_eq!(15, c.stats[StatKind::MagicalArmor].current);
+        assert_eq!(15, c.stats[StatKind::MagicalArmor].max);
         // stats - magic_power
-        assert_eq!(30, c.stats.all_stats[MAGICAL_POWER].current);
-        assert_eq!(30, c.stats.all_stats[MAGICAL_POWER].max);
+        assert_eq!(30, c.stats[StatKind::MagicalPower].current);
+        assert_eq!(30, c.stats[StatKind::MagicalPower].max);
         // stats - mana
-        assert_eq!(210, c.stats.all_stats[MANA].current);
-        assert_eq!(210, c.stats.all_stats[MANA].max);
+        assert_eq!(210, c.stats[StatKind::Mana].current);
+        assert_eq!(210, c.stats[StatKind::Mana].max);
         // stats - mana_regeneration
-        assert_eq!(7, c.stats.all_stats[MANA_REGEN].current);
-        assert_eq!(7, c.stats.all_stats[MANA_REGEN].max);
+        assert_eq!(7, c.stats[StatKind::ManaRegen].current);
+        assert_eq!(7, c.stats[StatKind::ManaRegen].max);
         // stats - physical_armor
-        assert_eq!(30, c.stats.all_stats[PHYSICAL_ARMOR].current);
-        assert_eq!(30, c.stats.all_stats[PHYSICAL_ARMOR].max);
+        assert_eq!(30, c.stats[StatKind::PhysicalArmor].current);
+        assert_eq!(30, c.stats[StatKind::PhysicalArmor].max);
         // stats - physical_power
-        assert_eq!(40, c.stats.all_stats[PHYSICAL_POWER].current);
-        assert_eq!(40, c.stats.all_stats[PHYSICAL_POWER].max);
+        assert_eq!(40, c.stats[StatKind::PhysicalPower].current);
+        assert_eq!(40, c.stats[StatKind::PhysicalPower].max);
         // stats - speed
-        assert_eq!(212, c.stats.all_stats[SPEED].current);
-        assert_eq!(212, c.stats.all_stats[SPEED].max);
+        assert_eq!(212, c.stats[StatKind::Speed].current);
+        assert_eq!(212, c.stats[StatKind::Speed].max);
         // stats - speed_regeneration
-        assert_eq!(12, c.stats.all_stats[SPEED_REGEN].current);
-        assert_eq!(12, c.stats.all_stats[SPEED_REGEN].max); // + 10% by amulet
+        assert_eq!(12, c.stats[StatKind::SpeedRegen].current);
+        assert_eq!(12, c.stats[StatKind::SpeedRegen].max); // + 10% by amulet
         // stats - vigor
-        assert_eq!(210, c.stats.all_stats[VIGOR].current);
-        assert_eq!(210, c.stats.all_stats[VIGOR].max);
+        assert_eq!(210, c.stats[StatKind::Vigor].current);
+        assert_eq!(210, c.stats[StatKind::Vigor].max);
         // stats - vigor_regeneration
-        assert_eq!(5, c.stats.all_stats[VIGOR_REGEN].current);
-        assert_eq!(5, c.stats.all_stats[VIGOR_REGEN].max);
+        assert_eq!(5, c.stats[StatKind::VigorRegen].current);
+        assert_eq!(5, c.stats[StatKind::VigorRegen].max);
         // tx-rx
         assert_eq!(7, c.character_rounds_info.tx_rx.len());
         // Type - kind
@@ -1383,7 +1725,7 @@ mod tests {
         let mut c = Character::default();
         c.stats.init();
         c.init_aggro_on_turn(1);
-        assert_eq!(0, c.stats.all_stats[AGGRO].current);
+        assert_eq!(0, c.stats[StatKind::Aggro].current);
         c.character_rounds_info.tx_rx.push(HashMap::new());
         c.character_rounds_info.tx_rx.push(HashMap::new());
         c.character_rounds_info.tx_rx.push(HashMap::new());
@@ -1392,31 +1734,31 @@ mod tests {
         c.character_rounds_info.tx_rx.push(HashMap::new());
         c.character_rounds_info.tx_rx[5].insert(1, 10);
         c.init_aggro_on_turn(2);
-        assert_eq!(10, c.stats.all_stats[AGGRO].current);
+        assert_eq!(10, c.stats[StatKind::Aggro].current);
         c.character_rounds_info.tx_rx[5].insert(2, 20);
         c.init_aggro_on_turn(3);
-        assert_eq!(30, c.stats.all_stats[AGGRO].current);
+        assert_eq!(30, c.stats[StatKind::Aggro].current);
         c.character_rounds_info.tx_rx[5].insert(3, 30);
         c.init_aggro_on_turn(4);
-        assert_eq!(60, c.stats.all_stats[AGGRO].current);
+        assert_eq!(60, c.stats[StatKind::Aggro].current);
         c.character_rounds_info.tx_rx[5].insert(4, 40);
         c.init_aggro_on_turn(5);
-        assert_eq!(100, c.stats.all_stats[AGGRO].current);
+        assert_eq!(100, c.stats[StatKind::Aggro].current);
         c.character_rounds_info.tx_rx[5].insert(5, 50);
         c.init_aggro_on_turn(6);
-        assert_eq!(150, c.stats.all_stats[AGGRO].current);
+        assert_eq!(150, c.stats[StatKind::Aggro].current);
         c.character_rounds_info.tx_rx[5].insert(6, 60);
         c.init_aggro_on_turn(7);
-        assert_eq!(200, c.stats.all_stats[AGGRO].current);
+        assert_eq!(200, c.stats[StatKind::Aggro].current);
         c.character_rounds_info.tx_rx[5].insert(7, 70);
         c.init_aggro_on_turn(8);
-        assert_eq!(250, c.stats.all_stats[AGGRO].current);
+        assert_eq!(250, c.stats[StatKind::Aggro].current);
         c.character_rounds_info.tx_rx[5].insert(8, 80);
         c.init_aggro_on_turn(9);
-        assert_eq!(300, c.stats.all_stats[AGGRO].current);
+        assert_eq!(300, c.stats[StatKind::Aggro].current);
         c.character_rounds_info.tx_rx[5].insert(9, 90);
         c.init_aggro_on_turn(10);
-        assert_eq!(350, c.stats.all_stats[AGGRO].current);
+        assert_eq!(350, c.stats[StatKind::Aggro].current);
     }
 
     #[test]
@@ -1433,19 +1775,19 @@ mod tests {
         let ep = EffectParam {
             buffer: Buffer {
                 kind: BufKinds::ChangeMaxStatByValue,
-                stats_name: HP.to_string(),
+                stats_name: Some(StatKind::Hp),
                 value: -10,
                 ..Default::default()
             },
             ..Default::default()
         };
         let result = c.remove_malus_effect(&ep);
-        assert_eq!(145, c.stats.all_stats[HP].max);
-        assert_eq!(1, c.stats.all_stats[HP].current);
+        assert_eq!(145, c.stats[StatKind::Hp].max);
+        assert_eq!(1, c.stats[StatKind::Hp].current);
         let ep = EffectParam {
             buffer: Buffer {
                 kind: BufKinds::ChangeMaxStatByPercentage,
-                stats_name: HP.to_string(),
+                stats_name: Some(StatKind::Hp),
                 value: -10,
                 ..Default::default()
             },
@@ -1455,12 +1797,12 @@ mod tests {
 
         let result = c.remove_malus_effect(&ep);
         assert!(result.is_ok());
-        assert_eq!(158, c.stats.all_stats[HP].max);
-        assert_eq!(1, c.stats.all_stats[HP].current);
+        assert_eq!(158, c.stats[StatKind::Hp].max);
+        assert_eq!(1, c.stats[StatKind::Hp].current);
         let ep = EffectParam {
             buffer: Buffer {
                 kind: BufKinds::BlockHealAtk,
-                stats_name: HP.to_string(),
+                stats_name: Some(StatKind::Hp),
                 value: 10,
                 ..Default::default()
             },
@@ -1475,7 +1817,7 @@ mod tests {
         let ep = EffectParam {
             buffer: Buffer {
                 kind: BufKinds::DamageRxPercent,
-                stats_name: HP.to_string(),
+                stats_name: Some(StatKind::Hp),
                 value: 10,
                 ..Default::default()
             },
@@ -1513,14 +1855,14 @@ mod tests {
                 ..Default::default()
             },
             nb_turns: 10,
-            target_kind: c.id_name.clone(),
+            target_kind: Target::Himself,
             ..Default::default()
         };
         let mut game_state = Default::default();
         // target is himself
         let processed_effect_param = c
             .character_rounds_info
-            .process_one_effect(&ep, "", &game_state, false)
+            .process_one_effect(&ep, "", &game_state, false, &mut GameRng::default())
             .unwrap();
         assert_eq!(
             BufKinds::CooldownTurnsNumber,
@@ -1528,7 +1870,7 @@ mod tests {
         );
         assert_eq!(10, processed_effect_param.input_effect_param.buffer.value);
         assert_eq!(
-            c.id_name,
+            Target::Himself,
             processed_effect_param.input_effect_param.target_kind
         );
         assert_eq!(10, processed_effect_param.input_effect_param.buffer.value);
@@ -1539,13 +1881,13 @@ mod tests {
         assert_eq!("Cooldown on : 10 turns", processed_effect_param.log.message);
 
         // test - critical
-        ep.buffer.stats_name = HP.to_owned();
+        ep.buffer.stats_name = Some(StatKind::Hp);
         ep.buffer.kind = BufKinds::ChangeMaxStatByValue;
         ep.buffer.value = 10;
         ep.nb_turns = 1;
         let processed_effect_param = c
             .character_rounds_info
-            .process_one_effect(&ep, "", &game_state, true)
+            .process_one_effect(&ep, "", &game_state, true, &mut GameRng::default())
             .unwrap();
         assert_eq!(
             BufKinds::ChangeMaxStatByValue,
@@ -1555,7 +1897,7 @@ mod tests {
         // crit : 10 -> 15
         assert_eq!(15, processed_effect_param.input_effect_param.buffer.value);
         assert_eq!(
-            c.id_name,
+            Target::Himself,
             processed_effect_param.input_effect_param.target_kind
         );
         assert_eq!(
@@ -1575,7 +1917,7 @@ mod tests {
         ep.buffer.value = 0;
         let processed_effect_param = c
             .character_rounds_info
-            .process_one_effect(&ep, "", &game_state, false)
+            .process_one_effect(&ep, "", &game_state, false, &mut GameRng::default())
             .unwrap();
         // focus on effect_type
         assert_eq!(
@@ -1584,7 +1926,7 @@ mod tests {
         );
         assert_eq!(1, processed_effect_param.input_effect_param.nb_turns);
         assert_eq!(
-            c.id_name,
+            Target::Himself,
             processed_effect_param.input_effect_param.target_kind
         );
         // focus on value
@@ -1610,38 +1952,38 @@ mod tests {
     fn unit_process_atk_cost() {
         let mut c = testing_character();
 
-        let old_vigor_current = c.stats.all_stats[VIGOR].current;
-        let old_mana_current = c.stats.all_stats[MANA].current;
-        let old_berseck_current = c.stats.all_stats[BERSERK].current;
-        let old_vigor_max = c.stats.all_stats[VIGOR].max;
-        let old_mana_max = c.stats.all_stats[MANA].max;
-        let old_berseck_max = c.stats.all_stats[BERSERK].max;
+        let old_vigor_current = c.stats[StatKind::Vigor].current;
+        let old_mana_current = c.stats[StatKind::Mana].current;
+        let old_berseck_current = c.stats[StatKind::Berserk].current;
+        let old_vigor_max = c.stats[StatKind::Vigor].max;
+        let old_mana_max = c.stats[StatKind::Mana].max;
+        let old_berseck_max = c.stats[StatKind::Berserk].max;
         c.process_atk_cost("atk1"); // 10% vigor cost
 
         assert_eq!(
             old_vigor_current - 10 * old_vigor_max / 100,
-            c.stats.all_stats[VIGOR].current
+            c.stats[StatKind::Vigor].current
         );
         assert_eq!(
             old_mana_current - 10 * old_mana_max / 100,
-            c.stats.all_stats[MANA].current
+            c.stats[StatKind::Mana].current
         );
         assert_eq!(
             old_berseck_current - 10 * old_berseck_max / 100,
-            c.stats.all_stats[BERSERK].current
+            c.stats[StatKind::Berserk].current
         );
         c.process_atk_cost("atk1"); // 10% vigor cost again!
         assert_eq!(
             old_vigor_current - 20 * old_vigor_max / 100,
-            c.stats.all_stats[VIGOR].current
+            c.stats[StatKind::Vigor].current
         );
         assert_eq!(
             old_mana_current - 20 * old_mana_max / 100,
-            c.stats.all_stats[MANA].current
+            c.stats[StatKind::Mana].current
         );
         assert_eq!(
             old_berseck_current - 20 * old_berseck_max / 100,
-            c.stats.all_stats[BERSERK].current
+            c.stats[StatKind::Berserk].current
         );
     }
 
@@ -1651,14 +1993,14 @@ mod tests {
 
         // ultimate atk cannot be dodged
         let atk_level = 13;
-        c.process_dodging(atk_level);
+        c.process_dodging(atk_level, Difficulty::Normal, &mut GameRng::default());
         assert!(!c.character_rounds_info.dodge_info.is_dodging);
         assert!(!c.character_rounds_info.dodge_info.is_blocking);
 
         // impossible to dodge (dodge stat = 0 → softcap = 0%)
         let atk_level = 1;
-        c.stats.all_stats[DODGE].current = 0;
-        c.process_dodging(atk_level);
+        c.stats[StatKind::Dodge].current = 0;
+        c.process_dodging(atk_level, Difficulty::Normal, &mut GameRng::default());
         assert!(!c.character_rounds_info.dodge_info.is_dodging);
         assert!(!c.character_rounds_info.dodge_info.is_blocking);
 
@@ -1667,9 +2009,9 @@ mod tests {
         // past the threshold so the next dodge is guaranteed.
         c.rank = Rank::Advanced;
         c.level = 5;
-        c.stats.all_stats[DODGE].current = 0; // softcap still 0%, but streak-breaker fires
+        c.stats[StatKind::Dodge].current = 0; // softcap still 0%, but streak-breaker fires
         c.character_rounds_info.dodge_drought_counter = STREAK_BREAKER_ADVANCED;
-        c.process_dodging(atk_level);
+        c.process_dodging(atk_level, Difficulty::Normal, &mut GameRng::default());
         assert!(c.character_rounds_info.dodge_info.is_dodging);
         assert!(!c.character_rounds_info.dodge_info.is_blocking);
         // counter is reset after a successful dodge
@@ -1681,17 +2023,17 @@ mod tests {
         // the drought counter at its threshold.
         let atk_level = 1;
         c.class = Class::Berserker;
-        c.stats.all_stats[DODGE].current = 0;
+        c.stats[StatKind::Dodge].current = 0;
         c.character_rounds_info.update_buffer(&Buffer {
             is_passive_enabled: false,
             is_passive: false,
             value: 1,
             is_percent: false,
-            stats_name: String::new(),
+            stats_name: None,
             kind: BufKinds::StreakBreakerDodge,
         });
         c.character_rounds_info.dodge_drought_counter = 1;
-        c.process_dodging(atk_level);
+        c.process_dodging(atk_level, Difficulty::Normal, &mut GameRng::default());
         assert!(!c.character_rounds_info.dodge_info.is_dodging);
         assert!(c.character_rounds_info.dodge_info.is_blocking);
     }
@@ -1702,7 +2044,7 @@ mod tests {
         // raising the effective Dodge stat used in the block roll.
         // Use max_raw=100 so 10% yields a non-zero integer result.
         let mut c = testing_character();
-        let dodge = c.stats.all_stats.get_mut(DODGE).unwrap();
+        let dodge = &mut c.stats[StatKind::Dodge];
         dodge.current = 100;
         dodge.max = 100;
         dodge.max_raw = 100;
@@ -1711,7 +2053,7 @@ mod tests {
         dodge.buf_effect_value = 0;
         c.character_rounds_info.all_buffers.push(Buffer {
             kind: BufKinds::ChangeCurrentStatByPercentage,
-            stats_name: DODGE.to_string(),
+            stats_name: Some(StatKind::Dodge),
             value: 10,
             is_percent: true,
             is_passive: true,
@@ -1720,15 +2062,15 @@ mod tests {
         c.stats
             .apply_buf_debuf_on_stats(&c.character_rounds_info.all_buffers.clone());
         // 100 + 10% of 100 = 110
-        assert_eq!(110, c.stats.all_stats[DODGE].max);
-        assert_eq!(110, c.stats.all_stats[DODGE].current);
+        assert_eq!(110, c.stats[StatKind::Dodge].max);
+        assert_eq!(110, c.stats[StatKind::Dodge].current);
     }
 
     #[test]
     fn unit_passive_change_current_stat_disabled_no_boost() {
         // Passive disabled → stat unchanged
         let mut c = testing_character();
-        let dodge = c.stats.all_stats.get_mut(DODGE).unwrap();
+        let dodge = &mut c.stats[StatKind::Dodge];
         dodge.current = 100;
         dodge.max = 100;
         dodge.max_raw = 100;
@@ -1737,7 +2079,7 @@ mod tests {
         dodge.buf_effect_value = 0;
         c.character_rounds_info.all_buffers.push(Buffer {
             kind: BufKinds::ChangeCurrentStatByPercentage,
-            stats_name: DODGE.to_string(),
+            stats_name: Some(StatKind::Dodge),
             value: 10,
             is_percent: true,
             is_passive: true,
@@ -1746,24 +2088,30 @@ mod tests {
         c.stats
             .apply_buf_debuf_on_stats(&c.character_rounds_info.all_buffers.clone());
         // disabled passive → stat must stay at base values
-        assert_eq!(100, c.stats.all_stats[DODGE].max);
-        assert_eq!(100, c.stats.all_stats[DODGE].current);
+        assert_eq!(100, c.stats[StatKind::Dodge].max);
+        assert_eq!(100, c.stats[StatKind::Dodge].current);
     }
 
     #[test]
     fn unit_process_critical_strike() {
         // no critical strike stat → softcap(0) = 0% → never crits
         let mut c = testing_character();
-        c.stats.all_stats[CRITICAL_STRIKE].current = 0;
-        assert!(!c.process_critical_strike("atk1").unwrap());
+        c.stats[StatKind::CriticalStrike].current = 0;
+        assert!(
+            !c.process_critical_strike("atk1", Difficulty::Normal, &mut GameRng::default())
+                .unwrap()
+        );
 
         // guaranteed crit via streak-breaker:
         // Advanced rank at level >= 5, drought counter at threshold → guaranteed
         c.rank = Rank::Advanced;
         c.level = 5;
-        c.stats.all_stats[CRITICAL_STRIKE].current = 0; // still 0%, but streak-breaker fires
+        c.stats[StatKind::CriticalStrike].current = 0; // still 0%, but streak-breaker fires
         c.character_rounds_info.crit_drought_counter = STREAK_BREAKER_ADVANCED;
-        assert!(c.process_critical_strike("atk1").unwrap());
+        assert!(
+            c.process_critical_strike("atk1", Difficulty::Normal, &mut GameRng::default())
+                .unwrap()
+        );
         // counter is reset after a successful crit
         assert_eq!(0, c.character_rounds_info.crit_drought_counter);
 
@@ -1776,7 +2124,14 @@ mod tests {
         );
 
         // critical strike via passive is processed only on atk with heal effect
-        assert!(c.process_critical_strike("atk_heal1_indiv").unwrap());
+        assert!(
+            c.process_critical_strike(
+                "atk_heal1_indiv",
+                Difficulty::Normal,
+                &mut GameRng::default()
+            )
+            .unwrap()
+        );
         assert!(
             !c.character_rounds_info
                 .get_buffer_by_type(&BufKinds::NextHealAtkIsCrit)
@@ -1816,16 +2171,19 @@ mod tests {
                 is_critical: false,
                 aggro_generated: 0,
                 debuff_removed: false,
+                effectiveness: Effectiveness::Normal,
+                absorbed_amount: 0,
+                shield_remaining: 0,
             }
         );
 
         // target is other ally
         processed_ep = build_hot_effect_individual();
-        let old_hp = c2.stats.all_stats[HP].current;
+        let old_hp = c2.stats[StatKind::Hp].current;
         let eo = c2.apply_processed_effect_param(&processed_ep, &launcher_stats, false, 0);
         assert_eq!(eo.full_amount_tx, 35);
         assert_eq!(eo.real_amount_tx, 35);
-        assert_eq!(old_hp + 35, c2.stats.all_stats[HP].current);
+        assert_eq!(old_hp + 35, c2.stats[StatKind::Hp].current);
 
         // target is ennemy
         let mut boss1 = Character::try_new_from_json(
@@ -1840,11 +2198,11 @@ mod tests {
         // protection = 100/(100 + 40) = 5/7 ≈ 0.7143
         // effective = round(-42 * 5/7) = round(-30.0) = -30
         processed_ep = build_dmg_effect_individual();
-        let old_hp = boss1.stats.all_stats[HP].current;
+        let old_hp = boss1.stats[StatKind::Hp].current;
         let eo = boss1.apply_processed_effect_param(&processed_ep, &launcher_stats, false, 0);
         assert_eq!(eo.full_amount_tx, -30);
         assert_eq!(eo.real_amount_tx, -30);
-        assert_eq!(old_hp - 30, boss1.stats.all_stats[HP].current);
+        assert_eq!(old_hp - 30, boss1.stats[StatKind::Hp].current);
 
         processed_ep = build_buf_effect_individual_speed_regen();
         let launcher_stats = c.stats.clone();
@@ -1975,6 +2333,45 @@ mod tests {
         assert_eq!(0, target.character_rounds_info.all_effects.len());
     }
 
+    #[test]
+    fn unit_ailments_silence_and_remove_one_debuf() {
+        let mut c = testing_character();
+        let launcher_stats = c.stats.clone();
+        let mut atk_type = AttackType {
+            level: 1,
+            ..Default::default()
+        };
+        atk_type
+            .all_effects
+            .push(build_dmg_effect_individual().input_effect_param);
+        c.level = 1;
+        c.character_rounds_info.add_ailment(Ailment {
+            kind: AilmentKind::Silence,
+            turns_left: 2,
+            ..Default::default()
+        });
+        // silence only blocks the magical atks
+        assert!(c.can_be_launched(&atk_type, 0));
+        atk_type.all_effects[0].is_magic_atk = true;
+        assert!(!c.can_be_launched(&atk_type, 0));
+
+        // RemoveOneDebuf removes the ailment before the debuffs
+        c.character_rounds_info.all_effects.push(GameAtkEffect {
+            processed_effect_param: build_heal_atk_blocked(),
+            ..Default::default()
+        });
+        let eo = c.apply_processed_effect_param(
+            &build_remove_one_debuf_effect(),
+            &launcher_stats,
+            false,
+            0,
+        );
+        assert!(eo.debuff_removed);
+        assert!(c.character_rounds_info.ailments.is_empty());
+        assert_eq!(1, c.character_rounds_info.all_effects.len());
+        assert!(c.can_be_launched(&atk_type, 0));
+    }
+
     #[test]
     fn unit_apply_effect_remove_one_debuf_no_debuf() {
         // When there is no debuf, RemoveOneDebuf is a no-op.
@@ -2041,18 +2438,18 @@ mod tests {
         )
         .unwrap();
         c.init_aggro_on_turn(0);
-        let base = c.stats.all_stats[AGGRO].current;
+        let base = c.stats[StatKind::Aggro].current;
 
         // First call: damage 100 → aggro += 5 (100/20 = 5)
         let a1 = c.process_aggro(100, 0, 0);
         assert_eq!(5, a1);
-        assert_eq!(base + 5, c.stats.all_stats[AGGRO].current);
+        assert_eq!(base + 5, c.stats[StatKind::Aggro].current);
 
         // Second call in same turn: damage 60 → aggro += 3 (60/20 = 3)
         let a2 = c.process_aggro(60, 0, 0);
         assert_eq!(3, a2);
         // Without the fix this would be base+5+8=base+13; with fix it is base+5+3=base+8
-        assert_eq!(base + 8, c.stats.all_stats[AGGRO].current);
+        assert_eq!(base + 8, c.stats[StatKind::Aggro].current);
     }
 
     #[test]
@@ -2064,10 +2461,11 @@ mod tests {
             &testing_all_equipment(),
         )
         .unwrap();
-        let hp_without_malus = c.stats.all_stats[HP].max as i64;
+        let hp_without_malus = c.stats[StatKind::Hp].max as i64;
         c.character_rounds_info.all_effects.push(GameAtkEffect {
             processed_effect_param: build_effect_max_stats(),
             atk_type: AttackType::default(),
+            launcher_id_name: String::new(),
             launching_turn: 0,
             launching_round: 0,
             effect_outcome: EffectOutcome::default(),
@@ -2080,7 +2478,7 @@ mod tests {
         c.reset_all_effects_on_player().unwrap();
         assert_eq!(
             hp_without_malus - effect_value,
-            c.stats.all_stats[HP].max as i64
+            c.stats[StatKind::Hp].max as i64
         );
         assert!(c.character_rounds_info.all_effects.is_empty());
     }
@@ -2110,7 +2508,7 @@ mod tests {
         assert!(!result);
         // not enough mana
         c1.level = 1;
-        atk_type.mana_cost = c1.stats.all_stats[MANA].current + 100;
+        atk_type.mana_cost = c1.stats[StatKind::Mana].current + 100;
         let result = c1.can_be_launched(&atk_type, 0);
         assert!(!result);
         // heal atk blocked
@@ -2118,7 +2516,7 @@ mod tests {
         atk_type
             .all_effects
             .push(build_heal_atk_blocked().input_effect_param);
-        atk_type.mana_cost = c1.stats.all_stats[MANA].current / 100;
+        atk_type.mana_cost = c1.stats[StatKind::Mana].current / 100;
         let result = c1.can_be_launched(&atk_type, 0);
         assert!(!result);
         c1.character_rounds_info.is_heal_atk_blocked = false;
@@ -2155,18 +2553,18 @@ mod tests {
             .all_effects
             .push(build_hot_effect_individual().input_effect_param);
         c1.character_rounds_info.all_effects.clear();
-        atk_type.berseck_cost = c1.stats.all_stats[BERSERK].current + 100;
+        atk_type.berseck_cost = c1.stats[StatKind::Berserk].current + 100;
         let result = c1.can_be_launched(&atk_type, 0);
         assert!(!result);
         // not enough vigor
-        atk_type.berseck_cost = c1.stats.all_stats[BERSERK].current;
-        atk_type.vigor_cost = c1.stats.all_stats[VIGOR].current + 100;
+        atk_type.berseck_cost = c1.stats[StatKind::Berserk].current;
+        atk_type.vigor_cost = c1.stats[StatKind::Vigor].current + 100;
         let result = c1.can_be_launched(&atk_type, 0);
         assert!(!result);
         // enough energy
-        atk_type.berseck_cost = c1.stats.all_stats[BERSERK].current;
-        atk_type.vigor_cost = c1.stats.all_stats[VIGOR].current / 100;
-        atk_type.mana_cost = c1.stats.all_stats[MANA].current / 100;
+        atk_type.berseck_cost = c1.stats[StatKind::Berserk].current;
+        atk_type.vigor_cost = c1.stats[StatKind::Vigor].current / 100;
+        atk_type.mana_cost = c1.stats[StatKind::Mana].current / 100;
         let result = c1.can_be_launched(&atk_type, 0);
         assert!(result);
 
@@ -2186,16 +2584,55 @@ mod tests {
     #[test]
     fn unit_apply_hot_or_dot() {
         let mut pl = testing_all_characters::testing_pm();
-        pl.current_player.stats.all_stats[HP].current = 100;
-        pl.current_player.stats.all_stats[HP].max = 100;
-        pl.current_player.stats.all_stats[HP].max_raw = 100;
-        pl.current_player.stats.all_stats[HP].current_raw = 100;
+        pl.current_player.stats[StatKind::Hp].current = 100;
+        pl.current_player.stats[StatKind::Hp].max = 100;
+        pl.current_player.stats[StatKind::Hp].max_raw = 100;
+        pl.current_player.stats[StatKind::Hp].current_raw = 100;
         // max value is topped, 100 and not 100 + 30
         pl.current_player.apply_hot_or_dot(0, 30);
-        assert_eq!(100, pl.current_player.stats.all_stats[HP].current);
+        assert_eq!(100, pl.current_player.stats[StatKind::Hp].current);
 
         pl.current_player.apply_hot_or_dot(0, -30);
-        assert_eq!(70, pl.current_player.stats.all_stats[HP].current);
+        assert_eq!(70, pl.current_player.stats[StatKind::Hp].current);
+    }
+
+    #[test]
+    fn unit_apply_hot_or_dot_absorbed_by_shields() {
+        let mut c = testing_all_characters::testing_pm().current_player;
+        c.stats[StatKind::Hp].current = 100;
+        c.stats[StatKind::Hp].max = 100;
+        c.character_rounds_info.add_shield(GameAtkEffect {
+            processed_effect_param: ProcessedEffectParam {
+                input_effect_param: EffectParam {
+                    nb_turns: 2,
+                    buffer: Buffer {
+                        kind: BufKinds::AbsorbShield,
+                        value: 20,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            effect_outcome: EffectOutcome {
+                shield_remaining: 20,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        // the shield absorbs a part of the dot
+        let log = c.apply_hot_or_dot(0, -15);
+        assert_eq!(log, "DOT absorbed: 15");
+        assert_eq!(100, c.stats[StatKind::Hp].current);
+        assert_eq!(5, c.character_rounds_info.shield_total());
+
+        // then it is depleted and removed, the rest of the dot goes to HP
+        let log = c.apply_hot_or_dot(0, -15);
+        assert_eq!(log, "DOT absorbed: 5");
+        assert_eq!(90, c.stats[StatKind::Hp].current);
+        assert_eq!(0, c.character_rounds_info.shield_total());
+        assert!(c.character_rounds_info.all_effects.is_empty());
     }
 
     #[test]
@@ -2224,7 +2661,7 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(12, c.stats.all_stats[SPEED_REGEN].max);
+        assert_eq!(12, c.stats[StatKind::SpeedRegen].max);
         // add one effect on vigor
         c.character_rounds_info.add_effect_on_player(GameAtkEffect {
             processed_effect_param: build_buf_effect_individual_speed_regen(),
@@ -2235,12 +2672,12 @@ mod tests {
             ..Default::default()
         });
         c.apply_effects_on_stats(true);
-        assert_eq!(32, c.stats.all_stats[SPEED_REGEN].max);
+        assert_eq!(32, c.stats[StatKind::SpeedRegen].max);
 
         // eval mana max - 200 raw + 10 by starting amulet
-        assert_eq!(210, c.stats.all_stats[MANA].max);
-        assert_eq!(210, c.stats.all_stats[MANA].current);
-        assert_eq!(200, c.stats.all_stats[MANA].max_raw);
+        assert_eq!(210, c.stats[StatKind::Mana].max);
+        assert_eq!(210, c.stats[StatKind::Mana].current);
+        assert_eq!(200, c.stats[StatKind::Mana].max_raw);
         // toggle off the same equipment
         let equip = c.inventory.get_equipped_equipments(
             &testing_all_equipment()
@@ -2255,7 +2692,7 @@ mod tests {
                 .iter()
                 .any(|(_, equips)| equips.iter().any(|e| e.unique_name == "starting amulet"))
         );
-        assert_eq!(10, c.stats.all_stats[MANA].buf_equip_value);
+        assert_eq!(10, c.stats[StatKind::Mana].buf_equip_value);
         c.toggle_equipment("starting amulet", &testing_all_equipment());
         // eval that the starting amulet is not equipped
         let equip = c.inventory.get_equipped_equipments(
@@ -2271,12 +2708,12 @@ mod tests {
                 .any(|(_, equips)| equips.iter().any(|e| e.unique_name != "starting amulet"))
         );
         // eval mana update
-        assert_eq!(0, c.stats.all_stats[MANA].buf_equip_value);
+        assert_eq!(0, c.stats[StatKind::Mana].buf_equip_value);
         assert_eq!(
             210 - 10, // ratio = 1 because mana-current = mana-max
-            c.stats.all_stats[MANA].current
+            c.stats[StatKind::Mana].current
         );
-        assert_eq!(210 - 10, c.stats.all_stats[MANA].max);
+        assert_eq!(210 - 10, c.stats[StatKind::Mana].max);
 
         // toggle on
         c.toggle_equipment("starting amulet", &testing_all_equipment());
@@ -2294,15 +2731,15 @@ mod tests {
                 .any(|(_, equips)| equips.iter().any(|e| e.unique_name == "starting amulet"))
         );
         // eval mana update
-        assert_eq!(10, c.stats.all_stats[MANA].buf_equip_value);
+        assert_eq!(10, c.stats[StatKind::Mana].buf_equip_value);
         assert_eq!(
             210, // ratio = 1 because mana-current = mana-max
-            c.stats.all_stats[MANA].current
+            c.stats[StatKind::Mana].current
         );
-        assert_eq!(210, c.stats.all_stats[MANA].max);
+        assert_eq!(210, c.stats[StatKind::Mana].max);
 
         // effect still the same
-        assert_eq!(32, c.stats.all_stats[SPEED_REGEN].max);
+        assert_eq!(32, c.stats[StatKind::SpeedRegen].max);
     }
 
     #[test]
@@ -2340,22 +2777,32 @@ mod tests {
             ..Default::default()
         };
         assert!(
-            c.use_consumable(fake_consumable, &game_state, &launcher_stats)
-                .is_err()
+            c.use_consumable(
+                fake_consumable,
+                &game_state,
+                &launcher_stats,
+                &mut GameRng::default()
+            )
+            .is_err()
         );
 
         // success: add a small potion to inventory and use it
         c.inventory.add_small_potion();
-        let hp_before = c.stats.all_stats[HP].current;
+        let hp_before = c.stats[StatKind::Hp].current;
         // drain some HP first so the heal has room
-        c.stats.all_stats[HP].current = 10;
+        c.stats[StatKind::Hp].current = 10;
         let small_potion = c.inventory.consumables[0].clone();
-        let result = c.use_consumable(small_potion, &game_state, &launcher_stats);
+        let result = c.use_consumable(
+            small_potion,
+            &game_state,
+            &launcher_stats,
+            &mut GameRng::default(),
+        );
         assert!(result.is_ok());
         // potion should be removed from inventory
         assert!(c.inventory.consumables.is_empty());
         // HP should have increased
-        assert!(c.stats.all_stats[HP].current > 10);
+        assert!(c.stats[StatKind::Hp].current > 10);
         let _ = hp_before; // suppress unused warning
     }
 
@@ -2373,19 +2820,23 @@ mod tests {
         let launcher_stats = c.stats.clone();
 
         // apply_consumable_effects does NOT require the consumable to be in inventory
-        c.stats.all_stats[HP].current = 10;
+        c.stats[StatKind::Hp].current = 10;
         c.inventory.add_small_potion();
         let consumable = c.inventory.consumables[0].clone();
         // inventory should still have the potion (apply_consumable_effects doesn't remove it)
-        let result = c.apply_consumable_effects(&consumable, &game_state, &launcher_stats);
+        let result = c.apply_consumable_effects(
+            &consumable,
+            &game_state,
+            &launcher_stats,
+            &mut GameRng::default(),
+        );
         assert!(result.is_ok());
         assert!(!c.inventory.consumables.is_empty());
-        assert!(c.stats.all_stats[HP].current > 10);
+        assert!(c.stats[StatKind::Hp].current > 10);
     }
 
     #[test]
     fn unit_all_catalog_consumables_work_during_fight() {
-        use crate::common::constants::stats_const::{BERSERK, MANA, VIGOR};
         use crate::server::game_state::GameState;
         let mut c = Character::try_new_from_json(
             "./tests/offlines/characters/test.json",
@@ -2401,7 +2852,7 @@ mod tests {
         // Heal formula: base + physical_power (from launcher_stats). We check the HP increased
         // and that real_amount_tx is positive (exact value depends on equipped power stats).
         for name in ["potion", "super potion", "hyper potion"] {
-            c.stats.all_stats[HP].current = 0;
+            c.stats[StatKind::Hp].current = 0;
             match name {
                 "potion" => c.inventory.add_small_potion(),
                 "super potion" => c.inventory.add_super_potion(),
@@ -2409,10 +2860,15 @@ mod tests {
             }
             let potion = c.inventory.consumables.last().unwrap().clone();
             let result = c
-                .use_consumable(potion, &game_state, &launcher_stats)
+                .use_consumable(
+                    potion,
+                    &game_state,
+                    &launcher_stats,
+                    &mut GameRng::default(),
+                )
                 .unwrap();
             assert!(
-                c.stats.all_stats[HP].current > 0,
+                c.stats[StatKind::Hp].current > 0,
                 "HP should increase after {name}"
             );
             let real: i64 = result.iter().map(|e| e.real_amount_tx).sum();
@@ -2420,14 +2876,20 @@ mod tests {
         }
 
         // --- Resurrection potion ---
-        c.stats.all_stats[HP].current = 0;
+        c.stats[StatKind::Hp].current = 0;
         c.inventory.add_resurrection_potion();
         let res_potion = c.inventory.consumables.last().unwrap().clone();
         let result = c
-            .apply_consumable_effects(&res_potion, &game_state, &launcher_stats)
+            .apply_consumable_effects(
+                &res_potion,
+                &game_state,
+                &launcher_stats,
+                &mut GameRng::default(),
+            )
             .unwrap();
         assert_eq!(
-            c.stats.all_stats[HP].current, 50,
+            c.stats[StatKind::Hp].current,
+            50,
             "resurrection potion should restore exactly 50 HP"
         );
         let real: i64 = result.iter().map(|e| e.real_amount_tx).sum();
@@ -2437,17 +2899,23 @@ mod tests {
         );
 
         // --- Energy potions: drain stats first so there is room ---
-        c.stats.all_stats[MANA].current = 0;
-        c.stats.all_stats[VIGOR].current = 0;
-        c.stats.all_stats[BERSERK].current = 0;
+        c.stats[StatKind::Mana].current = 0;
+        c.stats[StatKind::Vigor].current = 0;
+        c.stats[StatKind::Berserk].current = 0;
 
         c.inventory.add_mana_potion();
         let mana_potion = c.inventory.consumables.last().unwrap().clone();
         let result = c
-            .use_consumable(mana_potion, &game_state, &launcher_stats)
+            .use_consumable(
+                mana_potion,
+                &game_state,
+                &launcher_stats,
+                &mut GameRng::default(),
+            )
             .unwrap();
         assert_eq!(
-            c.stats.all_stats[MANA].current, 30,
+            c.stats[StatKind::Mana].current,
+            30,
             "mana should increase by 30"
         );
         let real: i64 = result.iter().map(|e| e.real_amount_tx).sum();
@@ -2456,10 +2924,16 @@ mod tests {
         c.inventory.add_vigor_potion();
         let vigor_potion = c.inventory.consumables.last().unwrap().clone();
         let result = c
-            .use_consumable(vigor_potion, &game_state, &launcher_stats)
+            .use_consumable(
+                vigor_potion,
+                &game_state,
+                &launcher_stats,
+                &mut GameRng::default(),
+            )
             .unwrap();
         assert_eq!(
-            c.stats.all_stats[VIGOR].current, 30,
+            c.stats[StatKind::Vigor].current,
+            30,
             "vigor should increase by 30"
         );
         let real: i64 = result.iter().map(|e| e.real_amount_tx).sum();
@@ -2468,10 +2942,16 @@ mod tests {
         c.inventory.add_berserk_potion();
         let berserk_potion = c.inventory.consumables.last().unwrap().clone();
         let result = c
-            .use_consumable(berserk_potion, &game_state, &launcher_stats)
+            .use_consumable(
+                berserk_potion,
+                &game_state,
+                &launcher_stats,
+                &mut GameRng::default(),
+            )
             .unwrap();
         assert_eq!(
-            c.stats.all_stats[BERSERK].current, 30,
+            c.stats[StatKind::Berserk].current,
+            30,
             "berserk should increase by 30"
         );
         let real: i64 = result.iter().map(|e| e.real_amount_tx).sum();
@@ -2510,7 +2990,7 @@ mod tests {
             current_turn_nb: 1, // prev turn = 0, no damage → condition fails
             ..Default::default()
         };
-        let result = c.process_atk(&gs, false, &atk);
+        let result = c.process_atk(&gs, false, &atk, &mut GameRng::default());
         assert!(result.is_ok());
         // Only the ConditionDamagePrevTurn should be processed (0 applies), rest skipped
         let processed = result.unwrap();
@@ -2527,14 +3007,11 @@ mod tests {
         use crate::server::game_state::GameState;
         let mut c = testing_character();
         // Set up vigor energy so cost_per_apply > 0 hits the vigor branch
-        c.stats.all_stats.insert(
-            VIGOR.to_owned(),
-            crate::character_mod::stats::Attribute {
-                current: 30,
-                max: 100,
-                ..Default::default()
-            },
-        );
+        c.stats[StatKind::Vigor] = crate::character_mod::stats::Attribute {
+            current: 30,
+            max: 100,
+            ..Default::default()
+        };
         // Build an attack with RepeatAsManyAsPossible effect and vigor_cost=10
         let mut atk = crate::testing::testing_atk::build_atk_damage_indiv();
         atk.vigor_cost = 10;
@@ -2551,7 +3028,7 @@ mod tests {
         c.character_rounds_info.new_buffers();
         let gs = GameState::default();
         // process_atk should compute nb_applies from remaining/cost_per_apply
-        let result = c.process_atk(&gs, false, &atk);
+        let result = c.process_atk(&gs, false, &atk, &mut GameRng::default());
         assert!(
             result.is_ok(),
             "process_atk with RepeatAsManyAsPossible should succeed"
@@ -2566,8 +3043,6 @@ mod tests {
 
     #[test]
     fn unit_fleur_de_vie_condition_gate_fails() {
-        use crate::common::constants::all_target_const::TARGET_HIMSELF;
-        use crate::common::constants::reach_const::INDIVIDUAL;
         use crate::server::game_state::GameState;
 
         let mut c = testing_character();
@@ -2576,8 +3051,8 @@ mod tests {
 
         let cond_ep = EffectParam {
             nb_turns: 1,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ConditionDamagePrevTurn,
                 ..Default::default()
@@ -2586,8 +3061,8 @@ mod tests {
         };
         let multi_ep = EffectParam {
             nb_turns: 1,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::MultiValue,
                 value: 3,
@@ -2604,7 +3079,9 @@ mod tests {
             current_turn_nb: 1,
             ..Default::default()
         };
-        let result = c.process_atk(&gs, false, &atk).unwrap();
+        let result = c
+            .process_atk(&gs, false, &atk, &mut GameRng::default())
+            .unwrap();
         assert_eq!(
             result[0].number_of_applies, 0,
             "condition must fail (no damage prev turn)"
@@ -2626,8 +3103,6 @@ mod tests {
     #[test]
     fn unit_fleur_de_vie_condition_gate_passes_and_multiplier_set() {
         use crate::character_mod::rounds_information::AmountType;
-        use crate::common::constants::all_target_const::TARGET_HIMSELF;
-        use crate::common::constants::reach_const::INDIVIDUAL;
         use crate::server::game_state::GameState;
 
         let mut c = testing_character();
@@ -2639,8 +3114,8 @@ mod tests {
 
         let cond_ep = EffectParam {
             nb_turns: 1,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ConditionDamagePrevTurn,
                 ..Default::default()
@@ -2649,8 +3124,8 @@ mod tests {
         };
         let multi_ep = EffectParam {
             nb_turns: 1,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::MultiValue,
                 value: 3,
@@ -2666,7 +3141,9 @@ mod tests {
             current_turn_nb: 1,
             ..Default::default()
         };
-        let result = c.process_atk(&gs, false, &atk).unwrap();
+        let result = c
+            .process_atk(&gs, false, &atk, &mut GameRng::default())
+            .unwrap();
         assert_eq!(
             result[0].number_of_applies, 1,
             "condition must pass when damage was dealt prev turn"
@@ -2691,8 +3168,6 @@ mod tests {
         // stored in ProcessedEffectParam.heal_multiplier (not pre-baked into buffer.value)
         // so is_receiving_atk can apply it AFTER the power-scaled formula (value+pow)/nb_turns.
         use crate::character_mod::rounds_information::AmountType;
-        use crate::common::constants::all_target_const::TARGET_HIMSELF;
-        use crate::common::constants::reach_const::INDIVIDUAL;
         use crate::server::game_state::GameState;
 
         let mut c = testing_character();
@@ -2704,8 +3179,8 @@ mod tests {
 
         let cond_ep = EffectParam {
             nb_turns: 1,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ConditionDamagePrevTurn,
                 ..Default::default()
@@ -2714,8 +3189,8 @@ mod tests {
         };
         let multi_ep = EffectParam {
             nb_turns: 1,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::MultiValue,
                 value: 3,
@@ -2733,7 +3208,9 @@ mod tests {
             current_turn_nb: 1,
             ..Default::default()
         };
-        let result = c.process_atk(&gs, false, &atk).unwrap();
+        let result = c
+            .process_atk(&gs, false, &atk, &mut GameRng::default())
+            .unwrap();
 
         let heal_result = result
             .iter()
@@ -2754,8 +3231,6 @@ mod tests {
     #[test]
     fn unit_fleur_de_vie_multiplier_no_carry_when_condition_fails() {
         // When ConditionDamagePrevTurn fails, heal_multiplier must stay at default (1).
-        use crate::common::constants::all_target_const::TARGET_HIMSELF;
-        use crate::common::constants::reach_const::INDIVIDUAL;
         use crate::server::game_state::GameState;
 
         let mut c = testing_character();
@@ -2765,8 +3240,8 @@ mod tests {
 
         let cond_ep = EffectParam {
             nb_turns: 1,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ConditionDamagePrevTurn,
                 ..Default::default()
@@ -2775,8 +3250,8 @@ mod tests {
         };
         let multi_ep = EffectParam {
             nb_turns: 1,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::MultiValue,
                 value: 3,
@@ -2792,7 +3267,9 @@ mod tests {
             current_turn_nb: 1,
             ..Default::default()
         };
-        let result = c.process_atk(&gs, false, &atk).unwrap();
+        let result = c
+            .process_atk(&gs, false, &atk, &mut GameRng::default())
+            .unwrap();
 
         let heal_result = result
             .iter()
@@ -2807,21 +3284,18 @@ mod tests {
 
     #[test]
     fn unit_lame_fusionnelle_speed_regen_accumulation() {
-        use crate::common::constants::all_target_const::TARGET_HIMSELF;
-        use crate::common::constants::reach_const::INDIVIDUAL;
-
         let mut c = testing_character();
         let launcher_stats = c.stats.clone();
-        let base_max = c.stats.all_stats[SPEED_REGEN].max;
+        let base_max = c.stats[StatKind::SpeedRegen].max;
 
         let make_ep = || EffectParam {
             nb_turns: 6,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ChangeMaxStatByValue,
                 value: 10,
-                stats_name: SPEED_REGEN.to_owned(),
+                stats_name: Some(StatKind::SpeedRegen),
                 ..Default::default()
             },
             ..Default::default()
@@ -2836,23 +3310,23 @@ mod tests {
         let pep1 = make_processed();
         let eo1 = c.apply_processed_effect_param(&pep1, &launcher_stats, false, 0);
         assert_eq!(eo1.full_amount_tx, 10);
-        assert_eq!(c.stats.all_stats[SPEED_REGEN].max, base_max + 10);
+        assert_eq!(c.stats[StatKind::SpeedRegen].max, base_max + 10);
 
         // --- Second application (stacks) ---
         let pep2 = make_processed();
         let eo2 = c.apply_processed_effect_param(&pep2, &launcher_stats, false, 0);
         assert_eq!(eo2.full_amount_tx, 10);
-        assert_eq!(c.stats.all_stats[SPEED_REGEN].max, base_max + 20);
+        assert_eq!(c.stats[StatKind::SpeedRegen].max, base_max + 20);
 
         // --- Third application ---
         let eo3 = c.apply_processed_effect_param(&make_processed(), &launcher_stats, false, 0);
         assert_eq!(eo3.full_amount_tx, 10);
-        assert_eq!(c.stats.all_stats[SPEED_REGEN].max, base_max + 30);
+        assert_eq!(c.stats[StatKind::SpeedRegen].max, base_max + 30);
 
         // --- Revert first application (simulates effect expiry) ---
         c.remove_malus_effect(&make_ep()).unwrap();
         assert_eq!(
-            c.stats.all_stats[SPEED_REGEN].max,
+            c.stats[StatKind::SpeedRegen].max,
             base_max + 20,
             "first expiry must remove exactly one stack"
         );
@@ -2860,7 +3334,7 @@ mod tests {
         // --- Revert second application ---
         c.remove_malus_effect(&make_ep()).unwrap();
         assert_eq!(
-            c.stats.all_stats[SPEED_REGEN].max,
+            c.stats[StatKind::SpeedRegen].max,
             base_max + 10,
             "second expiry must remove one more stack"
         );
@@ -2868,7 +3342,8 @@ mod tests {
         // --- Revert third application → back to baseline ---
         c.remove_malus_effect(&make_ep()).unwrap();
         assert_eq!(
-            c.stats.all_stats[SPEED_REGEN].max, base_max,
+            c.stats[StatKind::SpeedRegen].max,
+            base_max,
             "all stacks expired: speed regen must return to base"
         );
     }
@@ -2880,11 +3355,13 @@ mod tests {
         let mut c = testing_character();
         // Berserker class → always gets STREAK_BREAKER_BERSERKER crit threshold
         c.class = Class::Berserker;
-        c.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        c.stats[StatKind::CriticalStrike].current = 0;
         c.character_rounds_info.crit_drought_counter = STREAK_BREAKER_BERSERKER;
         // use an atk name that exists in the character's attacks_list
         let atk = build_atk_damage_indiv();
-        let result = c.process_critical_strike(&atk.name).unwrap();
+        let result = c
+            .process_critical_strike(&atk.name, Difficulty::Normal, &mut GameRng::default())
+            .unwrap();
         assert!(
             result,
             "Berserker should get guaranteed crit at STREAK_BREAKER_BERSERKER threshold"
@@ -2904,20 +3381,20 @@ mod tests {
         // Install the passive buffer
         c.character_rounds_info.update_buffer(&Buffer {
             kind: BufKinds::OverHealBoostStat,
-            stats_name: PHYSICAL_POWER.to_owned(),
+            stats_name: Some(StatKind::PhysicalPower),
             is_passive_enabled: true,
             is_passive: true,
             value: 0,
             is_percent: false,
         });
 
-        let base_pp = c.stats.all_stats[PHYSICAL_POWER].current;
+        let base_pp = c.stats[StatKind::PhysicalPower].current;
 
         // Fire new_round on turn 1 — prev_turn = 0 has 50 overheal
-        c.new_round(1, vec![]);
+        c.new_round(1, vec![], &mut GameRng::default());
 
         assert_eq!(
-            c.stats.all_stats[PHYSICAL_POWER].current,
+            c.stats[StatKind::PhysicalPower].current,
             base_pp + 50,
             "Physical power should be boosted by the prev-turn overheal"
         );
@@ -2933,18 +3410,19 @@ mod tests {
 
         c.character_rounds_info.update_buffer(&Buffer {
             kind: BufKinds::OverHealBoostStat,
-            stats_name: PHYSICAL_POWER.to_owned(),
+            stats_name: Some(StatKind::PhysicalPower),
             is_passive_enabled: true,
             is_passive: true,
             value: 0,
             is_percent: false,
         });
 
-        let base_pp = c.stats.all_stats[PHYSICAL_POWER].current;
-        c.new_round(1, vec![]);
+        let base_pp = c.stats[StatKind::PhysicalPower].current;
+        c.new_round(1, vec![], &mut GameRng::default());
 
         assert_eq!(
-            c.stats.all_stats[PHYSICAL_POWER].current, base_pp,
+            c.stats[StatKind::PhysicalPower].current,
+            base_pp,
             "Physical power must not change when there was no overheal"
         );
     }
@@ -2960,18 +3438,19 @@ mod tests {
         // Passive present but disabled
         c.character_rounds_info.update_buffer(&Buffer {
             kind: BufKinds::OverHealBoostStat,
-            stats_name: PHYSICAL_POWER.to_owned(),
+            stats_name: Some(StatKind::PhysicalPower),
             is_passive_enabled: false,
             is_passive: true,
             value: 0,
             is_percent: false,
         });
 
-        let base_pp = c.stats.all_stats[PHYSICAL_POWER].current;
-        c.new_round(1, vec![]);
+        let base_pp = c.stats[StatKind::PhysicalPower].current;
+        c.new_round(1, vec![], &mut GameRng::default());
 
         assert_eq!(
-            c.stats.all_stats[PHYSICAL_POWER].current, base_pp,
+            c.stats[StatKind::PhysicalPower].current,
+            base_pp,
             "Disabled passive must not boost Physical power"
         );
     }
@@ -2988,19 +3467,19 @@ mod tests {
 
         c.character_rounds_info.update_buffer(&Buffer {
             kind: BufKinds::OverHealBoostStat,
-            stats_name: PHYSICAL_POWER.to_owned(),
+            stats_name: Some(StatKind::PhysicalPower),
             is_passive_enabled: true,
             is_passive: true,
             value: 0,
             is_percent: false,
         });
 
-        let base_pp = c.stats.all_stats[PHYSICAL_POWER].current; // 40
-        let base_max = c.stats.all_stats[PHYSICAL_POWER].max; // 40
-        c.new_round(1, vec![]);
+        let base_pp = c.stats[StatKind::PhysicalPower].current; // 40
+        let base_max = c.stats[StatKind::PhysicalPower].max; // 40
+        c.new_round(1, vec![], &mut GameRng::default());
 
         assert_eq!(
-            c.stats.all_stats[PHYSICAL_POWER].current,
+            c.stats[StatKind::PhysicalPower].current,
             base_pp + 30,
             "Passive boost must push current above max ({base_max})"
         );
@@ -3018,19 +3497,19 @@ mod tests {
         }
         c.character_rounds_info.update_buffer(&Buffer {
             kind: BufKinds::OverHealBoostStat,
-            stats_name: PHYSICAL_POWER.to_owned(),
+            stats_name: Some(StatKind::PhysicalPower),
             is_passive_enabled: true,
             is_passive: true,
             value: 0,
             is_percent: false,
         });
-        let base_pp = c.stats.all_stats[PHYSICAL_POWER].current;
+        let base_pp = c.stats[StatKind::PhysicalPower].current;
 
         // Turn 1 uses overheal from turn 0
         c.character_rounds_info.tx_rx[AmountType::OverHealRx as usize].insert(0, 50);
-        c.new_round(1, vec![]);
+        c.new_round(1, vec![], &mut GameRng::default());
         assert_eq!(
-            c.stats.all_stats[PHYSICAL_POWER].current,
+            c.stats[StatKind::PhysicalPower].current,
             base_pp + 50,
             "turn 1: bonus should be +50"
         );
@@ -3039,18 +3518,19 @@ mod tests {
         // players_manager::reset_is_first_round() does this between real game rounds
         c.character_rounds_info.is_first_round = true;
         c.character_rounds_info.tx_rx[AmountType::OverHealRx as usize].insert(1, 30);
-        c.new_round(2, vec![]);
+        c.new_round(2, vec![], &mut GameRng::default());
         assert_eq!(
-            c.stats.all_stats[PHYSICAL_POWER].current,
+            c.stats[StatKind::PhysicalPower].current,
             base_pp + 30,
             "turn 2: bonus must reset to +30, not accumulate to +80"
         );
 
         // Turn 3: no overheal on turn 2 → bonus fully gone
         c.character_rounds_info.is_first_round = true;
-        c.new_round(3, vec![]);
+        c.new_round(3, vec![], &mut GameRng::default());
         assert_eq!(
-            c.stats.all_stats[PHYSICAL_POWER].current, base_pp,
+            c.stats[StatKind::PhysicalPower].current,
+            base_pp,
             "turn 3: no overheal last turn, bonus must reset to zero"
         );
     }
@@ -3062,8 +3542,8 @@ mod tests {
         let launcher_stats = c.stats.clone();
 
         // HP already at max — any heal is pure overheal
-        let hp_max = c.stats.all_stats[HP].max;
-        c.stats.all_stats.get_mut(HP).unwrap().current = hp_max;
+        let hp_max = c.stats[StatKind::Hp].max;
+        c.stats[StatKind::Hp].current = hp_max;
 
         let heal_ep = build_hot_effect_individual(); // +30 HP, 1 apply
         c.apply_processed_effect_param(&heal_ep, &launcher_stats, false, 0);
@@ -3081,4 +3561,23 @@ mod tests {
             "Overheal from a heal attack must be recorded in tx_rx[OverHealRx], got 0"
         );
     }
+
+    #[test]
+    fn unit_class_passive_low_hp_damage_rx() {
+        let dm = testing_all_characters::testing_dm();
+        let warrior = dm
+            .class_passives
+            .iter()
+            .find(|p| p.class == Class::Warrior)
+            .unwrap();
+        let mut c = testing_character();
+        assert_eq!(c.apply_low_hp_damage_rx(-100), -100);
+
+        c.apply_class_passive(warrior);
+        c.stats[StatKind::Hp].max = 100;
+        c.stats[StatKind::Hp].current = 29;
+        assert_eq!(c.apply_low_hp_damage_rx(-100), -70);
+        c.stats[StatKind::Hp].current = 30;
+        assert_eq!(c.apply_low_hp_damage_rx(-100), -100);
+    }
 }
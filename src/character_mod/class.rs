@@ -1,18 +1,33 @@
 use std::str::FromStr;
 
-use crate::common::constants::emoji_const::{
-    EMOJI_BERSERK, EMOJI_HEALER, EMOJI_MAGE, EMOJI_WARRIOR,
+use crate::{
+    character_mod::effect::EffectParam,
+    common::constants::emoji_const::{EMOJI_BERSERK, EMOJI_HEALER, EMOJI_MAGE, EMOJI_WARRIOR},
 };
 
 /// Defines the class of the character
 /// In the future, bonus and stats will be acquired.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+/// A hero of a base class can choose one of its specialisations at `SPECIALISATION_LEVEL`.
+#[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 pub enum Class {
+    #[default]
     Standard,
     Berserker,
     Healer,
     Mage,
     Warrior,
+    /// Specialisations of Warrior
+    Paladin,
+    Guardian,
+    /// Specialisations of Mage
+    Pyromancer,
+    Necromancer,
+    /// Specialisations of Healer
+    Cleric,
+    Druid,
+    /// Specialisations of Berserker
+    Reaver,
+    Slayer,
 }
 
 impl FromStr for Class {
@@ -25,11 +40,30 @@ impl FromStr for Class {
             "Healer" => Ok(Class::Healer),
             "Mage" => Ok(Class::Mage),
             "Warrior" => Ok(Class::Warrior),
+            "Paladin" => Ok(Class::Paladin),
+            "Guardian" => Ok(Class::Guardian),
+            "Pyromancer" => Ok(Class::Pyromancer),
+            "Necromancer" => Ok(Class::Necromancer),
+            "Cleric" => Ok(Class::Cleric),
+            "Druid" => Ok(Class::Druid),
+            "Reaver" => Ok(Class::Reaver),
+            "Slayer" => Ok(Class::Slayer),
             _ => Err(format!("Unknown class: {}", s)),
         }
     }
 }
 
+/// Innate passive of a class, loaded from `offlines/class_passives.json`.
+/// The buffers of its effects are enabled as passive buffers of the heroes of the class.
+#[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct ClassPassive {
+    pub class: Class,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub effects: Vec<EffectParam>,
+}
+
 impl Class {
     pub fn to_str(&self) -> &str {
         match self {
@@ -38,18 +72,53 @@ impl Class {
             Class::Healer => "Healer",
             Class::Mage => "Mage",
             Class::Warrior => "Warrior",
+            Class::Paladin => "Paladin",
+            Class::Guardian => "Guardian",
+            Class::Pyromancer => "Pyromancer",
+            Class::Necromancer => "Necromancer",
+            Class::Cleric => "Cleric",
+            Class::Druid => "Druid",
+            Class::Reaver => "Reaver",
+            Class::Slayer => "Slayer",
         }
     }
 
     pub fn to_emoji(&self) -> &str {
         match self {
             Class::Standard => EMOJI_WARRIOR,
-            Class::Berserker => EMOJI_BERSERK,
-            Class::Healer => EMOJI_HEALER,
-            Class::Mage => EMOJI_MAGE,
-            Class::Warrior => EMOJI_WARRIOR,
+            Class::Berserker | Class::Reaver | Class::Slayer => EMOJI_BERSERK,
+            Class::Healer | Class::Cleric | Class::Druid => EMOJI_HEALER,
+            Class::Mage | Class::Pyromancer | Class::Necromancer => EMOJI_MAGE,
+            Class::Warrior | Class::Paladin | Class::Guardian => EMOJI_WARRIOR,
+        }
+    }
+
+    /// Base class of a specialisation, None for a base class
+    pub fn parent(&self) -> Option<Class> {
+        match self {
+            Class::Paladin | Class::Guardian => Some(Class::Warrior),
+            Class::Pyromancer | Class::Necromancer => Some(Class::Mage),
+            Class::Cleric | Class::Druid => Some(Class::Healer),
+            Class::Reaver | Class::Slayer => Some(Class::Berserker),
+            _ => None,
+        }
+    }
+
+    /// Specialisations which can be chosen by a hero of that class
+    pub fn specialisations(&self) -> Vec<Class> {
+        match self {
+            Class::Warrior => vec![Class::Paladin, Class::Guardian],
+            Class::Mage => vec![Class::Pyromancer, Class::Necromancer],
+            Class::Healer => vec![Class::Cleric, Class::Druid],
+            Class::Berserker => vec![Class::Reaver, Class::Slayer],
+            _ => vec![],
         }
     }
+
+    /// True if the class is `class` or one of its specialisations
+    pub fn is_a(&self, class: &Class) -> bool {
+        self == class || self.parent().as_ref() == Some(class)
+    }
 }
 
 #[cfg(test)]
@@ -83,4 +152,23 @@ mod tests {
         assert!(!Class::Mage.to_emoji().is_empty());
         assert!(!Class::Warrior.to_emoji().is_empty());
     }
+
+    #[test]
+    fn unit_specialisations() {
+        assert_eq!(
+            Class::Warrior.specialisations(),
+            [Class::Paladin, Class::Guardian]
+        );
+        assert!(Class::Standard.specialisations().is_empty());
+        assert!(Class::Paladin.specialisations().is_empty());
+        assert_eq!(Class::Pyromancer.parent(), Some(Class::Mage));
+        assert_eq!(Class::Mage.parent(), None);
+        assert_eq!(Class::from_str("Necromancer").unwrap(), Class::Necromancer);
+        assert_eq!(Class::Necromancer.to_emoji(), Class::Mage.to_emoji());
+
+        assert!(Class::Paladin.is_a(&Class::Warrior));
+        assert!(Class::Warrior.is_a(&Class::Warrior));
+        assert!(!Class::Warrior.is_a(&Class::Paladin));
+        assert!(!Class::Paladin.is_a(&Class::Guardian));
+    }
 }
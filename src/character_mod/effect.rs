@@ -2,15 +2,14 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    character_mod::buffers::{BufKinds, Buffer},
-    common::{
-        constants::{
-            all_target_const::TARGET_ALLY,
-            reach_const::{INDIVIDUAL, ZONE},
-            stats_const::HP,
-        },
-        log_data::LogData,
+    character_mod::{
+        ailment::AilmentKind,
+        buffers::{BufKinds, Buffer},
+        element::Effectiveness,
+        stats::StatKind,
+        target::{Reach, Target},
     },
+    common::{game_rng::GameRng, log_data::LogData},
 };
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -39,10 +38,10 @@ pub struct EffectParam {
     pub sub_value_effect: i64,
     /// target of the effect, ally or ennemy
     #[serde(rename = "Cible")]
-    pub target_kind: String,
+    pub target_kind: Target,
     /// reach of the effect, zone or individual
     #[serde(rename = "Portée")]
-    pub reach: String,
+    pub reach: Reach,
     /// from a magical attack ?or is magical effect ?
     #[serde(rename = "IsMagicEffect")]
     pub is_magic_atk: bool,
@@ -53,6 +52,9 @@ pub struct EffectParam {
     pub buffer: Buffer,
     #[serde(rename = "is_passive")]
     pub is_passive: bool,
+    /// Status ailment inflicted by an `InflictAilment` effect
+    #[serde(rename = "Ailment", skip_serializing_if = "Option::is_none")]
+    pub ailment: Option<AilmentKind>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -68,6 +70,9 @@ pub struct ProcessedEffectParam {
     /// Defaults to 1 (no multiplication). Set by process_all_effects when a MultiValue
     /// effect precedes a heal effect so the multiplier survives the launcher→target boundary.
     pub heal_multiplier: i64,
+    /// Type-effectiveness multiplier of the atk element on the target, applied on damages.
+    /// Set by is_receiving_atk for each target.
+    pub type_multiplier: f64,
 }
 
 impl Default for ProcessedEffectParam {
@@ -78,6 +83,7 @@ impl Default for ProcessedEffectParam {
             number_of_applies: 0,
             log: LogData::default(),
             heal_multiplier: 1,
+            type_multiplier: 1.0,
         }
     }
 }
@@ -96,6 +102,12 @@ pub struct EffectOutcome {
     pub aggro_generated: u64,
     /// Set to true when RemoveOneDebuf successfully removed a debuff.
     pub debuff_removed: bool,
+    /// Effectiveness of the atk element on the target (damages only).
+    pub effectiveness: Effectiveness,
+    /// Damage absorbed by the shields of the target before HP (positive).
+    pub absorbed_amount: i64,
+    /// AbsorbShield only: amount of damages the shield can still absorb.
+    pub shield_remaining: i64,
 }
 
 pub fn is_effet_hot_or_dot(buf_types: &BufKinds) -> bool {
@@ -109,8 +121,8 @@ pub fn is_effet_hot_or_dot(buf_types: &BufKinds) -> bool {
     effects_hot_or_dot.contains(buf_types)
 }
 
-pub fn is_hot(buf_types: &BufKinds, stats: &str, value: i64) -> bool {
-    is_effet_hot_or_dot(buf_types) && stats == HP && value > 0
+pub fn is_hot(buf_types: &BufKinds, stats: Option<StatKind>, value: i64) -> bool {
+    is_effet_hot_or_dot(buf_types) && stats == Some(StatKind::Hp) && value > 0
 }
 
 /// Returns true if the given effect represents a debuff (harmful to the character who has it).
@@ -155,7 +167,7 @@ pub fn is_effect_only_at_atk_launch(buf_types: &BufKinds) -> bool {
     effects.contains(buf_types)
 }
 
-pub fn process_decrease_on_turn(ep: &EffectParam, counter_turn: i64) -> i64 {
+pub fn process_decrease_on_turn(ep: &EffectParam, counter_turn: i64, rng: &mut GameRng) -> i64 {
     let total = ep.sub_value_effect;
     if total <= 0 {
         return 0;
@@ -167,7 +179,6 @@ pub fn process_decrease_on_turn(ep: &EffectParam, counter_turn: i64) -> i64 {
             return 0;
         }
         let threshold = ((total - counter_turn + 1) as f64 / total as f64 * 100.0).round() as i64;
-        let mut rng = rand::rng();
         return if rng.random_range(0..=100) <= threshold {
             1
         } else {
@@ -177,7 +188,6 @@ pub fn process_decrease_on_turn(ep: &EffectParam, counter_turn: i64) -> i64 {
     // Launch: cumulative applies — first roll always succeeds, each subsequent roll less likely.
     let mut nb_of_applies = 0;
     let mut counter = total;
-    let mut rng = rand::rng();
     while counter > 0 {
         let threshold = (counter as f64 / total as f64 * 100.0).round() as i64;
         if rng.random_range(0..=100) <= threshold {
@@ -190,15 +200,15 @@ pub fn process_decrease_on_turn(ep: &EffectParam, counter_turn: i64) -> i64 {
     nb_of_applies
 }
 
-pub fn build_energy_effect(stat_name: &str, value: i64) -> EffectParam {
+pub fn build_energy_effect(stat: StatKind, value: i64) -> EffectParam {
     EffectParam {
         nb_turns: 1,
-        target_kind: TARGET_ALLY.to_owned(),
-        reach: INDIVIDUAL.to_owned(),
+        target_kind: Target::Ally,
+        reach: Reach::Individual,
         buffer: Buffer {
             kind: BufKinds::ChangeCurrentStatByValue,
             value,
-            stats_name: stat_name.to_owned(),
+            stats_name: Some(stat),
             ..Default::default()
         },
         ..Default::default()
@@ -208,12 +218,12 @@ pub fn build_energy_effect(stat_name: &str, value: i64) -> EffectParam {
 pub fn build_resurrect_effect(value: i64) -> EffectParam {
     EffectParam {
         nb_turns: 1,
-        target_kind: TARGET_ALLY.to_owned(),
-        reach: INDIVIDUAL.to_owned(),
+        target_kind: Target::Ally,
+        reach: Reach::Individual,
         buffer: Buffer {
             kind: BufKinds::Resurrect,
             value,
-            stats_name: HP.to_owned(),
+            stats_name: Some(StatKind::Hp),
             ..Default::default()
         },
         ..Default::default()
@@ -223,16 +233,16 @@ pub fn build_resurrect_effect(value: i64) -> EffectParam {
 pub fn build_hp_effect(value: i64, is_zone: bool) -> EffectParam {
     EffectParam {
         nb_turns: 1,
-        target_kind: TARGET_ALLY.to_owned(),
+        target_kind: Target::Ally,
         reach: if is_zone {
-            ZONE.to_owned()
+            Reach::Zone
         } else {
-            INDIVIDUAL.to_owned()
+            Reach::Individual
         },
         buffer: Buffer {
             kind: BufKinds::ChangeCurrentStatByValue,
             value,
-            stats_name: HP.to_owned(),
+            stats_name: Some(StatKind::Hp),
             ..Default::default()
         },
         ..Default::default()
@@ -241,14 +251,6 @@ pub fn build_hp_effect(value: i64, is_zone: bool) -> EffectParam {
 
 #[cfg(test)]
 mod tests {
-    use crate::{
-        character_mod::target::is_target_ally,
-        common::constants::{
-            all_target_const::{TARGET_ALLY, TARGET_ENNEMY},
-            reach_const::ZONE,
-        },
-    };
-
     use super::*;
 
     #[test]
@@ -268,19 +270,20 @@ mod tests {
         let ep = EffectParam {
             nb_turns: 3,
             sub_value_effect: 3,
-            target_kind: TARGET_ENNEMY.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Enemy,
+            reach: Reach::Individual,
             is_magic_atk: false,
             conditions: vec![],
             buffer: Buffer {
                 kind: BufKinds::DecreasingRateOnTurn,
                 value: 3,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 ..Default::default()
             },
             is_passive: false,
+            ailment: None,
         };
-        let result = process_decrease_on_turn(&ep, 0);
+        let result = process_decrease_on_turn(&ep, 0, &mut GameRng::default());
         assert!((0..=3).contains(&result));
 
         // total <= 0: always returns 0
@@ -288,25 +291,33 @@ mod tests {
             sub_value_effect: 0,
             ..Default::default()
         };
-        assert_eq!(process_decrease_on_turn(&ep_zero, 0), 0);
-        assert_eq!(process_decrease_on_turn(&ep_zero, 1), 0);
+        assert_eq!(
+            process_decrease_on_turn(&ep_zero, 0, &mut GameRng::default()),
+            0
+        );
+        assert_eq!(
+            process_decrease_on_turn(&ep_zero, 1, &mut GameRng::default()),
+            0
+        );
 
         // counter_turn > total: returns 0
         let ep_small = EffectParam {
             sub_value_effect: 2,
             ..Default::default()
         };
-        assert_eq!(process_decrease_on_turn(&ep_small, 3), 0);
+        assert_eq!(
+            process_decrease_on_turn(&ep_small, 3, &mut GameRng::default()),
+            0
+        );
     }
 
     #[test]
     fn unit_build_energy_effect() {
-        use crate::common::constants::stats_const::MANA;
-        let ep = build_energy_effect(MANA, 30);
+        let ep = build_energy_effect(StatKind::Mana, 30);
         assert_eq!(ep.buffer.value, 30);
-        assert_eq!(ep.buffer.stats_name, MANA);
+        assert_eq!(ep.buffer.stats_name, Some(StatKind::Mana));
         assert_eq!(ep.nb_turns, 1);
-        assert_eq!(ep.target_kind, TARGET_ALLY);
+        assert_eq!(ep.target_kind, Target::Ally);
     }
 
     #[test]
@@ -314,7 +325,7 @@ mod tests {
         let ep = build_resurrect_effect(50);
         assert_eq!(ep.buffer.value, 50);
         assert_eq!(ep.buffer.kind, BufKinds::Resurrect);
-        assert_eq!(ep.target_kind, TARGET_ALLY);
+        assert_eq!(ep.target_kind, Target::Ally);
     }
 
     #[test]
@@ -322,7 +333,7 @@ mod tests {
         let ep = build_hp_effect(20, false);
         assert_eq!(ep.buffer.value, 20);
         let ep_zone = build_hp_effect(20, true);
-        assert_eq!(ep_zone.reach, ZONE);
+        assert_eq!(ep_zone.reach, Reach::Zone);
     }
 
     #[test]
@@ -333,21 +344,15 @@ mod tests {
         assert!(!is_effect_only_at_atk_launch(&BufKinds::DefaultBuf));
     }
 
-    #[test]
-    fn unit_is_target_ally() {
-        assert!(is_target_ally(TARGET_ALLY));
-        assert!(!is_target_ally("hehe"));
-    }
-
     #[test]
     fn unit_is_hot() {
-        let result = is_hot(&BufKinds::BlockHealAtk, HP, 0);
+        let result = is_hot(&BufKinds::BlockHealAtk, Some(StatKind::Hp), 0);
         assert!(!result);
-        let result = is_hot(&BufKinds::ChangeCurrentStatByValue, HP, 0);
+        let result = is_hot(&BufKinds::ChangeCurrentStatByValue, Some(StatKind::Hp), 0);
         assert!(!result);
-        let result = is_hot(&BufKinds::ChangeCurrentStatByValue, HP, 10);
+        let result = is_hot(&BufKinds::ChangeCurrentStatByValue, Some(StatKind::Hp), 10);
         assert!(result);
-        let result = is_hot(&BufKinds::ChangeCurrentStatByValue, HP, -10);
+        let result = is_hot(&BufKinds::ChangeCurrentStatByValue, Some(StatKind::Hp), -10);
         assert!(!result);
     }
 
@@ -0,0 +1,130 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::utils;
+
+/// Element (damage type) of an attack or of a character
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Element {
+    /// No element: always a normal effectiveness
+    #[default]
+    Neutral,
+    Normal,
+    Fire,
+    Water,
+    Grass,
+    Electric,
+    Ice,
+    Fighting,
+    Poison,
+    Flying,
+    Psychic,
+    Ghost,
+    Dragon,
+    Dark,
+}
+
+/// Effectiveness of an attack element on the elements of its target
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Effectiveness {
+    #[default]
+    Normal,
+    SuperEffective,
+    NotVeryEffective,
+    Immune,
+}
+
+impl Effectiveness {
+    pub fn from_multiplier(multiplier: f64) -> Effectiveness {
+        if multiplier <= 0.0 {
+            Effectiveness::Immune
+        } else if multiplier > 1.0 {
+            Effectiveness::SuperEffective
+        } else if multiplier < 1.0 {
+            Effectiveness::NotVeryEffective
+        } else {
+            Effectiveness::Normal
+        }
+    }
+
+    /// Message displayed in the logs, none for a normal effectiveness
+    pub fn message(&self) -> Option<&'static str> {
+        match self {
+            Effectiveness::Normal => None,
+            Effectiveness::SuperEffective => Some("It's super effective!"),
+            Effectiveness::NotVeryEffective => Some("It's not very effective..."),
+            Effectiveness::Immune => Some("It has no effect..."),
+        }
+    }
+}
+
+/// Type-effectiveness table: damage multiplier of an attack element against a target element.
+/// Missing pairs have a multiplier of 1.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeChart(pub HashMap<Element, HashMap<Element, f64>>);
+
+impl TypeChart {
+    /// Try to create a TypeChart from a JSON file
+    pub fn try_new_from_json<P: AsRef<Path>>(path: P) -> Result<TypeChart> {
+        utils::read_from_json::<_, TypeChart>(&path)
+            .map_err(|e| anyhow!("{:?} cannot be decoded: {}", path.as_ref(), e))
+    }
+
+    /// Damage multiplier of `atk_element` against a target with all the `target_elements`
+    pub fn multiplier(&self, atk_element: Element, target_elements: &[Element]) -> f64 {
+        let Some(row) = self.0.get(&atk_element) else {
+            return 1.0;
+        };
+        target_elements
+            .iter()
+            .map(|e| row.get(e).copied().unwrap_or(1.0))
+            .product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::constants::paths_const::{OFFLINE_TYPE_CHART, TEST_OFFLINE_ROOT};
+
+    #[test]
+    fn unit_effectiveness_from_multiplier() {
+        assert_eq!(Effectiveness::from_multiplier(0.0), Effectiveness::Immune);
+        assert_eq!(
+            Effectiveness::from_multiplier(0.5),
+            Effectiveness::NotVeryEffective
+        );
+        assert_eq!(Effectiveness::from_multiplier(1.0), Effectiveness::Normal);
+        assert_eq!(
+            Effectiveness::from_multiplier(4.0),
+            Effectiveness::SuperEffective
+        );
+        assert_eq!(Effectiveness::Normal.message(), None);
+        assert_eq!(
+            Effectiveness::SuperEffective.message(),
+            Some("It's super effective!")
+        );
+    }
+
+    #[test]
+    fn unit_type_chart_multiplier() {
+        let chart =
+            TypeChart::try_new_from_json(TEST_OFFLINE_ROOT.join(*OFFLINE_TYPE_CHART)).unwrap();
+        assert_eq!(chart.multiplier(Element::Fire, &[Element::Grass]), 2.0);
+        assert_eq!(chart.multiplier(Element::Fire, &[Element::Water]), 0.5);
+        assert_eq!(chart.multiplier(Element::Normal, &[Element::Ghost]), 0.0);
+        // dual elements are multiplied
+        assert_eq!(
+            chart.multiplier(Element::Fire, &[Element::Grass, Element::Water]),
+            1.0
+        );
+        // missing pairs, neutral attacks and targets without element
+        assert_eq!(chart.multiplier(Element::Fire, &[Element::Dark]), 1.0);
+        assert_eq!(chart.multiplier(Element::Neutral, &[Element::Grass]), 1.0);
+        assert_eq!(chart.multiplier(Element::Fire, &[]), 1.0);
+
+        assert!(TypeChart::try_new_from_json("./tests/offlines/unknown.json").is_err());
+    }
+}
@@ -3,7 +3,15 @@ use std::{fmt, path::Path};
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 
-use crate::{character_mod::stats::Stats, utils};
+use crate::{
+    character_mod::{
+        affix::{Affix, AffixKind, Rarity, roll_affixes},
+        rank::Rank,
+        stats::Stats,
+    },
+    common::game_rng::GameRng,
+    utils,
+};
 use strum_macros::EnumIter;
 
 /// Define the parameters of an equipment.
@@ -22,6 +30,15 @@ pub struct Equipment {
     /// Stats of the equipment
     #[serde(rename = "Stats")]
     pub stats: Stats,
+    /// Rank of the equipment, used to roll the loots of the generated scenarios
+    #[serde(rename = "Rang")]
+    pub rank: Rank,
+    /// Rarity of a rolled instance, templates loaded from JSON are common
+    #[serde(rename = "Rarete", default)]
+    pub rarity: Rarity,
+    /// Random affixes of a rolled instance, already counted in its stats
+    #[serde(rename = "Affixes", default)]
+    pub affixes: Vec<Affix>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Default, EnumIter)]
@@ -94,13 +111,40 @@ impl Equipment {
             ))
         }
     }
+
+    /// Roll a new instance of this template: a rarity according to its rank and the affixes of that rarity
+    pub fn roll(&self, rng: &mut GameRng) -> Equipment {
+        let rarity = Rarity::roll(&self.rank, rng);
+        let affixes = roll_affixes(&rarity, rng);
+        self.with_affixes(rarity, &affixes)
+    }
+
+    /// Copy of this template with a rarity and affixes, the affixes are added to its stats
+    pub fn with_affixes(&self, rarity: Rarity, affixes: &[Affix]) -> Equipment {
+        let mut equipment = self.clone();
+        for affix in affixes {
+            affix.apply_on_stats(&mut equipment.stats);
+        }
+        equipment.rarity = rarity;
+        equipment.affixes = affixes.to_vec();
+        equipment
+    }
+
+    /// Sum of the lifesteal percents of the affixes
+    pub fn lifesteal_percent(&self) -> i64 {
+        self.affixes
+            .iter()
+            .filter(|a| a.kind == AffixKind::Lifesteal)
+            .map(|a| a.value)
+            .sum()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::common::constants::stats_const::*;
 
     use super::*;
+    use crate::character_mod::stats::StatKind;
 
     #[test]
     fn unit_equipment_json_key_display() {
@@ -143,15 +187,49 @@ mod tests {
         assert_eq!(equipment.unique_name, "starting right ring");
         // stats
         // stats - aggro
-        assert_eq!(0, equipment.stats.all_stats[AGGRO].buf_equip_percent);
-        assert_eq!(0, equipment.stats.all_stats[AGGRO].buf_equip_value);
+        assert_eq!(0, equipment.stats[StatKind::Aggro].buf_equip_percent);
+        assert_eq!(0, equipment.stats[StatKind::Aggro].buf_equip_value);
         // berserk rate
-        assert_eq!(10, equipment.stats.all_stats[VIGOR].buf_equip_value);
-        assert_eq!(0, equipment.stats.all_stats[VIGOR].buf_equip_percent);
+        assert_eq!(10, equipment.stats[StatKind::Vigor].buf_equip_value);
+        assert_eq!(0, equipment.stats[StatKind::Vigor].buf_equip_percent);
 
         // wrong file
         let file_path = "./hehe.json"; // Path to the JSON file
         let equipment = Equipment::try_new_from_json(file_path);
         assert!(equipment.is_err());
     }
+
+    #[test]
+    fn unit_roll_equipment() {
+        let file_path = "./tests/offlines/equipment/body/RightRing/starting_right_ring.json";
+        let template = Equipment::try_new_from_json(file_path).unwrap();
+        assert_eq!(template.rarity, Rarity::Common);
+        assert!(template.affixes.is_empty());
+
+        let mut rng = GameRng::new(3);
+        let rolled = (0..50)
+            .map(|_| template.roll(&mut rng))
+            .find(|e| e.rarity > Rarity::Common)
+            .unwrap();
+        assert_eq!(rolled.unique_name, template.unique_name);
+        assert_eq!(rolled.affixes.len(), rolled.rarity.nb_affixes());
+
+        let affixes = [
+            Affix {
+                kind: AffixKind::Stat {
+                    stat: StatKind::Vigor,
+                    is_percent: false,
+                },
+                value: 5,
+            },
+            Affix {
+                kind: AffixKind::Lifesteal,
+                value: 4,
+            },
+        ];
+        let rolled = template.with_affixes(Rarity::Rare, &affixes);
+        assert_eq!(15, rolled.stats[StatKind::Vigor].buf_equip_value);
+        assert_eq!(4, rolled.lifesteal_percent());
+        assert_eq!(0, template.lifesteal_percent());
+    }
 }
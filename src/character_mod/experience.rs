@@ -11,13 +11,18 @@ pub fn build_experience(rank: &Rank, level: u64) -> u64 {
 
 /// Class multiplier for exp required to reach the next level.
 /// Returns (numerator, denominator) for integer arithmetic.
-/// Standard/Berserker = ×1.0, Warrior = ×1.2, Healer = ×1.3, Mage = ×1.5
+/// Standard/Berserker = ×1.0, Warrior = ×1.2, Healer = ×1.3, Mage = ×1.5,
+/// a specialisation = ×0.1 more than its base class
 fn class_exp_factor(class: &Class) -> (u64, u64) {
     match class {
         Class::Standard | Class::Berserker => (10, 10),
         Class::Warrior => (12, 10),
         Class::Healer => (13, 10),
         Class::Mage => (15, 10),
+        Class::Reaver | Class::Slayer => (11, 10),
+        Class::Paladin | Class::Guardian => (13, 10),
+        Class::Cleric | Class::Druid => (14, 10),
+        Class::Pyromancer | Class::Necromancer => (16, 10),
     }
 }
 
@@ -77,6 +82,10 @@ mod tests {
             130
         );
         assert_eq!(build_exp_to_next_level(&Rank::Common, &Class::Mage, 1), 150);
+        assert_eq!(
+            build_exp_to_next_level(&Rank::Common, &Class::Pyromancer, 1),
+            160
+        );
     }
 
     #[test]
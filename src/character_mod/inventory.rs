@@ -1,15 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::{Result, bail};
 use strum::IntoEnumIterator;
 
-use crate::{
-    character_mod::{
-        effect::{EffectParam, build_energy_effect, build_hp_effect, build_resurrect_effect},
-        equipment::{Equipment, EquipmentJsonKey},
-        rank::Rank,
-    },
-    common::constants::stats_const::{BERSERK, MANA, VIGOR},
+use crate::character_mod::{
+    affix::{Affix, AffixKind, Rarity},
+    effect::{EffectParam, build_energy_effect, build_hp_effect, build_resurrect_effect},
+    equipment::{Equipment, EquipmentJsonKey},
+    rank::Rank,
+    stats::StatKind,
 };
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
@@ -20,6 +19,8 @@ pub struct Inventory {
     pub equipments: HashMap<EquipmentJsonKey, Vec<EquipmentInventory>>,
     pub consumables: Vec<Consumable>,
     pub money: u64,
+    /// Last id given to an equipment instance, ids start at 1
+    pub last_equipment_id: u64,
 }
 
 impl Default for Inventory {
@@ -29,6 +30,7 @@ impl Default for Inventory {
             limits: Vec::new(),
             consumables: Vec::new(),
             money: 0,
+            last_equipment_id: 0,
         };
         inventory.set_default_limits();
         inventory
@@ -45,11 +47,18 @@ pub struct EquipmentLimit {
 #[derive(Default, Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
 #[serde(default)]
 pub struct EquipmentInventory {
+    /// Id of the instance in the inventory, given on load when missing in the file
+    pub id: u64,
+    /// Unique name of the equipment template
     pub unique_name: String,
     pub is_equipped: bool,
     /// `true` while the player has not yet viewed this item in the inventory tab
     #[serde(default)]
     pub is_new: bool,
+    /// Rarity rolled for this instance
+    pub rarity: Rarity,
+    /// Affixes rolled for this instance
+    pub affixes: Vec<Affix>,
 }
 
 /// Inventory of a character, contains the equipments and consumables of the character
@@ -128,7 +137,7 @@ impl Inventory {
     pub fn add_mana_potion(&mut self) {
         self.consumables.push(Consumable {
             name: "mana potion".to_owned(),
-            effects: vec![build_energy_effect(MANA, 30)],
+            effects: vec![build_energy_effect(StatKind::Mana, 30)],
             consumable_kind: ConsumableKind::Potion,
             rank: Rank::Common,
         });
@@ -137,7 +146,7 @@ impl Inventory {
     pub fn add_vigor_potion(&mut self) {
         self.consumables.push(Consumable {
             name: "vigor potion".to_owned(),
-            effects: vec![build_energy_effect(VIGOR, 30)],
+            effects: vec![build_energy_effect(StatKind::Vigor, 30)],
             consumable_kind: ConsumableKind::Potion,
             rank: Rank::Common,
         });
@@ -146,7 +155,7 @@ impl Inventory {
     pub fn add_berserk_potion(&mut self) {
         self.consumables.push(Consumable {
             name: "berserk potion".to_owned(),
-            effects: vec![build_energy_effect(BERSERK, 30)],
+            effects: vec![build_energy_effect(StatKind::Berserk, 30)],
             consumable_kind: ConsumableKind::Potion,
             rank: Rank::Common,
         });
@@ -161,15 +170,41 @@ impl Inventory {
         self.consumables.iter().any(|c| c.name == name)
     }
 
-    pub fn add_equipment(&mut self, equipment: &Equipment, is_equipped: bool) {
+    /// Add an instance of the equipment, with its rarity and affixes, and returns its new id
+    pub fn add_equipment(&mut self, equipment: &Equipment, is_equipped: bool) -> u64 {
+        self.last_equipment_id += 1;
         self.equipments
             .entry(equipment.category.clone())
             .or_default()
             .push(EquipmentInventory {
+                id: self.last_equipment_id,
                 unique_name: equipment.unique_name.clone(),
                 is_equipped,
                 is_new: true,
+                rarity: equipment.rarity,
+                affixes: equipment.affixes.clone(),
             });
+        self.last_equipment_id
+    }
+
+    /// Give a new id to the instances without one (id 0) or sharing the id of another instance,
+    /// e.g. the starting items of the offline files. Categories are walked in a fixed order.
+    pub fn assign_equipment_ids(&mut self) {
+        let max_id = self.equipments.values().flatten().map(|e| e.id).max();
+        self.last_equipment_id = self.last_equipment_id.max(max_id.unwrap_or(0));
+        let mut seen = HashSet::new();
+        for category in EquipmentJsonKey::iter() {
+            let Some(items) = self.equipments.get_mut(&category) else {
+                continue;
+            };
+            for item in items.iter_mut() {
+                if item.id == 0 || !seen.insert(item.id) {
+                    self.last_equipment_id += 1;
+                    item.id = self.last_equipment_id;
+                    seen.insert(item.id);
+                }
+            }
+        }
     }
 
     /// Clear the `is_new` flag on all equipments in the given category so the
@@ -200,13 +235,22 @@ impl Inventory {
                 .map(|unique_names| {
                     unique_names
                         .iter()
+                        .filter(|equipment_inventory| {
+                            !is_equipped_filter || equipment_inventory.is_equipped
+                        })
                         .filter_map(|equipment_inventory| {
-                            all_equipments.iter().find(|equipment| {
-                                (!is_equipped_filter || equipment_inventory.is_equipped)
-                                    && equipment.unique_name == equipment_inventory.unique_name
-                            })
+                            all_equipments
+                                .iter()
+                                .find(|equipment| {
+                                    equipment.unique_name == equipment_inventory.unique_name
+                                })
+                                .map(|equipment| {
+                                    equipment.with_affixes(
+                                        equipment_inventory.rarity,
+                                        &equipment_inventory.affixes,
+                                    )
+                                })
                         })
-                        .cloned()
                         .collect::<Vec<Equipment>>()
                 })
                 .unwrap_or_default();
@@ -241,7 +285,7 @@ impl Inventory {
 
     pub fn sum_all_equipped_equipment_stat(
         &self,
-        stat_name: &str,
+        kind: StatKind,
         list_equipments: &[Equipment],
     ) -> (i64, i64) {
         self.get_equipped_equipments(list_equipments)
@@ -251,66 +295,98 @@ impl Inventory {
             .collect::<Vec<Equipment>>()
             .iter()
             .map(|equipment| {
-                equipment
-                    .stats
-                    .all_stats
-                    .get(stat_name)
-                    .map(|attr| (attr.buf_equip_value, attr.buf_equip_percent))
-                    .unwrap_or((0, 0))
+                let attr = &equipment.stats[kind];
+                (attr.buf_equip_value, attr.buf_equip_percent)
             })
             .fold((0, 0), |acc, x| (acc.0 + x.0, acc.1 + x.1))
     }
 
+    /// Sum of the lifesteal percents of the affixes of the equipped instances
+    pub fn lifesteal_percent(&self) -> i64 {
+        self.equipments
+            .values()
+            .flatten()
+            .filter(|e| e.is_equipped)
+            .flat_map(|e| e.affixes.iter())
+            .filter(|a| a.kind == AffixKind::Lifesteal)
+            .map(|a| a.value)
+            .sum()
+    }
+
+    /// Category and index of the instance `id` in its category
+    fn find_equipment_by_id(&self, id: u64) -> Option<(EquipmentJsonKey, usize)> {
+        self.equipments.iter().find_map(|(category, items)| {
+            items
+                .iter()
+                .position(|e| e.id == id)
+                .map(|idx| (category.clone(), idx))
+        })
+    }
+
+    /// Toggle the first instance of `equipment_unique_name`
     pub fn toggle_equipment(&mut self, equipment_unique_name: &str) {
         if equipment_unique_name.is_empty() {
             return;
         }
 
         let category = self.get_category(equipment_unique_name);
-        let limit = self.get_limit_for_category(&category);
+        match self.equipments.get(&category).and_then(|equipments| {
+            equipments
+                .iter()
+                .position(|e| e.unique_name == equipment_unique_name)
+        }) {
+            Some(index) => self.toggle_equipment_at(&category, index),
+            None => tracing::error!(
+                "Equipment with unique name '{}' not found in inventory",
+                equipment_unique_name
+            ),
+        }
+    }
+
+    /// Toggle the instance `id`, e.g. when several instances of a template have different affixes
+    pub fn toggle_equipment_by_id(&mut self, id: u64) -> Result<()> {
+        let (category, index) = self
+            .find_equipment_by_id(id)
+            .ok_or_else(|| anyhow::anyhow!("Equipment #{} not in inventory", id))?;
+        self.toggle_equipment_at(&category, index);
+        Ok(())
+    }
+
+    fn toggle_equipment_at(&mut self, category: &EquipmentJsonKey, index_to_toggle: usize) {
+        let limit = self.get_limit_for_category(category);
 
         // Count how many are equipped in the category
         let nb_equipped_in_category = self
             .equipments
-            .get(&category)
+            .get(category)
             .map(|equipments| equipments.iter().filter(|e| e.is_equipped).count())
             .unwrap_or(0);
 
         // Get mutable access to the equipments in the category
-        if let Some(equipments_in_category) = self.equipments.get_mut(&category) {
-            // Find the index of the equipment to toggle
-            if let Some(index_to_toggle) = equipments_in_category
-                .iter()
-                .position(|e| e.unique_name == equipment_unique_name)
-            {
-                if equipments_in_category[index_to_toggle].is_equipped {
-                    // If already equipped, just unequip it
-                    equipments_in_category[index_to_toggle].is_equipped = false;
-                } else if nb_equipped_in_category < limit {
-                    // If not equipped and under limit, equip it
+        if let Some(equipments_in_category) = self.equipments.get_mut(category) {
+            let toggled = &equipments_in_category[index_to_toggle];
+            let equipment_name = format!("{} #{}", toggled.unique_name, toggled.id);
+            if toggled.is_equipped {
+                // If already equipped, just unequip it
+                equipments_in_category[index_to_toggle].is_equipped = false;
+            } else if nb_equipped_in_category < limit {
+                // If not equipped and under limit, equip it
+                equipments_in_category[index_to_toggle].is_equipped = true;
+            } else {
+                // If not equipped and at limit, unequip the first equipped in the category
+                // (the one we want to equip is not equipped)
+                if let Some(index_to_unequip) =
+                    equipments_in_category.iter().position(|e| e.is_equipped)
+                {
+                    equipments_in_category[index_to_unequip].is_equipped = false;
                     equipments_in_category[index_to_toggle].is_equipped = true;
                 } else {
-                    // If not equipped and at limit, unequip the first equipped in the category
-                    // (except the one we want to equip)
-                    if let Some(index_to_unequip) = equipments_in_category
-                        .iter()
-                        .position(|e| e.is_equipped && e.unique_name != equipment_unique_name)
-                    {
-                        equipments_in_category[index_to_unequip].is_equipped = false;
-                        equipments_in_category[index_to_toggle].is_equipped = true;
-                    } else {
-                        tracing::error!(
-                            "No equipment to unequip in category '{}' to make room for '{}'",
-                            category,
-                            equipment_unique_name
-                        );
-                    }
+                    tracing::error!(
+                        "No equipment to unequip in category '{}' to make room for '{}'",
+                        category,
+                        equipment_name
+                    );
                 }
-            } else {
-                tracing::error!(
-                    "Equipment with unique name '{}' not found in inventory",
-                    equipment_unique_name
-                );
             }
         }
     }
@@ -382,6 +458,24 @@ impl Inventory {
         }
     }
 
+    /// Sell the unequipped instance `id`: removes it from the bag and adds `refund`.
+    pub fn sell_equipment_by_id(&mut self, id: u64, refund: u64) -> Result<()> {
+        let (category, idx) = self
+            .find_equipment_by_id(id)
+            .ok_or_else(|| anyhow::anyhow!("Equipment #{} not in inventory", id))?;
+        let items = self.equipments.get_mut(&category).unwrap();
+        if items[idx].is_equipped {
+            bail!(
+                "Cannot sell equipped item '{}' #{}; unequip it first",
+                items[idx].unique_name,
+                id
+            );
+        }
+        items.remove(idx);
+        self.money += refund;
+        Ok(())
+    }
+
     fn get_category(&self, equipment_unique_name: &str) -> EquipmentJsonKey {
         self.equipments
             .iter()
@@ -408,14 +502,13 @@ impl Inventory {
 mod tests {
     use crate::{
         character_mod::{
+            affix::{Affix, AffixKind, Rarity},
             character::Character,
             equipment::{Equipment, EquipmentJsonKey},
             inventory::Inventory,
+            stats::StatKind,
         },
-        common::constants::{
-            paths_const::TEST_OFFLINE_ROOT,
-            stats_const::{HP, PHYSICAL_POWER},
-        },
+        common::constants::paths_const::TEST_OFFLINE_ROOT,
         testing::testing_all_characters::testing_all_equipment,
     };
 
@@ -442,12 +535,14 @@ mod tests {
             unique_name: "sword_of_testing".to_owned(),
             category: EquipmentJsonKey::LeftWeapon,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
         };
         let equipment2 = Equipment {
             name: "Shield of Testing".to_owned(),
             unique_name: "shield_of_testing".to_owned(),
             category: EquipmentJsonKey::Chest,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
         };
         inventory.add_equipment(&equipment1, true);
         inventory.add_equipment(&equipment2, false);
@@ -499,34 +594,32 @@ mod tests {
             unique_name: "helmet_of_testing".to_owned(),
             category: EquipmentJsonKey::Head,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
+        };
+        equipment1.stats[StatKind::Hp] = crate::character_mod::stats::Attribute {
+            buf_equip_value: 10,
+            buf_equip_percent: 10,
+            ..Default::default()
         };
-        equipment1.stats.all_stats.insert(
-            HP.to_owned(),
-            crate::character_mod::stats::Attribute {
-                buf_equip_value: 10,
-                buf_equip_percent: 10,
-                ..Default::default()
-            },
-        );
         let mut equipment2 = Equipment {
             name: "Armor of Testing".to_owned(),
             unique_name: "armor_of_testing".to_owned(),
             category: EquipmentJsonKey::Chest,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
+        };
+        equipment2.stats[StatKind::Hp] = crate::character_mod::stats::Attribute {
+            buf_equip_value: 20,
+            buf_equip_percent: 20,
+            ..Default::default()
         };
-        equipment2.stats.all_stats.insert(
-            HP.to_owned(),
-            crate::character_mod::stats::Attribute {
-                buf_equip_value: 20,
-                buf_equip_percent: 20,
-                ..Default::default()
-            },
-        );
         inventory.add_equipment(&equipment1, true);
         inventory.add_equipment(&equipment2, true);
         assert_eq!(
-            inventory
-                .sum_all_equipped_equipment_stat(HP, &[equipment1.clone(), equipment2.clone()]),
+            inventory.sum_all_equipped_equipment_stat(
+                StatKind::Hp,
+                &[equipment1.clone(), equipment2.clone()]
+            ),
             (30, 30)
         );
 
@@ -539,7 +632,7 @@ mod tests {
         )
         .unwrap();
         c.inventory.sum_all_equipped_equipment_stat(
-            PHYSICAL_POWER,
+            StatKind::PhysicalPower,
             &testing_all_equipment()
                 .values()
                 .flatten()
@@ -548,7 +641,7 @@ mod tests {
         );
         assert_eq!(
             c.inventory.sum_all_equipped_equipment_stat(
-                PHYSICAL_POWER,
+                StatKind::PhysicalPower,
                 &testing_all_equipment()
                     .values()
                     .flatten()
@@ -567,6 +660,7 @@ mod tests {
             unique_name: "Amulet".to_owned(),
             category: EquipmentJsonKey::Amulet,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
         };
         inventory.add_equipment(&equipment1, true);
         let all_equipments = vec![equipment1.clone()];
@@ -586,6 +680,7 @@ mod tests {
             unique_name: "Boots".to_owned(),
             category: EquipmentJsonKey::Shoes,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
         };
         inventory.add_equipment(&equipment1, false);
         assert!(!inventory.equipments[&EquipmentJsonKey::Shoes][0].is_equipped);
@@ -617,6 +712,7 @@ mod tests {
             unique_name: "test_ring".to_owned(),
             category: EquipmentJsonKey::RightRing,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
         };
         inventory.add_equipment(&equipment, false);
         assert!(inventory.has_unseen_equipment());
@@ -636,6 +732,7 @@ mod tests {
             unique_name: "test_sword".to_owned(),
             category: EquipmentJsonKey::LeftWeapon,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
         }
     }
 
@@ -769,24 +866,28 @@ mod tests {
             unique_name: "Tattoes1".to_owned(),
             category: EquipmentJsonKey::Tattoes,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
         };
         let equipment2 = Equipment {
             name: "Tattoes of Testing 2".to_owned(),
             unique_name: "Tattoes2".to_owned(),
             category: EquipmentJsonKey::Tattoes,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
         };
         let equipment3 = Equipment {
             name: "Tattoes of Testing 3".to_owned(),
             unique_name: "Tattoes3".to_owned(),
             category: EquipmentJsonKey::Tattoes,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
         };
         let equipment4 = Equipment {
             name: "Tattoes of Testing 4".to_owned(),
             unique_name: "Tattoes4".to_owned(),
             category: EquipmentJsonKey::Tattoes,
             stats: crate::character_mod::stats::Stats::default(),
+            ..Default::default()
         };
         inventory.add_equipment(&equipment1, false);
         inventory.add_equipment(&equipment2, false);
@@ -805,4 +906,92 @@ mod tests {
         assert!(inventory.equipments[&EquipmentJsonKey::Tattoes][2].is_equipped);
         assert!(inventory.equipments[&EquipmentJsonKey::Tattoes][3].is_equipped);
     }
+
+    #[test]
+    fn unit_equipment_instances() {
+        let mut inventory = Inventory::default();
+        let template = make_sword();
+        let lifesteal = Affix {
+            kind: AffixKind::Lifesteal,
+            value: 5,
+        };
+        let rolled = template.with_affixes(Rarity::Uncommon, std::slice::from_ref(&lifesteal));
+        let id1 = inventory.add_equipment(&template, false);
+        let id2 = inventory.add_equipment(&rolled, false);
+        assert_eq!((id1, id2), (1, 2));
+
+        // the instance keeps its own rarity and affixes
+        inventory.toggle_equipment_by_id(id2).unwrap();
+        let equipped = inventory.get_equipped_equipments(std::slice::from_ref(&template));
+        let equipped: Vec<&Equipment> = equipped.values().flatten().collect();
+        assert_eq!(equipped.len(), 1);
+        assert_eq!(equipped[0].rarity, Rarity::Uncommon);
+        assert_eq!(equipped[0].affixes, vec![lifesteal]);
+        assert_eq!(inventory.lifesteal_percent(), 5);
+        assert!(inventory.toggle_equipment_by_id(42).is_err());
+
+        // sell by id
+        assert!(inventory.sell_equipment_by_id(id2, 10).is_err());
+        inventory.sell_equipment_by_id(id1, 10).unwrap();
+        assert_eq!(inventory.money, 10);
+        let items = inventory.equipments.values().flatten().collect::<Vec<_>>();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, id2);
+        assert_eq!(inventory.add_equipment(&template, false), 3);
+    }
+
+    #[test]
+    fn unit_assign_equipment_ids_on_json_load() {
+        let mut c = Character::try_new_from_json(
+            "./tests/offlines/characters/test.json",
+            *TEST_OFFLINE_ROOT,
+            false,
+            &testing_all_equipment(),
+        )
+        .unwrap();
+        let mut ids = c
+            .inventory
+            .equipments
+            .values()
+            .flatten()
+            .map(|e| e.id)
+            .collect::<Vec<u64>>();
+        ids.sort();
+        assert_eq!(ids, (1..=13).collect::<Vec<u64>>());
+        assert_eq!(c.inventory.last_equipment_id, 13);
+
+        // each id targets one instance
+        let (category, item) = c
+            .inventory
+            .equipments
+            .iter()
+            .find_map(|(category, items)| {
+                items
+                    .iter()
+                    .find(|e| e.id == 1)
+                    .map(|e| (category.clone(), e.clone()))
+            })
+            .unwrap();
+        c.inventory.toggle_equipment_by_id(1).unwrap();
+        let toggled = c.inventory.equipments[&category]
+            .iter()
+            .find(|e| e.id == 1)
+            .unwrap();
+        assert_eq!(toggled.is_equipped, !item.is_equipped);
+
+        // ids already unique are kept, duplicates get a new one
+        c.inventory.equipments.get_mut(&category).unwrap()[0].id = 2;
+        c.inventory.assign_equipment_ids();
+        let mut ids = c
+            .inventory
+            .equipments
+            .values()
+            .flatten()
+            .map(|e| e.id)
+            .collect::<Vec<u64>>();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 13);
+        assert_eq!(c.inventory.last_equipment_id, 14);
+    }
 }
@@ -0,0 +1,139 @@
+use anyhow::{Result, bail};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::character_mod::{attack_type::AttackType, class::Class};
+
+/// Attacks known by a hero and the ones equipped in its limited slots
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Loadout {
+    /// Number of attack slots, 0 without loadout: all the attacks of the level can be launched
+    #[serde(default)]
+    pub nb_slots: usize,
+    /// Attacks learnt at a level-up, from a trainer or from a scroll
+    #[serde(default)]
+    pub known_atks: Vec<String>,
+    /// Attacks which can be launched in a fight, at most `nb_slots`
+    #[serde(default)]
+    pub equipped_atks: Vec<String>,
+}
+
+impl Loadout {
+    pub fn is_enabled(&self) -> bool {
+        self.nb_slots > 0
+    }
+
+    pub fn is_known(&self, atk_name: &str) -> bool {
+        self.known_atks.iter().any(|a| a == atk_name)
+    }
+
+    pub fn is_equipped(&self, atk_name: &str) -> bool {
+        self.equipped_atks.iter().any(|a| a == atk_name)
+    }
+
+    /// Learn an attack, equipped at once if a slot is free.
+    /// Return false if the attack was already known.
+    pub fn learn(&mut self, atk_name: &str) -> bool {
+        if self.is_known(atk_name) {
+            return false;
+        }
+        self.known_atks.push(atk_name.to_owned());
+        if self.equipped_atks.len() < self.nb_slots {
+            self.equipped_atks.push(atk_name.to_owned());
+        }
+        true
+    }
+
+    pub fn forget(&mut self, atk_name: &str) {
+        self.known_atks.retain(|a| a != atk_name);
+        self.equipped_atks.retain(|a| a != atk_name);
+    }
+
+    /// Learn all the attacks of the class up to the level, in the order of the attacks list.
+    /// Return the names of the newly learnt attacks.
+    pub fn learn_atks_of_level(
+        &mut self,
+        attacks_list: &IndexMap<String, AttackType>,
+        level: u64,
+        class: &Class,
+    ) -> Vec<String> {
+        attacks_list
+            .values()
+            .filter(|atk| atk.level <= level && atk.is_available_for(class))
+            .filter(|atk| self.learn(&atk.name))
+            .map(|atk| atk.name.clone())
+            .collect()
+    }
+
+    /// Replace the equipped attacks by known attacks, within the number of slots
+    pub fn set_equipped(&mut self, atk_names: &[String]) -> Result<()> {
+        if atk_names.len() > self.nb_slots {
+            bail!(
+                "{} attacks cannot be equipped in {} slots",
+                atk_names.len(),
+                self.nb_slots
+            );
+        }
+        for (i, name) in atk_names.iter().enumerate() {
+            if !self.is_known(name) {
+                bail!("Attack '{}' is not known", name);
+            }
+            if atk_names[..i].contains(name) {
+                bail!("Attack '{}' is equipped twice", name);
+            }
+        }
+        self.equipped_atks = atk_names.to_vec();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_loadout() {
+        let mut attacks_list = IndexMap::new();
+        for (name, level) in [("a", 1), ("b", 1), ("c", 2), ("d", 3), ("e", 1)] {
+            let atk = AttackType {
+                name: name.to_owned(),
+                level,
+                ..Default::default()
+            };
+            attacks_list.insert(name.to_owned(), atk);
+        }
+        // attack of a specialisation
+        attacks_list["e"].specialisation = Some(Class::Paladin);
+        let mut loadout = Loadout {
+            nb_slots: 2,
+            ..Default::default()
+        };
+        assert!(loadout.is_enabled());
+        assert_eq!(
+            loadout.learn_atks_of_level(&attacks_list, 2, &Class::Warrior),
+            ["a", "b", "c"]
+        );
+        assert_eq!(loadout.equipped_atks, ["a", "b"]);
+        assert_eq!(
+            loadout.learn_atks_of_level(&attacks_list, 2, &Class::Paladin),
+            ["e"]
+        );
+
+        // a trainer teaches an attack of a higher level
+        assert!(loadout.learn("d"));
+        assert!(!loadout.learn("d"));
+        assert!(!loadout.is_equipped("d"));
+
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        loadout.set_equipped(&names(&["d", "c"])).unwrap();
+        assert_eq!(loadout.equipped_atks, ["d", "c"]);
+        assert!(loadout.set_equipped(&names(&["a", "b", "c"])).is_err());
+        assert!(loadout.set_equipped(&names(&["a", "a"])).is_err());
+        assert!(loadout.set_equipped(&names(&["unknown"])).is_err());
+
+        loadout.forget("d");
+        loadout.forget("e");
+        assert!(!loadout.is_known("d"));
+        assert_eq!(loadout.equipped_atks, ["c"]);
+    }
+}
@@ -20,6 +20,14 @@ pub enum LootType {
 }
 
 impl Loot {
+    /// True if a hero of the class can get the loot.
+    /// A loot of a base class also goes to its specialisations, a loot of the Standard class to everybody.
+    pub fn is_for_class(&self, class: &Class) -> bool {
+        self.classes
+            .iter()
+            .any(|c| *c == Class::Standard || class.is_a(c))
+    }
+
     pub fn format_classes(&self) -> String {
         self.classes
             .iter()
@@ -61,6 +69,24 @@ mod tests {
         assert_eq!(loot.format_classes(), "Warrior, Mage");
     }
 
+    #[test]
+    fn unit_is_for_class() {
+        let loot = Loot {
+            classes: vec![Class::Warrior, Class::Pyromancer],
+            ..Default::default()
+        };
+        assert!(loot.is_for_class(&Class::Warrior));
+        assert!(loot.is_for_class(&Class::Paladin));
+        assert!(loot.is_for_class(&Class::Pyromancer));
+        assert!(!loot.is_for_class(&Class::Mage));
+        assert!(!loot.is_for_class(&Class::Necromancer));
+        let loot = Loot {
+            classes: vec![Class::Standard],
+            ..Default::default()
+        };
+        assert!(loot.is_for_class(&Class::Druid));
+    }
+
     #[test]
     fn unit_format_classes_empty() {
         let loot = Loot {
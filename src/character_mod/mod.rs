@@ -1,15 +1,20 @@
+pub mod affix;
+pub mod ailment;
 pub mod attack_type;
 pub mod buffers;
 pub mod character;
 pub mod class;
 pub mod effect;
+pub mod element;
 pub mod energy;
 pub mod equipment;
 pub mod experience;
 pub mod inventory;
+pub mod loadout;
 pub mod loot;
 pub mod rank;
 pub mod rounds_information;
 pub mod stats;
 pub mod stats_in_game;
+pub mod talent;
 pub mod target;
@@ -1,4 +1,4 @@
-#[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+#[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, PartialOrd)]
 #[serde(rename_all = "PascalCase")]
 pub enum Rank {
     #[default]
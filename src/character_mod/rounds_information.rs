@@ -2,6 +2,7 @@ use anyhow::Result;
 
 use crate::{
     character_mod::{
+        ailment::{Ailment, AilmentKind},
         attack_type::AttackType,
         buffers::{BufKinds, Buffer, update_damage_by_buf, update_heal_by_multi},
         class::Class,
@@ -9,26 +10,25 @@ use crate::{
             self, ConditionKind, EffectParam, ProcessedEffectParam, is_boosted_by_crit,
             is_effet_hot_or_dot, process_decrease_on_turn,
         },
-        target::{TargetData, is_target_ally},
+        stats::StatKind,
+        target::{Reach, Target, TargetData},
     },
     common::{
         constants::{
-            all_target_const::{TARGET_ALLY, TARGET_ENNEMY},
             attak_const::{COEFF_CRIT_DMG, COEFF_CRIT_STATS},
             character_const::ULTIMATE_LEVEL,
-            reach_const::INDIVIDUAL,
-            stats_const::HP,
         },
+        game_rng::GameRng,
         log_data::{
             LogData,
-            const_colors::{DARK_RED, LIGHT_GREEN},
+            const_colors::{DARK_RED, LIGHT_GREEN, MUTED_GREY},
         },
     },
     server::{
         game_state::GameState,
         players_manager::{DodgeInfo, GameAtkEffect},
     },
-    utils::{get_random_nb, softcap_percent},
+    utils::softcap_percent,
 };
 use std::collections::{HashMap, VecDeque};
 
@@ -84,19 +84,36 @@ pub struct CharacterRoundsInfo {
     pub is_potential_target: bool,
     #[serde(default, rename = "Effects")]
     pub all_effects: Vec<GameAtkEffect>,
-    /// Queue of attack indexes from the scenario pattern, filled on first use and cycled
-    #[serde(default, skip)]
+    /// Queue of attack indexes from the scenario pattern, filled on first use and cycled.
+    /// Persisted in saves so that a reloaded boss resumes its pattern.
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
     pub atk_pattern_queue: VecDeque<u64>,
     /// Streak-breaker: number of consecutive turns without a critical strike.
     /// Reset to 0 each time a crit lands. Compared against the active threshold
     /// (from rank/class/level or `StreakBreakerCrit` buffer) to guarantee the next crit.
-    #[serde(default, skip)]
+    #[serde(default)]
     pub crit_drought_counter: u32,
     /// Streak-breaker: number of consecutive turns without a successful dodge.
     /// Reset to 0 each time a dodge/block succeeds. Compared against the active
     /// threshold (from rank/class/level or `StreakBreakerDodge` buffer).
-    #[serde(default, skip)]
+    #[serde(default)]
     pub dodge_drought_counter: u32,
+    /// Indexes of the scenario boss rules flagged `once` that were already used in the fight
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fired_boss_rules: Vec<usize>,
+    /// Boss only: number of phases of the scenario entered in the fight
+    #[serde(default)]
+    pub boss_phase: usize,
+    /// Boss only: id_name of the hero targeted by the previous attack
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub last_target_id_name: String,
+    /// Fight information: status ailments inflicted through the fight
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ailments: Vec<Ailment>,
+    /// Fight information: HP change of each hot, dot and ailment ticked at the first round of the turn,
+    /// with the id_name of its launcher. Read right after the new round to give threat to the launchers.
+    #[serde(skip)]
+    pub hot_and_dot_ticks: Vec<(String, i64)>,
 }
 
 impl Default for CharacterRoundsInfo {
@@ -118,6 +135,11 @@ impl Default for CharacterRoundsInfo {
             atk_pattern_queue: VecDeque::new(),
             crit_drought_counter: 0,
             dodge_drought_counter: 0,
+            fired_boss_rules: Vec::new(),
+            boss_phase: 0,
+            last_target_id_name: String::new(),
+            ailments: Vec::new(),
+            hot_and_dot_ticks: Vec::new(),
         }
     }
 }
@@ -157,8 +179,21 @@ impl CharacterRoundsInfo {
     }
 
     /// Output: hot, dot, buf, debuf
-    pub fn get_hot_and_buf_nbs_txts(all_effects: &Vec<GameAtkEffect>) -> HotsBufs {
+    /// Poison and burn ailments are counted as dots, the other ailments as debufs.
+    pub fn get_hot_and_buf_nbs_txts(
+        all_effects: &Vec<GameAtkEffect>,
+        ailments: &[Ailment],
+    ) -> HotsBufs {
         let mut hots_bufs = HotsBufs::default();
+        for a in ailments {
+            if a.kind.is_dot() {
+                hots_bufs.dot_nb += 1;
+                hots_bufs.dot_txt.push(a.txt());
+            } else {
+                hots_bufs.debuf_nb += 1;
+                hots_bufs.debuf_txt.push(a.txt());
+            }
+        }
         for e in all_effects {
             if e.processed_effect_param.input_effect_param.nb_turns < 2 {
                 continue;
@@ -166,7 +201,7 @@ impl CharacterRoundsInfo {
             let txt = Self::get_hot_and_buf_texts(e);
             if effect::is_hot(
                 &e.processed_effect_param.input_effect_param.buffer.kind,
-                &e.processed_effect_param
+                e.processed_effect_param
                     .input_effect_param
                     .buffer
                     .stats_name,
@@ -179,7 +214,7 @@ impl CharacterRoundsInfo {
                 .input_effect_param
                 .buffer
                 .stats_name
-                == HP
+                == Some(StatKind::Hp)
                 && e.processed_effect_param.input_effect_param.buffer.value < 0
             {
                 hots_bufs.dot_nb += 1;
@@ -189,7 +224,7 @@ impl CharacterRoundsInfo {
                 .input_effect_param
                 .buffer
                 .stats_name
-                == HP
+                == Some(StatKind::Hp)
                 && e.processed_effect_param.input_effect_param.buffer.value > 0
             {
                 hots_bufs.hot_nb += 1;
@@ -213,24 +248,30 @@ impl CharacterRoundsInfo {
         if ep.buffer.kind == BufKinds::CooldownTurnsNumber {
             return format!("{}: cooldown ({} turns)", atk_name, nb_turns);
         }
+        if ep.buffer.kind == BufKinds::AbsorbShield {
+            return format!(
+                "{}: shield {} HP × {} turns",
+                atk_name, gae.effect_outcome.shield_remaining, nb_turns
+            );
+        }
 
         let is_max_stat = ep.buffer.kind == BufKinds::ChangeMaxStatByPercentage
             || ep.buffer.kind == BufKinds::ChangeMaxStatByValue;
         let is_percent = ep.buffer.kind == BufKinds::ChangeMaxStatByPercentage;
 
         // For current-HP effects show the full computed amount; for max-stat or other stats use raw value.
-        let amount = if ep.buffer.stats_name == HP && !is_max_stat {
+        let amount = if ep.buffer.stats_name == Some(StatKind::Hp) && !is_max_stat {
             gae.effect_outcome.full_amount_tx.abs()
         } else {
             ep.buffer.value.abs()
         };
 
         let stat_label = if is_max_stat && is_percent {
-            format!("{}% max {}", amount, ep.buffer.stats_name)
+            format!("{}% max {}", amount, ep.buffer.stats_label())
         } else if is_max_stat {
-            format!("{} max {}", amount, ep.buffer.stats_name)
+            format!("{} max {}", amount, ep.buffer.stats_label())
         } else {
-            format!("{} {}", amount, ep.buffer.stats_name)
+            format!("{} {}", amount, ep.buffer.stats_label())
         };
 
         if ep.buffer.kind == BufKinds::DecreasingRateOnTurn
@@ -248,20 +289,22 @@ impl CharacterRoundsInfo {
         }
     }
 
-    pub fn is_dodging(&self, target_kind: &str) -> bool {
-        self.dodge_info.is_dodging && target_kind == TARGET_ENNEMY
+    pub fn is_dodging(&self, target_kind: &Target) -> bool {
+        self.dodge_info.is_dodging && *target_kind == Target::Enemy
     }
 
     pub fn is_blocking(&mut self, ep: &EffectParam) -> bool {
-        self.dodge_info.is_blocking && ep.buffer.stats_name == HP && ep.target_kind == TARGET_ENNEMY
+        self.dodge_info.is_blocking
+            && ep.buffer.stats_name == Some(StatKind::Hp)
+            && ep.target_kind == Target::Enemy
     }
 
-    pub fn apply_buf_debuf(&self, full_amount: i64, target: &str, is_crit: bool) -> i64 {
+    pub fn apply_buf_debuf(&self, full_amount: i64, target: &Target, is_crit: bool) -> i64 {
         let mut real_amount = full_amount;
         let mut buf_debuf = 0;
         let mut coeff_crit = COEFF_CRIT_DMG;
         // buf debuf heal
-        if full_amount > 0 && is_target_ally(target) {
+        if full_amount > 0 && target.is_ally() {
             // Launcher TX: BufTypes::MultiValue
             // To place first
 
@@ -282,7 +325,7 @@ impl CharacterRoundsInfo {
             });
         }
         // buf debuf damage
-        if full_amount < 0 && !is_target_ally(target) {
+        if full_amount < 0 && !target.is_ally() {
             // Receiver RX: BufTypes::DamageCritCapped
             if let Some(buf_dmg_crit) = self.get_buffer_by_type(&BufKinds::DamageCritCapped) {
                 // improve crit coeff
@@ -325,6 +368,7 @@ impl CharacterRoundsInfo {
         &mut self,
         ep: &EffectParam,
         atk_name: &str,
+        rng: &mut GameRng,
     ) -> Result<ProcessedEffectParam> {
         let mut processed_effect_param = ProcessedEffectParam {
             input_effect_param: ep.clone(),
@@ -347,7 +391,7 @@ impl CharacterRoundsInfo {
                 return Ok(processed_effect_param);
             }
             BufKinds::DecreasingRateOnTurn => {
-                processed_effect_param.number_of_applies = process_decrease_on_turn(ep, 0);
+                processed_effect_param.number_of_applies = process_decrease_on_turn(ep, 0, rng);
                 self.update_buffer(&Buffer {
                     value: processed_effect_param.number_of_applies,
                     is_percent: false,
@@ -381,7 +425,7 @@ impl CharacterRoundsInfo {
             }
             BufKinds::ReinitBuf => {
                 // Restart all HOTs/DOTs on the given stat
-                let stats_name = ep.buffer.stats_name.clone();
+                let stats_name = ep.buffer.stats_name;
                 for gae in self.all_effects.iter_mut() {
                     if gae
                         .processed_effect_param
@@ -397,7 +441,7 @@ impl CharacterRoundsInfo {
                     }
                 }
                 processed_effect_param.log = LogData {
-                    message: format!("HOTs/DOTs on '{}' restarted", stats_name),
+                    message: format!("HOTs/DOTs on '{}' restarted", ep.buffer.stats_label()),
                     color: "".to_owned(),
                 };
                 return Ok(processed_effect_param);
@@ -427,7 +471,7 @@ impl CharacterRoundsInfo {
                     .filter(|gae| {
                         effect::is_hot(
                             &gae.processed_effect_param.input_effect_param.buffer.kind,
-                            &gae.processed_effect_param
+                            gae.processed_effect_param
                                 .input_effect_param
                                 .buffer
                                 .stats_name,
@@ -448,6 +492,29 @@ impl CharacterRoundsInfo {
                 };
                 return Ok(processed_effect_param);
             }
+            BufKinds::AbsorbShield => {
+                // The shield is stored on the target side in Character::apply_processed_effect_param
+                processed_effect_param.log = LogData {
+                    message: format!(
+                        "Shield absorbing {} HP for {} turns",
+                        ep.buffer.value, ep.nb_turns
+                    ),
+                    color: "".to_owned(),
+                };
+                return Ok(processed_effect_param);
+            }
+            BufKinds::InflictAilment => {
+                // Actual infliction happens on the target side in Character::inflict_ailment
+                processed_effect_param.log = LogData {
+                    message: format!(
+                        "{} for {} turns",
+                        ep.ailment.unwrap_or_default(),
+                        ep.nb_turns
+                    ),
+                    color: "".to_owned(),
+                };
+                return Ok(processed_effect_param);
+            }
             BufKinds::BlockHealAtk => {
                 self.is_heal_atk_blocked = true;
                 processed_effect_param.log = LogData {
@@ -476,14 +543,15 @@ impl CharacterRoundsInfo {
                     kind: BufKinds::OverHealBoostStat,
                     value: 0,
                     is_percent: false,
-                    stats_name: ep.buffer.stats_name.clone(),
+                    stats_name: ep.buffer.stats_name,
                     is_passive_enabled: true,
                     is_passive: true,
                 });
                 processed_effect_param.log = LogData {
                     message: format!(
                         "Overheal boosts '{}' for {} turns",
-                        ep.buffer.stats_name, ep.nb_turns
+                        ep.buffer.stats_label(),
+                        ep.nb_turns
                     ),
                     color: "".to_owned(),
                 };
@@ -510,7 +578,9 @@ impl CharacterRoundsInfo {
                 processed_effect_param.log = LogData {
                     message: format!(
                         "{}% of '{}' heals converted to damages for {} turns",
-                        ep.sub_value_effect, ep.buffer.stats_name, ep.nb_turns
+                        ep.sub_value_effect,
+                        ep.buffer.stats_label(),
+                        ep.nb_turns
                     ),
                     color: "".to_owned(),
                 };
@@ -552,7 +622,7 @@ impl CharacterRoundsInfo {
                 processed_effect_param.log = LogData {
                     message: format!(
                         "Max {} {} by {}%",
-                        ep.buffer.stats_name,
+                        ep.buffer.stats_label(),
                         dir,
                         ep.buffer.value.abs()
                     ),
@@ -569,7 +639,7 @@ impl CharacterRoundsInfo {
                 processed_effect_param.log = LogData {
                     message: format!(
                         "Max {} {} by {}",
-                        ep.buffer.stats_name,
+                        ep.buffer.stats_label(),
                         dir,
                         ep.buffer.value.abs()
                     ),
@@ -586,7 +656,7 @@ impl CharacterRoundsInfo {
                 processed_effect_param.log = LogData {
                     message: format!(
                         "Current {} {} by {}",
-                        ep.buffer.stats_name,
+                        ep.buffer.stats_label(),
                         dir,
                         ep.buffer.value.abs()
                     ),
@@ -602,7 +672,7 @@ impl CharacterRoundsInfo {
                 processed_effect_param.log = LogData {
                     message: format!(
                         "Current {} {} by {}%",
-                        ep.buffer.stats_name,
+                        ep.buffer.stats_label(),
                         dir,
                         ep.buffer.value.abs()
                     ),
@@ -620,6 +690,7 @@ impl CharacterRoundsInfo {
         atk_name: &str,
         game_state: &GameState,
         is_crit: bool,
+        rng: &mut GameRng,
     ) -> Result<ProcessedEffectParam> {
         let mut effect_param_mutable = ep.clone();
 
@@ -661,7 +732,7 @@ impl CharacterRoundsInfo {
                     .unwrap_or(false);
             let number_of_applies = if did_heal {
                 let chance = ep.buffer.value.clamp(0, 100) as u64;
-                let roll = get_random_nb(1, 100);
+                let roll = rng.random_nb(1, 100);
                 if roll <= chance as i64 {
                     ep.sub_value_effect.max(1)
                 } else {
@@ -703,13 +774,16 @@ impl CharacterRoundsInfo {
             .iter()
             .find(|c| c.kind == ConditionKind::NbEnnemiesDied)
         {
-            effect_param_mutable.buffer.value +=
-                game_state.died_ennemies[&(game_state.current_turn_nb - 1)].len() as i64
-                    * cond.value;
+            effect_param_mutable.buffer.value += game_state
+                .died_ennemies
+                .get(&(game_state.current_turn_nb.saturating_sub(1)))
+                .map_or(0, |died| died.len())
+                as i64
+                * cond.value;
         }
 
         // Process and return the new effect param
-        self.process_effect_type(&effect_param_mutable, atk_name)
+        self.process_effect_type(&effect_param_mutable, atk_name, rng)
     }
 
     pub fn process_dodging(
@@ -719,6 +793,7 @@ impl CharacterRoundsInfo {
         current_dodge: u64,
         id_name: &str,
         drought_threshold: Option<u32>,
+        rng: &mut GameRng,
     ) {
         let dodge_info = if atk_level == ULTIMATE_LEVEL {
             // Ultimate attacks can never be dodged or blocked
@@ -729,7 +804,7 @@ impl CharacterRoundsInfo {
             }
         } else {
             let effective_dodge = softcap_percent(current_dodge);
-            let rand_nb = get_random_nb(1, 100);
+            let rand_nb = rng.random_nb(1, 100);
 
             // Streak-breaker: if the character hasn't dodged in `threshold` turns, guarantee dodge
             let dodge_guaranteed = drought_threshold
@@ -737,8 +812,8 @@ impl CharacterRoundsInfo {
                 .unwrap_or(false);
 
             let eval_dodge = dodge_guaranteed || rand_nb <= effective_dodge;
-            let is_dodging = *class != Class::Berserker && eval_dodge;
-            let is_blocking = *class == Class::Berserker && eval_dodge;
+            let is_dodging = !class.is_a(&Class::Berserker) && eval_dodge;
+            let is_blocking = class.is_a(&Class::Berserker) && eval_dodge;
 
             // Update drought counter
             if is_dodging {
@@ -774,6 +849,108 @@ impl CharacterRoundsInfo {
         Ok(())
     }
 
+    /// Remaining amounts of the active shields, the oldest first
+    pub fn shields_mut(&mut self) -> Vec<&mut i64> {
+        self.all_effects
+            .iter_mut()
+            .filter(|gae| {
+                gae.processed_effect_param.input_effect_param.buffer.kind == BufKinds::AbsorbShield
+            })
+            .map(|gae| &mut gae.effect_outcome.shield_remaining)
+            .collect()
+    }
+
+    /// Total amount of damages the active shields can still absorb
+    pub fn shield_total(&self) -> i64 {
+        self.all_effects
+            .iter()
+            .filter(|gae| {
+                gae.processed_effect_param.input_effect_param.buffer.kind == BufKinds::AbsorbShield
+            })
+            .map(|gae| gae.effect_outcome.shield_remaining)
+            .sum()
+    }
+
+    /// Add a shield, a new shield replaces the one of the same launcher
+    pub fn add_shield(&mut self, gae: GameAtkEffect) {
+        self.all_effects.retain(|e| {
+            e.processed_effect_param.input_effect_param.buffer.kind != BufKinds::AbsorbShield
+                || e.launcher_id_name != gae.launcher_id_name
+        });
+        self.all_effects.push(gae);
+    }
+
+    /// Remove the shields which cannot absorb any damage anymore
+    pub fn remove_depleted_shields(&mut self) {
+        self.all_effects.retain(|gae| {
+            gae.processed_effect_param.input_effect_param.buffer.kind != BufKinds::AbsorbShield
+                || gae.effect_outcome.shield_remaining > 0
+        });
+    }
+
+    /// Inflict an ailment, an ailment of the same kind is replaced
+    pub fn add_ailment(&mut self, ailment: Ailment) {
+        self.ailments.retain(|a| a.kind != ailment.kind);
+        self.ailments.push(ailment);
+    }
+
+    pub fn has_ailment(&self, kind: AilmentKind) -> bool {
+        self.ailments.iter().any(|a| a.kind == kind)
+    }
+
+    pub fn get_ailment(&self, kind: AilmentKind) -> Option<&Ailment> {
+        self.ailments.iter().find(|a| a.kind == kind)
+    }
+
+    /// Remove the ailment of that kind and return true if it was active
+    pub fn remove_ailment(&mut self, kind: AilmentKind) -> bool {
+        let len = self.ailments.len();
+        self.ailments.retain(|a| a.kind != kind);
+        len != self.ailments.len()
+    }
+
+    /// Ailment making the character skip its round, if any
+    pub fn skipping_ailment(&self) -> Option<AilmentKind> {
+        self.ailments
+            .iter()
+            .find(|a| a.kind.skips_round())
+            .map(|a| a.kind)
+    }
+
+    /// Process the ailments at the first round of the turn: the ended ailments are removed,
+    /// then the others tick once.
+    /// Output: logs, total HP change of the poison and burn ailments
+    pub fn process_ailments(&mut self) -> (Vec<LogData>, i64) {
+        let mut logs = Vec::new();
+        self.ailments.retain(|a| {
+            if a.turns_left > 0 {
+                return true;
+            }
+            logs.push(LogData {
+                message: format!("\u{1f550} Ailment expired: {} ({})", a.kind, a.atk_name),
+                color: MUTED_GREY.to_string(),
+            });
+            false
+        });
+        let mut dot = 0;
+        for a in self.ailments.iter_mut() {
+            if a.kind.is_dot() {
+                dot += a.dot_value;
+                self.hot_and_dot_ticks
+                    .push((a.source_id_name.clone(), a.dot_value));
+                logs.push(LogData {
+                    message: format!(
+                        "\u{1f7e2} {} {} HP from {}",
+                        a.kind, a.dot_value, a.atk_name
+                    ),
+                    color: DARK_RED.to_string(),
+                });
+            }
+            a.turns_left -= 1;
+        }
+        (logs, dot)
+    }
+
     pub fn has_buffer_type(&self, buf_type: &BufKinds) -> bool {
         self.all_buffers.iter().any(|b| b.kind == *buf_type)
     }
@@ -782,6 +959,12 @@ impl CharacterRoundsInfo {
         self.all_buffers.iter().find(|b| b.kind == *buf_type)
     }
 
+    /// Buffer of that kind if it is an enabled passive buffer
+    pub fn get_passive_buffer(&self, buf_type: &BufKinds) -> Option<&Buffer> {
+        self.get_buffer_by_type(buf_type)
+            .filter(|b| b.is_passive && b.is_passive_enabled)
+    }
+
     pub fn get_mut_buffer_by_type(&mut self, buf_type: &BufKinds) -> Option<&mut Buffer> {
         self.all_buffers.iter_mut().find(|b| b.kind == *buf_type)
     }
@@ -789,7 +972,7 @@ impl CharacterRoundsInfo {
     pub fn update_buffer(&mut self, buffer: &Buffer) {
         // find if the buffer already exists
         if let Some(buf) = self.all_buffers.iter_mut().find(|b| b.kind == buffer.kind) {
-            buf.update_buf(buffer.value, buffer.is_percent, "");
+            buf.update_buf(buffer.value, buffer.is_percent, None);
         } else {
             // else push new buffer
             self.all_buffers.push(buffer.clone());
@@ -801,6 +984,7 @@ impl CharacterRoundsInfo {
         atk: &AttackType,
         current_critical: i64,
         drought_threshold: Option<u32>,
+        rng: &mut GameRng,
     ) -> Result<bool> {
         // Priority 1: passive guarantee — `NextHealAtkIsCrit` fires unconditionally
         // on the next heal attack, regardless of the dice roll.
@@ -826,7 +1010,7 @@ impl CharacterRoundsInfo {
             .map(|t| self.crit_drought_counter >= t)
             .unwrap_or(false);
 
-        let rand_nb = get_random_nb(1, 100);
+        let rand_nb = rng.random_nb(1, 100);
         let is_crit = crit_guaranteed || rand_nb <= effective_critical;
 
         // For excess stat above raw 60: still converts to DamageCritCapped bonus
@@ -840,7 +1024,7 @@ impl CharacterRoundsInfo {
                     is_passive_enabled: false,
                     value: delta_capped,
                     is_percent: false,
-                    stats_name: String::new(),
+                    stats_name: None,
                     kind: BufKinds::DamageCritCapped,
                     is_passive: false,
                 });
@@ -859,13 +1043,13 @@ impl CharacterRoundsInfo {
         }
 
         // eval target choice `is_current_target`
-        if (target_data.effect_param.target_kind == TARGET_ENNEMY
-            || target_data.effect_param.target_kind == TARGET_ALLY)
-            && target_data.effect_param.reach == INDIVIDUAL
+        if (target_data.effect_param.target_kind == Target::Enemy
+            || target_data.effect_param.target_kind == Target::Ally)
+            && target_data.effect_param.reach == Reach::Individual
             && !self.is_current_target
         {
             tracing::debug!(
-                "Effect {} cannot be applied on {} because the target is {} but not current target.",
+                "Effect {} cannot be applied on {} because the target is {:?} but not current target.",
                 target_data.effect_param.buffer.kind,
                 target_data.target_id_name,
                 target_data.effect_param.target_kind
@@ -908,9 +1092,14 @@ impl CharacterRoundsInfo {
         });
     }
 
-    pub fn process_hot_and_dot(&mut self, current_turn_nb: usize) -> (Vec<LogData>, i64) {
+    pub fn process_hot_and_dot(
+        &mut self,
+        current_turn_nb: usize,
+        rng: &mut GameRng,
+    ) -> (Vec<LogData>, i64) {
         let mut logs = Vec::new();
         let mut hot_and_dot = 0;
+        let mut ticks = Vec::new();
         // First process all the effects whatever their order
         for gae in self.all_effects.iter() {
             if gae.launching_turn == current_turn_nb {
@@ -922,7 +1111,7 @@ impl CharacterRoundsInfo {
                 .input_effect_param
                 .buffer
                 .stats_name
-                == HP
+                == Some(StatKind::Hp)
                 && is_effet_hot_or_dot(&gae.processed_effect_param.input_effect_param.buffer.kind)
             {
                 if gae.processed_effect_param.input_effect_param.buffer.kind
@@ -930,13 +1119,19 @@ impl CharacterRoundsInfo {
                     && process_decrease_on_turn(
                         &gae.processed_effect_param.input_effect_param,
                         gae.processed_effect_param.counter_turn,
+                        rng,
                     ) == 0
                 {
                     continue;
                 }
                 Self::process_hot_or_dot(&mut logs, &mut hot_and_dot, gae);
+                ticks.push((
+                    gae.launcher_id_name.clone(),
+                    gae.effect_outcome.full_amount_tx,
+                ));
             }
         }
+        self.hot_and_dot_ticks.extend(ticks);
         (logs, hot_and_dot)
     }
 
@@ -950,6 +1145,10 @@ impl CharacterRoundsInfo {
         self.all_buffers.retain(|b| b.is_passive);
         self.is_first_round = true;
         self.atk_pattern_queue.clear();
+        self.fired_boss_rules.clear();
+        self.boss_phase = 0;
+        self.last_target_id_name.clear();
+        self.ailments.clear();
         self.is_heal_atk_blocked = false;
         self.is_random_target = false;
         self.is_current_target = false;
@@ -969,16 +1168,14 @@ impl CharacterRoundsInfo {
 mod tests {
     use crate::{
         character_mod::{
+            ailment::{Ailment, AilmentKind},
             buffers::{BufKinds, Buffer},
             character::Character,
             rounds_information::{CharacterRoundsInfo, HotsBufs},
-            target::TargetData,
-        },
-        common::constants::{
-            all_target_const::{TARGET_ALLY, TARGET_ENNEMY},
-            paths_const::TEST_OFFLINE_ROOT,
-            stats_const::*,
+            stats::StatKind,
+            target::{Target, TargetData},
         },
+        common::{constants::paths_const::TEST_OFFLINE_ROOT, game_rng::GameRng},
         server::players_manager::GameAtkEffect,
         testing::{
             testing_all_characters::testing_all_equipment,
@@ -990,6 +1187,55 @@ mod tests {
         },
     };
 
+    #[test]
+    fn unit_process_ailments() {
+        let mut cri = CharacterRoundsInfo::default();
+        cri.add_ailment(Ailment {
+            kind: AilmentKind::Stun,
+            turns_left: 1,
+            atk_name: "Bash".to_owned(),
+            ..Default::default()
+        });
+        cri.add_ailment(Ailment {
+            kind: AilmentKind::Poison,
+            turns_left: 1,
+            dot_value: -10,
+            ..Default::default()
+        });
+        // an ailment of the same kind is replaced
+        cri.add_ailment(Ailment {
+            kind: AilmentKind::Poison,
+            turns_left: 2,
+            dot_value: -12,
+            atk_name: "Toxic".to_owned(),
+            ..Default::default()
+        });
+        assert_eq!(cri.ailments.len(), 2);
+        assert_eq!(cri.skipping_ailment(), Some(AilmentKind::Stun));
+
+        // first tick: the stun is still active for this turn
+        let (logs, dot) = cri.process_ailments();
+        assert_eq!(dot, -12);
+        assert_eq!(logs.len(), 1);
+        assert_eq!(cri.skipping_ailment(), Some(AilmentKind::Stun));
+        let hots_bufs = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&vec![], &cri.ailments);
+        assert_eq!(hots_bufs.debuf_txt, vec!["Bash: Stun × 0 turns".to_owned()]);
+        assert_eq!(
+            hots_bufs.dot_txt,
+            vec!["Toxic: Poison 12 HP × 1 turns".to_owned()]
+        );
+
+        // second tick: the stun has ended
+        let (logs, dot) = cri.process_ailments();
+        assert_eq!(dot, -12);
+        assert_eq!(logs.len(), 2);
+        assert_eq!(cri.skipping_ailment(), None);
+        assert!(cri.has_ailment(AilmentKind::Poison));
+
+        assert!(cri.remove_ailment(AilmentKind::Poison));
+        assert!(!cri.remove_ailment(AilmentKind::Poison));
+    }
+
     #[test]
     fn unit_new_buffers() {
         let mut cri = CharacterRoundsInfo::default();
@@ -1011,7 +1257,7 @@ mod tests {
             },
             ..Default::default()
         };
-        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&vec![cooldown_gae]);
+        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&vec![cooldown_gae], &[]);
         // Cooldown goes to debuf bucket (value=3, stats_name empty, not HP)
         // but the text should contain "cooldown"
         let all_txt: Vec<String> = result
@@ -1039,7 +1285,7 @@ mod tests {
             },
             ..Default::default()
         };
-        let result2 = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&vec![max_stat_gae]);
+        let result2 = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&vec![max_stat_gae], &[]);
         let all_txt2: Vec<String> = result2
             .dot_txt
             .iter()
@@ -1067,7 +1313,7 @@ mod tests {
             buffer: Buffer {
                 kind: BufKinds::DecreasingRateOnTurn,
                 value: 30,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 ..Default::default()
             },
             ..Default::default()
@@ -1089,7 +1335,7 @@ mod tests {
             },
             ..Default::default()
         };
-        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&vec![gae]);
+        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&vec![gae], &[]);
         let all_txt: Vec<String> = result
             .hot_txt
             .iter()
@@ -1107,7 +1353,7 @@ mod tests {
     fn unit_get_hot_and_buf_nbs() {
         use crate::character_mod::{attack_type::AttackType, effect::EffectOutcome};
 
-        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&vec![]);
+        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&vec![], &[]);
         assert_eq!(result, HotsBufs::default());
         let mut all_effects: Vec<GameAtkEffect> = vec![];
         // add a 1-turn-effect (nb_turns < 2, should be ignored)
@@ -1115,7 +1361,7 @@ mod tests {
             processed_effect_param: build_dmg_effect_individual(),
             ..Default::default()
         });
-        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects);
+        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects, &[]);
         assert_eq!(result, HotsBufs::default());
 
         // add a 2-turn HOT: +30 HP
@@ -1131,7 +1377,7 @@ mod tests {
             },
             ..Default::default()
         });
-        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects);
+        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects, &[]);
         assert_eq!(
             result,
             HotsBufs {
@@ -1154,7 +1400,7 @@ mod tests {
             },
             ..Default::default()
         });
-        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects);
+        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects, &[]);
         assert_eq!(
             result,
             HotsBufs {
@@ -1175,7 +1421,7 @@ mod tests {
             },
             ..Default::default()
         });
-        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects);
+        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects, &[]);
         assert_eq!(
             result,
             HotsBufs {
@@ -1184,7 +1430,7 @@ mod tests {
                 buf_nb: 1,
                 hot_txt: vec!["TestHot: 30 HP × 2 turns".to_owned()],
                 dot_txt: vec!["TestDot: 20 HP × 3 turns".to_owned()],
-                buf_txt: vec![format!("TestBuf: 20 {} × 3 turns", MAGICAL_ARMOR)],
+                buf_txt: vec![format!("TestBuf: 20 {} × 3 turns", StatKind::MagicalArmor)],
                 ..Default::default()
             }
         );
@@ -1198,7 +1444,7 @@ mod tests {
             },
             ..Default::default()
         });
-        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects);
+        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects, &[]);
         assert_eq!(
             result,
             HotsBufs {
@@ -1208,8 +1454,11 @@ mod tests {
                 debuf_nb: 1,
                 hot_txt: vec!["TestHot: 30 HP × 2 turns".to_owned()],
                 dot_txt: vec!["TestDot: 20 HP × 3 turns".to_owned()],
-                buf_txt: vec![format!("TestBuf: 20 {} × 3 turns", MAGICAL_ARMOR)],
-                debuf_txt: vec![format!("TestDebuf: 20 {} × 3 turns", MAGICAL_ARMOR)],
+                buf_txt: vec![format!("TestBuf: 20 {} × 3 turns", StatKind::MagicalArmor)],
+                debuf_txt: vec![format!(
+                    "TestDebuf: 20 {} × 3 turns",
+                    StatKind::MagicalArmor
+                )],
             }
         );
     }
@@ -1218,7 +1467,7 @@ mod tests {
     fn unit_apply_buf_debuf() {
         let mut cri = CharacterRoundsInfo::default();
         // no buf/debuf
-        let result = cri.apply_buf_debuf(100, TARGET_ALLY, false);
+        let result = cri.apply_buf_debuf(100, &Target::Ally, false);
         assert_eq!(result, 100);
 
         // buf defub damage against ennemy
@@ -1229,15 +1478,15 @@ mod tests {
             kind: BufKinds::DamageTxPercent,
             value: 20,
             is_percent: false,
-            stats_name: String::new(),
+            stats_name: None,
             is_passive_enabled: false,
             is_passive: false,
         });
-        let result = cri.apply_buf_debuf(-100, TARGET_ENNEMY, false);
+        let result = cri.apply_buf_debuf(-100, &Target::Enemy, false);
         // -100 -20 = -120
         assert_eq!(result, -120);
         // same but with critical strike
-        let result = cri.apply_buf_debuf(-100, TARGET_ENNEMY, true);
+        let result = cri.apply_buf_debuf(-100, &Target::Enemy, true);
         // -100 -20 = -120 * 2 = -240
         assert_eq!(result, -240);
         cri.reset_all_buffers();
@@ -1247,11 +1496,11 @@ mod tests {
             is_passive_enabled: false,
             value: 20,
             is_percent: false,
-            stats_name: String::new(),
+            stats_name: None,
             kind: BufKinds::DamageRxPercent,
             is_passive: false,
         });
-        let result = cri.apply_buf_debuf(-100, TARGET_ENNEMY, false);
+        let result = cri.apply_buf_debuf(-100, &Target::Enemy, false);
         // -100 -20 = -120
         assert_eq!(result, -120);
         cri.reset_all_buffers();
@@ -1261,12 +1510,12 @@ mod tests {
             is_passive_enabled: false,
             value: 2,
             is_percent: false,
-            stats_name: String::new(),
+            stats_name: None,
             kind: BufKinds::DamageCritCapped,
             is_passive: false,
         });
         // crit is doubled init:2 -> 2 + 2 = 4
-        let result = cri.apply_buf_debuf(-100, TARGET_ENNEMY, true);
+        let result = cri.apply_buf_debuf(-100, &Target::Enemy, true);
         // -100 * 4 = -400
         assert_eq!(result, -400);
 
@@ -1275,11 +1524,11 @@ mod tests {
             is_passive_enabled: false,
             value: 20,
             is_percent: false,
-            stats_name: String::new(),
+            stats_name: None,
             kind: BufKinds::DamageTxPercent,
             is_passive: false,
         });
-        let result = cri.apply_buf_debuf(-100, TARGET_ENNEMY, true);
+        let result = cri.apply_buf_debuf(-100, &Target::Enemy, true);
         // -100 -20 = -120* 4 = -480
         assert_eq!(result, -480);
         cri.reset_all_buffers();
@@ -1291,11 +1540,11 @@ mod tests {
             is_passive_enabled: false,
             value: 3,
             is_percent: false,
-            stats_name: String::new(),
+            stats_name: None,
             kind: BufKinds::MultiValue,
             is_passive: false,
         });
-        let result = cri.apply_buf_debuf(100, TARGET_ALLY, false);
+        let result = cri.apply_buf_debuf(100, &Target::Ally, false);
         // 100 * 3 = 300
         assert_eq!(result, 300);
         cri.reset_all_buffers();
@@ -1305,11 +1554,11 @@ mod tests {
             is_passive_enabled: false,
             value: 20,
             is_percent: false,
-            stats_name: String::new(),
+            stats_name: None,
             kind: BufKinds::HealTxPercent,
             is_passive: false,
         });
-        let result = cri.apply_buf_debuf(100, TARGET_ALLY, false);
+        let result = cri.apply_buf_debuf(100, &Target::Ally, false);
         // 100 + 20 = 120
         assert_eq!(result, 120);
         cri.reset_all_buffers();
@@ -1318,11 +1567,11 @@ mod tests {
             is_passive_enabled: false,
             value: 20,
             is_percent: false,
-            stats_name: String::new(),
+            stats_name: None,
             kind: BufKinds::HealRxPercent,
             is_passive: false,
         });
-        let result = cri.apply_buf_debuf(100, TARGET_ALLY, false);
+        let result = cri.apply_buf_debuf(100, &Target::Ally, false);
         // 100 + 20 = 120
         assert_eq!(result, 120);
         cri.reset_all_buffers();
@@ -1331,11 +1580,11 @@ mod tests {
             is_passive_enabled: false,
             value: 20,
             is_percent: false,
-            stats_name: String::new(),
+            stats_name: None,
             kind: BufKinds::BoostedByHots,
             is_passive: false,
         });
-        let result = cri.apply_buf_debuf(100, TARGET_ALLY, false);
+        let result = cri.apply_buf_debuf(100, &Target::Ally, false);
         // 100 + 20 = 120
         assert_eq!(result, 120);
         cri.reset_all_buffers();
@@ -1348,7 +1597,7 @@ mod tests {
             is_passive_enabled: false,
             value: 20,
             is_percent: false,
-            stats_name: String::new(),
+            stats_name: None,
             kind: BufKinds::DamageTxPercent,
             is_passive: false,
         });
@@ -1363,7 +1612,7 @@ mod tests {
             is_passive_enabled: false,
             value: 20,
             is_percent: false,
-            stats_name: HP.to_owned(),
+            stats_name: Some(StatKind::Hp),
             kind: BufKinds::DamageTxPercent,
             is_passive: false,
         });
@@ -1381,7 +1630,7 @@ mod tests {
                 .is_percent
         );
         assert_eq!(
-            HP,
+            Some(StatKind::Hp),
             cri.get_buffer_by_type(&BufKinds::DamageTxPercent)
                 .as_ref()
                 .unwrap()
@@ -1609,7 +1858,7 @@ mod tests {
     fn unit_process_hot_and_dot() {
         let mut cri = CharacterRoundsInfo::default();
         // empty effects
-        let (logs, total) = cri.process_hot_and_dot(0);
+        let (logs, total) = cri.process_hot_and_dot(0, &mut GameRng::default());
         assert_eq!(0, total);
         assert!(logs.is_empty());
 
@@ -1620,7 +1869,7 @@ mod tests {
             ..Default::default()
         };
         cri.all_effects.push(hot.clone());
-        let (logs, total) = cri.process_hot_and_dot(1);
+        let (logs, total) = cri.process_hot_and_dot(1, &mut GameRng::default());
         assert_eq!(0, total);
         assert!(logs.is_empty());
 
@@ -1640,9 +1889,14 @@ mod tests {
         dot.effect_outcome.full_amount_tx = dot_value;
         cri.all_effects.push(dot);
 
-        let (logs, total) = cri.process_hot_and_dot(1);
+        let (logs, total) = cri.process_hot_and_dot(1, &mut GameRng::default());
         assert_eq!(hot_value + dot_value, total);
         assert_eq!(2, logs.len());
+        // the ticks are kept for the threat of their launchers
+        assert_eq!(
+            vec![(String::new(), hot_value), (String::new(), dot_value)],
+            cri.hot_and_dot_ticks
+        );
     }
 
     #[test]
@@ -1660,7 +1914,7 @@ mod tests {
             kind: BufKinds::OverHealBoostStat,
             is_passive: true,
             is_passive_enabled: true,
-            stats_name: "Physical power".to_string(),
+            stats_name: Some(StatKind::PhysicalPower),
             ..Default::default()
         });
         cri.is_heal_atk_blocked = true;
@@ -1714,7 +1968,7 @@ mod tests {
             },
             ..Default::default()
         }];
-        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects);
+        let result = CharacterRoundsInfo::get_hot_and_buf_nbs_txts(&all_effects, &[]);
         assert_eq!(
             result.hot_nb, 1,
             "ChangeMaxStatByPercentage +HP should be HOT"
@@ -1733,13 +1987,18 @@ mod tests {
     use crate::character_mod::rounds_information::AmountType;
     use crate::server::game_state::GameState;
 
-    fn make_ep(kind: BufKinds, value: i64, stats_name: &str, nb_turns: i64) -> EffectParam {
+    fn make_ep(
+        kind: BufKinds,
+        value: i64,
+        stats_name: Option<StatKind>,
+        nb_turns: i64,
+    ) -> EffectParam {
         EffectParam {
             nb_turns,
             buffer: Buffer {
                 kind,
                 value,
-                stats_name: stats_name.to_owned(),
+                stats_name,
                 ..Default::default()
             },
             ..Default::default()
@@ -1754,7 +2013,7 @@ mod tests {
                     buffer: Buffer {
                         kind: BufKinds::ChangeCurrentStatByValue,
                         value,
-                        stats_name: HP.to_owned(),
+                        stats_name: Some(StatKind::Hp),
                         ..Default::default()
                     },
                     ..Default::default()
@@ -1774,7 +2033,7 @@ mod tests {
                     buffer: Buffer {
                         kind: BufKinds::ChangeCurrentStatByValue,
                         value,
-                        stats_name: HP.to_owned(),
+                        stats_name: Some(StatKind::Hp),
                         ..Default::default()
                     },
                     ..Default::default()
@@ -1789,8 +2048,10 @@ mod tests {
     #[test]
     fn unit_process_effect_damage_tx_percent() {
         let mut cri = CharacterRoundsInfo::default();
-        let ep = make_ep(BufKinds::DamageTxPercent, 15, "", 1);
-        let result = cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::DamageTxPercent, 15, None, 1);
+        let result = cri
+            .process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         assert_eq!(result.number_of_applies, 1);
         let buf = cri.get_buffer_by_type(&BufKinds::DamageTxPercent).unwrap();
         assert_eq!(buf.value, 15);
@@ -1800,8 +2061,10 @@ mod tests {
     #[test]
     fn unit_process_effect_damage_rx_percent() {
         let mut cri = CharacterRoundsInfo::default();
-        let ep = make_ep(BufKinds::DamageRxPercent, 10, "", 1);
-        let result = cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::DamageRxPercent, 10, None, 1);
+        let result = cri
+            .process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         assert_eq!(result.number_of_applies, 1);
         let buf = cri.get_buffer_by_type(&BufKinds::DamageRxPercent).unwrap();
         assert_eq!(buf.value, 10);
@@ -1811,8 +2074,9 @@ mod tests {
     #[test]
     fn unit_process_effect_heal_tx_percent() {
         let mut cri = CharacterRoundsInfo::default();
-        let ep = make_ep(BufKinds::HealTxPercent, 20, "", 1);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::HealTxPercent, 20, None, 1);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         let buf = cri.get_buffer_by_type(&BufKinds::HealTxPercent).unwrap();
         assert_eq!(buf.value, 20);
         assert!(buf.is_percent);
@@ -1821,8 +2085,9 @@ mod tests {
     #[test]
     fn unit_process_effect_heal_rx_percent() {
         let mut cri = CharacterRoundsInfo::default();
-        let ep = make_ep(BufKinds::HealRxPercent, 25, "", 1);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::HealRxPercent, 25, None, 1);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         let buf = cri.get_buffer_by_type(&BufKinds::HealRxPercent).unwrap();
         assert_eq!(buf.value, 25);
         assert!(buf.is_percent);
@@ -1836,8 +2101,9 @@ mod tests {
         gae.processed_effect_param.counter_turn = 2;
         cri.all_effects.push(gae);
         // ReinitBuf on HP resets the counter
-        let ep = make_ep(BufKinds::ReinitBuf, 0, HP, 1);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::ReinitBuf, 0, Some(StatKind::Hp), 1);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         assert_eq!(0, cri.all_effects[0].processed_effect_param.counter_turn);
     }
 
@@ -1848,8 +2114,9 @@ mod tests {
         gae.processed_effect_param.counter_turn = 2;
         cri.all_effects.push(gae);
         // ReinitBuf on a different stat — should not reset HP HOT
-        let ep = make_ep(BufKinds::ReinitBuf, 0, MANA, 1);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::ReinitBuf, 0, Some(StatKind::Mana), 1);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         assert_eq!(2, cri.all_effects[0].processed_effect_param.counter_turn);
     }
 
@@ -1861,8 +2128,10 @@ mod tests {
         cri.all_effects.push(make_hot_gae(30));
         cri.all_effects.push(make_debuf_gae(-20));
         assert_eq!(2, cri.all_effects.len());
-        let ep = make_ep(BufKinds::RemoveOneDebuf, 0, "", 1);
-        let result = cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::RemoveOneDebuf, 0, None, 1);
+        let result = cri
+            .process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         // Launcher's all_effects untouched — no removal here
         assert_eq!(2, cri.all_effects.len());
         assert_eq!(
@@ -1875,8 +2144,10 @@ mod tests {
     fn unit_process_effect_remove_one_debuf_none() {
         let mut cri = CharacterRoundsInfo::default();
         cri.all_effects.push(make_hot_gae(30));
-        let ep = make_ep(BufKinds::RemoveOneDebuf, 0, "", 1);
-        let result = cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::RemoveOneDebuf, 0, None, 1);
+        let result = cri
+            .process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         // HOT stays — no removal on the launcher side
         assert_eq!(1, cri.all_effects.len());
         assert_eq!(
@@ -1892,8 +2163,10 @@ mod tests {
         // Character::apply_processed_effect_param so every receiving target is covered.
         let mut cri = CharacterRoundsInfo::default();
         cri.all_effects.push(make_hot_gae(100));
-        let ep = make_ep(BufKinds::BoostHotsByPercentage, 20, "", 1);
-        let result = cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::BoostHotsByPercentage, 20, None, 1);
+        let result = cri
+            .process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         // HOT value is unchanged here — mutation happens on the target side
         assert_eq!(
             100,
@@ -1912,8 +2185,9 @@ mod tests {
         let mut cri = CharacterRoundsInfo::default();
         // Only a DOT: value < 0, not a HOT
         cri.all_effects.push(make_debuf_gae(-50));
-        let ep = make_ep(BufKinds::BoostHotsByPercentage, 20, "", 1);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::BoostHotsByPercentage, 20, None, 1);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         // DOT should be unchanged
         assert_eq!(
             -50,
@@ -1931,8 +2205,9 @@ mod tests {
         cri.all_effects.push(make_hot_gae(30));
         cri.all_effects.push(make_hot_gae(40));
         // 2 HOTs × 10% = 20% boost stored in BoostedByHots
-        let ep = make_ep(BufKinds::BoostBufByHotsNumberInPercentage, 10, "", 1);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::BoostBufByHotsNumberInPercentage, 10, None, 1);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         let buf = cri.get_buffer_by_type(&BufKinds::BoostedByHots).unwrap();
         assert_eq!(20, buf.value);
         assert!(buf.is_percent);
@@ -1942,8 +2217,9 @@ mod tests {
     fn unit_process_effect_boost_buf_by_hots_number_no_hots() {
         let mut cri = CharacterRoundsInfo::default();
         // 0 HOTs → 0% boost
-        let ep = make_ep(BufKinds::BoostBufByHotsNumberInPercentage, 10, "", 1);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::BoostBufByHotsNumberInPercentage, 10, None, 1);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         let buf = cri.get_buffer_by_type(&BufKinds::BoostedByHots).unwrap();
         assert_eq!(0, buf.value);
     }
@@ -1952,16 +2228,18 @@ mod tests {
     fn unit_process_effect_block_heal_atk() {
         let mut cri = CharacterRoundsInfo::default();
         assert!(!cri.is_heal_atk_blocked);
-        let ep = make_ep(BufKinds::BlockHealAtk, 0, "", 3);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::BlockHealAtk, 0, None, 3);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         assert!(cri.is_heal_atk_blocked);
     }
 
     #[test]
     fn unit_process_effect_multi_value() {
         let mut cri = CharacterRoundsInfo::default();
-        let ep = make_ep(BufKinds::MultiValue, 3, "", 1);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::MultiValue, 3, None, 1);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         let buf = cri.get_buffer_by_type(&BufKinds::MultiValue).unwrap();
         assert_eq!(3, buf.value);
     }
@@ -1969,20 +2247,22 @@ mod tests {
     #[test]
     fn unit_process_effect_add_as_much_as_hp() {
         let mut cri = CharacterRoundsInfo::default();
-        let ep = make_ep(BufKinds::AddAsMuchAsHp, 0, MAGICAL_POWER, 3);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::AddAsMuchAsHp, 0, Some(StatKind::MagicalPower), 3);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         let buf = cri
             .get_buffer_by_type(&BufKinds::OverHealBoostStat)
             .unwrap();
         assert!(buf.is_passive_enabled);
-        assert_eq!(MAGICAL_POWER, buf.stats_name);
+        assert_eq!(Some(StatKind::MagicalPower), buf.stats_name);
     }
 
     #[test]
     fn unit_process_effect_is_damage_tx_heal_needy_ally() {
         let mut cri = CharacterRoundsInfo::default();
-        let ep = make_ep(BufKinds::IsDamageTxHealNeedyAlly, 0, "", 1);
-        cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::IsDamageTxHealNeedyAlly, 0, None, 1);
+        cri.process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         let buf = cri
             .get_buffer_by_type(&BufKinds::IsDamageTxHealNeedyAlly)
             .unwrap();
@@ -1992,11 +2272,13 @@ mod tests {
     #[test]
     fn unit_process_effect_percentage_into_damages() {
         let mut cri = CharacterRoundsInfo::default();
-        let mut ep = make_ep(BufKinds::PercentageIntoDamages, 0, HP, 5);
+        let mut ep = make_ep(BufKinds::PercentageIntoDamages, 0, Some(StatKind::Hp), 5);
         ep.sub_value_effect = 50;
-        let result = cri.process_effect_type(&ep, "test_atk").unwrap();
+        let result = cri
+            .process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         assert!(result.log.message.contains("50%"));
-        assert!(result.log.message.contains(HP));
+        assert!(result.log.message.contains(StatKind::Hp.as_str()));
     }
 
     #[test]
@@ -2008,8 +2290,10 @@ mod tests {
             value: 4,
             ..Default::default()
         });
-        let ep = make_ep(BufKinds::RepeatAsManyAsPossible, -50, HP, 1);
-        let result = cri.process_effect_type(&ep, "test_atk").unwrap();
+        let ep = make_ep(BufKinds::RepeatAsManyAsPossible, -50, Some(StatKind::Hp), 1);
+        let result = cri
+            .process_effect_type(&ep, "test_atk", &mut GameRng::default())
+            .unwrap();
         assert_eq!(4, result.number_of_applies);
     }
 
@@ -2035,7 +2319,9 @@ mod tests {
             },
             ..Default::default()
         };
-        let result = cri.process_one_effect(&ep, "test", &gs, false).unwrap();
+        let result = cri
+            .process_one_effect(&ep, "test", &gs, false, &mut GameRng::default())
+            .unwrap();
         assert_eq!(1, result.number_of_applies);
     }
 
@@ -2059,7 +2345,9 @@ mod tests {
             },
             ..Default::default()
         };
-        let result = cri.process_one_effect(&ep, "test", &gs, false).unwrap();
+        let result = cri
+            .process_one_effect(&ep, "test", &gs, false, &mut GameRng::default())
+            .unwrap();
         assert_eq!(0, result.number_of_applies);
         assert!(result.log.message.contains("failed"));
     }
@@ -2068,8 +2356,10 @@ mod tests {
     fn unit_process_effect_cooldown_uses_buffer_value() {
         let mut cri = CharacterRoundsInfo::default();
         // buffer.value=7 is the single source of truth for cooldown duration
-        let ep = make_ep(BufKinds::CooldownTurnsNumber, 7, "", 1);
-        let result = cri.process_effect_type(&ep, "my_atk").unwrap();
+        let ep = make_ep(BufKinds::CooldownTurnsNumber, 7, None, 1);
+        let result = cri
+            .process_effect_type(&ep, "my_atk", &mut GameRng::default())
+            .unwrap();
         assert!(
             result.log.message.contains("7 turns"),
             "Message: {}",
@@ -2085,13 +2375,17 @@ mod tests {
         let mut cri = CharacterRoundsInfo::default();
 
         // Direct call with RepeatIfHeal → hits lines 551-558 in process_effect_type
-        let ep_repeat = make_ep(BufKinds::RepeatIfHeal, 80, "", 1);
-        let result = cri.process_effect_type(&ep_repeat, "test_atk").unwrap();
+        let ep_repeat = make_ep(BufKinds::RepeatIfHeal, 80, None, 1);
+        let result = cri
+            .process_effect_type(&ep_repeat, "test_atk", &mut GameRng::default())
+            .unwrap();
         assert!(result.log.message.contains("RepeatIfHeal"));
 
         // Direct call with ConditionDamagePrevTurn → hits lines 559-566
-        let ep_cond = make_ep(BufKinds::ConditionDamagePrevTurn, 1, "", 1);
-        let result2 = cri.process_effect_type(&ep_cond, "test_atk").unwrap();
+        let ep_cond = make_ep(BufKinds::ConditionDamagePrevTurn, 1, None, 1);
+        let result2 = cri
+            .process_effect_type(&ep_cond, "test_atk", &mut GameRng::default())
+            .unwrap();
         assert!(result2.log.message.contains("ConditionDamagePrevTurn"));
         let _ = ProcessedEffectParam::default();
         let _ = EffectParam::default();
@@ -2126,7 +2420,9 @@ mod tests {
             },
             ..Default::default()
         };
-        let result = cri.process_one_effect(&ep, "test", &gs, false).unwrap();
+        let result = cri
+            .process_one_effect(&ep, "test", &gs, false, &mut GameRng::default())
+            .unwrap();
         // With 100% chance and heal on prev turn, should repeat sub_value_effect=2 times
         assert!(
             result.number_of_applies >= 0,
@@ -2145,7 +2441,9 @@ mod tests {
 
         // Guarantee a crit via streak-breaker with current_critical > 60 → DamageCritCapped
         cri.crit_drought_counter = 5;
-        let result = cri.process_critical_strike(&atk, 80, Some(5)).unwrap();
+        let result = cri
+            .process_critical_strike(&atk, 80, Some(5), &mut GameRng::default())
+            .unwrap();
         assert!(result, "Should be a critical strike via streak-breaker");
         // Check that DamageCritCapped buffer was set (since 80 > 60 → delta=20)
         let capped_buf = cri.get_buffer_by_type(&BufKinds::DamageCritCapped);
@@ -1,14 +1,17 @@
-use indexmap::IndexMap;
-use std::{cmp::Ordering, collections::HashMap};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    ops::{Index, IndexMut},
+};
 
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
 use crate::{
     character_mod::{buffers::Buffer, effect::EffectParam, equipment::Equipment},
-    common::constants::{
-        character_const::{NB_TURN_SUM_AGGRO, SPEED_THRESHOLD},
-        stats_const::*,
-    },
+    common::constants::character_const::{NB_TURN_SUM_AGGRO, SPEED_THRESHOLD},
     utils,
 };
 
@@ -57,7 +60,126 @@ impl Attribute {
     }
 }
 
-/// Define all the parameters of the stats of one character
+/// Identifier of a stat, decoded from the stat names of the JSON files
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, EnumIter,
+)]
+pub enum StatKind {
+    #[serde(rename = "HP")]
+    Hp,
+    #[serde(rename = "Mana")]
+    Mana,
+    #[serde(rename = "Vigor")]
+    Vigor,
+    #[serde(rename = "Berserk")]
+    Berserk,
+    #[serde(rename = "Physical armor")]
+    PhysicalArmor,
+    #[serde(rename = "Magic armor", alias = "Magical armor")]
+    MagicalArmor,
+    #[serde(rename = "Physical power")]
+    PhysicalPower,
+    #[serde(rename = "Magic power", alias = "Magical power")]
+    MagicalPower,
+    #[serde(rename = "Aggro")]
+    Aggro,
+    #[serde(rename = "Speed")]
+    Speed,
+    #[serde(rename = "Critical strike")]
+    CriticalStrike,
+    #[serde(rename = "Dodge")]
+    Dodge,
+    #[serde(rename = "HP regeneration")]
+    HpRegen,
+    #[serde(rename = "Mana regeneration")]
+    ManaRegen,
+    #[serde(rename = "Vigor regeneration")]
+    VigorRegen,
+    #[serde(rename = "Berserk rate")]
+    BerserkRate,
+    #[serde(rename = "Aggro rate")]
+    AggroRate,
+    #[serde(rename = "Speed regeneration")]
+    SpeedRegen,
+}
+
+impl StatKind {
+    /// Name of the stat in the JSON files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatKind::Hp => "HP",
+            StatKind::Mana => "Mana",
+            StatKind::Vigor => "Vigor",
+            StatKind::Berserk => "Berserk",
+            StatKind::PhysicalArmor => "Physical armor",
+            StatKind::MagicalArmor => "Magic armor",
+            StatKind::PhysicalPower => "Physical power",
+            StatKind::MagicalPower => "Magic power",
+            StatKind::Aggro => "Aggro",
+            StatKind::Speed => "Speed",
+            StatKind::CriticalStrike => "Critical strike",
+            StatKind::Dodge => "Dodge",
+            StatKind::HpRegen => "HP regeneration",
+            StatKind::ManaRegen => "Mana regeneration",
+            StatKind::VigorRegen => "Vigor regeneration",
+            StatKind::BerserkRate => "Berserk rate",
+            StatKind::AggroRate => "Aggro rate",
+            StatKind::SpeedRegen => "Speed regeneration",
+        }
+    }
+
+    /// HP and the energies spent by the attacks
+    pub fn is_energy(&self) -> bool {
+        matches!(
+            self,
+            StatKind::Hp | StatKind::Mana | StatKind::Vigor | StatKind::Berserk
+        )
+    }
+}
+
+impl fmt::Display for StatKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Serde of an optional stat: no stat is written as an empty string in the JSON files
+pub mod opt_stat_kind {
+    use serde::{
+        Deserialize, Deserializer, Serializer,
+        de::{IntoDeserializer, value::StrDeserializer},
+    };
+
+    use super::StatKind;
+
+    pub fn serialize<S: Serializer>(stat: &Option<StatKind>, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(stat.map(|k| k.as_str()).unwrap_or_default())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<StatKind>, D::Error> {
+        let name = String::deserialize(d)?;
+        if name.is_empty() {
+            return Ok(None);
+        }
+        let de: StrDeserializer<D::Error> = name.as_str().into_deserializer();
+        StatKind::deserialize(de).map(Some)
+    }
+}
+
+/// Stats which level up when a character gains a level (increases by 10% of raw max value)
+pub const STATS_TO_LEVEL_UP: &[StatKind] = &[
+    StatKind::Hp,
+    StatKind::Mana,
+    StatKind::Vigor,
+    StatKind::PhysicalPower,
+    StatKind::MagicalPower,
+    StatKind::PhysicalArmor,
+    StatKind::MagicalArmor,
+    StatKind::Speed,
+];
+
+/// Define all the parameters of the stats of one character.
+/// A stat is read and written with `stats[StatKind::Hp]`.
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
 #[serde(default)]
 pub struct Stats {
@@ -114,103 +236,95 @@ pub struct Stats {
 
     #[serde(rename = "Speed regeneration")]
     speed_regeneration: Attribute,
+}
+
+impl Index<StatKind> for Stats {
+    type Output = Attribute;
+
+    fn index(&self, kind: StatKind) -> &Attribute {
+        match kind {
+            StatKind::Hp => &self.hp,
+            StatKind::Mana => &self.mana,
+            StatKind::Vigor => &self.vigor,
+            StatKind::Berserk => &self.berserk,
+            StatKind::PhysicalArmor => &self.physical_armor,
+            StatKind::MagicalArmor => &self.magical_armor,
+            StatKind::PhysicalPower => &self.physical_power,
+            StatKind::MagicalPower => &self.magic_power,
+            StatKind::Aggro => &self.aggro,
+            StatKind::Speed => &self.speed,
+            StatKind::CriticalStrike => &self.critical_strike,
+            StatKind::Dodge => &self.dodge,
+            StatKind::HpRegen => &self.hp_regeneration,
+            StatKind::ManaRegen => &self.mana_regeneration,
+            StatKind::VigorRegen => &self.vigor_regeneration,
+            StatKind::BerserkRate => &self.berseck_rate,
+            StatKind::AggroRate => &self.aggro_rate,
+            StatKind::SpeedRegen => &self.speed_regeneration,
+        }
+    }
+}
 
-    pub all_stats: IndexMap<String, Attribute>,
+impl IndexMut<StatKind> for Stats {
+    fn index_mut(&mut self, kind: StatKind) -> &mut Attribute {
+        match kind {
+            StatKind::Hp => &mut self.hp,
+            StatKind::Mana => &mut self.mana,
+            StatKind::Vigor => &mut self.vigor,
+            StatKind::Berserk => &mut self.berserk,
+            StatKind::PhysicalArmor => &mut self.physical_armor,
+            StatKind::MagicalArmor => &mut self.magical_armor,
+            StatKind::PhysicalPower => &mut self.physical_power,
+            StatKind::MagicalPower => &mut self.magic_power,
+            StatKind::Aggro => &mut self.aggro,
+            StatKind::Speed => &mut self.speed,
+            StatKind::CriticalStrike => &mut self.critical_strike,
+            StatKind::Dodge => &mut self.dodge,
+            StatKind::HpRegen => &mut self.hp_regeneration,
+            StatKind::ManaRegen => &mut self.mana_regeneration,
+            StatKind::VigorRegen => &mut self.vigor_regeneration,
+            StatKind::BerserkRate => &mut self.berseck_rate,
+            StatKind::AggroRate => &mut self.aggro_rate,
+            StatKind::SpeedRegen => &mut self.speed_regeneration,
+        }
+    }
 }
 
 impl Stats {
     pub fn init(&mut self) {
-        self.aggro.sync_raw_values();
-        self.aggro_rate.sync_raw_values();
-        self.magical_armor.sync_raw_values();
-        self.physical_armor.sync_raw_values();
-        self.magic_power.sync_raw_values();
-        self.physical_power.sync_raw_values();
-        self.hp.sync_raw_values();
-        self.mana.sync_raw_values();
-        self.vigor.sync_raw_values();
-        self.berserk.sync_raw_values();
-        self.berseck_rate.sync_raw_values();
-        self.speed.sync_raw_values();
-        self.critical_strike.sync_raw_values();
-        self.dodge.sync_raw_values();
-        self.hp_regeneration.sync_raw_values();
-        self.mana_regeneration.sync_raw_values();
-        self.vigor_regeneration.sync_raw_values();
-        self.speed_regeneration.sync_raw_values();
-
-        self.all_stats.insert(AGGRO.to_string(), self.aggro.clone());
-        self.all_stats
-            .insert(AGGRO_RATE.to_string(), self.aggro_rate.clone());
-        self.all_stats
-            .insert(MAGICAL_ARMOR.to_string(), self.magical_armor.clone());
-        self.all_stats
-            .insert(PHYSICAL_ARMOR.to_string(), self.physical_armor.clone());
-        self.all_stats
-            .insert(MAGICAL_POWER.to_string(), self.magic_power.clone());
-        self.all_stats
-            .insert(PHYSICAL_POWER.to_string(), self.physical_power.clone());
-        self.all_stats.insert(HP.to_string(), self.hp.clone());
-        self.all_stats.insert(MANA.to_string(), self.mana.clone());
-        self.all_stats.insert(VIGOR.to_string(), self.vigor.clone());
-        self.all_stats
-            .insert(BERSERK.to_string(), self.berserk.clone());
-        self.all_stats
-            .insert(BERSECK_RATE.to_string(), self.berseck_rate.clone());
-        self.all_stats.insert(SPEED.to_string(), self.speed.clone());
-        self.all_stats
-            .insert(CRITICAL_STRIKE.to_string(), self.critical_strike.clone());
-        self.all_stats.insert(DODGE.to_string(), self.dodge.clone());
-        self.all_stats
-            .insert(HP_REGEN.to_string(), self.hp_regeneration.clone());
-        self.all_stats
-            .insert(MANA_REGEN.to_string(), self.mana_regeneration.clone());
-        self.all_stats
-            .insert(VIGOR_REGEN.to_string(), self.vigor_regeneration.clone());
-        self.all_stats
-            .insert(SPEED_REGEN.to_string(), self.speed_regeneration.clone());
-    }
-
-    pub fn get_mut_value(&mut self, name: &str) -> &mut Attribute {
-        self.all_stats
-            .get_mut(name)
-            .expect("key missing in all_stats")
-    }
-
-    pub fn is_energy_stat(name: &str) -> bool {
-        name == HP || name == MANA || name == VIGOR || name == BERSERK
+        for kind in StatKind::iter() {
+            self[kind].sync_raw_values();
+        }
+    }
+
+    /// All the stats with their kind
+    pub fn iter(&self) -> impl Iterator<Item = (StatKind, &Attribute)> {
+        StatKind::iter().map(move |kind| (kind, &self[kind]))
     }
 
     pub fn get_power_stat(&self, is_magic: bool) -> i64 {
         let pow = if is_magic {
-            &self.all_stats[MAGICAL_POWER]
+            &self[StatKind::MagicalPower]
         } else {
-            &self.all_stats[PHYSICAL_POWER]
+            &self[StatKind::PhysicalPower]
         };
         pow.current as i64
     }
     pub fn get_armor_stat(&self, is_magic: bool) -> i64 {
         let armor = if is_magic {
-            &self.all_stats[MAGICAL_ARMOR]
+            &self[StatKind::MagicalArmor]
         } else {
-            &self.all_stats[PHYSICAL_ARMOR]
+            &self[StatKind::PhysicalArmor]
         };
         armor.current as i64
     }
 
-    pub fn is_dead(&self) -> Option<bool> {
-        if self.all_stats.contains_key(HP) {
-            Some(self.all_stats[HP].current == 0)
-        } else {
-            None
-        }
+    pub fn is_dead(&self) -> bool {
+        self.hp.current == 0
     }
 
-    pub fn modify_stat_current(&mut self, attribute_name: &str, delta: i64) -> i64 {
-        let stat = self
-            .all_stats
-            .get_mut(attribute_name)
-            .unwrap_or_else(|| panic!("Stat not found: {}", attribute_name));
+    pub fn modify_stat_current(&mut self, kind: StatKind, delta: i64) -> i64 {
+        let stat = &mut self[kind];
 
         let mut new_value = stat.current as i128 + delta as i128;
 
@@ -232,7 +346,7 @@ impl Stats {
     }
 
     pub fn reset_speed(&mut self) {
-        let speed_pl1 = self.get_mut_value(SPEED);
+        let speed_pl1 = &mut self.speed;
         speed_pl1.current = speed_pl1.current.saturating_sub(SPEED_THRESHOLD);
         speed_pl1.current_raw = speed_pl1.current_raw.saturating_sub(SPEED_THRESHOLD);
         // max and max_raw must NOT be modified: the comment in apply_regen notes that
@@ -245,15 +359,12 @@ impl Stats {
     /// updateEffect: false -> update current value et max value while considering equipments buf (without adding another time the effect buf to `buf_effect_percent` and `buf_effect_value`)
     pub fn set_stats_on_effect(
         &mut self,
-        attribute_name: &str,
+        kind: StatKind,
         value: i64,
         is_percent: bool,
         update_effect: bool,
     ) {
-        let stat = self
-            .all_stats
-            .get_mut(attribute_name)
-            .unwrap_or_else(|| panic!("Stat not found: {}", attribute_name));
+        let stat = &mut self[kind];
         if update_effect {
             if is_percent {
                 stat.buf_effect_percent += value;
@@ -289,12 +400,12 @@ impl Stats {
         let multiplier = if is_equipping { 1 } else { -1 };
 
         for equipment in equipment_list {
-            for (stat_name, stat_effect) in &equipment.stats.all_stats {
+            for (kind, stat_effect) in equipment.stats.iter() {
                 if stat_effect.buf_equip_percent == 0 && stat_effect.buf_equip_value == 0 {
                     continue;
                 }
 
-                let attr = self.get_mut_value(stat_name);
+                let attr = &mut self[kind];
                 attr.buf_equip_value += multiplier * stat_effect.buf_equip_value;
                 attr.buf_equip_percent += multiplier * stat_effect.buf_equip_percent;
 
@@ -315,24 +426,23 @@ impl Stats {
     }
 
     pub fn init_aggro_on_turn(&mut self, turn_nb: usize, all_aggro: &HashMap<u64, i64>) {
-        if let Some(aggro_stat) = self.all_stats.get_mut(AGGRO) {
-            aggro_stat.current = 0;
-            let mut index: i64;
-            for i in 1..NB_TURN_SUM_AGGRO + 1 {
-                index = turn_nb as i64 - i as i64;
-                if index < 0 {
-                    break;
-                }
-                if i <= all_aggro.len() {
-                    let aggro = *all_aggro.get(&(index as u64)).unwrap_or(&0);
-                    aggro_stat.current = aggro_stat.current.saturating_add(aggro as u64);
-                }
+        let aggro_stat = &mut self.aggro;
+        aggro_stat.current = 0;
+        let mut index: i64;
+        for i in 1..NB_TURN_SUM_AGGRO + 1 {
+            index = turn_nb as i64 - i as i64;
+            if index < 0 {
+                break;
+            }
+            if i <= all_aggro.len() {
+                let aggro = *all_aggro.get(&(index as u64)).unwrap_or(&0);
+                aggro_stat.current = aggro_stat.current.saturating_add(aggro as u64);
             }
         }
     }
 
-    pub fn apply_cost_on_stats(&mut self, cost: u64, stats_name: &str) {
-        let attribute = self.get_mut_value(stats_name);
+    pub fn apply_cost_on_stats(&mut self, cost: u64, kind: StatKind) {
+        let attribute = &mut self[kind];
         attribute.current = std::cmp::max(
             0,
             attribute
@@ -342,44 +452,48 @@ impl Stats {
     }
 
     /// access the real amount received by the effect on that character
-    pub fn update_hp_process_real_amount(&mut self, ep: &EffectParam, full_amount: i64) -> i64 {
-        if ep.buffer.stats_name != HP {
-            return 0;
+    /// Damages are first absorbed by the `shields` (remaining amounts, consumed in order).
+    /// Output: real HP amount, absorbed amount
+    pub fn update_hp_process_real_amount(
+        &mut self,
+        ep: &EffectParam,
+        full_amount: i64,
+        shields: &mut [&mut i64],
+    ) -> (i64, i64) {
+        if ep.buffer.stats_name != Some(StatKind::Hp) {
+            return (0, 0);
         }
+        let (full_amount, absorbed_amount) = absorb_by_shields(full_amount, shields);
         let real_hp_amount;
         if full_amount > 0 {
             // heal
-            let delta = self.all_stats[HP].max as i64 - self.all_stats[HP].current as i64;
-            self.all_stats[HP].current = std::cmp::min(
-                full_amount + self.all_stats[HP].current as i64,
-                self.all_stats[HP].max as i64,
-            ) as u64;
+            let delta = self.hp.max as i64 - self.hp.current as i64;
+            self.hp.current =
+                std::cmp::min(full_amount + self.hp.current as i64, self.hp.max as i64) as u64;
             real_hp_amount = std::cmp::min(delta, full_amount);
         } else {
             // damage
-            let tmp = self.all_stats[HP].current as i64;
-            self.all_stats[HP].current =
-                std::cmp::max(0, self.all_stats[HP].current as i64 + full_amount) as u64;
+            let tmp = self.hp.current as i64;
+            self.hp.current = std::cmp::max(0, self.hp.current as i64 + full_amount) as u64;
             real_hp_amount = std::cmp::max(-tmp, full_amount);
         }
-        real_hp_amount
+        (real_hp_amount, absorbed_amount)
     }
 
     pub fn apply_regen(&mut self) {
-        let mut hp = self.all_stats.swap_remove(HP).expect("hp is missing");
-        let mut mana = self.all_stats.swap_remove(MANA).expect("mana is missing");
-        let mut berserk = self
-            .all_stats
-            .swap_remove(BERSERK)
-            .expect("berserk is missing");
-        let mut vigor = self.all_stats.swap_remove(VIGOR).expect("vigor is missing");
-        let mut speed = self.all_stats.swap_remove(SPEED).expect("speed is missing");
-
-        let regen_hp = &self.all_stats[HP_REGEN];
-        let regen_mana = &self.all_stats[MANA_REGEN];
-        let regen_berseck = &self.all_stats[BERSECK_RATE];
-        let regen_vigor = &self.all_stats[VIGOR_REGEN];
-        let regen_speed = &self.all_stats[SPEED_REGEN];
+        let Stats {
+            hp,
+            mana,
+            berserk,
+            vigor,
+            speed,
+            hp_regeneration: regen_hp,
+            mana_regeneration: regen_mana,
+            berseck_rate: regen_berseck,
+            vigor_regeneration: regen_vigor,
+            speed_regeneration: regen_speed,
+            ..
+        } = self;
 
         hp.current = std::cmp::min(hp.max, hp.current + regen_hp.current);
         hp.current_raw = hp.max_raw * (hp.current / hp.max);
@@ -406,45 +520,77 @@ impl Stats {
         if let Some(q) = speed.current.checked_div(speed.max) {
             speed.current_raw = speed.max_raw * q;
         }
-
-        self.all_stats.insert(HP.to_owned(), hp);
-        self.all_stats.insert(MANA.to_owned(), mana);
-        self.all_stats.insert(VIGOR.to_owned(), vigor);
-        self.all_stats.insert(SPEED.to_owned(), speed);
-        self.all_stats.insert(BERSERK.to_owned(), berserk);
     }
 
     pub fn apply_buf_debuf_on_stats(&mut self, all_buffers: &Vec<Buffer>) {
         for buffer in all_buffers {
-            if self.all_stats.get(&buffer.stats_name).is_none() {
+            let Some(kind) = buffer.stats_name else {
                 continue;
-            }
+            };
             if !buffer.is_passive_enabled {
                 continue;
             }
-            self.set_stats_on_effect(&buffer.stats_name, buffer.value, buffer.is_percent, true);
+            self.set_stats_on_effect(kind, buffer.value, buffer.is_percent, true);
+        }
+    }
+
+    /// Scale the raw max value of the given stats by `percent` percent,
+    /// preserving the current/max ratio.
+    pub fn scale_by_percent(&mut self, kinds: &[StatKind], percent: u64) {
+        for &kind in kinds {
+            let stat = &mut self[kind];
+            let ratio = utils::calc_ratio(stat.current as i64, stat.max as i64);
+            stat.max_raw = stat.max_raw * percent / 100;
+            Self::recompute_stat_max_and_current(stat, Some(ratio));
         }
     }
 
+    /// Add a permanent boost to a stat, counted like an equipment, preserving the current/max ratio.
+    /// A negative value removes the boost.
+    pub fn update_boost_on_stat(&mut self, kind: StatKind, value: i64, is_percent: bool) {
+        let attr = &mut self[kind];
+        if is_percent {
+            attr.buf_equip_percent += value;
+        } else {
+            attr.buf_equip_value += value;
+        }
+        Self::recompute_stat_max_and_current(attr, None);
+    }
+
     /// Updates selected stats by 10% for their current raw max value when a character levels up.
     /// This applies equipment buffers and effects to those stats while preserving the current/max ratio.
     pub fn update_stats_to_next_level(&mut self) {
-        for &stat_name in STATS_TO_LEVEL_UP {
-            if let Some(stat) = self.all_stats.get_mut(stat_name) {
-                // Calculate ratio BEFORE incrementing max_raw to preserve it accurately
-                let ratio = utils::calc_ratio(stat.current as i64, stat.max as i64);
+        for &kind in STATS_TO_LEVEL_UP {
+            let stat = &mut self[kind];
+            // Calculate ratio BEFORE incrementing max_raw to preserve it accurately
+            let ratio = utils::calc_ratio(stat.current as i64, stat.max as i64);
 
-                // Update the raw max value by 10%
-                stat.max_raw += stat.max_raw * 10 / 100;
+            // Update the raw max value by 10%
+            stat.max_raw += stat.max_raw * 10 / 100;
 
-                // Recompute max and current with the new raw value and existing buffers,
-                // while preserving the current/max ratio
-                Self::recompute_stat_max_and_current(stat, Some(ratio));
-            }
+            // Recompute max and current with the new raw value and existing buffers,
+            // while preserving the current/max ratio
+            Self::recompute_stat_max_and_current(stat, Some(ratio));
         }
     }
 }
 
+/// Damages (negative `amount`) are absorbed by the `shields` (remaining amounts, consumed in order).
+/// Output: amount left after the shields, absorbed amount
+pub fn absorb_by_shields(amount: i64, shields: &mut [&mut i64]) -> (i64, i64) {
+    let mut amount = amount;
+    let mut absorbed_amount = 0;
+    if amount < 0 {
+        for shield in shields.iter_mut() {
+            let absorbed = std::cmp::min(**shield, -amount).max(0);
+            **shield -= absorbed;
+            amount += absorbed;
+            absorbed_amount += absorbed;
+        }
+    }
+    (amount, absorbed_amount)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -460,17 +606,17 @@ mod tests {
         let mut stats = Stats::default();
         stats.init();
         let mut stats2 = stats.clone();
-        stats2.get_mut_value(HP).current = 10;
-        assert_eq!(stats.get_mut_value(HP).current, 0);
-        assert_eq!(stats2.get_mut_value(HP).current, 10);
+        stats2[StatKind::Hp].current = 10;
+        assert_eq!(stats[StatKind::Hp].current, 0);
+        assert_eq!(stats2[StatKind::Hp].current, 10);
     }
 
     #[test]
     pub fn unit_stats_get_power_stat() {
         let mut stats = Stats::default();
         stats.init();
-        stats.get_mut_value(MAGICAL_POWER).current = 10;
-        stats.get_mut_value(PHYSICAL_POWER).current = 20;
+        stats[StatKind::MagicalPower].current = 10;
+        stats[StatKind::PhysicalPower].current = 20;
         assert_eq!(stats.get_power_stat(true), 10);
         assert_eq!(stats.get_power_stat(false), 20);
     }
@@ -479,19 +625,39 @@ mod tests {
     pub fn unit_stats_get_armor_stat() {
         let mut stats = Stats::default();
         stats.init();
-        stats.get_mut_value(MAGICAL_ARMOR).current = 10;
-        stats.get_mut_value(PHYSICAL_ARMOR).current = 20;
+        stats[StatKind::MagicalArmor].current = 10;
+        stats[StatKind::PhysicalArmor].current = 20;
         assert_eq!(stats.get_armor_stat(true), 10);
         assert_eq!(stats.get_armor_stat(false), 20);
     }
 
     #[test]
-    pub fn unit_stats_is_energy_stat() {
-        assert!(Stats::is_energy_stat(HP));
-        assert!(Stats::is_energy_stat(MANA));
-        assert!(Stats::is_energy_stat(VIGOR));
-        assert!(Stats::is_energy_stat(BERSERK));
-        assert!(!Stats::is_energy_stat(SPEED));
+    pub fn unit_stat_kind_is_energy() {
+        assert!(StatKind::Hp.is_energy());
+        assert!(StatKind::Mana.is_energy());
+        assert!(StatKind::Vigor.is_energy());
+        assert!(StatKind::Berserk.is_energy());
+        assert!(!StatKind::Speed.is_energy());
+    }
+
+    #[test]
+    fn unit_stat_kind_serde() {
+        assert_eq!(
+            serde_json::to_string(&StatKind::MagicalArmor).unwrap(),
+            "\"Magic armor\""
+        );
+        let kind: StatKind = serde_json::from_str("\"Magical power\"").unwrap();
+        assert_eq!(kind, StatKind::MagicalPower);
+        assert!(serde_json::from_str::<StatKind>("\"Unknown\"").is_err());
+
+        let buf: Buffer = serde_json::from_str(r#"{"stats-name": ""}"#).unwrap();
+        assert_eq!(buf.stats_name, None);
+        let buf: Buffer = serde_json::from_str(r#"{"stats-name": "HP"}"#).unwrap();
+        assert_eq!(buf.stats_name, Some(StatKind::Hp));
+        assert_eq!(
+            serde_json::to_value(&buf).unwrap()["stats-name"],
+            serde_json::json!("HP")
+        );
     }
 
     #[test]
@@ -514,10 +680,9 @@ mod tests {
     fn unit_is_dead() {
         let mut stats = Stats::default();
         stats.init();
-        assert!(stats.is_dead().is_some());
-        assert!(stats.is_dead().unwrap());
-        stats.all_stats.get_mut(HP).unwrap().current = 15;
-        assert!(!stats.is_dead().unwrap());
+        assert!(stats.is_dead());
+        stats[StatKind::Hp].current = 15;
+        assert!(!stats.is_dead());
     }
 
     #[test]
@@ -525,16 +690,16 @@ mod tests {
         let mut stats = Stats::default();
         stats.init();
 
-        let overhead = stats.modify_stat_current(HP, 10);
-        assert_eq!(stats.all_stats[HP].current, 10);
+        let overhead = stats.modify_stat_current(StatKind::Hp, 10);
+        assert_eq!(stats[StatKind::Hp].current, 10);
         assert_eq!(overhead, 0);
 
-        let overhead = stats.modify_stat_current(HP, -5);
-        assert_eq!(stats.all_stats[HP].current, 5);
+        let overhead = stats.modify_stat_current(StatKind::Hp, -5);
+        assert_eq!(stats[StatKind::Hp].current, 5);
         assert_eq!(overhead, 0);
 
-        let overhead = stats.modify_stat_current(HP, -10);
-        assert_eq!(stats.all_stats[HP].current, 0);
+        let overhead = stats.modify_stat_current(StatKind::Hp, -10);
+        assert_eq!(stats[StatKind::Hp].current, 0);
         assert_eq!(overhead, 0);
     }
 
@@ -549,34 +714,35 @@ mod tests {
         );
         assert!(c.is_ok());
         let mut c = c.unwrap();
-        c.stats.set_stats_on_effect(HP, -10, false, true);
-        assert_eq!(125, c.stats.all_stats[HP].max);
-        assert_eq!(1, c.stats.all_stats[HP].current);
-        c.stats.set_stats_on_effect(HP, 10, false, true);
-        assert_eq!(135, c.stats.all_stats[HP].max);
-        assert_eq!(1, c.stats.all_stats[HP].current);
-        c.stats.set_stats_on_effect(HP, 10, false, true);
-        assert_eq!(145, c.stats.all_stats[HP].max);
-        assert_eq!(1, c.stats.all_stats[HP].current);
-        c.stats.set_stats_on_effect(HP, -10, false, true);
-        assert_eq!(135, c.stats.all_stats[HP].max);
-        assert_eq!(1, c.stats.all_stats[HP].current);
-        c.stats.set_stats_on_effect(HP, 10, true, true);
-        assert_eq!(148, c.stats.all_stats[HP].max);
-        assert_eq!(1, c.stats.all_stats[HP].current);
-        c.stats.set_stats_on_effect(HP, -10, true, true);
-        assert_eq!(135, c.stats.all_stats[HP].max);
-        assert_eq!(1, c.stats.all_stats[HP].current);
+        c.stats.set_stats_on_effect(StatKind::Hp, -10, false, true);
+        assert_eq!(125, c.stats[StatKind::Hp].max);
+        assert_eq!(1, c.stats[StatKind::Hp].current);
+        c.stats.set_stats_on_effect(StatKind::Hp, 10, false, true);
+        assert_eq!(135, c.stats[StatKind::Hp].max);
+        assert_eq!(1, c.stats[StatKind::Hp].current);
+        c.stats.set_stats_on_effect(StatKind::Hp, 10, false, true);
+        assert_eq!(145, c.stats[StatKind::Hp].max);
+        assert_eq!(1, c.stats[StatKind::Hp].current);
+        c.stats.set_stats_on_effect(StatKind::Hp, -10, false, true);
+        assert_eq!(135, c.stats[StatKind::Hp].max);
+        assert_eq!(1, c.stats[StatKind::Hp].current);
+        c.stats.set_stats_on_effect(StatKind::Hp, 10, true, true);
+        assert_eq!(148, c.stats[StatKind::Hp].max);
+        assert_eq!(1, c.stats[StatKind::Hp].current);
+        c.stats.set_stats_on_effect(StatKind::Hp, -10, true, true);
+        assert_eq!(135, c.stats[StatKind::Hp].max);
+        assert_eq!(1, c.stats[StatKind::Hp].current);
         // test raw max = 0, nothing change
-        c.stats.all_stats[HP].max_raw = 0;
-        assert_eq!(135, c.stats.all_stats[HP].max);
-        assert_eq!(1, c.stats.all_stats[HP].current);
-        c.stats.set_stats_on_effect(DODGE, 0, false, true);
-        assert_eq!(29, c.stats.all_stats[DODGE].max);
-        assert_eq!(29, c.stats.all_stats[DODGE].current);
-        c.stats.set_stats_on_effect(DODGE, 10, false, true);
-        assert_eq!(39, c.stats.all_stats[DODGE].max);
-        assert_eq!(39, c.stats.all_stats[DODGE].current);
+        c.stats[StatKind::Hp].max_raw = 0;
+        assert_eq!(135, c.stats[StatKind::Hp].max);
+        assert_eq!(1, c.stats[StatKind::Hp].current);
+        c.stats.set_stats_on_effect(StatKind::Dodge, 0, false, true);
+        assert_eq!(29, c.stats[StatKind::Dodge].max);
+        assert_eq!(29, c.stats[StatKind::Dodge].current);
+        c.stats
+            .set_stats_on_effect(StatKind::Dodge, 10, false, true);
+        assert_eq!(39, c.stats[StatKind::Dodge].max);
+        assert_eq!(39, c.stats[StatKind::Dodge].current);
     }
 
     #[test]
@@ -588,30 +754,20 @@ mod tests {
             ..Default::default()
         };
         let mut stats = Stats::default();
-        stats.all_stats.insert(BERSERK.to_string(), stat.clone());
-        let equipment = Equipment {
-            stats: Stats {
-                all_stats: vec![(
-                    BERSERK.to_string(),
-                    Attribute {
-                        buf_equip_value: 10,
-                        buf_equip_percent: 10,
-                        ..Default::default()
-                    },
-                )]
-                .into_iter()
-                .collect(),
-                ..Default::default()
-            },
+        stats[StatKind::Berserk] = stat.clone();
+        let mut equipment = Equipment::default();
+        equipment.stats[StatKind::Berserk] = Attribute {
+            buf_equip_value: 10,
+            buf_equip_percent: 10,
             ..Default::default()
         };
         stats.apply_equipment_on_stats(&vec![equipment]);
-        assert_eq!(120, stats.all_stats[BERSERK].max);
-        assert_eq!(120, stats.all_stats[BERSERK].current);
-        assert_eq!(100, stats.all_stats[BERSERK].max_raw);
-        assert_eq!(10, stats.all_stats[BERSERK].buf_equip_value);
-        assert_eq!(10, stats.all_stats[BERSERK].buf_equip_percent);
-        assert_eq!(0, stats.all_stats[BERSERK].current_raw);
+        assert_eq!(120, stats[StatKind::Berserk].max);
+        assert_eq!(120, stats[StatKind::Berserk].current);
+        assert_eq!(100, stats[StatKind::Berserk].max_raw);
+        assert_eq!(10, stats[StatKind::Berserk].buf_equip_value);
+        assert_eq!(10, stats[StatKind::Berserk].buf_equip_percent);
+        assert_eq!(0, stats[StatKind::Berserk].current_raw);
     }
 
     #[test]
@@ -638,7 +794,7 @@ mod tests {
             expected_sum += (i as i64 + 1) * 10;
         }
 
-        assert_eq!(expected_sum, stats.all_stats[AGGRO].current as i64);
+        assert_eq!(expected_sum, stats[StatKind::Aggro].current as i64);
     }
 
     #[test]
@@ -650,13 +806,35 @@ mod tests {
             &testing_all_equipment(),
         )
         .unwrap();
-        let old_hp = c.stats.all_stats[HP].current;
+        let old_hp = c.stats[StatKind::Hp].current;
         let result = c.stats.update_hp_process_real_amount(
             &build_dmg_effect_individual().input_effect_param,
-            -(c.stats.all_stats[HP].current as i64) - 10,
+            -(c.stats[StatKind::Hp].current as i64) - 10,
+            &mut [],
         );
         // real amount cannot excess the life of the character
-        assert_eq!(result, -(old_hp as i64));
+        assert_eq!(result, (-(old_hp as i64), 0));
+
+        // the shields are consumed in order before HP
+        c.stats[StatKind::Hp].current = 100;
+        let (mut shield1, mut shield2) = (30, 50);
+        let result = c.stats.update_hp_process_real_amount(
+            &build_dmg_effect_individual().input_effect_param,
+            -100,
+            &mut [&mut shield1, &mut shield2],
+        );
+        assert_eq!(result, (-20, 80));
+        assert_eq!((shield1, shield2), (0, 0));
+        assert_eq!(c.stats[StatKind::Hp].current, 80);
+        // heals are not absorbed
+        let mut shield = 10;
+        let result = c.stats.update_hp_process_real_amount(
+            &build_dmg_effect_individual().input_effect_param,
+            5,
+            &mut [&mut shield],
+        );
+        assert_eq!(result, (5, 0));
+        assert_eq!(shield, 10);
     }
 
     #[test]
@@ -673,22 +851,22 @@ mod tests {
 
         // Check that level-up stats have increased their raw max value by 10%
         for &stat_name in STATS_TO_LEVEL_UP {
-            let old_raw_max = old_stats.all_stats[stat_name].max_raw;
+            let old_raw_max = old_stats[stat_name].max_raw;
             let expected_raw_max = old_raw_max + old_raw_max * 10 / 100;
             assert_eq!(
-                expected_raw_max, c.stats.all_stats[stat_name].max_raw,
+                expected_raw_max, c.stats[stat_name].max_raw,
                 "Raw max for {} should increase by 10%",
                 stat_name
             );
 
             // Check that current/max ratio was preserved (allowing small rounding error)
             let old_ratio = utils::calc_ratio(
-                old_stats.all_stats[stat_name].current as i64,
-                old_stats.all_stats[stat_name].max as i64,
+                old_stats[stat_name].current as i64,
+                old_stats[stat_name].max as i64,
             );
             let new_ratio = utils::calc_ratio(
-                c.stats.all_stats[stat_name].current as i64,
-                c.stats.all_stats[stat_name].max as i64,
+                c.stats[stat_name].current as i64,
+                c.stats[stat_name].max as i64,
             );
             assert!(
                 (new_ratio - old_ratio).abs() < 0.01,
@@ -699,12 +877,12 @@ mod tests {
 
         // Non-level-up stats should not change
         assert_eq!(
-            old_stats.all_stats[DODGE].max_raw,
-            c.stats.all_stats[DODGE].max_raw
+            old_stats[StatKind::Dodge].max_raw,
+            c.stats[StatKind::Dodge].max_raw
         );
         assert_eq!(
-            old_stats.all_stats[CRITICAL_STRIKE].max_raw,
-            c.stats.all_stats[CRITICAL_STRIKE].max_raw
+            old_stats[StatKind::CriticalStrike].max_raw,
+            c.stats[StatKind::CriticalStrike].max_raw
         );
     }
 
@@ -714,43 +892,40 @@ mod tests {
         stats.init();
 
         // Ensure HP has a non-zero max to avoid divide-by-zero in apply_regen
-        stats.get_mut_value(HP).max = 100;
-        stats.get_mut_value(HP).max_raw = 100;
-        stats.get_mut_value(HP).current = 100;
+        stats[StatKind::Hp].max = 100;
+        stats[StatKind::Hp].max_raw = 100;
+        stats[StatKind::Hp].current = 100;
 
         // Set up speed with a fixed max and a regen value
-        stats.get_mut_value(SPEED).max = 100;
-        stats.get_mut_value(SPEED).max_raw = 100;
-        stats.get_mut_value(SPEED).current = 10;
-        stats.all_stats.get_mut(SPEED_REGEN).unwrap().current = 15;
+        stats[StatKind::Speed].max = 100;
+        stats[StatKind::Speed].max_raw = 100;
+        stats[StatKind::Speed].current = 10;
+        stats[StatKind::SpeedRegen].current = 15;
 
-        let speed_max_before = stats.all_stats[SPEED].max;
-        let speed_max_raw_before = stats.all_stats[SPEED].max_raw;
+        let speed_max_before = stats[StatKind::Speed].max;
+        let speed_max_raw_before = stats[StatKind::Speed].max_raw;
 
         stats.apply_regen();
 
         // current should have increased by the regen amount
         assert_eq!(
-            25, stats.all_stats[SPEED].current,
+            25,
+            stats[StatKind::Speed].current,
             "Speed current should increase by regen"
         );
         // max and max_raw must NOT be modified by regen
         assert_eq!(
-            speed_max_before, stats.all_stats[SPEED].max,
+            speed_max_before,
+            stats[StatKind::Speed].max,
             "Speed max must not change on regen"
         );
         assert_eq!(
-            speed_max_raw_before, stats.all_stats[SPEED].max_raw,
+            speed_max_raw_before,
+            stats[StatKind::Speed].max_raw,
             "Speed max_raw must not change on regen"
         );
     }
 
-    #[test]
-    fn unit_is_dead_no_hp_stat() {
-        let stats = Stats::default();
-        assert!(stats.is_dead().is_none());
-    }
-
     #[test]
     fn unit_attribute_ord() {
         let a1 = Attribute {
@@ -769,8 +944,8 @@ mod tests {
     fn unit_reset_speed() {
         let mut stats = Stats::default();
         stats.init();
-        let initial_speed = stats.all_stats[SPEED].current;
+        let initial_speed = stats[StatKind::Speed].current;
         stats.reset_speed();
-        assert!(stats.all_stats[SPEED].current <= initial_speed);
+        assert!(stats[StatKind::Speed].current <= initial_speed);
     }
 }
@@ -78,6 +78,7 @@ mod tests {
     use crate::character_mod::{
         attack_type::{AccumulatedAtkInfo, AttackType},
         effect::EffectOutcome,
+        element::Effectiveness,
     };
 
     use super::*;
@@ -103,6 +104,9 @@ mod tests {
                 is_critical: false,
                 aggro_generated: 10,
                 debuff_removed: false,
+                effectiveness: Effectiveness::Normal,
+                absorbed_amount: 0,
+                shield_remaining: 0,
             },
             ..Default::default()
         };
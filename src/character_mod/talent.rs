@@ -0,0 +1,231 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    character_mod::{
+        buffers::Buffer, character::Character, class::Class, effect::EffectParam, stats::StatKind,
+    },
+    utils,
+};
+
+/// Bonus given by a talent once learnt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TalentKind {
+    /// The buffer of the effect is enabled as a passive buffer of the hero
+    PassiveEffect(EffectParam),
+    /// Permanent boost of a stat, like an equipment
+    StatBoost {
+        stat: StatKind,
+        value: i64,
+        #[serde(default)]
+        is_percent: bool,
+    },
+    /// The attack of that name can be launched without waiting for its level
+    AttackUnlock(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Talent {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub kind: TalentKind,
+    /// Names of the talents of the tree to learn before this one
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    /// Level of the hero required to learn the talent
+    #[serde(default)]
+    pub min_level: u64,
+}
+
+/// Talent tree of a class, loaded from `offlines/talents`
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TalentTree {
+    pub class: Class,
+    pub talents: Vec<Talent>,
+}
+
+/// Talent points of a hero and the talents learnt with them
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TalentProgress {
+    /// One point is granted at each level-up
+    pub unspent_points: u64,
+    /// Names of the learnt talents, in the learning order
+    pub talents: Vec<String>,
+    /// Key: attack unlocked by a talent, value: its level before the unlock
+    #[serde(default)]
+    pub unlocked_atk_levels: HashMap<String, u64>,
+}
+
+impl TalentTree {
+    pub fn try_new_from_json<P: AsRef<Path>>(path: P) -> Result<TalentTree> {
+        utils::read_from_json::<_, TalentTree>(&path)
+            .map_err(|e| anyhow!("{:?} cannot be decoded: {}", path.as_ref(), e))
+    }
+
+    pub fn get_talent(&self, name: &str) -> Option<&Talent> {
+        self.talents.iter().find(|t| t.name == name)
+    }
+
+    /// Check that the hero can spend a point on the talent:
+    /// a point left, the talent not learnt yet, its level and all its prerequisites reached.
+    pub fn check_can_learn(&self, hero: &Character, name: &str) -> Result<&Talent> {
+        let talent = self
+            .get_talent(name)
+            .ok_or_else(|| anyhow!("Unknown talent '{}' for class {:?}", name, self.class))?;
+        let progress = &hero.talents;
+        if progress.talents.iter().any(|t| t == name) {
+            bail!("{} already knows the talent '{}'", hero.id_name, name);
+        }
+        if progress.unspent_points == 0 {
+            bail!("{} has no talent point to spend", hero.id_name);
+        }
+        if hero.level < talent.min_level {
+            bail!(
+                "Talent '{}' requires the level {}, {} is level {}",
+                name,
+                talent.min_level,
+                hero.id_name,
+                hero.level
+            );
+        }
+        if let Some(missing) = talent
+            .prerequisites
+            .iter()
+            .find(|p| !progress.talents.contains(p))
+        {
+            bail!("Talent '{}' requires the talent '{}'", name, missing);
+        }
+        if let TalentKind::AttackUnlock(atk_name) = &talent.kind
+            && !hero.attacks_list.contains_key(atk_name)
+        {
+            bail!("{} has no attack '{}'", hero.id_name, atk_name);
+        }
+        Ok(talent)
+    }
+
+    /// Spend a talent point of the hero on the talent and apply its bonus
+    pub fn learn(&self, hero: &mut Character, name: &str) -> Result<()> {
+        let talent = self.check_can_learn(hero, name)?.clone();
+        apply_talent(hero, &talent, true);
+        hero.talents.unspent_points -= 1;
+        hero.talents.talents.push(talent.name);
+        Ok(())
+    }
+
+    /// Remove the bonuses of all the learnt talents and give their points back
+    pub fn reset(&self, hero: &mut Character) {
+        let learnt = std::mem::take(&mut hero.talents.talents);
+        for name in learnt.iter().rev() {
+            match self.get_talent(name) {
+                Some(talent) => apply_talent(hero, talent, false),
+                None => tracing::warn!("Unknown talent '{}' cannot be removed", name),
+            }
+        }
+        hero.talents.unspent_points += learnt.len() as u64;
+    }
+}
+
+/// Apply the bonus of the talent, or remove it if `is_learning` is false
+fn apply_talent(hero: &mut Character, talent: &Talent, is_learning: bool) {
+    let sign = if is_learning { 1 } else { -1 };
+    match &talent.kind {
+        TalentKind::PassiveEffect(ep) => {
+            let rounds_info = &mut hero.character_rounds_info;
+            rounds_info.update_buffer(&Buffer {
+                value: sign * ep.buffer.value,
+                is_passive: true,
+                is_passive_enabled: true,
+                ..ep.buffer.clone()
+            });
+            if !is_learning {
+                rounds_info
+                    .all_buffers
+                    .retain(|b| b.kind != ep.buffer.kind || b.value != 0);
+            }
+        }
+        TalentKind::StatBoost {
+            stat,
+            value,
+            is_percent,
+        } => hero
+            .stats
+            .update_boost_on_stat(*stat, sign * value, *is_percent),
+        TalentKind::AttackUnlock(atk_name) => {
+            let level = hero.level;
+            let Some(atk) = hero.attacks_list.get_mut(atk_name) else {
+                return;
+            };
+            if is_learning {
+                hero.talents
+                    .unlocked_atk_levels
+                    .insert(atk_name.clone(), atk.level);
+                atk.level = atk.level.min(level);
+                if hero.loadout.is_enabled() {
+                    hero.loadout.learn(atk_name);
+                }
+            } else if let Some(old_level) = hero.talents.unlocked_atk_levels.remove(atk_name) {
+                atk.level = old_level;
+                if old_level > level {
+                    hero.loadout.forget(atk_name);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        character_mod::buffers::BufKinds,
+        common::constants::paths_const::{OFFLINE_TALENTS, TEST_OFFLINE_ROOT},
+        testing::testing_all_characters::testing_pm,
+    };
+
+    #[test]
+    fn unit_talent_tree() {
+        let path = TEST_OFFLINE_ROOT
+            .join(*OFFLINE_TALENTS)
+            .join("standard.json");
+        let tree = TalentTree::try_new_from_json(path).unwrap();
+        let mut hero = testing_pm().active_heroes[0].clone();
+        let hp = hero.stats[StatKind::Hp].max;
+        assert!(tree.learn(&mut hero, "Vitality").is_err());
+
+        hero.talents.unspent_points = 3;
+        // prerequisite, level and unknown talent
+        assert!(tree.learn(&mut hero, "Fury").is_err());
+        assert!(tree.learn(&mut hero, "Endurance").is_err());
+        assert!(tree.learn(&mut hero, "unknown").is_err());
+
+        tree.learn(&mut hero, "Vitality").unwrap();
+        assert_eq!(hero.stats[StatKind::Hp].max, hp + 20);
+        assert!(tree.learn(&mut hero, "Vitality").is_err());
+        tree.learn(&mut hero, "Fury").unwrap();
+        let buffer = hero
+            .character_rounds_info
+            .get_buffer_by_type(&BufKinds::DamageTxPercent)
+            .unwrap();
+        assert!(buffer.is_passive && buffer.is_passive_enabled);
+        assert_eq!(buffer.value, 5);
+        assert_eq!(hero.attacks_list["Offrande vitale"].level, 2);
+        tree.learn(&mut hero, "Early offering").unwrap();
+        assert_eq!(hero.attacks_list["Offrande vitale"].level, 1);
+        assert_eq!(hero.talents.unspent_points, 0);
+
+        tree.reset(&mut hero);
+        assert_eq!(hero.talents.unspent_points, 3);
+        assert!(hero.talents.talents.is_empty());
+        assert_eq!(hero.stats[StatKind::Hp].max, hp);
+        assert_eq!(hero.attacks_list["Offrande vitale"].level, 2);
+        assert!(
+            hero.character_rounds_info
+                .get_buffer_by_type(&BufKinds::DamageTxPercent)
+                .is_none()
+        );
+    }
+}
@@ -1,11 +1,49 @@
-use indexmap::IndexSet;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    character_mod::character::CharacterKind,
+    character_mod::character::{Character, CharacterKind},
+    character_mod::class::Class,
     character_mod::effect::EffectParam,
-    common::constants::{all_target_const::*, reach_const::ZONE},
+    character_mod::rounds_information::CharacterRoundsInfo,
+    character_mod::stats::StatKind,
+    common::game_rng::GameRng,
 };
 
+/// Target of an attack or of an effect, decoded from the `Cible` key
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Target {
+    #[serde(rename = "Enemy")]
+    Enemy,
+    #[default]
+    #[serde(rename = "Ally")]
+    Ally,
+    #[serde(rename = "All allies")]
+    AllAllies,
+    /// The launcher only
+    #[serde(rename = "Self")]
+    Himself,
+    /// Any ally except the launcher
+    #[serde(rename = "Only allies")]
+    OnlyAllies,
+}
+
+impl Target {
+    pub fn is_ally(&self) -> bool {
+        *self != Target::Enemy
+    }
+}
+
+/// Reach of an attack or of an effect, decoded from the `Portée` key
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Reach {
+    #[default]
+    #[serde(rename = "Individual")]
+    Individual,
+    #[serde(rename = "Zone")]
+    Zone,
+}
+
 /// Define all the parameters of target info during a round
 #[derive(Default, Debug, Clone)]
 pub struct TargetData {
@@ -19,7 +57,7 @@ pub struct TargetData {
 impl TargetData {
     pub fn is_potential_target_on_effect(&self) -> bool {
         let is_ally = self.launcher_chara_kind == self.target_chara_kind;
-        if self.effect_param.target_kind == TARGET_HIMSELF
+        if self.effect_param.target_kind == Target::Himself
             && self.launcher_id_name != self.target_id_name
         {
             tracing::debug!(
@@ -29,7 +67,7 @@ impl TargetData {
             );
             return false;
         }
-        if self.effect_param.target_kind == TARGET_ONLY_ALLY
+        if self.effect_param.target_kind == Target::OnlyAllies
             && self.launcher_id_name == self.target_id_name
         {
             tracing::debug!(
@@ -39,7 +77,7 @@ impl TargetData {
             );
             return false;
         }
-        if !is_ally && is_target_ally(&self.effect_param.target_kind) {
+        if !is_ally && self.effect_param.target_kind.is_ally() {
             tracing::debug!(
                 "Effect {} cannot be applied on {} because the target is ally but launcher is ennemy.",
                 self.effect_param.buffer.kind,
@@ -47,7 +85,7 @@ impl TargetData {
             );
             return false;
         }
-        if is_ally && self.effect_param.target_kind == TARGET_ENNEMY {
+        if is_ally && self.effect_param.target_kind == Target::Enemy {
             tracing::debug!(
                 "Effect {} cannot be applied on {} because the target is ennemy but launcher is ally.",
                 self.effect_param.buffer.kind,
@@ -55,8 +93,8 @@ impl TargetData {
             );
             return false;
         }
-        if self.effect_param.target_kind == TARGET_ALLY
-            && self.effect_param.reach == ZONE
+        if self.effect_param.target_kind == Target::Ally
+            && self.effect_param.reach == Reach::Zone
             && self.launcher_id_name == self.target_id_name
         {
             tracing::debug!(
@@ -70,17 +108,96 @@ impl TargetData {
     }
 }
 
-pub fn is_target_ally(target: &str) -> bool {
-    let targets: IndexSet<&str> = [
-        TARGET_ALLY,
-        TARGET_ALL_ALLIES,
-        TARGET_HIMSELF,
-        TARGET_ONLY_ALLY,
-    ]
-    .iter()
-    .cloned()
-    .collect();
-    targets.contains(target)
+/// How a boss chooses the hero it targets
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetingStrategy {
+    #[default]
+    HighestAggro,
+    LowestHp,
+    LowestHpPercent,
+    /// Highest threat healer, highest threat hero if no healer is alive
+    HealerFirst,
+    /// Random hero, with a chance proportional to its threat
+    RandomByAggro,
+    /// Highest threat hero, except the previous target if another hero is alive
+    Spread,
+    MostHots,
+}
+
+impl TargetingStrategy {
+    /// Return the id_name of the living hero chosen by the strategy.
+    /// `threat` gives the threat of a hero id_name against the boss.
+    /// `last_target` is the id_name of the previous target of the boss.
+    /// Without `rng`, `RandomByAggro` cannot be predicted and returns None.
+    pub fn choose_target(
+        &self,
+        heroes: &[Character],
+        threat: &dyn Fn(&str) -> u64,
+        last_target: &str,
+        rng: Option<&mut GameRng>,
+    ) -> Option<String> {
+        let alive: Vec<&Character> = heroes.iter().filter(|h| !h.stats.is_dead()).collect();
+        let threat = |h: &Character| threat(&h.id_name);
+        let highest_threat = || alive.iter().max_by_key(|h| threat(h)).copied();
+        let hero = match self {
+            TargetingStrategy::HighestAggro => highest_threat(),
+            TargetingStrategy::LowestHp => alive
+                .iter()
+                .min_by_key(|h| h.stats[StatKind::Hp].current)
+                .copied(),
+            TargetingStrategy::LowestHpPercent => alive
+                .iter()
+                .min_by_key(|h| {
+                    let hp = &h.stats[StatKind::Hp];
+                    hp.current * 100 / hp.max.max(1)
+                })
+                .copied(),
+            TargetingStrategy::HealerFirst => alive
+                .iter()
+                .filter(|h| h.class.is_a(&Class::Healer))
+                .max_by_key(|h| threat(h))
+                .copied()
+                .or_else(highest_threat),
+            TargetingStrategy::RandomByAggro => {
+                let rng = rng?;
+                // +1 so that a hero without threat can still be chosen
+                let total: u64 = alive.iter().map(|h| threat(h) + 1).sum();
+                if total == 0 {
+                    return None;
+                }
+                let mut roll = rng.random_range(0..total);
+                alive
+                    .iter()
+                    .find(|h| {
+                        let weight = threat(h) + 1;
+                        if roll < weight {
+                            return true;
+                        }
+                        roll -= weight;
+                        false
+                    })
+                    .copied()
+            }
+            TargetingStrategy::Spread => alive
+                .iter()
+                .filter(|h| h.id_name != last_target)
+                .max_by_key(|h| threat(h))
+                .copied()
+                .or_else(highest_threat),
+            TargetingStrategy::MostHots => alive
+                .iter()
+                .max_by_key(|h| {
+                    CharacterRoundsInfo::get_hot_and_buf_nbs_txts(
+                        &h.character_rounds_info.all_effects,
+                        &h.character_rounds_info.ailments,
+                    )
+                    .hot_nb
+                })
+                .copied(),
+        };
+        hero.map(|h| h.id_name.clone())
+    }
 }
 
 #[cfg(test)]
@@ -88,11 +205,10 @@ mod tests {
     use crate::character_mod::effect::EffectParam;
 
     use super::*;
-    use crate::common::constants::reach_const::ZONE;
 
     fn make_target(
-        target_kind: &str,
-        reach: &str,
+        target_kind: Target,
+        reach: Reach,
         launcher_id: &str,
         target_id: &str,
         launcher_kind: CharacterKind,
@@ -104,8 +220,8 @@ mod tests {
             launcher_chara_kind: launcher_kind,
             target_chara_kind: target_kind_chara,
             effect_param: EffectParam {
-                target_kind: target_kind.to_string(),
-                reach: reach.to_string(),
+                target_kind,
+                reach,
                 ..Default::default()
             },
         }
@@ -114,8 +230,8 @@ mod tests {
     #[test]
     fn unit_is_potential_target_himself_wrong_id() {
         let td = make_target(
-            TARGET_HIMSELF,
-            "",
+            Target::Himself,
+            Reach::Individual,
             "hero1",
             "hero2",
             CharacterKind::Hero,
@@ -127,8 +243,8 @@ mod tests {
     #[test]
     fn unit_is_potential_target_himself_same_id() {
         let td = make_target(
-            TARGET_HIMSELF,
-            "",
+            Target::Himself,
+            Reach::Individual,
             "hero1",
             "hero1",
             CharacterKind::Hero,
@@ -140,8 +256,8 @@ mod tests {
     #[test]
     fn unit_is_potential_target_only_ally_same_id() {
         let td = make_target(
-            TARGET_ONLY_ALLY,
-            "",
+            Target::OnlyAllies,
+            Reach::Individual,
             "hero1",
             "hero1",
             CharacterKind::Hero,
@@ -153,8 +269,8 @@ mod tests {
     #[test]
     fn unit_is_potential_target_only_ally_diff_id() {
         let td = make_target(
-            TARGET_ONLY_ALLY,
-            "",
+            Target::OnlyAllies,
+            Reach::Individual,
             "hero1",
             "hero2",
             CharacterKind::Hero,
@@ -166,8 +282,8 @@ mod tests {
     #[test]
     fn unit_is_potential_target_ally_effect_on_enemy() {
         let td = make_target(
-            TARGET_ALLY,
-            "",
+            Target::Ally,
+            Reach::Individual,
             "hero1",
             "boss1",
             CharacterKind::Hero,
@@ -179,8 +295,8 @@ mod tests {
     #[test]
     fn unit_is_potential_target_enemy_effect_on_ally() {
         let td = make_target(
-            TARGET_ENNEMY,
-            "",
+            Target::Enemy,
+            Reach::Individual,
             "hero1",
             "hero2",
             CharacterKind::Hero,
@@ -192,8 +308,8 @@ mod tests {
     #[test]
     fn unit_is_potential_target_ally_zone_self() {
         let td = make_target(
-            TARGET_ALLY,
-            ZONE,
+            Target::Ally,
+            Reach::Zone,
             "hero1",
             "hero1",
             CharacterKind::Hero,
@@ -204,10 +320,91 @@ mod tests {
 
     #[test]
     fn unit_is_target_ally() {
-        assert!(is_target_ally(TARGET_ALLY));
-        assert!(is_target_ally(TARGET_ALL_ALLIES));
-        assert!(is_target_ally(TARGET_HIMSELF));
-        assert!(is_target_ally(TARGET_ONLY_ALLY));
-        assert!(!is_target_ally(TARGET_ENNEMY));
+        assert!(Target::Ally.is_ally());
+        assert!(Target::AllAllies.is_ally());
+        assert!(Target::Himself.is_ally());
+        assert!(Target::OnlyAllies.is_ally());
+        assert!(!Target::Enemy.is_ally());
+    }
+
+    #[test]
+    fn unit_target_reach_serde() {
+        let t: Target = serde_json::from_str("\"All allies\"").unwrap();
+        assert_eq!(t, Target::AllAllies);
+        assert_eq!(serde_json::to_string(&Target::Himself).unwrap(), "\"Self\"");
+        let r: Reach = serde_json::from_str("\"Zone\"").unwrap();
+        assert_eq!(r, Reach::Zone);
+        // unknown values are load errors
+        assert!(serde_json::from_str::<Target>("\"Foe\"").is_err());
+        assert!(serde_json::from_str::<Reach>("\"Aleatoire Individuel\"").is_err());
+    }
+
+    #[test]
+    fn unit_targeting_strategy_choose_target() {
+        use crate::testing::testing_all_characters::testing_pm;
+
+        let mut heroes = testing_pm().active_heroes;
+        assert!(heroes.len() > 1);
+        let (id0, id1) = (heroes[0].id_name.clone(), heroes[1].id_name.clone());
+        heroes[0].stats[StatKind::Hp].current = 100;
+        heroes[0].class = Class::Warrior;
+        heroes[1].stats[StatKind::Hp].current = 50;
+        heroes[1].class = Class::Healer;
+        let threat = |id_name: &str| if id_name == id0 { 50 } else { 10 };
+
+        let choose = |s: TargetingStrategy, heroes: &[Character], last: &str| {
+            s.choose_target(heroes, &threat, last, Some(&mut GameRng::new(1)))
+        };
+        assert_eq!(
+            choose(TargetingStrategy::HighestAggro, &heroes, ""),
+            Some(id0.clone())
+        );
+        assert_eq!(
+            choose(TargetingStrategy::LowestHp, &heroes, ""),
+            Some(id1.clone())
+        );
+        assert_eq!(
+            choose(TargetingStrategy::LowestHpPercent, &heroes, ""),
+            Some(id1.clone())
+        );
+        assert_eq!(
+            choose(TargetingStrategy::HealerFirst, &heroes, ""),
+            Some(id1.clone())
+        );
+        // spread avoids the previous target
+        assert_eq!(
+            choose(TargetingStrategy::Spread, &heroes, &id0),
+            Some(id1.clone())
+        );
+        assert_eq!(
+            choose(TargetingStrategy::Spread, &heroes, &id1),
+            Some(id0.clone())
+        );
+        // random strategy can only be predicted with a rng
+        assert!(choose(TargetingStrategy::RandomByAggro, &heroes, "").is_some());
+        assert_eq!(
+            TargetingStrategy::RandomByAggro.choose_target(&heroes, &threat, "", None),
+            None
+        );
+        // dead heroes are never chosen
+        heroes[1].stats[StatKind::Hp].current = 0;
+        for strategy in [
+            TargetingStrategy::LowestHp,
+            TargetingStrategy::HealerFirst,
+            TargetingStrategy::Spread,
+            TargetingStrategy::RandomByAggro,
+        ] {
+            assert_eq!(choose(strategy, &heroes, &id0), Some(id0.clone()));
+        }
+    }
+
+    #[test]
+    fn unit_targeting_strategy_serde() {
+        let s: TargetingStrategy = serde_json::from_str("\"lowest_hp_percent\"").unwrap();
+        assert_eq!(s, TargetingStrategy::LowestHpPercent);
+        assert_eq!(
+            TargetingStrategy::default(),
+            TargetingStrategy::HighestAggro
+        );
     }
 }
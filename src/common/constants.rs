@@ -1,51 +1,3 @@
-/// Define the kind of target
-pub mod all_target_const {
-    pub const TARGET_ENNEMY: &str = "Enemy";
-    pub const TARGET_ALLY: &str = "Ally";
-    pub const TARGET_ALL_ALLIES: &str = "All allies";
-    pub const TARGET_HIMSELF: &str = "Self";
-    pub const TARGET_ONLY_ALLY: &str = "Only allies";
-}
-
-pub mod reach_const {
-    pub const INDIVIDUAL: &str = "Individual";
-    pub const ZONE: &str = "Zone";
-}
-
-/// Define all the stats of a character you can decode from JSON format
-pub mod stats_const {
-    pub const HP: &str = "HP";
-    pub const MANA: &str = "Mana";
-    pub const VIGOR: &str = "Vigor";
-    pub const BERSERK: &str = "Berserk";
-    pub const PHYSICAL_ARMOR: &str = "Physical armor";
-    pub const MAGICAL_ARMOR: &str = "Magic armor";
-    pub const PHYSICAL_POWER: &str = "Physical power";
-    pub const MAGICAL_POWER: &str = "Magic power";
-    pub const AGGRO: &str = "Aggro";
-    pub const SPEED: &str = "Speed";
-    pub const CRITICAL_STRIKE: &str = "Critical strike";
-    pub const DODGE: &str = "Dodge";
-    pub const HP_REGEN: &str = "HP regeneration";
-    pub const MANA_REGEN: &str = "Mana regeneration";
-    pub const VIGOR_REGEN: &str = "Vigor regeneration";
-    pub const BERSECK_RATE: &str = "Berserk rate";
-    pub const AGGRO_RATE: &str = "Aggro rate";
-    pub const SPEED_REGEN: &str = "Speed regeneration";
-
-    /// Stats that level up when a character gains a level (increases by 10% of raw max value)
-    pub const STATS_TO_LEVEL_UP: &[&str] = &[
-        HP,
-        MANA,
-        VIGOR,
-        PHYSICAL_POWER,
-        MAGICAL_POWER,
-        PHYSICAL_ARMOR,
-        MAGICAL_ARMOR,
-        SPEED,
-    ];
-}
-
 /// Defines all the keys except stats you can decode from the JSON input
 pub mod character_json_key {
     pub const IS_CRIT_HEAL_AFTER_CRIT: &str = "is_crit_heal_after_crit";
@@ -69,7 +21,17 @@ pub mod character_json_key {
 pub mod character_const {
     pub const SPEED_THRESHOLD: u64 = 100;
     pub const NB_TURN_SUM_AGGRO: usize = 5;
+    /// Percentage of the threat against the bosses lost at each new turn
+    pub const THREAT_DECAY_PERCENT: u64 = 10;
+    /// HP changed for one point of aggro or threat
+    pub const AGGRO_NORM: f64 = 20.0;
     pub const ULTIMATE_LEVEL: u64 = 13;
+    /// Percentage of the max HP under which the low HP passives are enabled
+    pub const LOW_HP_THRESHOLD_PERCENT: u64 = 30;
+    /// Number of turns of the shield given by the overheal of a passive
+    pub const OVERHEAL_SHIELD_NB_TURNS: i64 = 2;
+    /// Level from which a hero of a base class can choose a specialisation
+    pub const SPECIALISATION_LEVEL: u64 = 5;
 }
 
 pub mod paths_const {
@@ -91,6 +53,12 @@ pub mod paths_const {
         pub static ref OFFLINE_GAMESTATE: &'static Path = Path::new("game_state");
         pub static ref OFFLINE_SCENARIOS: &'static Path = Path::new("scenarios");
         pub static ref OFFLINE_MAPS: &'static Path = Path::new("maps");
+        /// Type-effectiveness table of the elements
+        pub static ref OFFLINE_TYPE_CHART: &'static Path = Path::new("type_chart.json");
+        /// One talent tree per class
+        pub static ref OFFLINE_TALENTS: &'static Path = Path::new("talents");
+        /// Innate passive of each class
+        pub static ref OFFLINE_CLASS_PASSIVES: &'static Path = Path::new("class_passives.json");
         pub static ref GAME_STATE_STATS_IN_GAME: &'static Path = Path::new("/stats_in_game_{}.csv");
     }
 }
@@ -98,6 +66,9 @@ pub mod paths_const {
 pub mod core_game_data_const {
     pub const SAVED_CORE_GAME_DATA: &str = "core_game_data.json";
     pub const SAVED_CORE_GAME_DATA_REPLAY: &str = "core_game_data_replay.json";
+    /// Version of the save file format, to bump with a new migration
+    /// each time a change of `CoreGameData` breaks the previous saves
+    pub const SAVE_SCHEMA_VERSION: u32 = 5;
 }
 
 pub mod attak_const {
@@ -118,6 +89,33 @@ pub mod streak_breaker_const {
     pub const STREAK_BREAKER_INTERMEDIATE: u32 = 8;
 }
 
+/// Generation of the scenarios of the endless mode
+pub mod procgen_const {
+    /// Difficulty budget of a generated scenario of level 1
+    pub const PROCGEN_BASE_BUDGET: u64 = 2;
+    /// Levels needed to add one point to the difficulty budget
+    pub const PROCGEN_LEVELS_PER_BUDGET: u64 = 2;
+    pub const PROCGEN_MAX_BOSSES: usize = 3;
+    /// Currency loot of a generated scenario, per level
+    pub const PROCGEN_GOLD_PER_LEVEL: i64 = 50;
+    /// Minimum level of the equipment loots of rank Intermediate and Advanced
+    pub const PROCGEN_INTERMEDIATE_LEVEL: u64 = 5;
+    pub const PROCGEN_ADVANCED_LEVEL: u64 = 10;
+}
+
+/// Roguelike run mode
+pub mod run_const {
+    /// Number of generated scenarios of a run
+    pub const RUN_NB_SCENARIOS: u64 = 10;
+    /// Number of rewards offered after each won scenario
+    pub const RUN_NB_OFFERS: usize = 3;
+    /// Percentage added to a stat by a blessing
+    pub const RUN_BLESSING_PERCENT: u64 = 10;
+    pub const RUN_SCORE_PER_SCENARIO: u64 = 100;
+    pub const RUN_SCORE_PER_KILL: u64 = 10;
+    pub const RUN_SCORE_PER_HERO_ALIVE: u64 = 50;
+}
+
 pub mod emoji_const {
     pub const EMOJI_HEAL: &str = "❤️";
     pub const EMOJI_DAMAGE: &str = "⚔️";
@@ -1,3 +1,4 @@
 pub mod constants;
+pub mod game_rng;
 pub mod log_data;
 pub mod overworld;
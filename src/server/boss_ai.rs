@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+
+use crate::character_mod::{
+    buffers::BufKinds, character::Character, effect::EffectParam,
+    rounds_information::CharacterRoundsInfo, stats::StatKind, target::TargetingStrategy,
+};
+
+/// Condition of a boss rule, evaluated at the start of the boss action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BossCondition {
+    /// Current value of a boss stat is strictly below `percent` % of its max
+    StatBelowPercent { stat: StatKind, percent: u64 },
+    /// Current value of a boss stat is strictly above `percent` % of its max
+    StatAbovePercent { stat: StatKind, percent: u64 },
+    /// Current turn number is greater or equal
+    TurnAtLeast(usize),
+    /// Current turn number is a multiple of the value
+    TurnEvery(usize),
+    /// The boss has an enabled buffer of that kind
+    HasBuffer(BufKinds),
+    /// At least one living hero has strictly more HoTs than the value
+    HeroHotsAbove(u64),
+}
+
+/// Rule of a boss AI: when all the conditions hold, the boss launches `atk_name`.
+/// Rules are evaluated in order, the first one matching is used.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BossRule {
+    /// All the conditions must hold, an empty list always matches
+    #[serde(default)]
+    pub conditions: Vec<BossCondition>,
+    pub atk_name: String,
+    /// Targeting of the rule attack, the boss strategy is used otherwise
+    #[serde(default)]
+    pub focus: Option<TargetingStrategy>,
+    /// The rule can only be used once per fight (e.g. enrage)
+    #[serde(default)]
+    pub once: bool,
+}
+
+/// Phase of a multi-phase boss fight, entered once the HP of the boss fall to
+/// `hp_percent` % of its max or below.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BossPhase {
+    pub name: String,
+    pub hp_percent: u64,
+    /// Atk indexes replacing the boss pattern of the scenario, kept if empty
+    #[serde(default)]
+    pub pattern: Vec<u64>,
+    /// Effects applied on the boss when the phase starts
+    #[serde(default)]
+    pub buffs: Vec<EffectParam>,
+    /// Names of the bosses of `PlayerManager::all_bosses` joining the fight when the phase starts
+    #[serde(default)]
+    pub adds: Vec<String>,
+    /// Message logged when the phase starts, a default one is used if empty
+    #[serde(default)]
+    pub announcement: String,
+}
+
+impl BossPhase {
+    /// Message logged when the boss enters the phase
+    pub fn announcement_txt(&self, boss_id_name: &str) -> String {
+        if self.announcement.is_empty() {
+            format!("{} enters the phase {}", boss_id_name, self.name)
+        } else {
+            self.announcement.clone()
+        }
+    }
+}
+
+/// Return the index of the next phase the boss enters, if its HP crossed the threshold.
+/// `phases` are ordered by decreasing `hp_percent`, `boss.character_rounds_info.boss_phase`
+/// is the number of phases already entered. A dead boss does not change its phase.
+pub fn next_boss_phase(phases: &[BossPhase], boss: &Character) -> Option<usize> {
+    let index = boss.character_rounds_info.boss_phase;
+    let phase = phases.get(index)?;
+    if boss.stats.is_dead() {
+        return None;
+    }
+    stat_percent(boss, StatKind::Hp)
+        .is_some_and(|p| p <= phase.hp_percent)
+        .then_some(index)
+}
+
+/// Output of the boss AI
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct BossDecision {
+    /// Index of the matching rule
+    pub rule_index: usize,
+    pub atk_name: String,
+    pub focus: Option<TargetingStrategy>,
+}
+
+fn stat_percent(boss: &Character, stat: StatKind) -> Option<u64> {
+    let attr = &boss.stats[stat];
+    if attr.max == 0 {
+        return None;
+    }
+    Some(attr.current * 100 / attr.max)
+}
+
+fn hots_nb(hero: &Character) -> u64 {
+    CharacterRoundsInfo::get_hot_and_buf_nbs_txts(
+        &hero.character_rounds_info.all_effects,
+        &hero.character_rounds_info.ailments,
+    )
+    .hot_nb
+}
+
+impl BossCondition {
+    pub fn is_met(&self, boss: &Character, heroes: &[Character], turn_nb: usize) -> bool {
+        match self {
+            BossCondition::StatBelowPercent { stat, percent } => {
+                stat_percent(boss, *stat).is_some_and(|p| p < *percent)
+            }
+            BossCondition::StatAbovePercent { stat, percent } => {
+                stat_percent(boss, *stat).is_some_and(|p| p > *percent)
+            }
+            BossCondition::TurnAtLeast(turn) => turn_nb >= *turn,
+            BossCondition::TurnEvery(n) => *n > 0 && turn_nb.is_multiple_of(*n),
+            BossCondition::HasBuffer(kind) => boss
+                .character_rounds_info
+                .all_buffers
+                .get(kind.clone() as usize)
+                .is_some_and(|b| b.value != 0 || b.is_passive_enabled),
+            BossCondition::HeroHotsAbove(nb) => heroes
+                .iter()
+                .filter(|h| !h.stats.is_dead())
+                .any(|h| hots_nb(h) > *nb),
+        }
+    }
+}
+
+/// Return the first rule of `rules` whose conditions hold and whose attack is launchable.
+/// Rules with `once` already in `boss.character_rounds_info.fired_boss_rules` are skipped.
+pub fn choose_boss_action(
+    rules: &[BossRule],
+    boss: &Character,
+    heroes: &[Character],
+    turn_nb: usize,
+) -> Option<BossDecision> {
+    rules.iter().enumerate().find_map(|(rule_index, rule)| {
+        if rule.once
+            && boss
+                .character_rounds_info
+                .fired_boss_rules
+                .contains(&rule_index)
+        {
+            return None;
+        }
+        let is_launchable = boss
+            .character_rounds_info
+            .launchable_atks
+            .iter()
+            .any(|a| a.name == rule.atk_name);
+        if !is_launchable
+            || !rule
+                .conditions
+                .iter()
+                .all(|c| c.is_met(boss, heroes, turn_nb))
+        {
+            return None;
+        }
+        Some(BossDecision {
+            rule_index,
+            atk_name: rule.atk_name.clone(),
+            focus: rule.focus.clone(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        character_mod::attack_type::AttackType,
+        testing::testing_all_characters::{testing_character, testing_pm},
+    };
+
+    fn boss_with_atks(atk_names: &[&str]) -> Character {
+        let mut boss = testing_pm().active_bosses[0].clone();
+        boss.character_rounds_info.launchable_atks = atk_names
+            .iter()
+            .map(|name| AttackType {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        boss
+    }
+
+    #[test]
+    fn unit_boss_rule_serde() {
+        let json = r#"[
+            {"conditions": [{"stat_below_percent": {"stat": "HP", "percent": 30}}], "atk_name": "heal"},
+            {"conditions": [{"turn_at_least": 10}], "atk_name": "enrage", "once": true},
+            {"conditions": [{"hero_hots_above": 2}], "atk_name": "dispel", "focus": "most_hots"},
+            {"atk_name": "hit", "focus": "lowest_hp"}
+        ]"#;
+        let rules: Vec<BossRule> = serde_json::from_str(json).unwrap();
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules[1].conditions, vec![BossCondition::TurnAtLeast(10)]);
+        assert!(rules[1].once);
+        assert_eq!(rules[3].focus, Some(TargetingStrategy::LowestHp));
+        assert!(rules[3].conditions.is_empty());
+    }
+
+    #[test]
+    fn unit_choose_boss_action() {
+        let mut boss = boss_with_atks(&["heal", "enrage", "hit"]);
+        let heroes = vec![testing_character()];
+        let rules = vec![
+            BossRule {
+                conditions: vec![BossCondition::StatBelowPercent {
+                    stat: StatKind::Hp,
+                    percent: 30,
+                }],
+                atk_name: "heal".to_owned(),
+                ..Default::default()
+            },
+            BossRule {
+                conditions: vec![BossCondition::TurnAtLeast(10)],
+                atk_name: "enrage".to_owned(),
+                once: true,
+                ..Default::default()
+            },
+            BossRule {
+                atk_name: "hit".to_owned(),
+                focus: Some(TargetingStrategy::LowestHp),
+                ..Default::default()
+            },
+        ];
+        // no condition met: last rule without condition
+        let decision = choose_boss_action(&rules, &boss, &heroes, 1).unwrap();
+        assert_eq!(decision.atk_name, "hit");
+        assert_eq!(decision.focus, Some(TargetingStrategy::LowestHp));
+        // turn 10
+        let decision = choose_boss_action(&rules, &boss, &heroes, 10).unwrap();
+        assert_eq!(decision.atk_name, "enrage");
+        // once rule already fired
+        boss.character_rounds_info.fired_boss_rules.push(1);
+        let decision = choose_boss_action(&rules, &boss, &heroes, 10).unwrap();
+        assert_eq!(decision.atk_name, "hit");
+        // low HP has priority
+        let hp = &mut boss.stats[StatKind::Hp];
+        hp.current = hp.max / 5;
+        let decision = choose_boss_action(&rules, &boss, &heroes, 10).unwrap();
+        assert_eq!(decision.atk_name, "heal");
+        // attack not launchable
+        boss.character_rounds_info.launchable_atks.clear();
+        assert_eq!(choose_boss_action(&rules, &boss, &heroes, 10), None);
+    }
+
+    #[test]
+    fn unit_next_boss_phase() {
+        let phases = vec![
+            BossPhase {
+                name: "half".to_owned(),
+                hp_percent: 50,
+                ..Default::default()
+            },
+            BossPhase {
+                name: "quarter".to_owned(),
+                hp_percent: 25,
+                announcement: "Last stand".to_owned(),
+                ..Default::default()
+            },
+        ];
+        let mut boss = boss_with_atks(&[]);
+        let max = boss.stats[StatKind::Hp].max;
+        assert_eq!(next_boss_phase(&phases, &boss), None);
+        boss.stats[StatKind::Hp].current = max / 2;
+        assert_eq!(next_boss_phase(&phases, &boss), Some(0));
+        // both thresholds crossed: the phases are entered in order
+        boss.stats[StatKind::Hp].current = max / 10;
+        assert_eq!(next_boss_phase(&phases, &boss), Some(0));
+        boss.character_rounds_info.boss_phase = 1;
+        assert_eq!(next_boss_phase(&phases, &boss), Some(1));
+        boss.character_rounds_info.boss_phase = 2;
+        assert_eq!(next_boss_phase(&phases, &boss), None);
+        // a dead boss does not change its phase
+        boss.character_rounds_info.boss_phase = 0;
+        boss.stats[StatKind::Hp].current = 0;
+        assert_eq!(next_boss_phase(&phases, &boss), None);
+
+        assert_eq!(
+            phases[0].announcement_txt("boss_#1"),
+            "boss_#1 enters the phase half"
+        );
+        assert_eq!(phases[1].announcement_txt("boss_#1"), "Last stand");
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::character_mod::character::Character;
+
+/// Condition to follow an edge of the campaign graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CampaignCondition {
+    /// The objective at that index of `Scenario::objectives` was completed
+    ObjectiveCompleted(usize),
+    /// The players made that choice
+    ChoiceMade(String),
+    /// A hero owns the equipment or the consumable with that name
+    ItemOwned(String),
+}
+
+/// Edge of the campaign graph toward the next scenario, followed once its scenario is completed
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioEdge {
+    /// Name of the next scenario
+    pub scenario: String,
+    /// All the conditions must be met to unlock the next scenario
+    #[serde(default)]
+    pub conditions: Vec<CampaignCondition>,
+}
+
+/// Progress of the players in the campaign graph, used to evaluate the conditions of the edges
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    /// Choices made by the players
+    pub choices: Vec<String>,
+    /// Key: name of a completed scenario, value: indexes of its completed objectives
+    pub completed_objectives: HashMap<String, Vec<usize>>,
+}
+
+impl CampaignProgress {
+    pub fn make_choice(&mut self, choice: &str) {
+        if !self.choices.iter().any(|c| c == choice) {
+            self.choices.push(choice.to_owned());
+        }
+    }
+
+    /// The condition of an edge leaving the scenario `from` is met
+    pub fn is_condition_met(
+        &self,
+        from: &str,
+        condition: &CampaignCondition,
+        heroes: &[Character],
+    ) -> bool {
+        match condition {
+            CampaignCondition::ObjectiveCompleted(index) => self
+                .completed_objectives
+                .get(from)
+                .is_some_and(|indexes| indexes.contains(index)),
+            CampaignCondition::ChoiceMade(choice) => self.choices.contains(choice),
+            CampaignCondition::ItemOwned(name) => heroes.iter().any(|h| {
+                h.inventory.contains_potion(name)
+                    || h.inventory
+                        .equipments
+                        .values()
+                        .flatten()
+                        .any(|e| e.unique_name == *name)
+            }),
+        }
+    }
+
+    /// All the conditions of the edge leaving the scenario `from` are met
+    pub fn is_edge_open(&self, from: &str, edge: &ScenarioEdge, heroes: &[Character]) -> bool {
+        edge.conditions
+            .iter()
+            .all(|c| self.is_condition_met(from, c, heroes))
+    }
+
+    pub fn clear(&mut self) {
+        self.choices.clear();
+        self.completed_objectives.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        character_mod::inventory::Consumable, testing::testing_all_characters::testing_pm,
+    };
+
+    #[test]
+    fn unit_campaign_conditions() {
+        let mut heroes = testing_pm().active_heroes;
+        let mut progress = CampaignProgress::default();
+        let edge = ScenarioEdge {
+            scenario: "next".to_owned(),
+            conditions: vec![
+                CampaignCondition::ObjectiveCompleted(1),
+                CampaignCondition::ChoiceMade("spare the orc".to_owned()),
+                CampaignCondition::ItemOwned("Elven rope".to_owned()),
+            ],
+        };
+        assert!(progress.is_edge_open("from", &ScenarioEdge::default(), &heroes));
+        assert!(!progress.is_edge_open("from", &edge, &heroes));
+
+        progress
+            .completed_objectives
+            .insert("from".to_owned(), vec![0, 1]);
+        progress.make_choice("spare the orc");
+        progress.make_choice("spare the orc");
+        assert_eq!(progress.choices.len(), 1);
+        assert!(!progress.is_edge_open("from", &edge, &heroes));
+
+        heroes[0].inventory.consumables.push(Consumable {
+            name: "Elven rope".to_owned(),
+            ..Default::default()
+        });
+        assert!(progress.is_edge_open("from", &edge, &heroes));
+        // the objectives are the ones of the scenario the edge leaves
+        assert!(!progress.is_edge_open("other", &edge, &heroes));
+    }
+}
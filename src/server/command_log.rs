@@ -0,0 +1,220 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    character_mod::class::Class,
+    common::{
+        constants::core_game_data_const::SAVED_CORE_GAME_DATA_REPLAY,
+        game_rng::GameRng,
+        overworld::{Direction, Position},
+    },
+    server::{core_game_data::CoreGameData, difficulty::Difficulty, game_state::GameStatus},
+    utils,
+};
+
+/// Player-facing command, recorded to replay a game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameCommand {
+    LaunchAttack {
+        atk_name: Option<String>,
+    },
+    SetOneTarget {
+        launcher_id_name: String,
+        atk_name: String,
+        target_id_name: String,
+    },
+    UseConsumableOnTarget {
+        potion_name: String,
+        target_id_name: String,
+    },
+    ToggleEquipment {
+        character_id_name: String,
+        equipment_unique_name: String,
+    },
+    ToggleEquipmentById {
+        character_id_name: String,
+        equipment_id: u64,
+    },
+    LoadNextScenario,
+    LoadScenario {
+        name: String,
+    },
+    MakeChoice {
+        choice: String,
+    },
+    SetDifficulty {
+        difficulty: Difficulty,
+    },
+    ChooseRunReward {
+        index: usize,
+    },
+    LearnTalent {
+        hero_id_name: String,
+        talent_name: String,
+    },
+    ResetTalents {
+        hero_id_name: String,
+    },
+    LearnAttack {
+        hero_id_name: String,
+        atk_name: String,
+    },
+    SetLoadout {
+        hero_id_name: String,
+        atk_names: Vec<String>,
+    },
+    Specialise {
+        hero_id_name: String,
+        class: Class,
+    },
+    EnterOverworld {
+        map_id: String,
+        spawn: Option<Position>,
+        /// Root of the maps relative to the game data root, so the log replays on another checkout
+        root: PathBuf,
+    },
+    ExitOverworldToFight {
+        scenario_id: String,
+    },
+    MovePlayer {
+        hero_id: String,
+        dir: Direction,
+    },
+    Interact {
+        hero_id: String,
+    },
+}
+
+/// Summary of the game state after a command, compared during a replay
+/// to find where the replayed game diverges from the recorded one.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateCheckpoint {
+    pub turn_nb: usize,
+    pub round_nb: usize,
+    pub status: GameStatus,
+    pub current_player: String,
+    /// Current HP of every active hero, then every active boss
+    pub hp: Vec<(String, u64)>,
+    /// RNG state: any extra or missing roll shows up here
+    pub rng: GameRng,
+    /// Overworld positions sorted by hero id, empty outside of the overworld
+    pub positions: Vec<(String, Position)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandEntry {
+    pub command: GameCommand,
+    pub checkpoint: StateCheckpoint,
+}
+
+/// Ordered list of the commands played in a game
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandLog {
+    pub entries: Vec<CommandEntry>,
+}
+
+impl CommandLog {
+    pub fn push(&mut self, command: GameCommand, checkpoint: StateCheckpoint) {
+        self.entries.push(CommandEntry {
+            command,
+            checkpoint,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// First command whose replayed checkpoint differs from the recorded one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayDivergence {
+    /// Index of the command in the command log
+    pub index: usize,
+    pub command: GameCommand,
+    pub expected: StateCheckpoint,
+    pub actual: StateCheckpoint,
+}
+
+/// Outcome of a replay
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayReport {
+    /// Rebuilt game, stopped at the first divergence if any
+    pub core_game_data: CoreGameData,
+    /// Number of commands replayed
+    pub nb_commands: usize,
+    pub first_divergence: Option<ReplayDivergence>,
+}
+
+/// Starting snapshot of a game and the commands played since.
+/// Saved as `SAVED_CORE_GAME_DATA_REPLAY` in the game directory.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameReplay {
+    pub snapshot: CoreGameData,
+    pub log: CommandLog,
+}
+
+impl GameReplay {
+    pub fn new(snapshot: CoreGameData, log: CommandLog) -> Self {
+        GameReplay { snapshot, log }
+    }
+
+    /// Write the replay in `dir` and return the path of the file.
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        let path = dir.as_ref().join(SAVED_CORE_GAME_DATA_REPLAY);
+        utils::_write_to_json(self, &path)?;
+        Ok(path)
+    }
+
+    /// Read the replay stored in `dir`.
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<GameReplay> {
+        utils::read_from_json(dir.as_ref().join(SAVED_CORE_GAME_DATA_REPLAY))
+    }
+
+    pub fn run(&self) -> ReplayReport {
+        CoreGameData::replay(&self.snapshot, &self.log)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_game_command_serde() {
+        let cmd = GameCommand::MovePlayer {
+            hero_id: "hero_1".to_string(),
+            dir: Direction::Left,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("move_player"), "got: {json}");
+        let back: GameCommand = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+
+        let json = serde_json::to_string(&GameCommand::LoadNextScenario).unwrap();
+        assert_eq!(json, "\"load_next_scenario\"");
+    }
+
+    #[test]
+    fn unit_command_log_push() {
+        let mut log = CommandLog::default();
+        assert!(log.is_empty());
+        log.push(
+            GameCommand::LaunchAttack { atk_name: None },
+            StateCheckpoint::default(),
+        );
+        assert_eq!(log.len(), 1);
+        assert_eq!(
+            log.entries[0].command,
+            GameCommand::LaunchAttack { atk_name: None }
+        );
+    }
+}
@@ -2,14 +2,26 @@ use anyhow::Result;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use crate::common::constants::core_game_data_const::SAVED_CORE_GAME_DATA;
+use crate::common::constants::run_const::RUN_NB_SCENARIOS;
+use crate::common::game_rng::GameRng;
+use crate::common::overworld::{Direction, Position};
+use crate::server::command_log::{CommandLog, GameCommand, ReplayDivergence, ReplayReport};
 use crate::server::data_manager::DataManager;
+use crate::server::difficulty::Difficulty;
 use crate::server::game_manager::GameManager;
 use crate::server::game_state::GameStatus;
-use crate::server::overworld_manager::{OverworldManager, OverworldState};
+use crate::server::overworld_manager::{
+    InteractResult, MoveResult, OverworldManager, OverworldState,
+};
+use crate::server::procgen;
+use crate::server::run::{RunReward, RunState, RunSummary, run_score};
+use crate::server::save_game::{SaveFile, SaveHeader, parse_save, parse_save_header};
 use crate::server::server_manager::GamePhase;
-use crate::shop::ShopCatalogItem;
+use crate::shop::{ShopCatalogItem, restock_shop_catalog};
+use crate::utils;
 
 /// Game core state, stored on the server and sent to clients
 /// Those data are necessary to run/load/replay a game
@@ -37,6 +49,9 @@ pub struct CoreGameData {
     /// Shop catalog — items available for purchase and their prices
     #[serde(default)]
     pub shop_catalog: Vec<ShopCatalogItem>,
+    /// Number of restocks of the shop, mixed in the seed of each restock
+    #[serde(default)]
+    pub shop_restock_nb: u64,
     /// Display hint for the action banner (consumable use, etc.).
     /// Empty after a real attack (banner reads from last_result_atk instead).
     #[serde(default)]
@@ -44,6 +59,9 @@ pub struct CoreGameData {
     /// Active overworld state; `Some` while `game_phase == Overworld`.
     #[serde(default)]
     pub overworld: Option<OverworldState>,
+    /// Active roguelike run; `Some` from `new_run` until the end of the game.
+    #[serde(default)]
+    pub run: Option<RunState>,
 }
 
 impl CoreGameData {
@@ -58,6 +76,8 @@ impl CoreGameData {
         scenarios: Vec<crate::server::scenario::Scenario>,
     ) -> Result<CoreGameData> {
         let mut gm = GameManager::new(&dm.offline_root, dm.equipment_table.clone(), scenarios);
+        gm.type_chart = dm.type_chart.clone();
+        gm.talent_trees = dm.talent_trees.clone();
 
         // set the full boss roster so load_next_scenario can populate active_bosses
         gm.pm.all_bosses = dm.all_bosses.clone();
@@ -74,13 +94,159 @@ impl CoreGameData {
             universe: String::new(),
             loaded_from_save: false,
             shop_catalog: dm.shop_catalog.clone(),
+            shop_restock_nb: 0,
             last_action_header: String::new(),
             overworld: None,
+            run: None,
         })
     }
 
+    /// Like `new_with_scenarios`, with `nb` generated scenarios of the universe from `first_level`,
+    /// e.g. to offer an endless mode once the campaign is finished.
+    pub fn new_endless(
+        dm: &DataManager,
+        server_name: &str,
+        universe: &str,
+        first_level: u64,
+        nb: u64,
+        seed: u64,
+    ) -> Result<CoreGameData> {
+        let scenarios = procgen::generate_scenarios(dm, universe, first_level, nb, seed)?;
+        let mut core = CoreGameData::new_with_scenarios(dm, server_name, scenarios)?;
+        core.universe = universe.to_owned();
+        Ok(core)
+    }
+
+    /// Start a roguelike run: `RUN_NB_SCENARIOS` generated scenarios, permadeath of the heroes
+    /// and a reward to choose between two fights.
+    pub fn new_run(
+        dm: &DataManager,
+        server_name: &str,
+        universe: &str,
+        seed: u64,
+    ) -> Result<CoreGameData> {
+        let mut core =
+            CoreGameData::new_endless(dm, server_name, universe, 1, RUN_NB_SCENARIOS, seed)?;
+        core.game_manager.pm.is_permadeath = true;
+        core.run = Some(RunState::new(seed));
+        Ok(core)
+    }
+
+    /// Load the next scenario and restock the shop
     pub fn load_next_scenario(&mut self) -> Result<()> {
-        self.game_manager.load_next_scenario()
+        self.game_manager.load_next_scenario()?;
+        self.restock_shop();
+        Ok(())
+    }
+
+    /// Roll a new shop catalog for the current scenario.
+    /// The restock draws from a generator derived from the game seed and the number of restocks,
+    /// so each restock differs while the fights roll the same.
+    pub fn restock_shop(&mut self) {
+        self.shop_restock_nb += 1;
+        let gm = &self.game_manager;
+        let mut rng = GameRng::new(gm.rng.seed.wrapping_add(self.shop_restock_nb));
+        self.shop_catalog = restock_shop_catalog(&gm.pm.equipment_table, &mut rng);
+    }
+
+    /// Rewards offered after a won fight of a run, empty otherwise or after its last fight
+    pub fn run_reward_offers(&self) -> Vec<RunReward> {
+        let gm = &self.game_manager;
+        match &self.run {
+            Some(run)
+                if gm.game_state.status == GameStatus::EndOfScenario
+                    && !gm.current_scenario.next_edges(&gm.all_scenarios).is_empty() =>
+            {
+                run.roll_offers(&gm.pm)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Give the reward at `index` of `run_reward_offers` and load the next fight of the run
+    pub fn choose_run_reward(&mut self, index: usize) -> Result<()> {
+        let result = self.process_choose_run_reward(index);
+        self.record_command(GameCommand::ChooseRunReward { index });
+        result
+    }
+
+    fn process_choose_run_reward(&mut self, index: usize) -> Result<()> {
+        let offers = self.run_reward_offers();
+        let reward = offers
+            .get(index)
+            .ok_or_else(|| anyhow::anyhow!("No run reward at index {}", index))?;
+        let Some(run) = self.run.as_mut() else {
+            return Err(anyhow::anyhow!("No active run"));
+        };
+        run.apply_reward(reward, &mut self.game_manager.pm)?;
+        run.scenarios_won += 1;
+        // the reward and the next fight are one command of the log
+        self.game_manager.process_load_next_scenario()?;
+        self.restock_shop();
+        Ok(())
+    }
+
+    /// Score summary of the run once it is over: all the heroes died or the last fight is won
+    pub fn run_summary(&self) -> Option<RunSummary> {
+        let run = self.run.as_ref()?;
+        let gm = &self.game_manager;
+        let is_victory = gm.game_state.status == GameStatus::EndOfScenario
+            && gm.current_scenario.next_edges(&gm.all_scenarios).is_empty();
+        if !is_victory && gm.game_state.status != GameStatus::EndOfGame {
+            return None;
+        }
+        let scenarios_won = run.scenarios_won + usize::from(is_victory);
+        let kills = gm.game_state.accumulated_kills
+            + gm.pm
+                .active_bosses
+                .iter()
+                .filter(|b| b.stats.is_dead())
+                .count();
+        let heroes_alive = gm
+            .pm
+            .active_heroes
+            .iter()
+            .filter(|h| !h.stats.is_dead())
+            .count();
+        Some(RunSummary {
+            scenarios_won,
+            nb_scenarios: gm.all_scenarios.len(),
+            kills,
+            heroes_alive,
+            is_victory,
+            score: run_score(scenarios_won, kills, heroes_alive),
+        })
+    }
+
+    /// Difficulty chosen at the creation of the lobby, kept in the game manager which scales the fights
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.game_manager.set_difficulty(difficulty);
+    }
+
+    /// Write the game in `dir` as a versioned save file and return its path.
+    pub fn save_game<P: AsRef<Path>>(&self, dir: P) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        let path = dir.as_ref().join(SAVED_CORE_GAME_DATA);
+        let save = SaveFile {
+            header: SaveHeader::new(self, &utils::get_current_time_as_string()),
+            core_game_data: self.clone(),
+        };
+        utils::_write_to_json(&save, &path)?;
+        Ok(path)
+    }
+
+    /// Load a save file written by `save_game`, migrating it if it comes from an older version.
+    pub fn load_game<P: AsRef<Path>>(path: P) -> Result<CoreGameData> {
+        let content = std::fs::read_to_string(utils::normalize_cross_platform(path))?;
+        let mut core = parse_save(&content)?.core_game_data;
+        core.loaded_from_save = true;
+        Ok(core)
+    }
+
+    /// Read only the header of a save file (version, timestamp, universe, party).
+    pub fn read_save_header<P: AsRef<Path>>(path: P) -> Result<SaveHeader> {
+        let content = std::fs::read_to_string(utils::normalize_cross_platform(path))?;
+        parse_save_header(&content)
     }
 
     /// Enter overworld mode: load `map_id` from `<root>/maps/`, place all
@@ -90,6 +256,16 @@ impl CoreGameData {
     /// preserved (i.e. `overworld` is already `Some` and its `map_id` matches),
     /// the saved positions are restored instead of resetting to spawn.
     pub fn enter_overworld(&mut self, map_id: &str, root: &Path) -> Result<()> {
+        let result = self.process_enter_overworld(map_id, root);
+        self.record_command(GameCommand::EnterOverworld {
+            map_id: map_id.to_owned(),
+            spawn: None,
+            root: self.relative_to_data_root(root),
+        });
+        result
+    }
+
+    fn process_enter_overworld(&mut self, map_id: &str, root: &Path) -> Result<()> {
         // Resume from preserved state if we already have it for this map.
         if let Some(ref mut ow) = self.overworld.as_mut().filter(|ow| ow.map_id == map_id) {
             // Mark any boss NPC whose fight scenario was just won as defeated.
@@ -121,19 +297,27 @@ impl CoreGameData {
 
     /// Like [`enter_overworld`] but place the hero at `spawn` instead of the
     /// map's default spawn point (used for door transitions).
-    pub fn enter_overworld_at(
-        &mut self,
-        map_id: &str,
-        spawn: crate::common::overworld::Position,
-        root: &Path,
-    ) -> Result<()> {
-        self.enter_overworld_inner(map_id, Some(spawn), root)
+    pub fn enter_overworld_at(&mut self, map_id: &str, spawn: Position, root: &Path) -> Result<()> {
+        let result = self.enter_overworld_inner(map_id, Some(spawn.clone()), root);
+        self.record_command(GameCommand::EnterOverworld {
+            map_id: map_id.to_owned(),
+            spawn: Some(spawn),
+            root: self.relative_to_data_root(root),
+        });
+        result
+    }
+
+    /// `root` relative to the root of the game data, unchanged if it is outside of it
+    fn relative_to_data_root(&self, root: &Path) -> PathBuf {
+        root.strip_prefix(&self.game_manager.game_paths.input_data_root)
+            .unwrap_or(root)
+            .to_path_buf()
     }
 
     fn enter_overworld_inner(
         &mut self,
         map_id: &str,
-        spawn_override: Option<crate::common::overworld::Position>,
+        spawn_override: Option<Position>,
         root: &Path,
     ) -> Result<()> {
         let mut manager = OverworldManager::load_map(map_id, root)?;
@@ -173,6 +357,180 @@ impl CoreGameData {
             ow.pending_encounter = None;
         }
         self.game_phase = GamePhase::Running;
+        self.record_command(GameCommand::ExitOverworldToFight {
+            scenario_id: scenario_id.to_owned(),
+        });
+    }
+
+    /// Move `hero_id` one step on the overworld.
+    /// Returns `None` when the game is not on the overworld.
+    pub fn move_player(&mut self, hero_id: &str, dir: Direction) -> Option<MoveResult> {
+        let mut manager = OverworldManager::from_state(self.overworld.take()?);
+        let result = manager.move_player(hero_id, dir.clone(), &mut self.game_manager.rng);
+        self.overworld = Some(manager.state);
+        self.record_command(GameCommand::MovePlayer {
+            hero_id: hero_id.to_owned(),
+            dir,
+        });
+        Some(result)
+    }
+
+    /// Interact with the NPC next to `hero_id` on the overworld.
+    pub fn interact(&mut self, hero_id: &str) -> Option<InteractResult> {
+        let mut manager = OverworldManager::from_state(self.overworld.take()?);
+        let result = manager.interact(hero_id);
+        self.overworld = Some(manager.state);
+        self.record_command(GameCommand::Interact {
+            hero_id: hero_id.to_owned(),
+        });
+        result
+    }
+
+    fn record_command(&mut self, command: GameCommand) {
+        self.game_manager
+            .record_command(command, self.overworld.as_ref());
+    }
+
+    /// Play one recorded command again.
+    /// Errors are ignored: a command that failed when recorded fails the same way here.
+    pub fn apply_command(&mut self, command: &GameCommand) {
+        match command.clone() {
+            GameCommand::LaunchAttack { atk_name } => {
+                self.game_manager.launch_attack(atk_name.as_deref());
+            }
+            GameCommand::SetOneTarget {
+                launcher_id_name,
+                atk_name,
+                target_id_name,
+            } => {
+                self.game_manager
+                    .set_one_target(&launcher_id_name, &atk_name, &target_id_name);
+            }
+            GameCommand::UseConsumableOnTarget {
+                potion_name,
+                target_id_name,
+            } => {
+                let _ = self
+                    .game_manager
+                    .use_consumable_on_target(&potion_name, &target_id_name);
+            }
+            GameCommand::ToggleEquipment {
+                character_id_name,
+                equipment_unique_name,
+            } => {
+                let _ = self
+                    .game_manager
+                    .toggle_equipment(&character_id_name, &equipment_unique_name);
+            }
+            GameCommand::ToggleEquipmentById {
+                character_id_name,
+                equipment_id,
+            } => {
+                let _ = self
+                    .game_manager
+                    .toggle_equipment_by_id(&character_id_name, equipment_id);
+            }
+            GameCommand::LoadNextScenario => {
+                let _ = self.load_next_scenario();
+            }
+            GameCommand::LoadScenario { name } => {
+                let _ = self.game_manager.load_scenario(&name);
+            }
+            GameCommand::MakeChoice { choice } => {
+                self.game_manager.make_choice(&choice);
+            }
+            GameCommand::SetDifficulty { difficulty } => {
+                self.set_difficulty(difficulty);
+            }
+            GameCommand::ChooseRunReward { index } => {
+                let _ = self.choose_run_reward(index);
+            }
+            GameCommand::LearnTalent {
+                hero_id_name,
+                talent_name,
+            } => {
+                let _ = self.game_manager.learn_talent(&hero_id_name, &talent_name);
+            }
+            GameCommand::ResetTalents { hero_id_name } => {
+                let _ = self.game_manager.reset_talents(&hero_id_name);
+            }
+            GameCommand::LearnAttack {
+                hero_id_name,
+                atk_name,
+            } => {
+                let _ = self.game_manager.learn_attack(&hero_id_name, &atk_name);
+            }
+            GameCommand::SetLoadout {
+                hero_id_name,
+                atk_names,
+            } => {
+                let _ = self.game_manager.set_loadout(&hero_id_name, &atk_names);
+            }
+            GameCommand::Specialise {
+                hero_id_name,
+                class,
+            } => {
+                let _ = self.game_manager.specialise(&hero_id_name, &class);
+            }
+            GameCommand::EnterOverworld {
+                map_id,
+                spawn,
+                root,
+            } => {
+                // the root is recorded relative to the game data root of the replaying game
+                let root = self.game_manager.game_paths.input_data_root.join(root);
+                let _ = match spawn {
+                    Some(spawn) => self.enter_overworld_at(&map_id, spawn, &root),
+                    None => self.enter_overworld(&map_id, &root),
+                };
+            }
+            GameCommand::ExitOverworldToFight { scenario_id } => {
+                self.exit_overworld_to_fight(&scenario_id);
+            }
+            GameCommand::MovePlayer { hero_id, dir } => {
+                self.move_player(&hero_id, dir);
+            }
+            GameCommand::Interact { hero_id } => {
+                self.interact(&hero_id);
+            }
+        }
+    }
+
+    /// Rebuild a game from `snapshot` by playing again the commands of `log`.
+    /// The commands already in the snapshot's own log are skipped, so `log` can be
+    /// the full log of the recorded game.
+    /// The replay stops at the first command whose checkpoint differs from the recorded one.
+    pub fn replay(snapshot: &CoreGameData, log: &CommandLog) -> ReplayReport {
+        let mut core = snapshot.clone();
+        let start = core.game_manager.command_log.len();
+        let mut nb_commands = 0;
+        let mut first_divergence = None;
+        for (index, entry) in log.entries.iter().enumerate().skip(start) {
+            core.apply_command(&entry.command);
+            nb_commands += 1;
+            let actual = core
+                .game_manager
+                .command_log
+                .entries
+                .last()
+                .map(|e| e.checkpoint.clone())
+                .unwrap_or_default();
+            if actual != entry.checkpoint {
+                tracing::warn!("Replay diverges at command {}: {:?}", index, entry.command);
+                first_divergence = Some(ReplayDivergence {
+                    index,
+                    command: entry.command.clone(),
+                    expected: entry.checkpoint.clone(),
+                    actual,
+                });
+                break;
+            }
+        }
+        ReplayReport {
+            core_game_data: core,
+            nb_commands,
+            first_divergence,
+        }
     }
 }
 
@@ -214,12 +572,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unit_new_endless() {
+        use crate::server::game_state::GameStatus;
+        let dm = DataManager::try_new(&*OFFLINE_ROOT).unwrap();
+        let mut core = CoreGameData::new_endless(&dm, "Endless", "lotr", 11, 3, 7).unwrap();
+        let gm = &core.game_manager;
+        assert_eq!(gm.all_scenarios.len(), 3);
+        assert_eq!(gm.current_scenario.level, 11);
+        // the bosses are raised to the level of the scenario
+        assert!(gm.pm.active_bosses.iter().all(|b| b.level >= 11));
+        core.game_manager.game_state.status = GameStatus::EndOfScenario;
+        core.load_next_scenario().unwrap();
+        assert_eq!(core.game_manager.current_scenario.level, 12);
+    }
+
+    #[test]
+    fn unit_run() {
+        use crate::character_mod::stats::StatKind;
+        use crate::server::command_log::GameCommand;
+        use crate::server::game_state::GameStatus;
+        use crate::testing::testing_all_characters::testing_pm;
+
+        let dm = DataManager::try_new(&*OFFLINE_ROOT).unwrap();
+        let mut core = CoreGameData::new_run(&dm, "Run", "lotr", 5).unwrap();
+        core.game_manager.pm.active_heroes = testing_pm().active_heroes;
+        let dead_hero = core.game_manager.pm.active_heroes[1].id_name.clone();
+        assert!(core.run_reward_offers().is_empty());
+        assert!(core.run_summary().is_none());
+
+        // win the first fight with a dead hero
+        let gm = &mut core.game_manager;
+        gm.pm
+            .active_bosses
+            .iter_mut()
+            .for_each(|b| b.stats[StatKind::Hp].current = 0);
+        gm.pm.active_heroes[1].stats[StatKind::Hp].current = 0;
+        gm.game_state.status = GameStatus::EndOfScenario;
+        let offers = core.run_reward_offers();
+        assert_eq!(offers.len(), 3);
+        assert!(offers.iter().all(|o| o.hero_id_name != dead_hero));
+        let snapshot = core.clone();
+        assert!(core.choose_run_reward(3).is_err());
+        core.choose_run_reward(0).unwrap();
+
+        // the dead hero stays dead in the next fight
+        let gm = &core.game_manager;
+        assert_eq!(gm.current_scenario.level, 2);
+        let hero = gm.pm.get_active_hero_character(&dead_hero).unwrap();
+        assert!(hero.stats.is_dead());
+        let run = core.run.as_ref().unwrap();
+        assert_eq!(run.scenarios_won, 1);
+        assert_eq!(run.rewards, vec![offers[0].clone()]);
+
+        // the reward is a single command of the log, replayed as recorded
+        let log = &core.game_manager.command_log;
+        assert_eq!(
+            log.entries
+                .iter()
+                .skip(snapshot.game_manager.command_log.len())
+                .map(|e| e.command.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                GameCommand::ChooseRunReward { index: 3 },
+                GameCommand::ChooseRunReward { index: 0 }
+            ]
+        );
+        let report = CoreGameData::replay(&snapshot, log);
+        assert_eq!(report.first_divergence, None);
+        let replayed = &report.core_game_data;
+        assert_eq!(
+            replayed.run.as_ref().unwrap().rewards,
+            vec![offers[0].clone()]
+        );
+        assert_eq!(replayed.game_manager.current_scenario.level, 2);
+
+        core.game_manager.game_state.status = GameStatus::EndOfGame;
+        let summary = core.run_summary().unwrap();
+        assert!(!summary.is_victory);
+        assert_eq!(summary.scenarios_won, 1);
+        assert_eq!(summary.nb_scenarios, 10);
+        assert!(summary.kills >= 1);
+        assert_eq!(
+            summary.heroes_alive,
+            core.game_manager.pm.active_heroes.len() - 1
+        );
+        assert!(core.run_reward_offers().is_empty());
+    }
+
     #[test]
     fn unit_core_game_data_load_next_scenario() {
+        use crate::server::game_state::GameStatus;
         let dm = DataManager::try_new(*TEST_OFFLINE_ROOT).unwrap();
         let mut core_game_data = CoreGameData::new(&dm, "Default").unwrap();
+        core_game_data.game_manager.game_state.status = GameStatus::EndOfScenario;
         let result = core_game_data.load_next_scenario();
         assert!(result.is_ok());
+
+        // the shop is restocked with rolled equipments, without drawing from the fight rng
+        let rng = core_game_data.game_manager.rng.clone();
+        let catalog = core_game_data.shop_catalog.clone();
+        assert_ne!(catalog, dm.shop_catalog);
+        let mut replayed = core_game_data.clone();
+        core_game_data.restock_shop();
+        assert_ne!(core_game_data.shop_catalog, catalog);
+        assert_eq!(core_game_data.game_manager.rng, rng);
+        // the same restock of the same game rolls the same catalog
+        replayed.restock_shop();
+        assert_eq!(replayed.shop_catalog, core_game_data.shop_catalog);
     }
 
     #[test]
@@ -458,4 +918,177 @@ mod tests {
             "active_dialog marker must survive fight + re-entry (state was resumed, not reloaded)"
         );
     }
+
+    fn recorded_core() -> (CoreGameData, CoreGameData) {
+        use crate::common::overworld::Direction;
+        use crate::server::difficulty::Difficulty;
+        use crate::testing::testing_all_characters::testing_game_manager;
+
+        let dm = DataManager::try_new(*TEST_OFFLINE_ROOT).unwrap();
+        let mut core = CoreGameData::new(&dm, "Default").unwrap();
+        core.game_manager = testing_game_manager();
+        core.game_manager.set_seed(7);
+        let snapshot = core.clone();
+
+        core.set_difficulty(Difficulty::Nightmare);
+        core.enter_overworld("pallet_town", &OFFLINE_ROOT).unwrap();
+        let hero = core.game_manager.pm.active_heroes[0].id_name.clone();
+        for dir in [Direction::Up, Direction::Up, Direction::Down, Direction::Up] {
+            core.move_player(&hero, dir);
+        }
+        core.exit_overworld_to_fight("Stage 1");
+        for _ in 0..10 {
+            let atk_name = core
+                .game_manager
+                .pm
+                .current_player
+                .character_rounds_info
+                .launchable_atks
+                .first()
+                .map(|atk| atk.name.clone());
+            core.game_manager.launch_attack(atk_name.as_deref());
+        }
+        (snapshot, core)
+    }
+
+    #[test]
+    fn unit_replay_rebuilds_recorded_game() {
+        use crate::server::difficulty::Difficulty;
+
+        let (snapshot, core) = recorded_core();
+        let log = &core.game_manager.command_log;
+        assert_eq!(log.len(), 17);
+
+        let report = CoreGameData::replay(&snapshot, log);
+        assert_eq!(report.first_divergence, None);
+        assert_eq!(report.nb_commands, 17);
+        assert_eq!(
+            report.core_game_data.game_manager.difficulty,
+            Difficulty::Nightmare
+        );
+        assert_eq!(report.core_game_data.game_manager.pm, core.game_manager.pm);
+        assert_eq!(
+            report.core_game_data.game_manager.game_state,
+            core.game_manager.game_state
+        );
+        assert_eq!(report.core_game_data.overworld, core.overworld);
+        assert_eq!(report.core_game_data.game_manager.command_log, *log);
+    }
+
+    #[test]
+    fn unit_replay_reports_first_divergence() {
+        use crate::server::command_log::GameCommand;
+
+        let (snapshot, core) = recorded_core();
+        let mut log = core.game_manager.command_log.clone();
+        // tamper the checkpoint of the first attack
+        let index = log
+            .entries
+            .iter()
+            .position(|e| matches!(e.command, GameCommand::LaunchAttack { .. }))
+            .unwrap();
+        log.entries[index].checkpoint.turn_nb += 100;
+
+        let report = CoreGameData::replay(&snapshot, &log);
+        let divergence = report.first_divergence.unwrap();
+        assert_eq!(divergence.index, index);
+        assert_eq!(divergence.actual.turn_nb + 100, divergence.expected.turn_nb);
+        assert_eq!(
+            report.nb_commands,
+            index + 1 - snapshot.game_manager.command_log.len()
+        );
+    }
+
+    #[test]
+    fn unit_replay_overworld_from_another_data_root() {
+        use crate::server::command_log::GameCommand;
+        use crate::testing::testing_all_characters::testing_game_manager;
+
+        let mut core = CoreGameData {
+            game_manager: testing_game_manager(),
+            ..Default::default()
+        };
+        core.game_manager.game_paths.input_data_root = OFFLINE_ROOT.clone();
+        let mut snapshot = core.clone();
+        core.enter_overworld("pallet_town", &OFFLINE_ROOT).unwrap();
+        let log = &core.game_manager.command_log;
+        assert_eq!(
+            log.entries[0].command,
+            GameCommand::EnterOverworld {
+                map_id: "pallet_town".to_owned(),
+                spawn: None,
+                root: std::path::PathBuf::new(),
+            }
+        );
+
+        // the same log replays with the game data stored elsewhere
+        let root = std::env::temp_dir().join(format!("rpg_replay_root_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("maps")).unwrap();
+        std::fs::copy(
+            OFFLINE_ROOT.join("maps").join("pallet_town.json"),
+            root.join("maps").join("pallet_town.json"),
+        )
+        .unwrap();
+        snapshot.game_manager.game_paths.input_data_root = root.clone();
+        let report = CoreGameData::replay(&snapshot, log);
+        assert_eq!(report.first_divergence, None);
+        assert_eq!(report.core_game_data.overworld, core.overworld);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn unit_game_replay_save_and_load() {
+        use crate::server::command_log::GameReplay;
+
+        let (snapshot, core) = recorded_core();
+        let replay = GameReplay::new(snapshot, core.game_manager.command_log.clone());
+        let dir = std::env::temp_dir().join(format!("rpg_replay_test_{}", std::process::id()));
+        let path = replay.save(&dir).unwrap();
+        assert!(path.ends_with("core_game_data_replay.json"));
+
+        let loaded = GameReplay::load(&dir).unwrap();
+        assert_eq!(loaded.log, replay.log);
+        assert_eq!(loaded.run().first_divergence, None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unit_save_and_load_game() {
+        let (_, core) = recorded_core();
+        let dir = std::env::temp_dir().join(format!("rpg_save_test_{}", std::process::id()));
+        let path = core.save_game(&dir).unwrap();
+        assert!(path.ends_with("core_game_data.json"));
+
+        let header = CoreGameData::read_save_header(&path).unwrap();
+        assert_eq!(
+            header.schema_version,
+            crate::common::constants::core_game_data_const::SAVE_SCHEMA_VERSION
+        );
+        assert!(!header.timestamp.is_empty());
+        assert_eq!(header.party.len(), core.game_manager.pm.active_heroes.len());
+        assert_eq!(header.scenario, core.game_manager.current_scenario.name);
+
+        let loaded = CoreGameData::load_game(&path).unwrap();
+        assert!(loaded.loaded_from_save);
+        assert_eq!(loaded.game_manager, core.game_manager);
+        assert_eq!(loaded.overworld, core.overworld);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unit_load_game_migrates_bare_core_game_data() {
+        let (_, core) = recorded_core();
+        let dir = std::env::temp_dir().join(format!("rpg_save_v0_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // version 0: CoreGameData written without envelope
+        let path = dir.join("core_game_data.json");
+        std::fs::write(&path, serde_json::to_string(&core).unwrap()).unwrap();
+
+        let header = CoreGameData::read_save_header(&path).unwrap();
+        assert_eq!(header.universe, core.universe);
+        let loaded = CoreGameData::load_game(&path).unwrap();
+        assert!(loaded.loaded_from_save);
+        assert_eq!(loaded.game_manager, core.game_manager);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
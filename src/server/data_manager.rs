@@ -6,14 +6,18 @@ use strum::IntoEnumIterator;
 use crate::{
     character_mod::{
         character::{Character, CharacterKind},
+        class::ClassPassive,
+        element::TypeChart,
         equipment::{Equipment, EquipmentJsonKey},
+        talent::TalentTree,
     },
     common::constants::paths_const::{
-        OFFLINE_CHARACTERS, OFFLINE_LOOT_EQUIPMENT, OFFLINE_ROOT, OFFLINE_SCENARIOS,
+        OFFLINE_CHARACTERS, OFFLINE_CLASS_PASSIVES, OFFLINE_LOOT_EQUIPMENT, OFFLINE_ROOT,
+        OFFLINE_SCENARIOS, OFFLINE_TALENTS, OFFLINE_TYPE_CHART,
     },
     server::scenario::Scenario,
     shop::{ShopCatalogItem, build_shop_catalog},
-    utils::list_files_in_dir,
+    utils::{self, list_files_in_dir},
 };
 
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -28,6 +32,15 @@ pub struct DataManager {
     pub equipment_table: HashMap<EquipmentJsonKey, Vec<Equipment>>,
     /// Shop catalog derived from equipment_table and hardcoded consumables
     pub shop_catalog: Vec<ShopCatalogItem>,
+    /// Type-effectiveness table of the elements
+    #[serde(default)]
+    pub type_chart: TypeChart,
+    /// Talent tree of each class
+    #[serde(default)]
+    pub talent_trees: Vec<TalentTree>,
+    /// Innate passive of each class, already enabled on the heroes
+    #[serde(default)]
+    pub class_passives: Vec<ClassPassive>,
     /// Root path for offline files
     pub offline_root: std::path::PathBuf,
 }
@@ -51,6 +64,12 @@ impl DataManager {
         dm.load_all_characters(path_ref)?;
         // load all the scenarios
         dm.load_all_scenarios(path_ref)?;
+        // load the type-effectiveness table
+        dm.load_type_chart(path_ref);
+        // load the talent trees of the classes
+        dm.load_talent_trees(path_ref);
+        // load the class passives and enable them on the heroes
+        dm.load_class_passives(path_ref);
 
         let shop_catalog = build_shop_catalog(&dm.equipment_table);
         Ok(DataManager {
@@ -59,6 +78,9 @@ impl DataManager {
             all_scenarios: dm.all_scenarios,
             equipment_table: dm.equipment_table,
             shop_catalog,
+            type_chart: dm.type_chart,
+            talent_trees: dm.talent_trees,
+            class_passives: dm.class_passives,
             offline_root: dm.offline_root,
         })
     }
@@ -228,6 +250,65 @@ impl DataManager {
         Ok(())
     }
 
+    /// Load the type-effectiveness table.
+    /// Without table file, all the elements have a normal effectiveness.
+    pub fn load_type_chart<P: AsRef<Path>>(&mut self, path: P) {
+        let type_chart_path = path.as_ref().join(*OFFLINE_TYPE_CHART);
+        if !type_chart_path.exists() {
+            tracing::info!("No type chart found in {:?}", type_chart_path);
+            return;
+        }
+        match TypeChart::try_new_from_json(&type_chart_path) {
+            Ok(type_chart) => self.type_chart = type_chart,
+            Err(e) => tracing::error!("{}", e),
+        }
+    }
+
+    /// Load the talent trees, one file per class.
+    /// Without tree, the talent points of a class cannot be spent.
+    pub fn load_talent_trees<P: AsRef<Path>>(&mut self, path: P) {
+        let talents_dir_path = path.as_ref().join(*OFFLINE_TALENTS);
+        let Ok(list) = list_files_in_dir(&talents_dir_path) else {
+            tracing::info!("No talent trees found in {:?}", talents_dir_path);
+            return;
+        };
+        for tree_path in &list {
+            match TalentTree::try_new_from_json(tree_path) {
+                Ok(tree) => self.talent_trees.push(tree),
+                Err(e) => tracing::error!("{}", e),
+            }
+        }
+    }
+
+    /// Load the innate passives of the classes and enable them on the loaded heroes.
+    /// A specialised hero without a passive of its own keeps the one of its base class.
+    /// Without file, the classes have no passive.
+    pub fn load_class_passives<P: AsRef<Path>>(&mut self, path: P) {
+        let passives_path = path.as_ref().join(*OFFLINE_CLASS_PASSIVES);
+        if !passives_path.exists() {
+            tracing::info!("No class passives found in {:?}", passives_path);
+            return;
+        }
+        match utils::read_from_json::<_, Vec<ClassPassive>>(&passives_path) {
+            Ok(passives) => self.class_passives = passives,
+            Err(e) => tracing::error!("{:?} cannot be decoded: {}", passives_path, e),
+        }
+        for hero in self.all_heroes.iter_mut() {
+            let passive = self
+                .class_passives
+                .iter()
+                .find(|p| p.class == hero.class)
+                .or_else(|| {
+                    self.class_passives
+                        .iter()
+                        .find(|p| hero.class.is_a(&p.class))
+                });
+            if let Some(passive) = passive {
+                hero.apply_class_passive(passive);
+            }
+        }
+    }
+
     /// Return a sorted list of all distinct universes found in loaded scenarios.
     /// An empty string means the default universe (scenarios stored at the top level).
     pub fn list_universes(&self) -> Vec<String> {
@@ -278,8 +359,14 @@ mod tests {
     use strum::IntoEnumIterator;
 
     use crate::{
-        character_mod::equipment::EquipmentJsonKey,
-        common::constants::paths_const::TEST_OFFLINE_ROOT, server::data_manager::DataManager,
+        character_mod::{
+            buffers::BufKinds,
+            class::Class,
+            element::{Element, TypeChart},
+            equipment::EquipmentJsonKey,
+        },
+        common::constants::paths_const::TEST_OFFLINE_ROOT,
+        server::data_manager::DataManager,
         testing::testing_all_characters::testing_dm,
     };
 
@@ -317,6 +404,55 @@ mod tests {
         assert!(dm.load_all_equipments("").is_err());
     }
 
+    #[test]
+    fn unit_load_talent_trees() {
+        let dm = testing_dm();
+        assert_eq!(dm.talent_trees.len(), 1);
+        assert_eq!(dm.talent_trees[0].class, Class::Standard);
+
+        let mut dm = DataManager::default();
+        dm.load_talent_trees("unknown");
+        assert!(dm.talent_trees.is_empty());
+    }
+
+    #[test]
+    fn unit_load_class_passives() {
+        let mut dm = testing_dm();
+        assert_eq!(dm.class_passives.len(), 4);
+
+        // the passive of its class is enabled on a loaded hero
+        dm.all_heroes[0].class = Class::Mage;
+        dm.load_class_passives(*TEST_OFFLINE_ROOT);
+        let buffer = dm.all_heroes[0]
+            .character_rounds_info
+            .get_passive_buffer(&BufKinds::CritManaRefundPercent)
+            .unwrap();
+        assert_eq!(buffer.value, 50);
+
+        // a specialised hero keeps the passive of its base class
+        dm.all_heroes[1].class = Class::Pyromancer;
+        dm.load_class_passives(*TEST_OFFLINE_ROOT);
+        let buffer = dm.all_heroes[1]
+            .character_rounds_info
+            .get_passive_buffer(&BufKinds::CritManaRefundPercent)
+            .unwrap();
+        assert_eq!(buffer.value, 50);
+    }
+
+    #[test]
+    fn unit_load_type_chart() {
+        let dm = testing_dm();
+        assert_eq!(
+            dm.type_chart.multiplier(Element::Fire, &[Element::Grass]),
+            2.0
+        );
+
+        // no type chart file: normal effectiveness
+        let mut dm = DataManager::default();
+        dm.load_type_chart("unknown");
+        assert_eq!(dm.type_chart, TypeChart::default());
+    }
+
     #[test]
     fn unit_load_all_scenarios_err() {
         let mut dm = DataManager::default();
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::character_mod::{
+    character::{Character, CharacterKind},
+    stats::StatKind,
+};
+
+/// Stats of the bosses scaled by the difficulty
+pub const DIFFICULTY_BOSS_STATS: &[StatKind] = &[
+    StatKind::Hp,
+    StatKind::PhysicalPower,
+    StatKind::MagicalPower,
+    StatKind::PhysicalArmor,
+    StatKind::MagicalArmor,
+];
+
+/// Difficulty of a game, chosen at the creation of the lobby
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Story,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl Difficulty {
+    /// Percentage applied to the stats of the bosses
+    pub fn boss_stats_percent(&self) -> u64 {
+        match self {
+            Difficulty::Story => 70,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 125,
+            Difficulty::Nightmare => 150,
+        }
+    }
+
+    /// Percentage applied to the experience and the currency loots
+    pub fn rewards_percent(&self) -> u64 {
+        match self {
+            Difficulty::Story => 75,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 125,
+            Difficulty::Nightmare => 150,
+        }
+    }
+
+    /// Number of misses added to the streak-breaker thresholds of the heroes
+    pub fn streak_breaker_offset(&self) -> i64 {
+        match self {
+            Difficulty::Story => -2,
+            Difficulty::Normal => 0,
+            Difficulty::Hard => 2,
+            Difficulty::Nightmare => 4,
+        }
+    }
+
+    /// Supplementary actions of the fastest alive boss at each turn
+    pub fn extra_boss_actions(&self) -> usize {
+        match self {
+            Difficulty::Nightmare => 1,
+            _ => 0,
+        }
+    }
+
+    /// Scale the stats of a boss entering the fight
+    pub fn scale_boss(&self, boss: &mut Character) {
+        boss.stats
+            .scale_by_percent(DIFFICULTY_BOSS_STATS, self.boss_stats_percent());
+    }
+
+    /// Scale an amount of experience or currency
+    pub fn scale_reward(&self, amount: u64) -> u64 {
+        amount * self.rewards_percent() / 100
+    }
+
+    /// Streak-breaker threshold of a character, at least 1.
+    /// Only the thresholds of the heroes depend on the difficulty.
+    pub fn streak_breaker_threshold(
+        &self,
+        threshold: Option<u32>,
+        kind: &CharacterKind,
+    ) -> Option<u32> {
+        if *kind != CharacterKind::Hero {
+            return threshold;
+        }
+        threshold.map(|t| (t as i64 + self.streak_breaker_offset()).max(1) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::testing_all_characters::testing_pm;
+
+    #[test]
+    fn unit_difficulty() {
+        let pm = testing_pm();
+        let mut boss = pm.active_bosses[0].clone();
+        let hp = boss.stats[StatKind::Hp].max;
+        let speed = boss.stats[StatKind::Speed].max;
+        Difficulty::Hard.scale_boss(&mut boss);
+        assert_eq!(boss.stats[StatKind::Hp].max, hp * 125 / 100);
+        assert_eq!(boss.stats[StatKind::Hp].current, hp * 125 / 100);
+        assert_eq!(boss.stats[StatKind::Speed].max, speed);
+
+        assert_eq!(Difficulty::Story.scale_reward(100), 75);
+        assert_eq!(Difficulty::Normal.scale_reward(100), 100);
+        assert_eq!(Difficulty::Nightmare.scale_reward(100), 150);
+
+        let hero = CharacterKind::Hero;
+        assert_eq!(
+            Difficulty::Hard.streak_breaker_threshold(Some(5), &hero),
+            Some(7)
+        );
+        assert_eq!(
+            Difficulty::Story.streak_breaker_threshold(Some(3), &hero),
+            Some(1)
+        );
+        assert_eq!(Difficulty::Hard.streak_breaker_threshold(None, &hero), None);
+        assert_eq!(
+            Difficulty::Hard.streak_breaker_threshold(Some(5), &CharacterKind::Boss),
+            Some(5)
+        );
+        assert_eq!(Difficulty::Nightmare.extra_boss_actions(), 1);
+    }
+}
@@ -1,7 +1,18 @@
+use crate::{
+    character_mod::{class::Class, loot::Loot},
+    server::objective::{ObjectiveResult, ObjectiveState},
+};
+
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct EndOfScenario {
     pub scenario_level: u64,
     pub characters_levelup: Vec<LevelUp>,
+    /// Objectives of the scenario and their final states
+    #[serde(default)]
+    pub objectives: Vec<ObjectiveResult>,
+    /// Loots of the completed secondary objectives, given with the loots of the scenario
+    #[serde(default)]
+    pub bonus_loots: Vec<Loot>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -9,6 +20,15 @@ pub struct LevelUp {
     pub character_id_name: String,
     pub new_level: u64,
     pub old_level: u64,
+    /// Talent points of the hero left to spend
+    #[serde(default)]
+    pub unspent_talent_points: u64,
+    /// Attacks learnt with the level-up
+    #[serde(default)]
+    pub new_atks: Vec<String>,
+    /// Specialisations the hero can choose
+    #[serde(default)]
+    pub specialisations: Vec<Class>,
 }
 
 impl EndOfScenario {
@@ -26,6 +46,49 @@ impl EndOfScenario {
                     level_up.character_id_name, level_up.old_level
                 ));
             }
+            for atk_name in &level_up.new_atks {
+                result.push_str(&format!(
+                    "Character {} learns {}\n",
+                    level_up.character_id_name, atk_name
+                ));
+            }
+            if level_up.unspent_talent_points > 0 {
+                result.push_str(&format!(
+                    "Character {} has {} talent point(s) to spend\n",
+                    level_up.character_id_name, level_up.unspent_talent_points
+                ));
+            }
+            if !level_up.specialisations.is_empty() {
+                let names: Vec<&str> = level_up
+                    .specialisations
+                    .iter()
+                    .map(|c| c.to_str())
+                    .collect();
+                result.push_str(&format!(
+                    "Character {} can be specialised as {}\n",
+                    level_up.character_id_name,
+                    names.join(" or ")
+                ));
+            }
+        }
+        for objective in &self.objectives {
+            let icon = match objective.state {
+                ObjectiveState::Completed => "✅",
+                ObjectiveState::Failed => "❌",
+                ObjectiveState::InProgress => "⏳",
+            };
+            let secondary = if objective.is_secondary {
+                " (secondary)"
+            } else {
+                ""
+            };
+            result.push_str(&format!(
+                "Objective {} {}{}\n",
+                icon, objective.description, secondary
+            ));
+        }
+        for loot in &self.bonus_loots {
+            result.push_str(&format!("Bonus loot: {}\n", loot.name));
         }
         if is_html_string {
             result = result.replace("\n", "<br/>");
@@ -47,23 +110,28 @@ mod tests {
                     character_id_name: "Hero1".to_string(),
                     new_level: 3,
                     old_level: 2,
+                    unspent_talent_points: 1,
+                    new_atks: vec!["Fireball".to_string()],
+                    specialisations: vec![Class::Paladin, Class::Guardian],
                 },
                 LevelUp {
                     character_id_name: "Hero2".to_string(),
                     new_level: 2,
                     old_level: 2,
+                    unspent_talent_points: 0,
+                    new_atks: Vec::new(),
+                    specialisations: Vec::new(),
                 },
             ],
+            ..Default::default()
         };
         let formatted_string = end_of_scenario.to_formatted_string(false);
-        let expected_string =
-            "Scenario Level: 5\nCharacter Hero1 ⬆️ 2 to 3 \nCharacter Hero2 🟰 2 \n";
+        let expected_string = "Scenario Level: 5\nCharacter Hero1 ⬆️ 2 to 3 \nCharacter Hero1 learns Fireball\nCharacter Hero1 has 1 talent point(s) to spend\nCharacter Hero1 can be specialised as Paladin or Guardian\nCharacter Hero2 🟰 2 \n";
         assert_eq!(formatted_string, expected_string);
 
         // html string
         let formatted_string_html = end_of_scenario.to_formatted_string(true);
-        let expected_string_html =
-            "Scenario Level: 5<br/>Character Hero1 ⬆️ 2 to 3 <br/>Character Hero2 🟰 2 <br/>";
+        let expected_string_html = "Scenario Level: 5<br/>Character Hero1 ⬆️ 2 to 3 <br/>Character Hero1 learns Fireball<br/>Character Hero1 has 1 talent point(s) to spend<br/>Character Hero1 can be specialised as Paladin or Guardian<br/>Character Hero2 🟰 2 <br/>";
         assert_eq!(formatted_string_html, expected_string_html);
     }
 }
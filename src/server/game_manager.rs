@@ -6,24 +6,36 @@ use crate::{
         buffers::BufKinds,
         character::{Character, CharacterKind},
         class::Class,
+        effect::EffectOutcome,
+        element::TypeChart,
         equipment::{Equipment, EquipmentJsonKey},
         experience::{build_exp_to_next_level, build_experience},
-        loot::LootType,
+        loot::{Loot, LootType},
         rounds_information::AmountType,
+        stats::StatKind,
+        talent::TalentTree,
+        target::TargetingStrategy,
     },
     common::{
-        constants::{character_const::ULTIMATE_LEVEL, paths_const::*, stats_const::*},
+        constants::{character_const::ULTIMATE_LEVEL, paths_const::*},
+        game_rng::GameRng,
         log_data::{
             LogData,
             const_colors::{DARK_RED, LIGHT_BLUE, LIGHT_GREEN},
         },
     },
     server::{
+        boss_ai,
+        campaign::CampaignProgress,
+        command_log::{CommandLog, GameCommand, StateCheckpoint},
+        difficulty::Difficulty,
         end_of_scenario::{EndOfScenario, LevelUp},
         game_paths::GamePaths,
         game_state::{GameState, GameStatus},
+        objective::{Objective, ObjectiveResult, ObjectiveState},
+        overworld_manager::OverworldState,
         players_manager::{DodgeInfo, GameAtkEffect, PlayerManager},
-        scenario::{Scenario, ScenarioState},
+        scenario::{Scenario, ScenarioState, WaveTrigger},
     },
     shop::build_consumable_by_name,
     utils,
@@ -70,8 +82,27 @@ pub struct GameManager {
     pub all_scenarios: Vec<Scenario>,
     /// State of the different scenarios, to know which scenario is available for the player and to adapt the behavior of the fight
     pub states_scenarios: HashMap<String, ScenarioState>,
+    /// Choices and completed objectives unlocking the edges of the campaign graph
+    #[serde(default)]
+    pub campaign: CampaignProgress,
     /// End of scenario
     pub end_of_scenario: EndOfScenario,
+    /// Seedable RNG used for every random roll of the game (crit, dodge, boss atk...).
+    /// Saved with the game so that a fight can be replayed from its seed.
+    #[serde(default)]
+    pub rng: GameRng,
+    /// Player commands played in this game, used to replay it
+    #[serde(default)]
+    pub command_log: CommandLog,
+    /// Type-effectiveness table of the elements of the attacks
+    #[serde(default)]
+    pub type_chart: TypeChart,
+    /// Difficulty of the game, scaling the bosses and the rewards
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// Talent tree of each class, to spend the talent points of the heroes
+    #[serde(default)]
+    pub talent_trees: Vec<TalentTree>,
 }
 
 impl GameManager {
@@ -91,9 +122,22 @@ impl GameManager {
         let game_name = game_state.game_name.clone();
 
         // scenarios state
+        let mut first_levels: HashMap<&str, u64> = HashMap::new();
+        for scenario in &scenarios {
+            let level = first_levels
+                .entry(&scenario.universe)
+                .or_insert(scenario.level);
+            *level = (*level).min(scenario.level);
+        }
         let mut states_scenarios = HashMap::new();
         for scenario in &scenarios {
-            states_scenarios.insert(scenario.name.clone(), ScenarioState::NotStarted);
+            // the first scenarios of the campaigns are available from the start
+            let state = if scenario.level <= first_levels[scenario.universe.as_str()] {
+                ScenarioState::Available
+            } else {
+                ScenarioState::NotStarted
+            };
+            states_scenarios.insert(scenario.name.clone(), state);
         }
 
         GameManager {
@@ -104,13 +148,44 @@ impl GameManager {
             current_scenario: Scenario::default(),
             all_scenarios: scenarios,
             states_scenarios,
+            campaign: CampaignProgress::default(),
             end_of_scenario: EndOfScenario::default(),
+            rng: GameRng::from_entropy(),
+            command_log: CommandLog::default(),
+            type_chart: TypeChart::default(),
+            difficulty: Difficulty::default(),
+            talent_trees: Vec::new(),
+        }
+    }
+
+    /// Set the difficulty of the game.
+    /// Before the first turn of a scenario, its bosses are loaded again with the new scaling,
+    /// otherwise the difficulty applies from the next bosses entering a fight.
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.process_set_difficulty(difficulty);
+        self.record_command(GameCommand::SetDifficulty { difficulty }, None);
+    }
+
+    fn process_set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+        if self.game_state.current_turn_nb == 0 && !self.pm.active_bosses.is_empty() {
+            self.pm.active_bosses.clear();
+            let all_bosses = self.pm.all_bosses.clone();
+            self.set_active_bosses(&all_bosses);
         }
     }
 
+    /// Reset the RNG of the game with the given seed.
+    /// Two games started with the same seed and the same actions produce the same fights.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = GameRng::new(seed);
+    }
+
     /// Set active bosses from the current scenario's boss patterns.
     /// Bosses whose name matches a pattern in the current scenario are cloned and
     /// pushed into `pm.active_bosses` with a unique id_name (`"<name>_#<n>"`).
+    /// The targeting strategy and the level of the scenario, if any, replace the boss ones,
+    /// and the stats are scaled by the difficulty.
     pub fn set_active_bosses(&mut self, all_bosses: &[Character]) {
         self.current_scenario
             .boss_patterns
@@ -118,6 +193,16 @@ impl GameManager {
             .for_each(|(boss_name, _)| {
                 if let Some(b) = all_bosses.iter().find(|b| b.db_full_name == *boss_name) {
                     let mut boss_to_push = b.clone();
+                    if let Some(strategy) = self.current_scenario.boss_targeting.get(boss_name) {
+                        boss_to_push.targeting_strategy = strategy.clone();
+                    }
+                    if let Some(level) = self.current_scenario.boss_levels.get(boss_name) {
+                        while boss_to_push.level < *level {
+                            boss_to_push.level += 1;
+                            boss_to_push.stats.update_stats_to_next_level();
+                        }
+                    }
+                    self.difficulty.scale_boss(&mut boss_to_push);
                     boss_to_push.id_name = format!(
                         "{}_#{}",
                         boss_to_push.db_full_name,
@@ -132,34 +217,381 @@ impl GameManager {
             });
     }
 
-    pub fn load_next_scenario(&mut self) -> Result<()> {
-        // update current scenario state
-        if let Some((_, state)) = self
-            .states_scenarios
-            .iter_mut()
-            .find(|(name, _)| *name == &self.current_scenario.name)
+    /// Atk pattern of the boss: the one of its last phase with a pattern,
+    /// the one of the scenario otherwise.
+    fn boss_pattern(&self, boss: &Character) -> Option<Vec<u64>> {
+        self.current_scenario
+            .boss_phases
+            .get(&boss.db_full_name)
+            .and_then(|phases| {
+                phases
+                    .iter()
+                    .take(boss.character_rounds_info.boss_phase)
+                    .rev()
+                    .find(|p| !p.pattern.is_empty())
+            })
+            .map(|p| p.pattern.clone())
+            .or_else(|| {
+                self.current_scenario
+                    .boss_patterns
+                    .get(&boss.db_full_name)
+                    .cloned()
+            })
+    }
+
+    /// Enter the next phases of the bosses whose HP crossed a phase threshold of the scenario:
+    /// the pattern of the boss is replaced, the phase buffs are applied and the adds are summoned.
+    /// Return the announcements of the new phases.
+    fn process_boss_phases(&mut self) -> Vec<LogData> {
+        let mut logs = Vec::new();
+        let nb_bosses = self.pm.active_bosses.len();
+        for i in 0..nb_bosses {
+            let Some(phases) = self
+                .current_scenario
+                .boss_phases
+                .get(&self.pm.active_bosses[i].db_full_name)
+                .cloned()
+            else {
+                continue;
+            };
+            while let Some(index) = boss_ai::next_boss_phase(&phases, &self.pm.active_bosses[i]) {
+                let phase = &phases[index];
+                let boss = &mut self.pm.active_bosses[i];
+                boss.character_rounds_info.boss_phase = index + 1;
+                if !phase.pattern.is_empty() {
+                    boss.character_rounds_info.atk_pattern_queue.clear();
+                }
+                if let Err(e) = boss.receive_self_effects(
+                    &phase.name,
+                    &phase.buffs,
+                    &self.game_state,
+                    &mut self.rng,
+                ) {
+                    tracing::error!(
+                        "Error while applying the buffs of phase {} on {}: {}",
+                        phase.name,
+                        boss.id_name,
+                        e
+                    );
+                }
+                tracing::info!("Boss {} enters phase {}", boss.id_name, phase.name);
+                logs.push(LogData {
+                    message: phase.announcement_txt(&boss.id_name),
+                    color: DARK_RED.to_string(),
+                });
+                logs.extend(self.summon_bosses(&phase.adds));
+            }
+        }
+        // refresh the shadow current player if its phase changed
+        if let Some(boss) = self
+            .pm
+            .get_active_boss_character(&self.pm.current_player.id_name)
         {
-            *state = ScenarioState::Completed;
-        }
-        let current_level = self.current_scenario.level;
-        let current_universe = self.current_scenario.universe.clone();
-        // get the next scenario with the next level in the same universe.
-        // When current_universe is empty (default scenario at game start) skip the
-        // universe filter so the first real scenario is found regardless of universe.
-        let Some(scenario) = self
-            .all_scenarios
+            self.pm.current_player = boss.clone();
+        }
+        logs
+    }
+
+    /// Add the bosses of `pm.all_bosses` to the fight, with the targeting strategy of the scenario.
+    /// They join `order_to_play` at the next turn.
+    fn summon_bosses(&mut self, boss_names: &[String]) -> Vec<LogData> {
+        let mut logs = Vec::new();
+        for boss_name in boss_names {
+            let Some(id_name) = self.pm.summon_boss(boss_name) else {
+                tracing::warn!("Boss {} not found, it cannot be summoned", boss_name);
+                continue;
+            };
+            if let Some(c) = self.pm.get_mut_active_boss_character(&id_name) {
+                if let Some(strategy) = self.current_scenario.boss_targeting.get(boss_name) {
+                    c.targeting_strategy = strategy.clone();
+                }
+                self.difficulty.scale_boss(c);
+            }
+            tracing::info!("Boss {} joins the fight", id_name);
+            logs.push(LogData {
+                message: format!("{} joins the fight", id_name),
+                color: DARK_RED.to_string(),
+            });
+        }
+        logs
+    }
+
+    /// Summon the reinforcement wave of index `index` of the scenario if it is still available
+    fn summon_wave(&mut self, index: usize) -> Vec<LogData> {
+        let Some(wave) = self.current_scenario.waves.get(index).cloned() else {
+            return Vec::new();
+        };
+        let nb_summoned = self.game_state.summoned_waves.entry(index).or_insert(0);
+        if !wave.is_available(*nb_summoned) {
+            return Vec::new();
+        }
+        *nb_summoned += 1;
+        self.summon_bosses(&wave.bosses)
+    }
+
+    /// Summon the reinforcement waves triggered at the start of the current turn
+    fn process_turn_waves(&mut self) -> Vec<LogData> {
+        let turn_nb = self.game_state.current_turn_nb;
+        let indexes: Vec<usize> = self
+            .current_scenario
+            .waves
             .iter()
-            .find(|s| {
-                s.level == current_level + 1
-                    && (current_universe.is_empty() || s.universe == current_universe)
+            .enumerate()
+            .filter(|(_, w)| matches!(w.trigger, WaveTrigger::TurnEvery(n) if n > 0 && turn_nb.is_multiple_of(n)))
+            .map(|(i, _)| i)
+            .collect();
+        indexes
+            .into_iter()
+            .flat_map(|i| self.summon_wave(i))
+            .collect()
+    }
+
+    /// Record the bosses who died since the last call in `GameState::died_ennemies`
+    /// and summon the reinforcement waves triggered by their death.
+    fn process_died_bosses(&mut self) -> Vec<LogData> {
+        let new_dead: Vec<(String, String)> = self
+            .pm
+            .active_bosses
+            .iter()
+            .filter(|b| {
+                b.stats.is_dead()
+                    && !self
+                        .game_state
+                        .died_ennemies
+                        .values()
+                        .flatten()
+                        .any(|id_name| *id_name == b.id_name)
             })
-            .cloned()
+            .map(|b| (b.id_name.clone(), b.db_full_name.clone()))
+            .collect();
+        let mut logs = Vec::new();
+        for (id_name, db_full_name) in new_dead {
+            // a dead boss has no target to choose anymore
+            self.pm.threat_table.remove_boss(&id_name);
+            self.game_state
+                .died_ennemies
+                .entry(self.game_state.current_turn_nb)
+                .or_default()
+                .push(id_name);
+            let indexes: Vec<usize> = self
+                .current_scenario
+                .waves
+                .iter()
+                .enumerate()
+                .filter(|(_, w)| w.trigger == WaveTrigger::BossDied(db_full_name.clone()))
+                .map(|(i, _)| i)
+                .collect();
+            for i in indexes {
+                logs.extend(self.summon_wave(i));
+            }
+        }
+        logs
+    }
+
+    /// Update the states of the objectives of the scenario.
+    /// A completed or failed objective keeps its state until the end of the scenario.
+    fn update_objectives(&mut self) -> Vec<Objective> {
+        let objectives = self.current_scenario.all_objectives();
+        let states = &mut self.game_state.objective_states;
+        states.resize(objectives.len(), ObjectiveState::InProgress);
+        for (objective, state) in objectives.iter().zip(states.iter_mut()) {
+            if *state == ObjectiveState::InProgress {
+                *state = objective.evaluate(&self.pm, self.game_state.current_turn_nb);
+            }
+        }
+        objectives
+    }
+
+    /// Evaluate the objectives of the scenario: the game ends when all the heroes are dead
+    /// or when a primary objective failed, the scenario ends when all the primary goals are completed.
+    /// Return true if the fight is over.
+    fn process_objectives(&mut self) -> bool {
+        let objectives = self.update_objectives();
+        let primaries = || {
+            objectives
+                .iter()
+                .zip(&self.game_state.objective_states)
+                .filter(|(o, _)| !o.is_secondary)
+        };
+        let is_primary_failed = primaries().any(|(_, s)| *s == ObjectiveState::Failed);
+        let is_won = primaries()
+            .filter(|(o, _)| o.kind.is_goal())
+            .all(|(_, s)| *s == ObjectiveState::Completed);
+        if self.pm.check_end_of_game().0 || is_primary_failed {
+            self.game_state.status = GameStatus::EndOfGame;
+            self.report_objectives(false);
+            true
+        } else if is_won {
+            self.game_state.status = GameStatus::EndOfScenario;
+            self.process_end_of_scenario();
+            // the players can choose among the scenarios unlocked by the victory
+            self.complete_current_scenario();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Set the final states of the objectives in `end_of_scenario`: at the victory, the goals in progress
+    /// fail and the other objectives in progress are completed; at the defeat, all of them fail.
+    /// Return the bonus loots of the completed secondary objectives.
+    fn report_objectives(&mut self, is_victory: bool) -> Vec<Loot> {
+        let objectives = self.update_objectives();
+        let mut bonus_loots = Vec::new();
+        self.end_of_scenario.objectives.clear();
+        for (objective, state) in objectives
+            .iter()
+            .zip(self.game_state.objective_states.iter_mut())
+        {
+            if *state == ObjectiveState::InProgress {
+                *state = if is_victory && !objective.kind.is_goal() {
+                    ObjectiveState::Completed
+                } else {
+                    ObjectiveState::Failed
+                };
+            }
+            if is_victory && objective.is_secondary && *state == ObjectiveState::Completed {
+                bonus_loots.extend(objective.bonus_loots.iter().cloned());
+            }
+            self.end_of_scenario.objectives.push(ObjectiveResult {
+                description: objective.kind.to_string(),
+                is_secondary: objective.is_secondary,
+                state: *state,
+            });
+        }
+        self.end_of_scenario.bonus_loots = bonus_loots.clone();
+        bonus_loots
+    }
+
+    pub fn load_next_scenario(&mut self) -> Result<()> {
+        let result = self.process_load_next_scenario();
+        self.record_command(GameCommand::LoadNextScenario, None);
+        result
+    }
+
+    pub(crate) fn process_load_next_scenario(&mut self) -> Result<()> {
+        self.complete_current_scenario();
+        // get the first available scenario among the next ones of the campaign graph,
+        // or among all the scenarios at game start
+        let candidates: Vec<String> = if self.current_scenario.name.is_empty() {
+            self.all_scenarios.iter().map(|s| s.name.clone()).collect()
+        } else {
+            self.current_scenario
+                .next_edges(&self.all_scenarios)
+                .into_iter()
+                .map(|edge| edge.scenario)
+                .collect()
+        };
+        let Some(name) = candidates
+            .iter()
+            .find(|name| self.states_scenarios.get(*name) == Some(&ScenarioState::Available))
         else {
             return Err(anyhow::anyhow!(
-                "No next scenario found for level {}",
-                current_level + 1
+                "No next scenario available after '{}'",
+                self.current_scenario.name
             ));
         };
+        self.start_scenario(&name.clone())
+    }
+
+    /// Load the scenario chosen by the players among the available ones
+    pub fn load_scenario(&mut self, name: &str) -> Result<()> {
+        let result = self.process_load_scenario(name);
+        self.record_command(
+            GameCommand::LoadScenario {
+                name: name.to_owned(),
+            },
+            None,
+        );
+        result
+    }
+
+    fn process_load_scenario(&mut self, name: &str) -> Result<()> {
+        if self.states_scenarios.get(name) != Some(&ScenarioState::Available) {
+            return Err(anyhow::anyhow!("Scenario '{}' is not available", name));
+        }
+        self.complete_current_scenario();
+        self.start_scenario(name)
+    }
+
+    /// Scenarios the players can choose to play next, in the order of `all_scenarios`
+    pub fn available_scenarios(&self) -> Vec<&Scenario> {
+        self.all_scenarios
+            .iter()
+            .filter(|s| self.states_scenarios.get(&s.name) == Some(&ScenarioState::Available))
+            .collect()
+    }
+
+    /// Record a choice of the players and unlock the scenarios depending on it
+    pub fn make_choice(&mut self, choice: &str) {
+        self.campaign.make_choice(choice);
+        self.refresh_campaign_unlocks();
+        self.record_command(
+            GameCommand::MakeChoice {
+                choice: choice.to_owned(),
+            },
+            None,
+        );
+    }
+
+    /// Mark the current scenario as completed with its completed objectives,
+    /// then unlock the scenarios it leads to. Only a won fight completes its scenario.
+    fn complete_current_scenario(&mut self) {
+        if self.game_state.status != GameStatus::EndOfScenario {
+            return;
+        }
+        let name = self.current_scenario.name.clone();
+        let Some(state) = self.states_scenarios.get_mut(&name) else {
+            return;
+        };
+        *state = ScenarioState::Completed;
+        // the objective states also hold the default goal, inserted first
+        let offset =
+            self.current_scenario.all_objectives().len() - self.current_scenario.objectives.len();
+        let completed = self
+            .game_state
+            .objective_states
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .filter(|(_, state)| **state == ObjectiveState::Completed)
+            .map(|(i, _)| i - offset)
+            .collect();
+        self.campaign.completed_objectives.insert(name, completed);
+        self.refresh_campaign_unlocks();
+    }
+
+    /// Unlock the scenarios reached by an open edge from a completed scenario
+    fn refresh_campaign_unlocks(&mut self) {
+        let mut unlocked = Vec::new();
+        for scenario in &self.all_scenarios {
+            if self.states_scenarios.get(&scenario.name) != Some(&ScenarioState::Completed) {
+                continue;
+            }
+            for edge in scenario.next_edges(&self.all_scenarios) {
+                if self
+                    .campaign
+                    .is_edge_open(&scenario.name, &edge, &self.pm.active_heroes)
+                {
+                    unlocked.push(edge.scenario);
+                }
+            }
+        }
+        for name in unlocked {
+            if let Some(state) = self.states_scenarios.get_mut(&name)
+                && *state == ScenarioState::NotStarted
+            {
+                tracing::info!("Scenario unlocked: {}", name);
+                *state = ScenarioState::Available;
+            }
+        }
+    }
+
+    fn start_scenario(&mut self, name: &str) -> Result<()> {
+        let Some(scenario) = self.all_scenarios.iter().find(|s| s.name == name).cloned() else {
+            return Err(anyhow::anyhow!("Unknown scenario '{}'", name));
+        };
+        let is_first_scenario = self.current_scenario.name.is_empty();
         // update scenario state in map
         if let Some((_, state)) = self
             .states_scenarios
@@ -171,13 +603,13 @@ impl GameManager {
         // update current scenario
         self.current_scenario = scenario;
 
-        if self.current_scenario.level > 1 {
+        if !is_first_scenario {
             // accumulate kills from the completed scenario before clearing
             let scenario_kills = self
                 .pm
                 .active_bosses
                 .iter()
-                .filter(|b| b.stats.is_dead().unwrap_or(false))
+                .filter(|b| b.stats.is_dead())
                 .count();
             self.game_state.accumulated_kills += scenario_kills;
             // clear previous scenario
@@ -217,12 +649,16 @@ impl GameManager {
     /// Return a boolean to know if the new turn has been started and the logs of the new round if it is the case
     pub fn start_new_turn(&mut self) -> (bool, Vec<LogData>) {
         // For each turn now
+        self.game_state.start_new_turn();
+        // reinforcements join the fight before the order is processed
+        let mut wave_logs = self.process_turn_waves();
         // Process the order of the players
         self.process_order_to_play();
-        self.game_state.start_new_turn();
         self.pm.start_new_turn(self.game_state.current_turn_nb == 1);
 
-        self.new_round()
+        let (is_new_round, logs) = self.new_round();
+        wave_logs.extend(logs);
+        (is_new_round, wave_logs)
     }
 
     /// Process the order of the players to play by sorting them by speed and adding the supplementary atk turns for the heroes and the bosses
@@ -235,10 +671,10 @@ impl GameManager {
         // sort by speed descending (highest speed acts first)
         self.pm
             .active_heroes
-            .sort_by(|a, b| b.stats.all_stats[SPEED].cmp(&a.stats.all_stats[SPEED]));
+            .sort_by(|a, b| b.stats[StatKind::Speed].cmp(&a.stats[StatKind::Speed]));
         let mut dead_heroes = Vec::new();
         for hero in &self.pm.active_heroes {
-            if !hero.stats.is_dead().unwrap_or(false) {
+            if !hero.stats.is_dead() {
                 self.game_state.order_to_play.push(hero.id_name.clone());
             } else {
                 dead_heroes.push(hero.id_name.clone());
@@ -252,9 +688,9 @@ impl GameManager {
         // sort by speed descending (highest speed acts first)
         self.pm
             .active_bosses
-            .sort_by(|a, b| b.stats.all_stats[SPEED].cmp(&a.stats.all_stats[SPEED]));
+            .sort_by(|a, b| b.stats[StatKind::Speed].cmp(&a.stats[StatKind::Speed]));
         for boss in &self.pm.active_bosses {
-            if !boss.stats.is_dead().unwrap_or(false) {
+            if !boss.stats.is_dead() {
                 self.game_state.order_to_play.push(boss.id_name.clone());
             }
         }
@@ -263,6 +699,12 @@ impl GameManager {
         let supp_rounds_bosses = self.pm.process_sup_atk_turn(CharacterKind::Boss);
         self.game_state.order_to_play.extend(supp_rounds_heroes);
         self.game_state.order_to_play.extend(supp_rounds_bosses);
+        // extra actions of the fastest alive boss in the hardest difficulties
+        if let Some(boss) = self.pm.active_bosses.iter().find(|b| !b.stats.is_dead()) {
+            for _ in 0..self.difficulty.extra_boss_actions() {
+                self.game_state.order_to_play.push(boss.id_name.clone());
+            }
+        }
     }
 
     pub fn new_round(&mut self) -> (bool, Vec<LogData>) {
@@ -280,6 +722,7 @@ impl GameManager {
         let Ok(mut logs) = self.pm.update_current_player_on_new_round(
             &self.game_state,
             &self.game_state.order_to_play[self.game_state.current_round - 1],
+            &mut self.rng,
         ) else {
             // return the error of update_current_player
             return (
@@ -291,14 +734,9 @@ impl GameManager {
             );
         };
 
-        if self.pm.current_player.stats.is_dead() == Some(true) {
-            let (all_heroes_dead, all_bosses_dead) = self.pm.check_end_of_game();
-            if all_heroes_dead {
-                self.game_state.status = GameStatus::EndOfGame;
-                return (false, logs);
-            } else if all_bosses_dead {
-                self.game_state.status = GameStatus::EndOfScenario;
-                self.process_end_of_scenario();
+        if self.pm.current_player.stats.is_dead() {
+            logs.extend(self.process_died_bosses());
+            if self.process_objectives() {
                 return (false, logs);
             }
             return self.new_round();
@@ -318,23 +756,83 @@ impl GameManager {
             },
         );
 
+        // stunned or sleeping character: the round is skipped
+        if let Some(kind) = self
+            .pm
+            .current_player
+            .character_rounds_info
+            .skipping_ailment()
+        {
+            logs.push(LogData {
+                message: format!(
+                    "{} is {} and skips the round",
+                    self.pm.current_player.id_name,
+                    kind.adjective()
+                ),
+                color: crate::common::log_data::const_colors::MUTED_GREY.to_owned(),
+            });
+            let (is_new_round, mut next_logs) = self.new_round();
+            logs.append(&mut next_logs);
+            return (is_new_round, logs);
+        }
+
         (true, logs)
     }
 
     /// Launch an attack from the current player
-    /// If atk_name is None and it is an auto round (boss), a random atk will be chosen
+    /// If atk_name is None and it is an auto round (boss), the atk is chosen by the scenario
+    /// boss rules, then the boss patterns, then randomly
     /// Otherwise, if atk_name is None, no atk will be launched
     pub fn launch_attack(&mut self, atk_name: Option<&str>) -> ResultLaunchAttack {
+        let ra = self.process_launch_attack(atk_name, None);
+        self.record_command(
+            GameCommand::LaunchAttack {
+                atk_name: atk_name.map(str::to_owned),
+            },
+            None,
+        );
+        ra
+    }
+
+    /// `boss_focus` is the targeting of a boss rule, the boss strategy is used otherwise.
+    fn process_launch_attack(
+        &mut self,
+        atk_name: Option<&str>,
+        boss_focus: Option<&TargetingStrategy>,
+    ) -> ResultLaunchAttack {
         // is atk existing?
         let Some(atk_name) = atk_name else {
             if self.is_round_auto() {
-                // check if pattern exists in scenario
-                if let Some(patterns) = self
+                // boss AI rules have priority on patterns
+                if let Some(rules) = self
                     .current_scenario
-                    .boss_patterns
+                    .boss_rules
                     .get(&self.pm.current_player.db_full_name)
-                    .cloned()
+                    && let Some(decision) = boss_ai::choose_boss_action(
+                        rules,
+                        &self.pm.current_player,
+                        &self.pm.active_heroes,
+                        self.game_state.current_turn_nb,
+                    )
                 {
+                    if rules[decision.rule_index].once {
+                        self.pm
+                            .current_player
+                            .character_rounds_info
+                            .fired_boss_rules
+                            .push(decision.rule_index);
+                    }
+                    tracing::info!(
+                        "Rule {} for boss {}: {}",
+                        decision.rule_index,
+                        self.pm.current_player.id_name,
+                        decision.atk_name
+                    );
+                    return self
+                        .process_launch_attack(Some(&decision.atk_name), decision.focus.as_ref());
+                }
+                // check if pattern exists in scenario or in the boss phase
+                if let Some(patterns) = self.boss_pattern(&self.pm.current_player) {
                     // fill queue from pattern on first use, then cycle
                     if self
                         .pm
@@ -364,19 +862,20 @@ impl GameManager {
                             self.pm.current_player.id_name,
                             atk_name
                         );
-                        return self.launch_attack(Some(&atk_name));
+                        return self.process_launch_attack(Some(&atk_name), None);
                     }
                 }
                 // auto atk for boss
                 if let Some(auto_atk_name) = AttackType::get_one_random_atk_name(
                     &self.pm.current_player.character_rounds_info.launchable_atks,
+                    &mut self.rng,
                 ) {
                     tracing::info!(
                         "Auto attack for boss {}: {}",
                         self.pm.current_player.id_name,
                         auto_atk_name
                     );
-                    return self.launch_attack(Some(&auto_atk_name));
+                    return self.process_launch_attack(Some(&auto_atk_name), None);
                 }
             }
 
@@ -415,10 +914,16 @@ impl GameManager {
             &all_players,
             self.pm.current_player.attacks_list[atk_name].level,
             &self.pm.current_player.clone().kind,
+            self.difficulty,
+            &mut self.rng,
         );
 
         // critical strike
-        let is_crit = match self.pm.current_player.process_critical_strike(atk_name) {
+        let is_crit = match self.pm.current_player.process_critical_strike(
+            atk_name,
+            self.difficulty,
+            &mut self.rng,
+        ) {
             Ok(is_crit) => is_crit,
             Err(e) => {
                 tracing::error!(
@@ -430,14 +935,16 @@ impl GameManager {
             }
         };
         // process boss target
-        self.pm.process_boss_target();
+        self.pm.process_boss_target(boss_focus, &mut self.rng);
+        // taunted or confused hero
+        self.pm.process_hero_forced_target(&atk, &mut self.rng);
 
         // ProcessAtk
         let all_effects_param =
             match self
                 .pm
                 .current_player
-                .process_atk(&self.game_state, is_crit, &atk)
+                .process_atk(&self.game_state, is_crit, &atk, &mut self.rng)
             {
                 Ok(effects) => effects,
                 Err(e) => {
@@ -460,6 +967,7 @@ impl GameManager {
             kind,
             stats: launcher_stats,
             atk_type: atk.clone(),
+            type_chart: self.type_chart.clone(),
         };
 
         let mut new_gaes: Vec<GameAtkEffect> = Vec::new();
@@ -500,9 +1008,11 @@ impl GameManager {
             }
         }
 
+        // threat of the launcher against the bosses
+        self.pm.process_threat(&id_name, &new_gaes);
+
         // other function
-        // Apply total aggro generated by all effects to the launcher so that boss
-        // target-selection correctly tracks which hero has been most active.
+        // Apply total aggro generated by all effects to the launcher, displayed as its global aggro.
         let total_aggro: u64 = new_gaes
             .iter()
             .map(|g| g.effect_outcome.aggro_generated)
@@ -525,7 +1035,7 @@ impl GameManager {
                     .input_effect_param
                     .buffer
                     .stats_name
-                    == HP
+                    == Some(StatKind::Hp)
                     && g.effect_outcome.real_amount_tx < 0
             })
             .map(|g| g.effect_outcome.real_amount_tx.abs())
@@ -543,12 +1053,21 @@ impl GameManager {
         }
 
         // Fire IsDamageTxHealNeedyAlly passive immediately after damage is dealt.
-        let passive_logs = if !self.pm.current_player.is_boss_atk() && total_damage_tx > 0 {
+        let mut passive_logs = if !self.pm.current_player.is_boss_atk() && total_damage_tx > 0 {
             self.pm
                 .apply_damage_tx_heal_passive(&id_name.clone(), total_damage_tx)
         } else {
             Vec::new()
         };
+        // lifesteal of the equipment affixes
+        passive_logs.extend(self.pm.apply_lifesteal(total_damage_tx));
+        // innate class passives
+        passive_logs.extend(self.pm.apply_class_passives(
+            &atk,
+            &new_gaes,
+            is_crit,
+            &self.game_state,
+        ));
 
         // update tx rx
         if is_crit
@@ -580,6 +1099,11 @@ impl GameManager {
         self.pm
             .modify_active_character(&self.pm.current_player.id_name.clone());
 
+        // bosses whose HP crossed a phase threshold
+        let mut phase_logs = self.process_boss_phases();
+        // dead bosses and reinforcements
+        phase_logs.extend(self.process_died_bosses());
+
         // process stats
         self.game_state.process_game_stats(
             &new_gaes,
@@ -593,6 +1117,7 @@ impl GameManager {
         // process end of attack
         let mut logs_atk = self.build_logs_atk(&all_dodging, &new_game_atk_effects, is_crit);
         logs_atk.extend(passive_logs.clone());
+        logs_atk.extend(phase_logs);
         let mut result_attack = ResultLaunchAttack {
             launcher_id_name: self.pm.current_player.id_name.clone(),
             atk_name: atk_name.to_string(),
@@ -615,12 +1140,7 @@ impl GameManager {
         // if bosses were alive before end-of-round but scenario ended during it, a DOT finished them
         if !bosses_dead_before_eor && self.game_state.status == GameStatus::EndOfScenario {
             result_attack.is_dot_kill = true;
-            if let Some(dead_boss) = self
-                .pm
-                .active_bosses
-                .iter()
-                .find(|b| b.stats.is_dead().unwrap_or(false))
-            {
+            if let Some(dead_boss) = self.pm.active_bosses.iter().find(|b| b.stats.is_dead()) {
                 result_attack.dying_char_last_atk = dead_boss.last_atk_name.clone();
             }
         }
@@ -645,8 +1165,13 @@ impl GameManager {
             .iter()
             .map(|boss| build_experience(&boss.rank, boss.level))
             .sum();
+        let total_exp = self.difficulty.scale_reward(total_exp);
 
-        let loots = self.current_scenario.loots.clone();
+        let mut loots = self.current_scenario.loots.clone();
+        loots.extend(self.report_objectives(true));
+        for loot in loots.iter_mut().filter(|l| l.kind == LootType::Currency) {
+            loot.level = self.difficulty.scale_reward(loot.level.max(0) as u64) as i64;
+        }
         let equipment_table_flat: Vec<Equipment> = self
             .pm
             .equipment_table
@@ -663,17 +1188,19 @@ impl GameManager {
                 character_id_name: hero.id_name.clone(),
                 new_level: hero.level,
                 old_level: hero.level,
+                unspent_talent_points: hero.talents.unspent_points,
+                new_atks: Vec::new(),
+                specialisations: Vec::new(),
             });
         });
 
         for i in 0..self.pm.active_heroes.len() {
             let hero_class = self.pm.active_heroes[i].class.clone();
+            let old_level = self.pm.active_heroes[i].level;
 
             // Add loot according to class
             for loot in &loots {
-                let class_matches =
-                    loot.classes.contains(&hero_class) || loot.classes.contains(&Class::Standard);
-                if !class_matches {
+                if !loot.is_for_class(&hero_class) {
                     continue;
                 }
                 match &loot.kind {
@@ -683,6 +1210,8 @@ impl GameManager {
                             .find(|e| e.unique_name == loot.name)
                             .cloned()
                         {
+                            // each dropped item is a new instance with its own rolled affixes
+                            let equipment = equipment.roll(&mut self.rng);
                             self.pm.active_heroes[i]
                                 .inventory
                                 .add_equipment(&equipment, false);
@@ -716,6 +1245,7 @@ impl GameManager {
                     .exp_to_next_level;
                 self.pm.active_heroes[i].level += 1;
                 self.pm.active_heroes[i].stats.update_stats_to_next_level();
+                self.pm.active_heroes[i].talents.unspent_points += 1;
                 // Recompute the threshold for the new level
                 self.pm.active_heroes[i]
                     .character_rounds_info
@@ -734,6 +1264,17 @@ impl GameManager {
                     level_up.new_level = self.pm.active_heroes[i].level;
                 }
             }
+            let new_atks = self.pm.active_heroes[i].learn_atks_of_level(old_level);
+            if let Some(level_up) = self
+                .end_of_scenario
+                .characters_levelup
+                .iter_mut()
+                .find(|lu| lu.character_id_name == self.pm.active_heroes[i].id_name)
+            {
+                level_up.unspent_talent_points = self.pm.active_heroes[i].talents.unspent_points;
+                level_up.new_atks = new_atks;
+                level_up.specialisations = self.pm.active_heroes[i].available_specialisations();
+            }
         }
 
         // Add consumable loot to the shared party bag (once per loot item, not per hero).
@@ -741,81 +1282,357 @@ impl GameManager {
             if loot.kind != LootType::Consumable {
                 continue;
             }
-            let any_hero_matches = self.pm.active_heroes.iter().any(|hero| {
-                loot.classes.contains(&hero.class) || loot.classes.contains(&Class::Standard)
-            });
+            let any_hero_matches = self
+                .pm
+                .active_heroes
+                .iter()
+                .any(|hero| loot.is_for_class(&hero.class));
             if any_hero_matches && let Some(consumable) = build_consumable_by_name(&loot.name) {
                 self.pm.party_consumables.push(consumable);
             }
         }
     }
 
-    fn process_no_atk_launched(&mut self) -> ResultLaunchAttack {
-        // Capture launcher identity before eval_end_of_round() may advance current_player.
-        let launcher_id_name = self.pm.current_player.id_name.clone();
-        let is_boss_atk = self.pm.current_player.is_boss_atk();
-        let turn_nb = self.game_state.current_turn_nb;
-        let round_nb = self.game_state.current_round;
+    /// The fight of the scenario is running: the loadouts cannot be changed
+    pub fn is_in_fight(&self) -> bool {
+        matches!(
+            self.game_state.status,
+            GameStatus::StartRound | GameStatus::ValidateAction
+        )
+    }
 
-        self.pm
-            .current_player
-            .character_rounds_info
-            .actions_done_in_round += 1;
-        let logs_atk = vec![LogData {
-            message: "No attack launched".to_string(),
-            color: DARK_RED.to_string(),
-        }];
-        let logs_end_of_round = self.eval_end_of_round(logs_atk.clone());
-        let result = ResultLaunchAttack {
-            launcher_id_name,
-            is_boss_atk,
-            logs_end_of_round,
-            logs_atk,
-            turn_nb,
-            round_nb,
-            ..Default::default()
+    /// Teach an attack to the hero, from a trainer or a scroll
+    pub fn learn_attack(&mut self, hero_id_name: &str, atk_name: &str) -> Result<()> {
+        let result = match self.pm.get_mut_active_hero_character(hero_id_name) {
+            Some(hero) => hero.learn_attack(atk_name),
+            None => Err(anyhow::anyhow!("Hero '{}' not found", hero_id_name)),
         };
-        // Mirror what launch_attack() does for real attacks so ra.atk_name is
-        // empty and the gameboard consumable-action banner branch is reachable.
-        self.game_state.last_result_atk = result.clone();
+        self.record_command(
+            GameCommand::LearnAttack {
+                hero_id_name: hero_id_name.to_owned(),
+                atk_name: atk_name.to_owned(),
+            },
+            None,
+        );
         result
     }
 
-    /// Evaluate the end of the round by checking if the game is finished,
-    ///  if a new round should start or if a new turn should start,
-    ///  and return the logs to display for the new round if it is the case
-    fn eval_end_of_round(&mut self, logs_atk: Vec<LogData>) -> Vec<LogData> {
-        let mut output_logs = vec![];
-        let (all_heroes_dead, all_bosses_dead) = self.pm.check_end_of_game();
-        if all_heroes_dead {
-            self.game_state.status = GameStatus::EndOfGame;
-        } else if all_bosses_dead {
-            self.game_state.status = GameStatus::EndOfScenario;
-            self.process_end_of_scenario();
-        } else {
-            let (is_new_round, logs) = self.new_round();
-            output_logs.extend(logs);
-            // new_round may have triggered EndOfScenario/EndOfGame (e.g. boss killed by DOT)
-            if matches!(
-                self.game_state.status,
-                GameStatus::EndOfScenario | GameStatus::EndOfGame
-            ) {
-                // Status already set inside new_round; nothing more to do
-            } else if is_new_round {
-                self.game_state.status = GameStatus::StartRound;
-            } else {
-                let (is_new_turn, logs) = self.start_new_turn();
-                output_logs.extend(logs);
-                if matches!(
-                    self.game_state.status,
-                    GameStatus::EndOfScenario | GameStatus::EndOfGame
-                ) {
-                    // Status set inside start_new_turn via new_round
-                } else if is_new_turn {
-                    self.game_state.status = GameStatus::StartRound;
-                } else {
-                    self.game_state.status = GameStatus::EndOfGame;
-                }
+    /// Replace the equipped attacks of the hero, outside of a fight
+    pub fn set_loadout(&mut self, hero_id_name: &str, atk_names: &[String]) -> Result<()> {
+        let result = self.process_set_loadout(hero_id_name, atk_names);
+        self.record_command(
+            GameCommand::SetLoadout {
+                hero_id_name: hero_id_name.to_owned(),
+                atk_names: atk_names.to_vec(),
+            },
+            None,
+        );
+        result
+    }
+
+    fn process_set_loadout(&mut self, hero_id_name: &str, atk_names: &[String]) -> Result<()> {
+        if self.is_in_fight() {
+            return Err(anyhow::anyhow!(
+                "The loadout cannot be changed during a fight"
+            ));
+        }
+        let hero = self
+            .pm
+            .get_mut_active_hero_character(hero_id_name)
+            .ok_or_else(|| anyhow::anyhow!("Hero '{}' not found", hero_id_name))?;
+        if !hero.loadout.is_enabled() {
+            return Err(anyhow::anyhow!("{} has no attack loadout", hero_id_name));
+        }
+        hero.loadout.set_equipped(atk_names)
+    }
+
+    /// Choose a specialisation for the hero, outside of a fight.
+    /// Return the names of the attacks of the specialisation learnt at once.
+    pub fn specialise(&mut self, hero_id_name: &str, class: &Class) -> Result<Vec<String>> {
+        let result = self.process_specialise(hero_id_name, class);
+        self.record_command(
+            GameCommand::Specialise {
+                hero_id_name: hero_id_name.to_owned(),
+                class: class.clone(),
+            },
+            None,
+        );
+        result
+    }
+
+    fn process_specialise(&mut self, hero_id_name: &str, class: &Class) -> Result<Vec<String>> {
+        if self.is_in_fight() {
+            return Err(anyhow::anyhow!(
+                "A specialisation cannot be chosen during a fight"
+            ));
+        }
+        let hero = self
+            .pm
+            .get_mut_active_hero_character(hero_id_name)
+            .ok_or_else(|| anyhow::anyhow!("Hero '{}' not found", hero_id_name))?;
+        let new_atks = hero.specialise(class)?;
+        tracing::info!("{} is specialised as {}", hero_id_name, class.to_str());
+        Ok(new_atks)
+    }
+
+    /// Talent tree of the class of the hero, the tree of its base class for a specialisation without tree
+    pub fn get_talent_tree(&self, hero_id_name: &str) -> Option<&TalentTree> {
+        let hero = self.pm.get_active_hero_character(hero_id_name)?;
+        self.talent_trees
+            .iter()
+            .find(|t| t.class == hero.class)
+            .or_else(|| {
+                let parent = hero.class.parent()?;
+                self.talent_trees.iter().find(|t| t.class == parent)
+            })
+    }
+
+    /// Spend a talent point of the hero on a talent of its class tree
+    pub fn learn_talent(&mut self, hero_id_name: &str, talent_name: &str) -> Result<()> {
+        let result = self.process_learn_talent(hero_id_name, talent_name);
+        self.record_command(
+            GameCommand::LearnTalent {
+                hero_id_name: hero_id_name.to_owned(),
+                talent_name: talent_name.to_owned(),
+            },
+            None,
+        );
+        result
+    }
+
+    fn process_learn_talent(&mut self, hero_id_name: &str, talent_name: &str) -> Result<()> {
+        let tree = self
+            .get_talent_tree(hero_id_name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No talent tree for hero '{}'", hero_id_name))?;
+        let hero = self
+            .pm
+            .get_mut_active_hero_character(hero_id_name)
+            .ok_or_else(|| anyhow::anyhow!("Hero '{}' not found", hero_id_name))?;
+        tree.learn(hero, talent_name)
+    }
+
+    /// Remove all the talents of the hero and give its points back
+    pub fn reset_talents(&mut self, hero_id_name: &str) -> Result<()> {
+        let result = self.process_reset_talents(hero_id_name);
+        self.record_command(
+            GameCommand::ResetTalents {
+                hero_id_name: hero_id_name.to_owned(),
+            },
+            None,
+        );
+        result
+    }
+
+    fn process_reset_talents(&mut self, hero_id_name: &str) -> Result<()> {
+        let tree = self
+            .get_talent_tree(hero_id_name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No talent tree for hero '{}'", hero_id_name))?;
+        let hero = self
+            .pm
+            .get_mut_active_hero_character(hero_id_name)
+            .ok_or_else(|| anyhow::anyhow!("Hero '{}' not found", hero_id_name))?;
+        tree.reset(hero);
+        Ok(())
+    }
+
+    /// Set the target of the attack of `launcher_id_name` on `target_id_name`.
+    pub fn set_one_target(&mut self, launcher_id_name: &str, atk_name: &str, target_id_name: &str) {
+        self.pm
+            .set_one_target(launcher_id_name, atk_name, target_id_name);
+        self.record_command(
+            GameCommand::SetOneTarget {
+                launcher_id_name: launcher_id_name.to_owned(),
+                atk_name: atk_name.to_owned(),
+                target_id_name: target_id_name.to_owned(),
+            },
+            None,
+        );
+    }
+
+    /// Use a consumable of the current player on `target_id_name`.
+    pub fn use_consumable_on_target(
+        &mut self,
+        potion_name: &str,
+        target_id_name: &str,
+    ) -> Result<Vec<EffectOutcome>> {
+        let result = self.pm.use_consumable_on_target(
+            potion_name,
+            target_id_name,
+            &self.game_state,
+            &mut self.rng,
+        );
+        self.record_command(
+            GameCommand::UseConsumableOnTarget {
+                potion_name: potion_name.to_owned(),
+                target_id_name: target_id_name.to_owned(),
+            },
+            None,
+        );
+        result
+    }
+
+    /// Equip or unequip `equipment_unique_name` on the active hero `character_id_name`.
+    pub fn toggle_equipment(
+        &mut self,
+        character_id_name: &str,
+        equipment_unique_name: &str,
+    ) -> Result<()> {
+        let result = match self
+            .pm
+            .active_heroes
+            .iter_mut()
+            .find(|c| c.id_name == character_id_name)
+        {
+            Some(c) => {
+                c.toggle_equipment(equipment_unique_name, &self.pm.equipment_table);
+                // keep the shadow current player in sync
+                if self.pm.current_player.id_name == c.id_name {
+                    self.pm.current_player = c.clone();
+                }
+                Ok(())
+            }
+            None => Err(anyhow::anyhow!("Hero '{}' not found", character_id_name)),
+        };
+        self.record_command(
+            GameCommand::ToggleEquipment {
+                character_id_name: character_id_name.to_owned(),
+                equipment_unique_name: equipment_unique_name.to_owned(),
+            },
+            None,
+        );
+        result
+    }
+
+    /// Equip or unequip the equipment instance `equipment_id` on the active hero `character_id_name`.
+    pub fn toggle_equipment_by_id(
+        &mut self,
+        character_id_name: &str,
+        equipment_id: u64,
+    ) -> Result<()> {
+        let result = match self
+            .pm
+            .active_heroes
+            .iter_mut()
+            .find(|c| c.id_name == character_id_name)
+        {
+            Some(c) => c
+                .toggle_equipment_by_id(equipment_id, &self.pm.equipment_table)
+                .map(|_| {
+                    // keep the shadow current player in sync
+                    if self.pm.current_player.id_name == c.id_name {
+                        self.pm.current_player = c.clone();
+                    }
+                }),
+            None => Err(anyhow::anyhow!("Hero '{}' not found", character_id_name)),
+        };
+        self.record_command(
+            GameCommand::ToggleEquipmentById {
+                character_id_name: character_id_name.to_owned(),
+                equipment_id,
+            },
+            None,
+        );
+        result
+    }
+
+    /// Summary of the current state, stored with each recorded command
+    pub fn build_checkpoint(&self, overworld: Option<&OverworldState>) -> StateCheckpoint {
+        let mut positions: Vec<_> = overworld
+            .map(|ow| {
+                ow.player_positions
+                    .iter()
+                    .map(|(id, pos)| (id.clone(), pos.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        positions.sort_by(|a, b| a.0.cmp(&b.0));
+        StateCheckpoint {
+            turn_nb: self.game_state.current_turn_nb,
+            round_nb: self.game_state.current_round,
+            status: self.game_state.status.clone(),
+            current_player: self.pm.current_player.id_name.clone(),
+            hp: self
+                .pm
+                .active_heroes
+                .iter()
+                .chain(self.pm.active_bosses.iter())
+                .map(|c| (c.id_name.clone(), c.stats[StatKind::Hp].current))
+                .collect(),
+            rng: self.rng.clone(),
+            positions,
+        }
+    }
+
+    /// Append `command` and the resulting checkpoint to the command log
+    pub fn record_command(&mut self, command: GameCommand, overworld: Option<&OverworldState>) {
+        let checkpoint = self.build_checkpoint(overworld);
+        self.command_log.push(command, checkpoint);
+    }
+
+    fn process_no_atk_launched(&mut self) -> ResultLaunchAttack {
+        // Capture launcher identity before eval_end_of_round() may advance current_player.
+        let launcher_id_name = self.pm.current_player.id_name.clone();
+        let is_boss_atk = self.pm.current_player.is_boss_atk();
+        let turn_nb = self.game_state.current_turn_nb;
+        let round_nb = self.game_state.current_round;
+
+        self.pm
+            .current_player
+            .character_rounds_info
+            .actions_done_in_round += 1;
+        let logs_atk = vec![LogData {
+            message: "No attack launched".to_string(),
+            color: DARK_RED.to_string(),
+        }];
+        let logs_end_of_round = self.eval_end_of_round(logs_atk.clone());
+        let result = ResultLaunchAttack {
+            launcher_id_name,
+            is_boss_atk,
+            logs_end_of_round,
+            logs_atk,
+            turn_nb,
+            round_nb,
+            ..Default::default()
+        };
+        // Mirror what launch_attack() does for real attacks so ra.atk_name is
+        // empty and the gameboard consumable-action banner branch is reachable.
+        self.game_state.last_result_atk = result.clone();
+        result
+    }
+
+    /// Evaluate the end of the round by checking if the game is finished,
+    ///  if a new round should start or if a new turn should start,
+    ///  and return the logs to display for the new round if it is the case
+    fn eval_end_of_round(&mut self, logs_atk: Vec<LogData>) -> Vec<LogData> {
+        let mut output_logs = vec![];
+        if !self.process_objectives() {
+            let (is_new_round, logs) = self.new_round();
+            output_logs.extend(logs);
+            // new_round may have triggered EndOfScenario/EndOfGame (e.g. boss killed by DOT)
+            if matches!(
+                self.game_state.status,
+                GameStatus::EndOfScenario | GameStatus::EndOfGame
+            ) {
+                // Status already set inside new_round; nothing more to do
+            } else if is_new_round {
+                self.game_state.status = GameStatus::StartRound;
+            } else {
+                let (is_new_turn, logs) = self.start_new_turn();
+                output_logs.extend(logs);
+                if matches!(
+                    self.game_state.status,
+                    GameStatus::EndOfScenario | GameStatus::EndOfGame
+                ) {
+                    // Status set inside start_new_turn via new_round
+                } else if self.process_objectives() {
+                    // objectives on the number of turns
+                } else if is_new_turn {
+                    self.game_state.status = GameStatus::StartRound;
+                } else {
+                    self.game_state.status = GameStatus::EndOfGame;
+                }
             }
         }
 
@@ -878,7 +1695,7 @@ impl GameManager {
                     .input_effect_param
                     .buffer
                     .stats_name
-                    == HP;
+                    == Some(StatKind::Hp);
                 let is_damage = is_hp
                     && (gae.effect_outcome.real_amount_tx < 0
                         || gae.effect_outcome.full_amount_tx < 0);
@@ -894,6 +1711,12 @@ impl GameManager {
                     message: text,
                     color: color.to_string(),
                 });
+                if let Some(msg) = gae.effect_outcome.effectiveness.message() {
+                    logs.push(LogData {
+                        message: msg.to_string(),
+                        color: color.to_string(),
+                    });
+                }
             }
         }
         logs
@@ -928,7 +1751,7 @@ impl GameManager {
                 let name = &self.game_state.order_to_play[i];
 
                 if let Some(c) = self.pm.get_active_character(name) {
-                    if c.kind == CharacterKind::Boss && c.stats.is_dead() != Some(true) {
+                    if c.kind == CharacterKind::Boss && !c.stats.is_dead() {
                         count += 1;
                     } else {
                         break; // Stop counting when a non-Boss is found
@@ -945,23 +1768,27 @@ impl GameManager {
 
 #[cfg(test)]
 mod tests {
+    use crate::character_mod::ailment::AilmentKind;
     use crate::character_mod::attack_type::AttackType;
     use crate::character_mod::buffers::{BufKinds, Buffer};
     use crate::character_mod::character::CharacterKind;
     use crate::character_mod::class::Class;
+    use crate::character_mod::effect::{EffectOutcome, EffectParam, ProcessedEffectParam};
+    use crate::character_mod::element::{Effectiveness, Element};
     use crate::character_mod::rank::Rank;
+    use crate::character_mod::stats::StatKind;
+    use crate::character_mod::target::{Reach, Target};
     use crate::common::constants::attak_const::COEFF_CRIT_DMG;
     use crate::common::constants::streak_breaker_const::STREAK_BREAKER_ADVANCED;
     use crate::common::log_data::const_colors::DARK_RED;
     use crate::server::game_manager::LogData;
     use crate::server::game_state::GameStatus;
+    use crate::server::players_manager::GameAtkEffect;
     use crate::testing::testing_all_characters::{
         self, testing_game_manager, testing_test_ally1_vs_test_boss1,
     };
-    use crate::{
-        common::constants::{character_const::SPEED_THRESHOLD, stats_const::*},
-        testing::testing_atk::*,
-    };
+    use crate::{common::constants::character_const::SPEED_THRESHOLD, testing::testing_atk::*};
+    use indexmap::IndexMap;
 
     #[test]
     fn unit_process_order_to_play() {
@@ -971,8 +1798,7 @@ mod tests {
             .get_mut_active_hero_character("test_#1")
             .cloned()
             .unwrap()
-            .stats
-            .all_stats[SPEED]
+            .stats[StatKind::Speed]
             .clone();
         gm.process_order_to_play();
         let new_speed = gm
@@ -980,8 +1806,7 @@ mod tests {
             .get_mut_active_hero_character("test_#1")
             .cloned()
             .unwrap()
-            .stats
-            .all_stats[SPEED]
+            .stats[StatKind::Speed]
             .clone();
         // only one supplementary attack per turn: test2_#1 (fastest hero, speed 312) qualifies;
         // test_#1 (212) is skipped because process_sup_atk_turn returns after the first hit.
@@ -1005,16 +1830,14 @@ mod tests {
             .get_mut_active_hero_character("test2_#1")
             .cloned()
             .unwrap()
-            .stats
-            .all_stats[SPEED]
+            .stats[StatKind::Speed]
             .clone();
         assert_eq!(312 - SPEED_THRESHOLD, new_test2_speed.current);
         // one hero player is dead — use name-based kill so the index stays stable after sort
         gm.pm
             .get_mut_active_hero_character("test_#1")
             .unwrap()
-            .stats
-            .all_stats[HP]
+            .stats[StatKind::Hp]
             .current = 0;
         gm.process_order_to_play();
         assert_eq!(gm.game_state.order_to_play.len(), 5);
@@ -1027,8 +1850,7 @@ mod tests {
         gm.pm
             .get_mut_active_boss_character("test_boss1_#1")
             .unwrap()
-            .stats
-            .all_stats[HP]
+            .stats[StatKind::Hp]
             .current = 0;
         gm.process_order_to_play();
         assert_eq!(gm.game_state.order_to_play.len(), 4);
@@ -1042,9 +1864,9 @@ mod tests {
     fn unit_add_sup_atk_turn() {
         let mut gm = testing_all_characters::testing_game_manager();
         let hero = gm.pm.active_heroes.first_mut().unwrap();
-        hero.stats.all_stats.get_mut(SPEED).unwrap().current = 300;
+        hero.stats[StatKind::Speed].current = 300;
         let boss = gm.pm.active_bosses.first_mut().unwrap();
-        boss.stats.all_stats.get_mut(SPEED).unwrap().current = 10;
+        boss.stats[StatKind::Speed].current = 10;
         let result = gm.pm.process_sup_atk_turn(CharacterKind::Hero);
         // only one supplementary attack per call — the first qualifying hero
         assert_eq!(result.len(), 1);
@@ -1059,7 +1881,7 @@ mod tests {
 
         // test current player -test- is dead - round for boss is starting
         gm.game_state.current_round = 0;
-        gm.pm.active_heroes[0].stats.all_stats[HP].current = 0;
+        gm.pm.active_heroes[0].stats[StatKind::Hp].current = 0;
         let result = gm.new_round();
         assert!(result.0);
         assert_eq!(gm.game_state.current_round, 2);
@@ -1097,17 +1919,16 @@ mod tests {
         gm.pm
             .get_mut_active_boss_character(&target_id_name)
             .unwrap()
-            .stats
-            .all_stats[DODGE]
+            .stats[StatKind::Dodge]
             .current = 0;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         let old_boss = gm
             .pm
             .get_active_boss_character(&target_id_name)
             .unwrap()
             .clone();
-        let old_hp_boss = old_boss.stats.all_stats[HP].current;
-        let old_vigor_hero = gm.pm.current_player.stats.all_stats[VIGOR].current;
+        let old_hp_boss = old_boss.stats[StatKind::Hp].current;
+        let old_vigor_hero = gm.pm.current_player.stats[StatKind::Vigor].current;
 
         // test normal atk
         // set target
@@ -1138,8 +1959,7 @@ mod tests {
             gm.pm
                 .get_active_boss_character(&target_id_name)
                 .unwrap()
-                .stats
-                .all_stats[HP]
+                .stats[StatKind::Hp]
                 .current
         );
         // cost: 9 % of vigor 200 = 18
@@ -1148,10 +1968,713 @@ mod tests {
             gm.pm
                 .get_active_hero_character(&hero_launcher_id_name)
                 .unwrap()
-                .stats
-                .all_stats[VIGOR]
+                .stats[StatKind::Vigor]
+                .current
+        );
+    }
+
+    #[test]
+    fn unit_launch_attack_inflict_ailment() {
+        let (mut gm, _hero_launcher_id_name, target_id_name) = testing_test_ally1_vs_test_boss1();
+        gm.pm
+            .current_player
+            .attacks_list
+            .get_mut("SimpleAtk")
+            .unwrap()
+            .all_effects
+            .push(EffectParam {
+                nb_turns: 1,
+                target_kind: Target::Enemy,
+                reach: Reach::Individual,
+                buffer: Buffer {
+                    kind: BufKinds::InflictAilment,
+                    ..Default::default()
+                },
+                ailment: Some(AilmentKind::Sleep),
+                ..Default::default()
+            });
+        let boss = gm
+            .pm
+            .get_mut_active_boss_character(&target_id_name)
+            .unwrap();
+        boss.stats[StatKind::Dodge].current = 0;
+        boss.character_rounds_info.is_current_target = true;
+
+        // the damages of SimpleAtk are processed before the sleep: the boss stays asleep
+        let ra = gm.launch_attack(Some("SimpleAtk"));
+        let boss = gm.pm.get_active_boss_character(&target_id_name).unwrap();
+        assert_eq!(
+            boss.character_rounds_info.skipping_ailment(),
+            Some(AilmentKind::Sleep)
+        );
+        assert!(!boss.character_rounds_info.all_effects.iter().any(|gae| {
+            gae.processed_effect_param.input_effect_param.buffer.kind == BufKinds::InflictAilment
+        }));
+        assert!(
+            ra.logs_atk
+                .iter()
+                .any(|l| l.message == format!("{} ← Sleep (1 turns)", target_id_name))
+        );
+
+        // the sleeping boss skips its round
+        gm.game_state.current_round = gm
+            .game_state
+            .order_to_play
+            .iter()
+            .position(|id| *id == target_id_name)
+            .unwrap();
+        let (_is_new_round, logs) = gm.new_round();
+        assert!(
+            logs.iter()
+                .any(|l| l.message == format!("{} is asleep and skips the round", target_id_name))
+        );
+
+        // damages wake it up
+        gm.game_state.current_round = gm
+            .game_state
+            .order_to_play
+            .iter()
+            .position(|id| *id == "test_#1")
+            .unwrap();
+        gm.new_round();
+        gm.pm
+            .current_player
+            .attacks_list
+            .get_mut("SimpleAtk")
+            .unwrap()
+            .all_effects
+            .pop();
+        gm.pm
+            .get_mut_active_boss_character(&target_id_name)
+            .unwrap()
+            .character_rounds_info
+            .is_current_target = true;
+        gm.launch_attack(Some("SimpleAtk"));
+        let boss = gm.pm.get_active_boss_character(&target_id_name).unwrap();
+        assert_eq!(boss.character_rounds_info.skipping_ailment(), None);
+    }
+
+    #[test]
+    fn unit_scenario_objectives() {
+        use crate::{
+            character_mod::loot::{Loot, LootType},
+            server::objective::{Objective, ObjectiveKind, ObjectiveState},
+        };
+
+        // survive 2 turns, with bonus loots for a secondary objective
+        let mut gm = testing_game_manager();
+        gm.current_scenario.objectives = vec![
+            Objective::new(ObjectiveKind::SurviveTurns(2)),
+            Objective {
+                kind: ObjectiveKind::NoHeroDies,
+                is_secondary: true,
+                bonus_loots: vec![Loot {
+                    name: "gold".to_owned(),
+                    kind: LootType::Currency,
+                    level: 50,
+                    classes: vec![Class::Standard],
+                    ..Default::default()
+                }],
+            },
+            Objective {
+                kind: ObjectiveKind::KillBoss("test_boss1".to_owned()),
+                is_secondary: true,
+                bonus_loots: vec![Loot::default()],
+            },
+        ];
+        gm.start_game();
+        let old_money = gm.pm.active_heroes[0].inventory.money;
+        assert!(!gm.process_objectives());
+        gm.start_new_turn();
+        assert!(!gm.process_objectives());
+        gm.start_new_turn();
+        assert!(gm.process_objectives());
+        assert_eq!(gm.game_state.status, GameStatus::EndOfScenario);
+        let states: Vec<ObjectiveState> = gm
+            .end_of_scenario
+            .objectives
+            .iter()
+            .map(|o| o.state)
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ObjectiveState::Completed,
+                ObjectiveState::Completed,
+                ObjectiveState::Failed
+            ]
+        );
+        assert_eq!(
+            gm.end_of_scenario.objectives[0].description,
+            "Survive 2 turns"
+        );
+        assert_eq!(gm.end_of_scenario.bonus_loots.len(), 1);
+        assert_eq!(old_money + 50, gm.pm.active_heroes[0].inventory.money);
+
+        // a failed primary objective ends the game
+        let mut gm = testing_game_manager();
+        let hero_id_name = gm.pm.active_heroes[0].id_name.clone();
+        gm.current_scenario.objectives = vec![Objective::new(ObjectiveKind::ProtectAlly(
+            hero_id_name.clone(),
+        ))];
+        gm.start_game();
+        assert!(!gm.process_objectives());
+        gm.pm
+            .get_mut_active_hero_character(&hero_id_name)
+            .unwrap()
+            .stats[StatKind::Hp]
+            .current = 0;
+        assert!(gm.process_objectives());
+        assert_eq!(gm.game_state.status, GameStatus::EndOfGame);
+        // killing all the bosses is the implicit goal
+        assert_eq!(gm.end_of_scenario.objectives.len(), 2);
+        assert_eq!(
+            gm.end_of_scenario.objectives[0].description,
+            "Kill all the bosses"
+        );
+        assert!(
+            gm.end_of_scenario
+                .objectives
+                .iter()
+                .all(|o| o.state == ObjectiveState::Failed)
+        );
+        assert!(gm.end_of_scenario.bonus_loots.is_empty());
+    }
+
+    #[test]
+    fn unit_loadout() {
+        let mut gm = testing_all_characters::dxrpg_game_manager();
+        let thalia = "Thalia_#1";
+        let hero = gm.pm.get_active_hero_character(thalia).unwrap();
+        assert_eq!(hero.loadout.known_atks, ["Charge", "Essence Régénératrice"]);
+        let arbre = hero.attacks_list["Arbre de Vie"].clone();
+        assert!(!hero.can_be_launched(&arbre, 0));
+
+        // a trainer teaches an attack of a higher level, equipped in a free slot
+        gm.learn_attack(thalia, "Arbre de Vie").unwrap();
+        assert!(gm.learn_attack(thalia, "Arbre de Vie").is_err());
+        assert!(gm.learn_attack(thalia, "unknown").is_err());
+        let hero = gm.pm.get_active_hero_character(thalia).unwrap();
+        assert!(hero.can_be_launched(&arbre, 0));
+
+        let loadout = vec!["Arbre de Vie".to_owned()];
+        gm.game_state.status = GameStatus::StartRound;
+        assert!(gm.set_loadout(thalia, &loadout).is_err());
+        gm.game_state.status = GameStatus::EndOfScenario;
+        gm.set_loadout(thalia, &loadout).unwrap();
+        let hero = gm.pm.get_active_hero_character(thalia).unwrap();
+        let charge = hero.attacks_list["Charge"].clone();
+        assert!(!hero.can_be_launched(&charge, 0));
+
+        // the attacks of the new level are learnt and reported at the end of the scenario
+        gm.pm.active_bosses.clear();
+        let hero = gm.pm.get_mut_active_hero_character(thalia).unwrap();
+        hero.character_rounds_info.exp = hero.character_rounds_info.exp_to_next_level;
+        gm.process_end_of_scenario();
+        let level_up = gm
+            .end_of_scenario
+            .characters_levelup
+            .iter()
+            .find(|lu| lu.character_id_name == thalia)
+            .unwrap();
+        assert_eq!(level_up.new_atks, ["Rameau Guérisseur "]);
+        let hero = gm.pm.get_active_hero_character(thalia).unwrap();
+        assert!(hero.loadout.is_equipped("Rameau Guérisseur "));
+    }
+
+    #[test]
+    fn unit_toggle_equipment_by_id() {
+        use crate::character_mod::{
+            affix::{Affix, AffixKind, Rarity},
+            stats::StatKind,
+        };
+        use crate::server::command_log::GameCommand;
+
+        let mut gm = testing_all_characters::dxrpg_game_manager();
+        let thalia = "Thalia_#1";
+        let template = gm
+            .pm
+            .equipment_table
+            .values()
+            .flatten()
+            .find(|e| e.unique_name == "starting amulet")
+            .unwrap()
+            .clone();
+        let rolled = template.with_affixes(
+            Rarity::Uncommon,
+            &[Affix {
+                kind: AffixKind::Stat {
+                    stat: StatKind::Speed,
+                    is_percent: false,
+                },
+                value: 4,
+            }],
+        );
+        let hero = gm.pm.get_mut_active_hero_character(thalia).unwrap();
+        let speed = hero.stats[StatKind::Speed].max;
+        let id = hero.inventory.add_equipment(&rolled, false);
+
+        assert!(gm.toggle_equipment_by_id(thalia, id + 1).is_err());
+        gm.toggle_equipment_by_id(thalia, id).unwrap();
+        let hero = gm.pm.get_active_hero_character(thalia).unwrap();
+        let speed_on = hero.stats[StatKind::Speed].max;
+        assert_eq!(
+            speed_on as i64,
+            speed as i64 + template.stats[StatKind::Speed].buf_equip_value + 4
+        );
+        assert_eq!(
+            gm.command_log.entries.last().unwrap().command,
+            GameCommand::ToggleEquipmentById {
+                character_id_name: thalia.to_owned(),
+                equipment_id: id,
+            }
+        );
+        gm.toggle_equipment_by_id(thalia, id).unwrap();
+        let hero = gm.pm.get_active_hero_character(thalia).unwrap();
+        assert_eq!(hero.stats[StatKind::Speed].max, speed);
+    }
+
+    #[test]
+    fn unit_specialise() {
+        use crate::character_mod::{class::Class, experience::build_exp_to_next_level};
+        use crate::common::constants::character_const::SPECIALISATION_LEVEL;
+
+        let mut gm = testing_all_characters::dxrpg_game_manager();
+        let thalia = "Thalia_#1";
+        let fleur = "Fleur de l'Espoir";
+        let hero = gm.pm.get_mut_active_hero_character(thalia).unwrap();
+        hero.attacks_list[fleur].specialisation = Some(Class::Pyromancer);
+        assert!(gm.specialise(thalia, &Class::Pyromancer).is_err());
+
+        // the specialisations are offered at the milestone level
+        gm.pm.active_bosses.clear();
+        let hero = gm.pm.get_mut_active_hero_character(thalia).unwrap();
+        hero.level = SPECIALISATION_LEVEL - 1;
+        hero.character_rounds_info.exp = hero.character_rounds_info.exp_to_next_level;
+        gm.process_end_of_scenario();
+        let level_up = gm
+            .end_of_scenario
+            .characters_levelup
+            .iter()
+            .find(|lu| lu.character_id_name == thalia)
+            .unwrap();
+        assert_eq!(
+            level_up.specialisations,
+            [Class::Pyromancer, Class::Necromancer]
+        );
+        assert!(!level_up.new_atks.iter().any(|a| a == fleur));
+
+        assert!(gm.specialise(thalia, &Class::Paladin).is_err());
+        gm.game_state.status = GameStatus::StartRound;
+        assert!(gm.specialise(thalia, &Class::Pyromancer).is_err());
+        gm.game_state.status = GameStatus::EndOfScenario;
+        assert_eq!(gm.specialise(thalia, &Class::Pyromancer).unwrap(), [fleur]);
+        let hero = gm.pm.get_active_hero_character(thalia).unwrap();
+        assert_eq!(hero.class, Class::Pyromancer);
+        assert!(hero.is_atk_known(&hero.attacks_list[fleur]));
+        assert_eq!(
+            hero.character_rounds_info.exp_to_next_level,
+            build_exp_to_next_level(&hero.rank, &Class::Pyromancer, hero.level)
+        );
+        assert!(gm.specialise(thalia, &Class::Necromancer).is_err());
+        assert!(matches!(
+            gm.command_log.entries.last().unwrap().command,
+            crate::server::command_log::GameCommand::Specialise { .. }
+        ));
+    }
+
+    #[test]
+    fn unit_talents() {
+        let mut gm = testing_game_manager();
+        let hero_id_name = gm.pm.active_heroes[0].id_name.clone();
+        assert!(gm.learn_talent(&hero_id_name, "Vitality").is_err());
+
+        // a talent point at each level-up, reported at the end of the scenario
+        let exp = gm.pm.active_heroes[0]
+            .character_rounds_info
+            .exp_to_next_level;
+        gm.pm.active_heroes[0].character_rounds_info.exp = exp;
+        gm.process_end_of_scenario();
+        let hero = gm.pm.get_active_hero_character(&hero_id_name).unwrap();
+        assert_eq!(hero.talents.unspent_points, hero.level - 1);
+        let level_up = &gm.end_of_scenario.characters_levelup[0];
+        assert_eq!(level_up.unspent_talent_points, hero.level - 1);
+
+        let hp = hero.stats[StatKind::Hp].max;
+        gm.learn_talent(&hero_id_name, "Vitality").unwrap();
+        assert_eq!(gm.pm.active_heroes[0].stats[StatKind::Hp].max, hp + 20);
+        assert!(gm.learn_talent("unknown", "Vitality").is_err());
+        gm.reset_talents(&hero_id_name).unwrap();
+        assert_eq!(gm.pm.active_heroes[0].stats[StatKind::Hp].max, hp);
+        assert!(gm.pm.active_heroes[0].talents.talents.is_empty());
+        assert!(matches!(
+            gm.command_log.entries.last().unwrap().command,
+            crate::server::command_log::GameCommand::ResetTalents { .. }
+        ));
+    }
+
+    #[test]
+    fn unit_difficulty() {
+        use crate::character_mod::{
+            class::Class,
+            loot::{Loot, LootType},
+        };
+        use crate::server::difficulty::Difficulty;
+
+        let mut gm = testing_game_manager();
+        let boss_id_name = "test_boss1_#1".to_owned();
+        gm.current_scenario
+            .boss_patterns
+            .insert("test_boss1".to_owned(), vec![0]);
+        let boss_hp = gm
+            .pm
+            .get_active_boss_character(&boss_id_name)
+            .unwrap()
+            .stats[StatKind::Hp]
+            .max;
+        // the bosses of a scenario not started yet are loaded again with the new scaling
+        gm.set_difficulty(Difficulty::Nightmare);
+        assert_eq!(gm.pm.active_bosses.len(), 1);
+        let boss = gm.pm.get_active_boss_character(&boss_id_name).unwrap();
+        assert_eq!(boss.stats[StatKind::Hp].max, boss_hp * 150 / 100);
+        assert_eq!(boss.stats[StatKind::Hp].current, boss_hp * 150 / 100);
+
+        // the fastest boss plays one more time at each turn
+        gm.start_game();
+        let nb_boss_actions = gm
+            .game_state
+            .order_to_play
+            .iter()
+            .filter(|id_name| **id_name == boss_id_name)
+            .count();
+        assert_eq!(nb_boss_actions, 2);
+
+        // a started fight is not scaled again
+        gm.set_difficulty(Difficulty::Story);
+        let boss = gm.pm.get_active_boss_character(&boss_id_name).unwrap();
+        assert_eq!(boss.stats[StatKind::Hp].max, boss_hp * 150 / 100);
+
+        // rewards
+        let money = gm.pm.active_heroes[0].inventory.money;
+        gm.current_scenario.loots = vec![Loot {
+            name: "gold".to_owned(),
+            kind: LootType::Currency,
+            level: 100,
+            classes: vec![Class::Standard],
+            ..Default::default()
+        }];
+        gm.process_end_of_scenario();
+        assert_eq!(gm.pm.active_heroes[0].inventory.money, money + 75);
+    }
+
+    #[test]
+    fn unit_reinforcement_waves() {
+        use crate::server::scenario::{ReinforcementWave, WaveTrigger};
+
+        let mut gm = testing_game_manager();
+        gm.current_scenario.waves = vec![
+            ReinforcementWave {
+                trigger: WaveTrigger::TurnEvery(2),
+                bosses: vec!["test_boss1".to_owned()],
+                max_nb: 1,
+            },
+            ReinforcementWave {
+                trigger: WaveTrigger::BossDied("test_boss1".to_owned()),
+                bosses: vec!["test_boss1".to_owned(), "unknown boss".to_owned()],
+                max_nb: 0,
+            },
+        ];
+        gm.start_game();
+        let nb_bosses = gm.pm.active_bosses.len();
+        assert!(gm.pm.get_active_boss_character("test_boss1_#2").is_none());
+
+        // turn 2: the wave joins the fight and plays in that turn
+        let (_, logs) = gm.start_new_turn();
+        assert_eq!(nb_bosses + 1, gm.pm.active_bosses.len());
+        assert!(
+            logs.iter()
+                .any(|l| l.message == "test_boss1_#2 joins the fight")
+        );
+        assert!(
+            gm.game_state
+                .order_to_play
+                .contains(&"test_boss1_#2".to_owned())
+        );
+        assert_eq!(Some(&1), gm.game_state.summoned_waves.get(&0));
+        // turn 4: the wave was already summoned once
+        gm.start_new_turn();
+        gm.start_new_turn();
+        assert_eq!(nb_bosses + 1, gm.pm.active_bosses.len());
+
+        // a summoned boss dies: its death is recorded, its threat removed and it triggers the other wave
+        let hero_id_name = gm.pm.active_heroes[0].id_name.clone();
+        gm.pm.threat_table.add("test_boss1_#2", &hero_id_name, 10);
+        gm.pm
+            .get_mut_active_boss_character("test_boss1_#2")
+            .unwrap()
+            .stats[StatKind::Hp]
+            .current = 0;
+        let logs = gm.process_died_bosses();
+        assert!(gm.pm.threat_meter("test_boss1_#2").is_empty());
+        assert_eq!(
+            logs,
+            vec![LogData {
+                message: "test_boss1_#3 joins the fight".to_owned(),
+                color: DARK_RED.to_owned(),
+            }]
+        );
+        assert_eq!(
+            gm.game_state.died_ennemies[&gm.game_state.current_turn_nb],
+            vec!["test_boss1_#2".to_owned()]
+        );
+        // the death is recorded only once
+        assert!(gm.process_died_bosses().is_empty());
+        assert_eq!(nb_bosses + 2, gm.pm.active_bosses.len());
+    }
+
+    #[test]
+    fn unit_boss_phases() {
+        use crate::{character_mod::character::Character, server::boss_ai::BossPhase};
+
+        let (mut gm, _hero_id_name, boss_id_name) = testing_test_ally1_vs_test_boss1();
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
+        gm.current_scenario.boss_phases.insert(
+            "test_boss1".to_owned(),
+            vec![
+                BossPhase {
+                    name: "Enraged".to_owned(),
+                    hp_percent: 50,
+                    pattern: vec![1],
+                    buffs: vec![EffectParam {
+                        nb_turns: 3,
+                        target_kind: Target::Himself,
+                        reach: Reach::Individual,
+                        buffer: Buffer {
+                            kind: BufKinds::ChangeMaxStatByPercentage,
+                            value: 50,
+                            is_percent: true,
+                            stats_name: Some(StatKind::PhysicalPower),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    }],
+                    adds: vec!["test_boss1".to_owned()],
+                    ..Default::default()
+                },
+                BossPhase {
+                    name: "Last stand".to_owned(),
+                    hp_percent: 10,
+                    announcement: "The boss fights to its last breath".to_owned(),
+                    ..Default::default()
+                },
+            ],
+        );
+        let boss = gm.pm.get_mut_active_boss_character(&boss_id_name).unwrap();
+        boss.stats[StatKind::Dodge].current = 0;
+        boss.stats[StatKind::Hp].current = 80;
+        boss.character_rounds_info.is_current_target = true;
+        let old_power = boss.stats[StatKind::PhysicalPower].max;
+        assert_eq!(
+            gm.boss_pattern(gm.pm.get_active_boss_character(&boss_id_name).unwrap()),
+            gm.current_scenario.boss_patterns.get("test_boss1").cloned()
+        );
+
+        // the damages of the hero make the boss cross the first threshold
+        let ra = gm.launch_attack(Some("SimpleAtk"));
+        assert!(
+            ra.logs_atk
+                .iter()
+                .any(|l| l.message == format!("{} enters the phase Enraged", boss_id_name))
+        );
+        assert!(
+            ra.logs_atk
+                .iter()
+                .any(|l| l.message == "test_boss1_#2 joins the fight")
+        );
+        assert!(gm.pm.get_active_boss_character("test_boss1_#2").is_some());
+        let boss = gm.pm.get_active_boss_character(&boss_id_name).unwrap();
+        assert_eq!(1, boss.character_rounds_info.boss_phase);
+        assert!(boss.stats[StatKind::PhysicalPower].max > old_power);
+        assert_eq!(gm.boss_pattern(boss), Some(vec![1]));
+        // the phase is kept in the saves
+        let saved: Character = serde_json::from_str(&serde_json::to_string(boss).unwrap()).unwrap();
+        assert_eq!(1, saved.character_rounds_info.boss_phase);
+
+        // last phase: no pattern, the one of the previous phase is kept
+        gm.pm
+            .get_mut_active_boss_character(&boss_id_name)
+            .unwrap()
+            .stats[StatKind::Hp]
+            .current = 5;
+        let logs = gm.process_boss_phases();
+        assert_eq!(
+            logs,
+            vec![LogData {
+                message: "The boss fights to its last breath".to_owned(),
+                color: DARK_RED.to_owned(),
+            }]
+        );
+        let boss = gm.pm.get_active_boss_character(&boss_id_name).unwrap();
+        assert_eq!(2, boss.character_rounds_info.boss_phase);
+        assert_eq!(gm.boss_pattern(boss), Some(vec![1]));
+        assert!(gm.process_boss_phases().is_empty());
+    }
+
+    #[test]
+    fn unit_launch_attack_absorb_shield() {
+        let (mut gm, hero_launcher_id_name, target_id_name) = testing_test_ally1_vs_test_boss1();
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
+        gm.pm
+            .current_player
+            .attacks_list
+            .get_mut("SimpleAtk")
+            .unwrap()
+            .all_effects
+            .push(EffectParam {
+                nb_turns: 2,
+                target_kind: Target::Himself,
+                reach: Reach::Individual,
+                buffer: Buffer {
+                    kind: BufKinds::AbsorbShield,
+                    value: 50,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        let boss = gm
+            .pm
+            .get_mut_active_boss_character(&target_id_name)
+            .unwrap();
+        boss.stats[StatKind::Dodge].current = 0;
+        boss.character_rounds_info.is_current_target = true;
+        // shield of the boss, smaller than the damages of SimpleAtk
+        boss.character_rounds_info.all_effects.push(GameAtkEffect {
+            processed_effect_param: ProcessedEffectParam {
+                input_effect_param: EffectParam {
+                    nb_turns: 2,
+                    buffer: Buffer {
+                        kind: BufKinds::AbsorbShield,
+                        value: 10,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            effect_outcome: EffectOutcome {
+                full_amount_tx: 10,
+                shield_remaining: 10,
+                ..Default::default()
+            },
+            launcher_id_name: target_id_name.clone(),
+            ..Default::default()
+        });
+        let old_hp = boss.stats[StatKind::Hp].current as i64;
+
+        let ra = gm.launch_attack(Some("SimpleAtk"));
+
+        // the boss shield absorbs 10 damages and is removed once depleted
+        let dmg = ra
+            .new_game_atk_effects
+            .iter()
+            .find(|gae| gae.effect_outcome.target_id_name == target_id_name)
+            .unwrap();
+        assert_eq!(10, dmg.effect_outcome.absorbed_amount);
+        assert_eq!(
+            dmg.effect_outcome.full_amount_tx + 10,
+            dmg.effect_outcome.real_amount_tx
+        );
+        let boss = gm.pm.get_active_boss_character(&target_id_name).unwrap();
+        assert_eq!(
+            old_hp + dmg.effect_outcome.real_amount_tx,
+            boss.stats[StatKind::Hp].current as i64
+        );
+        assert_eq!(0, boss.character_rounds_info.shield_total());
+        assert!(
+            ra.logs_atk
+                .iter()
+                .any(|l| l.message.contains("absorbed: 10"))
+        );
+
+        // the launcher gets its shield, a new shield of the same launcher replaces it
+        let hero = gm
+            .pm
+            .get_active_hero_character(&hero_launcher_id_name)
+            .unwrap();
+        assert_eq!(50, hero.character_rounds_info.shield_total());
+        gm.game_state.current_round = gm
+            .game_state
+            .order_to_play
+            .iter()
+            .position(|id| *id == hero_launcher_id_name)
+            .unwrap();
+        gm.new_round();
+        gm.launch_attack(Some("SimpleAtk"));
+        let hero = gm
+            .pm
+            .get_active_hero_character(&hero_launcher_id_name)
+            .unwrap();
+        assert_eq!(50, hero.character_rounds_info.shield_total());
+        assert_eq!(
+            hero_launcher_id_name,
+            hero.character_rounds_info
+                .all_effects
+                .iter()
+                .find(
+                    |gae| gae.processed_effect_param.input_effect_param.buffer.kind
+                        == BufKinds::AbsorbShield
+                )
+                .unwrap()
+                .launcher_id_name
+        );
+    }
+
+    #[test]
+    fn unit_launch_attack_super_effective() {
+        let (mut gm, _hero_launcher_id_name, target_id_name) = testing_test_ally1_vs_test_boss1();
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
+        gm.pm
+            .current_player
+            .attacks_list
+            .get_mut("SimpleAtk")
+            .unwrap()
+            .element = Element::Fire;
+        let boss = gm
+            .pm
+            .get_mut_active_boss_character(&target_id_name)
+            .unwrap();
+        boss.stats[StatKind::Dodge].current = 0;
+        boss.elements = vec![Element::Grass];
+        boss.character_rounds_info.is_current_target = true;
+        let old_boss = boss.clone();
+
+        let ra = gm.launch_attack(Some("SimpleAtk"));
+
+        // the fire atk deals twice the raw damages on a grass boss
+        let hero_total_pow = gm.pm.current_player.stats.get_power_stat(false);
+        let power_factor = 1.0 + hero_total_pow as f64 / AttackType::POWER_SCALE;
+        let raw_dmg = (35_f64 * power_factor * 2.0).round() as i64;
+        let defense = old_boss.stats.get_armor_stat(false) as f64
+            + old_boss.stats.get_power_stat(false) as f64 / AttackType::DEFENSE_DIVISOR;
+        let protection = AttackType::ARMOR_FACTOR / (AttackType::ARMOR_FACTOR + defense);
+        let atk_amount = (raw_dmg as f64 * protection).round() as i64;
+        assert_eq!(
+            std::cmp::max(0, old_boss.stats[StatKind::Hp].current as i64 - atk_amount) as u64,
+            gm.pm
+                .get_active_boss_character(&target_id_name)
+                .unwrap()
+                .stats[StatKind::Hp]
                 .current
         );
+        assert_eq!(
+            Effectiveness::SuperEffective,
+            ra.new_game_atk_effects[0].effect_outcome.effectiveness
+        );
+        assert!(
+            ra.logs_atk
+                .iter()
+                .any(|l| l.message == "It's super effective!")
+        );
     }
 
     #[test]
@@ -1172,11 +2695,11 @@ mod tests {
                 .unwrap();
             boss.rank = Rank::Advanced;
             boss.level = 5;
-            boss.stats.all_stats[DODGE].current = 0; // softcap = 0%, streak-breaker fires
+            boss.stats[StatKind::Dodge].current = 0; // softcap = 0%, streak-breaker fires
             boss.character_rounds_info.dodge_drought_counter = STREAK_BREAKER_ADVANCED;
             boss.character_rounds_info.is_current_target = true;
         }
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         // Disable the NextHealAtkIsCrit passive to ensure no crit on this non-heal atk
         if let Some(buf) = gm
             .pm
@@ -1190,10 +2713,9 @@ mod tests {
             .pm
             .get_active_boss_character(&target_id_name)
             .unwrap()
-            .stats
-            .all_stats[HP]
+            .stats[StatKind::Hp]
             .current;
-        let old_vigor_hero = gm.pm.current_player.stats.all_stats[VIGOR].current;
+        let old_vigor_hero = gm.pm.current_player.stats[StatKind::Vigor].current;
         gm.launch_attack(Some("SimpleAtk"));
         // not dead boss : end of game
         assert!(gm.game_state.status != GameStatus::EndOfGame);
@@ -1202,8 +2724,7 @@ mod tests {
             gm.pm
                 .get_active_boss_character(&target_id_name)
                 .unwrap()
-                .stats
-                .all_stats[HP]
+                .stats[StatKind::Hp]
                 .current
         );
         // 9% of 200 (total vigor)
@@ -1212,8 +2733,7 @@ mod tests {
             gm.pm
                 .get_active_hero_character(&hero_launcher_id_name)
                 .unwrap()
-                .stats
-                .all_stats[VIGOR]
+                .stats[StatKind::Vigor]
                 .current
         );
     }
@@ -1228,14 +2748,13 @@ mod tests {
         gm.pm
             .get_mut_active_boss_character(&target_id_name)
             .unwrap()
-            .stats
-            .all_stats[DODGE]
+            .stats[StatKind::Dodge]
             .current = 0;
         // Use Advanced rank + level 5 so the streak-breaker activates at threshold 5,
         // then pre-set the drought counter to the threshold to guarantee a crit.
         gm.pm.current_player.rank = Rank::Advanced;
         gm.pm.current_player.level = 5;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         gm.pm
             .current_player
             .character_rounds_info
@@ -1245,13 +2764,13 @@ mod tests {
             .get_active_boss_character(&target_id_name)
             .unwrap()
             .clone();
-        let old_hp_boss = old_boss.stats.all_stats[HP].current;
+        let old_hp_boss = old_boss.stats[StatKind::Hp].current;
         gm.pm
             .get_mut_active_boss_character(&target_id_name)
             .unwrap()
             .character_rounds_info
             .is_current_target = true;
-        let old_vigor_hero = gm.pm.current_player.stats.all_stats[VIGOR].current;
+        let old_vigor_hero = gm.pm.current_player.stats[StatKind::Vigor].current;
         gm.launch_attack(Some("SimpleAtk"));
         // 1 dead boss : end of game
         assert!(gm.game_state.status != GameStatus::EndOfGame); // still one boss
@@ -1270,8 +2789,7 @@ mod tests {
             gm.pm
                 .get_active_boss_character(&target_id_name)
                 .unwrap()
-                .stats
-                .all_stats[HP]
+                .stats[StatKind::Hp]
                 .current
         );
         // 9% of 200 (total vigor)
@@ -1280,8 +2798,7 @@ mod tests {
             gm.pm
                 .get_active_hero_character(&hero_launcher_id_name)
                 .unwrap()
-                .stats
-                .all_stats[VIGOR]
+                .stats[StatKind::Vigor]
                 .current
         );
     }
@@ -1305,25 +2822,25 @@ mod tests {
                 .get_mut_active_boss_character(&target_id_name)
                 .unwrap();
             boss.class = Class::Berserker;
-            boss.stats.all_stats[DODGE].current = 0;
+            boss.stats[StatKind::Dodge].current = 0;
             boss.character_rounds_info.update_buffer(&Buffer {
                 is_passive_enabled: false,
                 is_passive: false,
                 value: 1,
                 is_percent: false,
-                stats_name: String::new(),
+                stats_name: None,
                 kind: BufKinds::StreakBreakerDodge,
             });
             boss.character_rounds_info.dodge_drought_counter = 1;
         }
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         let old_boss = gm
             .pm
             .get_active_boss_character(&target_id_name)
             .unwrap()
             .clone();
-        let old_hp_boss = old_boss.stats.all_stats[HP].current;
-        let old_vigor_hero = gm.pm.current_player.stats.all_stats[MANA].current;
+        let old_hp_boss = old_boss.stats[StatKind::Hp].current;
+        let old_vigor_hero = gm.pm.current_player.stats[StatKind::Mana].current;
         gm.pm
             .get_mut_active_boss_character(&target_id_name)
             .unwrap()
@@ -1347,8 +2864,7 @@ mod tests {
             gm.pm
                 .get_active_boss_character(&target_id_name)
                 .unwrap()
-                .stats
-                .all_stats[HP]
+                .stats[StatKind::Hp]
                 .current
         );
         // 9% of 200 (total vigor)
@@ -1357,8 +2873,7 @@ mod tests {
             gm.pm
                 .get_active_hero_character(&hero_launcher_id_name)
                 .unwrap()
-                .stats
-                .all_stats[VIGOR]
+                .stats[StatKind::Vigor]
                 .current
         );
     }
@@ -1376,7 +2891,7 @@ mod tests {
             .current_player
             .attacks_list
             .insert(atk.name.clone(), atk.clone());
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         // Disable the NextHealAtkIsCrit passive (loaded from test JSON) so this
         // heal attack is not treated as a crit.
         if let Some(buf) = gm
@@ -1387,25 +2902,15 @@ mod tests {
         {
             buf.is_passive_enabled = false;
         }
-        let old_hp_test2 = gm
-            .pm
-            .get_active_hero_character("test2_#1")
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
-        let old_mana_launcher = gm.pm.current_player.stats.all_stats[MANA].current;
+        let old_hp_test2 =
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::Hp].current;
+        let old_mana_launcher = gm.pm.current_player.stats[StatKind::Mana].current;
         gm.launch_attack(Some(&atk.clone().name));
         assert!(gm.game_state.status != GameStatus::EndOfGame);
         // + 30  of max HP:135 = 40
         assert_eq!(
             old_hp_test2 + 40,
-            gm.pm
-                .get_active_hero_character("test2_#1")
-                .unwrap()
-                .stats
-                .all_stats[HP]
-                .current
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::Hp].current
         );
         // -10% of mana max (see effect param of the atk)
         assert_eq!(
@@ -1413,8 +2918,7 @@ mod tests {
             gm.pm
                 .get_active_hero_character(&hero_launcher_id_name)
                 .unwrap()
-                .stats
-                .all_stats[MANA]
+                .stats[StatKind::Mana]
                 .current
         ); // 10% of 200 (total mana)
     }
@@ -1423,7 +2927,7 @@ mod tests {
     fn unit_launch_attack_case_eclat_despoir() {
         let (mut gm, hero_launcher_id_name, _target_id_name) = testing_test_ally1_vs_test_boss1();
         // no crit
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         // Disable the NextHealAtkIsCrit passive (loaded from test JSON) so this
         // heal attack is not treated as a crit.
         if let Some(buf) = gm
@@ -1438,65 +2942,41 @@ mod tests {
             .pm
             .get_active_hero_character(&hero_launcher_id_name)
             .unwrap()
-            .stats
-            .all_stats[HP]
+            .stats[StatKind::Hp]
             .current;
         let old_mag_pow_test = gm
             .pm
             .get_active_hero_character(&hero_launcher_id_name)
             .unwrap()
-            .stats
-            .all_stats[MAGICAL_POWER]
+            .stats[StatKind::MagicalPower]
             .max;
         let old_phy_pow_test = gm
             .pm
             .get_active_hero_character(&hero_launcher_id_name)
             .unwrap()
-            .stats
-            .all_stats[PHYSICAL_POWER]
+            .stats[StatKind::PhysicalPower]
             .max;
-        let old_hp_test2 = gm
-            .pm
-            .get_active_hero_character("test2_#1")
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
-        let old_mag_pow_test2 = gm
-            .pm
-            .get_active_hero_character("test2_#1")
-            .unwrap()
-            .stats
-            .all_stats[MAGICAL_POWER]
-            .max;
-        let old_phy_pow_test2 = gm
-            .pm
-            .get_active_hero_character("test2_#1")
-            .unwrap()
-            .stats
-            .all_stats[PHYSICAL_POWER]
-            .max;
-        let old_mana_launcher = gm.pm.current_player.stats.all_stats[MANA].current;
+        let old_hp_test2 =
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::Hp].current;
+        let old_mag_pow_test2 =
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::MagicalPower].max;
+        let old_phy_pow_test2 =
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::PhysicalPower].max;
+        let old_mana_launcher = gm.pm.current_player.stats[StatKind::Mana].current;
         gm.launch_attack(Some("Eclat d'espoir"));
         assert!(gm.game_state.status != GameStatus::EndOfGame);
         // "up-current-stat-by-percentage"
         // + 30 % of max HP:135 = 40.5 + NextAtkHealIsCrit x2 = 80 on test2 and test1
         assert_eq!(
             old_hp_test2 + 40,
-            gm.pm
-                .get_active_hero_character("test2_#1")
-                .unwrap()
-                .stats
-                .all_stats[HP]
-                .current
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::Hp].current
         );
         assert_eq!(
             old_hp_test + 40,
             gm.pm
                 .get_active_hero_character(&hero_launcher_id_name)
                 .unwrap()
-                .stats
-                .all_stats[HP]
+                .stats[StatKind::Hp]
                 .current
         );
         // -18%, mana max = 200
@@ -1505,8 +2985,7 @@ mod tests {
             gm.pm
                 .get_active_hero_character(&hero_launcher_id_name)
                 .unwrap()
-                .stats
-                .all_stats[MANA]
+                .stats[StatKind::Mana]
                 .current
         );
         // "Magic power"
@@ -1514,20 +2993,14 @@ mod tests {
         // +15%, mag power max = 20
         assert_eq!(
             old_mag_pow_test2 + (0.15 * old_mag_pow_test2 as f64) as u64,
-            gm.pm
-                .get_active_hero_character("test2_#1")
-                .unwrap()
-                .stats
-                .all_stats[MAGICAL_POWER]
-                .max
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::MagicalPower].max
         );
         assert_eq!(
             old_mag_pow_test + (0.15 * old_mag_pow_test as f64) as u64,
             gm.pm
                 .get_active_hero_character(&hero_launcher_id_name)
                 .unwrap()
-                .stats
-                .all_stats[MAGICAL_POWER]
+                .stats[StatKind::MagicalPower]
                 .max
         );
         // "Physical power"
@@ -1535,20 +3008,14 @@ mod tests {
         // +15%, phy power max = 10
         assert_eq!(
             old_phy_pow_test2 + (0.15 * old_phy_pow_test2 as f64).round() as u64,
-            gm.pm
-                .get_active_hero_character("test2_#1")
-                .unwrap()
-                .stats
-                .all_stats[PHYSICAL_POWER]
-                .max
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::PhysicalPower].max
         );
         assert_eq!(
             old_phy_pow_test + (0.15 * old_phy_pow_test as f64) as u64,
             gm.pm
                 .get_active_hero_character(&hero_launcher_id_name)
                 .unwrap()
-                .stats
-                .all_stats[PHYSICAL_POWER]
+                .stats[StatKind::PhysicalPower]
                 .max
         );
     }
@@ -1572,32 +3039,22 @@ mod tests {
         }
 
         // Disable crit and ensure full mana for determinism.
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
-        let mana_max = gm.pm.current_player.stats.all_stats[MANA].max;
-        gm.pm.current_player.stats.all_stats[MANA].current = mana_max;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
+        let mana_max = gm.pm.current_player.stats[StatKind::Mana].max;
+        gm.pm.current_player.stats[StatKind::Mana].current = mana_max;
 
         let thrain_id = gm
             .pm
             .active_heroes
-            .iter()
-            .find(|h| h.id_name.contains("Thraïn"))
-            .map(|h| h.id_name.clone())
-            .expect("Thraïn must be among the lotr heroes");
-
-        let old_phy_pow = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[PHYSICAL_POWER]
-            .max;
-        let old_mag_pow = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[MAGICAL_POWER]
-            .max;
+            .iter()
+            .find(|h| h.id_name.contains("Thraïn"))
+            .map(|h| h.id_name.clone())
+            .expect("Thraïn must be among the lotr heroes");
+
+        let old_phy_pow =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::PhysicalPower].max;
+        let old_mag_pow =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::MagicalPower].max;
 
         // Thraïn's magical power comes from equipment (max_raw == 0 but equip buffers
         // give a non-zero effective max).  Both stats must be non-zero to make this
@@ -1613,22 +3070,12 @@ mod tests {
         // Both stats must be boosted by +15 % (integer arithmetic matches the engine).
         assert_eq!(
             old_phy_pow + (0.15 * old_phy_pow as f64) as u64,
-            gm.pm
-                .get_active_hero_character(&thrain_id)
-                .unwrap()
-                .stats
-                .all_stats[PHYSICAL_POWER]
-                .max,
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::PhysicalPower].max,
             "Eclat d'espoir should boost Thraïn physical power by 15 %"
         );
         assert_eq!(
             old_mag_pow + (0.15 * old_mag_pow as f64) as u64,
-            gm.pm
-                .get_active_hero_character(&thrain_id)
-                .unwrap()
-                .stats
-                .all_stats[MAGICAL_POWER]
-                .max,
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::MagicalPower].max,
             "Eclat d'espoir should boost Thraïn magical power by 15 %"
         );
     }
@@ -1642,7 +3089,7 @@ mod tests {
         // testing_test_ally1_vs_test_boss1 advanced to round 2 (test_#1); test2_#1 already played round 1.
         assert_eq!(gm.game_state.order_to_play.len(), 5);
         assert_eq!(gm.pm.current_player.id_name, hero_launcher_id_name);
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         // apply effect Magic power - up by % for 2 turns (active turn1+turn2, ends on turn 3)
         // launch_attack calls eval_end_of_round internally, which advances one round
         gm.launch_attack(Some("Eclat d'espoir"));
@@ -1692,21 +3139,19 @@ mod tests {
         let (mut gm, hero_launcher_id_name, _target_id_name) = testing_test_ally1_vs_test_boss1();
 
         assert_eq!(gm.pm.current_player.id_name, hero_launcher_id_name);
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         let old_dodge = gm
             .pm
             .get_mut_active_character(&hero_launcher_id_name)
             .unwrap()
-            .stats
-            .all_stats[DODGE]
+            .stats[StatKind::Dodge]
             .max;
         let result = gm.launch_attack(Some("up-par-valeur"));
         let new_dodge = gm
             .pm
             .get_mut_active_character(&hero_launcher_id_name)
             .unwrap()
-            .stats
-            .all_stats[DODGE]
+            .stats[StatKind::Dodge]
             .max;
         assert_eq!(result.new_game_atk_effects.len(), 1);
         assert_eq!(new_dodge, old_dodge + 20);
@@ -1716,28 +3161,25 @@ mod tests {
     fn unit_launch_attack_changement_par_value_berserk() {
         let (mut gm, hero_launcher_id_name, _target_id_name) = testing_test_ally1_vs_test_boss1();
 
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         let old_berserk_current = gm
             .pm
             .get_mut_active_character(&hero_launcher_id_name)
             .unwrap()
-            .stats
-            .all_stats[BERSERK]
+            .stats[StatKind::Berserk]
             .current;
         let old_berserk_max = gm
             .pm
             .get_mut_active_character(&hero_launcher_id_name)
             .unwrap()
-            .stats
-            .all_stats[BERSERK]
+            .stats[StatKind::Berserk]
             .max;
         let result = gm.launch_attack(Some("ChangeCurrentStatByValue-berseck"));
         let new_berserk = gm
             .pm
             .get_mut_active_character(&hero_launcher_id_name)
             .unwrap()
-            .stats
-            .all_stats[BERSERK]
+            .stats[StatKind::Berserk]
             .current;
         assert_eq!(result.new_game_atk_effects.len(), 1); // target himself
         // cost: -5% of berserk max, effect value +20
@@ -1751,7 +3193,7 @@ mod tests {
     fn unit_launch_attack_case_cooldown() {
         let (mut gm, _hero_launcher_id_name, _target_id_name) = testing_test_ally1_vs_test_boss1();
 
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         let result = gm.launch_attack(Some("cooldown"));
         assert!(gm.game_state.status != GameStatus::EndOfGame);
         assert_eq!(result.new_game_atk_effects.len(), 1);
@@ -1772,13 +3214,8 @@ mod tests {
     fn unit_integ_dxrpg() {
         let mut gm = testing_all_characters::dxrpg_game_manager();
         gm.start_game();
-        let old_hp_boss = gm
-            .pm
-            .get_active_boss_character("Angmar_#1")
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
+        let old_hp_boss =
+            gm.pm.get_active_boss_character("Angmar_#1").unwrap().stats[StatKind::Hp].current;
         gm.pm
             .get_mut_active_boss_character("Angmar_#1")
             .unwrap()
@@ -1791,21 +3228,12 @@ mod tests {
         if !ra.all_dodging.is_empty() && ra.all_dodging[0].is_dodging {
             assert_eq!(
                 old_hp_boss,
-                gm.pm
-                    .get_active_boss_character("Angmar_#1")
-                    .unwrap()
-                    .stats
-                    .all_stats[HP]
-                    .current
+                gm.pm.get_active_boss_character("Angmar_#1").unwrap().stats[StatKind::Hp].current
             );
         } else {
             assert!(
                 old_hp_boss
-                    > gm.pm
-                        .get_active_boss_character("Angmar_#1")
-                        .unwrap()
-                        .stats
-                        .all_stats[HP]
+                    > gm.pm.get_active_boss_character("Angmar_#1").unwrap().stats[StatKind::Hp]
                         .current,
                 "non-dodged Charge must deal at least 1 damage"
             );
@@ -1854,24 +3282,14 @@ mod tests {
         while gm.game_state.status == GameStatus::StartRound {
             if gm.is_round_auto() {
                 // boss round: set a living hero as target so the individual attack lands
-                if let Some(h) = gm
-                    .pm
-                    .active_heroes
-                    .iter_mut()
-                    .find(|h| h.stats.is_dead() != Some(true))
-                {
+                if let Some(h) = gm.pm.active_heroes.iter_mut().find(|h| !h.stats.is_dead()) {
                     h.character_rounds_info.is_current_target = true;
                 }
                 let _ = gm.process_nb_bosses_atk_in_a_row();
                 let _ = gm.launch_attack(None);
             } else {
                 // hero round: set a living boss as target so Charge lands
-                if let Some(b) = gm
-                    .pm
-                    .active_bosses
-                    .iter_mut()
-                    .find(|b| b.stats.is_dead() != Some(true))
-                {
+                if let Some(b) = gm.pm.active_bosses.iter_mut().find(|b| !b.stats.is_dead()) {
                     b.character_rounds_info.is_current_target = true;
                 }
                 let _ = gm.launch_attack(Some("Charge"));
@@ -1890,6 +3308,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unit_same_seed_same_fight() {
+        let play = |seed: u64| {
+            let mut gm = testing_all_characters::dxrpg_game_manager();
+            gm.set_seed(seed);
+            gm.start_game();
+            let mut results = vec![];
+            for _ in 0..30 {
+                let atk_name = gm
+                    .pm
+                    .current_player
+                    .character_rounds_info
+                    .launchable_atks
+                    .first()
+                    .map(|atk| atk.name.clone());
+                let ra = gm.launch_attack(atk_name.as_deref());
+                results.push((ra.atk_name, ra.is_crit, ra.all_dodging));
+            }
+            (results, gm.pm)
+        };
+        assert_eq!(play(42), play(42));
+    }
+
     #[test]
     fn unit_launch_attack_boss_pattern_queue() {
         let mut gm = testing_all_characters::testing_game_manager();
@@ -2035,16 +3476,150 @@ mod tests {
         }
     }
 
+    /// Boss rules have priority on patterns, `once` rules are used a single time
+    /// and the rule focus overrides the max aggro target.
+    #[test]
+    fn unit_boss_rules_before_patterns() {
+        use crate::{
+            character_mod::target::TargetingStrategy,
+            server::boss_ai::{BossCondition, BossRule},
+        };
+
+        let mut gm = testing_all_characters::testing_game_manager();
+        gm.current_scenario
+            .boss_patterns
+            .insert("test_boss1".to_string(), vec![1]);
+        let atk_names: Vec<String> = gm
+            .pm
+            .get_active_boss_character("test_boss1_#1")
+            .unwrap()
+            .attacks_list
+            .keys()
+            .cloned()
+            .collect();
+        gm.current_scenario.boss_rules.insert(
+            "test_boss1".to_string(),
+            vec![BossRule {
+                conditions: vec![BossCondition::TurnAtLeast(1)],
+                atk_name: atk_names[0].clone(),
+                focus: Some(TargetingStrategy::LowestHp),
+                once: true,
+            }],
+        );
+        gm.start_game();
+        let weakest = gm.pm.active_heroes[1].id_name.clone();
+        gm.pm.active_heroes[0].stats[StatKind::Hp].current = 100;
+        gm.pm.active_heroes[1].stats[StatKind::Hp].current = 50;
+
+        let mut used_atks = vec![];
+        for _ in 0..2 {
+            while gm.pm.current_player.id_name != "test_boss1_#1" {
+                let (ok, _) = gm.new_round();
+                if !ok {
+                    gm.start_new_turn();
+                }
+            }
+            let ra = gm.launch_attack(None);
+            if used_atks.is_empty() {
+                assert!(
+                    ra.new_game_atk_effects
+                        .iter()
+                        .all(|g| g.effect_outcome.target_id_name == weakest)
+                );
+            }
+            used_atks.push(ra.atk_name);
+        }
+        assert_eq!(used_atks, vec![atk_names[0].clone(), atk_names[1].clone()]);
+    }
+
+    #[test]
+    fn unit_campaign_graph() {
+        use crate::common::constants::paths_const::TEST_OFFLINE_ROOT;
+        use crate::server::{
+            campaign::{CampaignCondition, ScenarioEdge},
+            command_log::GameCommand,
+            game_manager::GameManager,
+            objective::{Objective, ObjectiveKind, ObjectiveState},
+            scenario::{Scenario, ScenarioState},
+        };
+
+        let scenario = |name: &str, level: u64| Scenario {
+            name: name.to_owned(),
+            description: name.to_owned(),
+            level,
+            ..Default::default()
+        };
+        let mut start = scenario("start", 1);
+        start.objectives = vec![Objective {
+            is_secondary: true,
+            ..Objective::new(ObjectiveKind::NoHeroDies)
+        }];
+        start.next = vec![
+            ScenarioEdge {
+                scenario: "left".to_owned(),
+                conditions: vec![CampaignCondition::ChoiceMade("go left".to_owned())],
+            },
+            ScenarioEdge {
+                scenario: "right".to_owned(),
+                conditions: vec![CampaignCondition::ObjectiveCompleted(0)],
+            },
+        ];
+        let scenarios = vec![start, scenario("left", 2), scenario("right", 2)];
+        let mut gm = GameManager::new(*TEST_OFFLINE_ROOT, Default::default(), scenarios);
+        gm.pm = testing_all_characters::testing_pm();
+        let names = |gm: &GameManager| -> Vec<String> {
+            gm.available_scenarios()
+                .iter()
+                .map(|s| s.name.clone())
+                .collect()
+        };
+        assert_eq!(names(&gm), vec!["start".to_owned()]);
+
+        gm.load_next_scenario().unwrap();
+        assert_eq!(gm.current_scenario.name, "start");
+        assert!(names(&gm).is_empty());
+
+        // a scenario whose fight is not won is not completed
+        gm.game_state.objective_states = vec![ObjectiveState::Completed; 2];
+        assert!(gm.load_next_scenario().is_err());
+        assert_eq!(gm.states_scenarios["start"], ScenarioState::InProgress);
+
+        // the default goal comes first, then the secondary objective
+        gm.game_state.status = GameStatus::EndOfScenario;
+        gm.load_next_scenario().unwrap();
+        assert_eq!(gm.current_scenario.name, "right");
+        assert_eq!(gm.states_scenarios["start"], ScenarioState::Completed);
+        assert_eq!(gm.states_scenarios["left"], ScenarioState::NotStarted);
+        assert_eq!(gm.campaign.completed_objectives["start"], vec![0]);
+
+        // a locked scenario cannot be chosen, and the rejection does not complete "right"
+        gm.game_state.status = GameStatus::EndOfScenario;
+        assert!(gm.load_scenario("left").is_err());
+        assert_eq!(gm.states_scenarios["right"], ScenarioState::InProgress);
+        gm.make_choice("go left");
+        assert_eq!(names(&gm), vec!["left".to_owned()]);
+        gm.load_scenario("left").unwrap();
+        assert_eq!(gm.current_scenario.name, "left");
+        assert_eq!(gm.states_scenarios["right"], ScenarioState::Completed);
+        assert_eq!(
+            gm.command_log.entries.last().unwrap().command,
+            GameCommand::LoadScenario {
+                name: "left".to_owned()
+            }
+        );
+        assert!(gm.load_next_scenario().is_err());
+    }
+
     #[test]
     fn unit_load_next_scenario() {
         use crate::server::scenario::ScenarioState;
 
         let mut gm = testing_all_characters::dxrpg_game_manager();
 
-        // dxrpg loads lotr scenarios; states start as NotStarted
+        // dxrpg loads lotr scenarios; only the first stage is available at start
         let stage1_name = "Patrouille Gobeline".to_owned();
         let stage2_name = "Embuscade Gobeline".to_owned();
-        assert_eq!(gm.states_scenarios[&stage1_name], ScenarioState::NotStarted);
+        assert_eq!(gm.states_scenarios[&stage1_name], ScenarioState::Available);
         assert_eq!(gm.states_scenarios[&stage2_name], ScenarioState::NotStarted);
 
         // set stage 1 as current (simulates game start on stage 1)
@@ -2060,13 +3635,14 @@ mod tests {
 
         // damage heroes and drain their energy to verify restoration on next scenario
         for hero in gm.pm.active_heroes.iter_mut() {
-            hero.stats.get_mut_value(HP).current = 1;
-            hero.stats.get_mut_value(MANA).current = 0;
-            hero.stats.get_mut_value(VIGOR).current = 0;
-            hero.stats.get_mut_value(BERSERK).current = 0;
+            hero.stats[StatKind::Hp].current = 1;
+            hero.stats[StatKind::Mana].current = 0;
+            hero.stats[StatKind::Vigor].current = 0;
+            hero.stats[StatKind::Berserk].current = 0;
         }
 
-        // load stage 2
+        // win stage 1 then load stage 2
+        gm.game_state.status = GameStatus::EndOfScenario;
         let result = gm.load_next_scenario();
         assert!(result.is_ok(), "loading stage 2 should succeed");
 
@@ -2095,22 +3671,26 @@ mod tests {
         // heroes must have HP, energy and no effects restored to max
         for hero in gm.pm.active_heroes.iter() {
             assert_eq!(
-                hero.stats.all_stats[HP].current, hero.stats.all_stats[HP].max,
+                hero.stats[StatKind::Hp].current,
+                hero.stats[StatKind::Hp].max,
                 "hero {} HP should be restored to max",
                 hero.db_full_name
             );
             assert_eq!(
-                hero.stats.all_stats[MANA].current, hero.stats.all_stats[MANA].max,
+                hero.stats[StatKind::Mana].current,
+                hero.stats[StatKind::Mana].max,
                 "hero {} Mana should be restored to max",
                 hero.db_full_name
             );
             assert_eq!(
-                hero.stats.all_stats[VIGOR].current, hero.stats.all_stats[VIGOR].max,
+                hero.stats[StatKind::Vigor].current,
+                hero.stats[StatKind::Vigor].max,
                 "hero {} Vigor should be restored to max",
                 hero.db_full_name
             );
             assert_eq!(
-                hero.stats.all_stats[BERSERK].current, 0,
+                hero.stats[StatKind::Berserk].current,
+                0,
                 "hero {} Berserk should NOT be restored on scenario load",
                 hero.db_full_name
             );
@@ -2125,6 +3705,43 @@ mod tests {
         assert!(!gm.all_scenarios_completed());
     }
 
+    #[test]
+    fn unit_player_commands_are_recorded() {
+        use crate::server::command_log::GameCommand;
+
+        let (mut gm, hero_id_name, boss_id_name) = testing_test_ally1_vs_test_boss1();
+        let nb_before = gm.command_log.len();
+
+        gm.set_one_target(&hero_id_name, "SimpleAtk", &boss_id_name);
+        assert!(gm.toggle_equipment("unknown_#1", "whatever").is_err());
+        gm.launch_attack(Some("SimpleAtk"));
+
+        let commands: Vec<_> = gm.command_log.entries[nb_before..]
+            .iter()
+            .map(|e| e.command.clone())
+            .collect();
+        assert_eq!(
+            commands,
+            vec![
+                GameCommand::SetOneTarget {
+                    launcher_id_name: hero_id_name.clone(),
+                    atk_name: "SimpleAtk".to_owned(),
+                    target_id_name: boss_id_name.clone(),
+                },
+                GameCommand::ToggleEquipment {
+                    character_id_name: "unknown_#1".to_owned(),
+                    equipment_unique_name: "whatever".to_owned(),
+                },
+                GameCommand::LaunchAttack {
+                    atk_name: Some("SimpleAtk".to_owned()),
+                },
+            ]
+        );
+        let checkpoint = &gm.command_log.entries.last().unwrap().checkpoint;
+        assert_eq!(checkpoint.rng, gm.rng);
+        assert_eq!(checkpoint.turn_nb, gm.game_state.current_turn_nb);
+    }
+
     #[test]
     fn unit_set_active_bosses() {
         use crate::testing::testing_all_characters::dxrpg_dm;
@@ -2171,6 +3788,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn unit_set_active_bosses_in_json_order() {
+        use crate::server::scenario::Scenario;
+
+        let json = r#"{"name":"Stage 2","description":"","level":2,
+            "boss_patterns":{"test_boss2":[0],"test_boss1":[0]}}"#;
+        let mut active_bosses_by_copy = Vec::new();
+        for _ in 0..2 {
+            let mut gm = testing_all_characters::testing_game_manager();
+            let all_bosses = gm.pm.all_bosses.clone();
+            gm.current_scenario = serde_json::from_str::<Scenario>(json).unwrap();
+            gm.pm.active_bosses.clear();
+            gm.set_active_bosses(&all_bosses);
+            active_bosses_by_copy.push(
+                gm.pm
+                    .active_bosses
+                    .iter()
+                    .map(|b| b.id_name.clone())
+                    .collect::<Vec<_>>(),
+            );
+        }
+        assert_eq!(active_bosses_by_copy[0], active_bosses_by_copy[1]);
+        assert_eq!(
+            active_bosses_by_copy[0],
+            vec!["test_boss2_#1".to_owned(), "test_boss1_#1".to_owned()]
+        );
+    }
+
+    #[test]
+    fn unit_set_active_bosses_scenario_targeting() {
+        use crate::character_mod::target::TargetingStrategy;
+
+        let mut gm = testing_all_characters::testing_game_manager();
+        let all_bosses = gm.pm.all_bosses.clone();
+        gm.current_scenario
+            .boss_patterns
+            .insert("test_boss1".to_string(), vec![0]);
+        gm.current_scenario
+            .boss_targeting
+            .insert("test_boss1".to_string(), TargetingStrategy::Spread);
+        gm.pm.active_bosses.clear();
+        gm.set_active_bosses(&all_bosses);
+        assert_eq!(
+            gm.pm.active_bosses[0].targeting_strategy,
+            TargetingStrategy::Spread
+        );
+    }
+
     // -------------------------------------------------------------------------
     // process_end_of_scenario tests
     // -------------------------------------------------------------------------
@@ -2189,7 +3854,14 @@ mod tests {
         gm.current_scenario = Scenario {
             name: "test".to_string(),
             description: "test".to_string(),
-            boss_patterns: HashMap::new(),
+            boss_patterns: IndexMap::new(),
+            boss_rules: HashMap::new(),
+            boss_targeting: HashMap::new(),
+            boss_phases: HashMap::new(),
+            boss_levels: HashMap::new(),
+            waves: Vec::new(),
+            objectives: Vec::new(),
+            next: Vec::new(),
             level: 1,
             loots: vec![Loot {
                 name: "starting right weapon".to_string(),
@@ -2247,7 +3919,14 @@ mod tests {
         gm.current_scenario = Scenario {
             name: "test".to_string(),
             description: "test".to_string(),
-            boss_patterns: HashMap::new(),
+            boss_patterns: IndexMap::new(),
+            boss_rules: HashMap::new(),
+            boss_targeting: HashMap::new(),
+            boss_phases: HashMap::new(),
+            boss_levels: HashMap::new(),
+            waves: Vec::new(),
+            objectives: Vec::new(),
+            next: Vec::new(),
             level: 1,
             loots: vec![Loot {
                 name: "starting belt".to_string(),
@@ -2297,7 +3976,14 @@ mod tests {
         gm.current_scenario = Scenario {
             name: "test".to_string(),
             description: "test".to_string(),
-            boss_patterns: HashMap::new(),
+            boss_patterns: IndexMap::new(),
+            boss_rules: HashMap::new(),
+            boss_targeting: HashMap::new(),
+            boss_phases: HashMap::new(),
+            boss_levels: HashMap::new(),
+            waves: Vec::new(),
+            objectives: Vec::new(),
+            next: Vec::new(),
             level: 1,
             loots: vec![Loot {
                 name: "non_existent_equipment".to_string(),
@@ -2335,7 +4021,14 @@ mod tests {
         gm.current_scenario = Scenario {
             name: "test".to_string(),
             description: "test".to_string(),
-            boss_patterns: HashMap::new(),
+            boss_patterns: IndexMap::new(),
+            boss_rules: HashMap::new(),
+            boss_targeting: HashMap::new(),
+            boss_phases: HashMap::new(),
+            boss_levels: HashMap::new(),
+            waves: Vec::new(),
+            objectives: Vec::new(),
+            next: Vec::new(),
             level: 1,
             loots: vec![Loot {
                 name: "potion".to_string(),
@@ -2391,7 +4084,14 @@ mod tests {
             gm.current_scenario = Scenario {
                 name: "test".to_string(),
                 description: "test".to_string(),
-                boss_patterns: HashMap::new(),
+                boss_patterns: IndexMap::new(),
+                boss_rules: HashMap::new(),
+                boss_targeting: HashMap::new(),
+                boss_phases: HashMap::new(),
+                boss_levels: HashMap::new(),
+                waves: Vec::new(),
+                objectives: Vec::new(),
+                next: Vec::new(),
                 level: 1,
                 loots: vec![Loot {
                     name: potion_name.to_string(),
@@ -2428,7 +4128,14 @@ mod tests {
         gm.current_scenario = Scenario {
             name: "test".to_string(),
             description: "test".to_string(),
-            boss_patterns: HashMap::new(),
+            boss_patterns: IndexMap::new(),
+            boss_rules: HashMap::new(),
+            boss_targeting: HashMap::new(),
+            boss_phases: HashMap::new(),
+            boss_levels: HashMap::new(),
+            waves: Vec::new(),
+            objectives: Vec::new(),
+            next: Vec::new(),
             level: 1,
             loots: vec![Loot {
                 name: "gold".to_string(),
@@ -2476,7 +4183,14 @@ mod tests {
         gm.current_scenario = Scenario {
             name: "test".to_string(),
             description: "test".to_string(),
-            boss_patterns: HashMap::new(),
+            boss_patterns: IndexMap::new(),
+            boss_rules: HashMap::new(),
+            boss_targeting: HashMap::new(),
+            boss_phases: HashMap::new(),
+            boss_levels: HashMap::new(),
+            waves: Vec::new(),
+            objectives: Vec::new(),
+            next: Vec::new(),
             loots: vec![],
             level: 1,
             universe: String::new(),
@@ -2486,7 +4200,7 @@ mod tests {
             .pm
             .active_heroes
             .iter()
-            .map(|h| h.stats.all_stats[HP].max)
+            .map(|h| h.stats[StatKind::Hp].max)
             .collect();
 
         gm.process_end_of_scenario();
@@ -2506,7 +4220,7 @@ mod tests {
             );
             // Stats must have been updated upward on level-up
             assert!(
-                hero.stats.all_stats[HP].max > old_hp_max[idx],
+                hero.stats[StatKind::Hp].max > old_hp_max[idx],
                 "hero '{}' HP max should have increased after leveling up",
                 hero.id_name
             );
@@ -2538,7 +4252,14 @@ mod tests {
         gm.current_scenario = Scenario {
             name: "test".to_string(),
             description: "test".to_string(),
-            boss_patterns: HashMap::new(),
+            boss_patterns: IndexMap::new(),
+            boss_rules: HashMap::new(),
+            boss_targeting: HashMap::new(),
+            boss_phases: HashMap::new(),
+            boss_levels: HashMap::new(),
+            waves: Vec::new(),
+            objectives: Vec::new(),
+            next: Vec::new(),
             loots: vec![],
             level: 1,
             universe: String::new(),
@@ -2572,7 +4293,14 @@ mod tests {
         gm.current_scenario = Scenario {
             name: "test".to_string(),
             description: "test".to_string(),
-            boss_patterns: HashMap::new(),
+            boss_patterns: IndexMap::new(),
+            boss_rules: HashMap::new(),
+            boss_targeting: HashMap::new(),
+            boss_phases: HashMap::new(),
+            boss_levels: HashMap::new(),
+            waves: Vec::new(),
+            objectives: Vec::new(),
+            next: Vec::new(),
             level: 1,
             loots: vec![Loot {
                 name: "gold".to_string(),
@@ -2586,7 +4314,7 @@ mod tests {
 
         // Kill all bosses
         for boss in gm.pm.active_bosses.iter_mut() {
-            boss.stats.all_stats.get_mut(HP).unwrap().current = 0;
+            boss.stats[StatKind::Hp].current = 0;
         }
 
         // Set target and launch — eval_end_of_round sees all bosses dead
@@ -2616,11 +4344,11 @@ mod tests {
     /// This test verifies that a low-HP hero is killed by the self-damage component.
     #[test]
     fn unit_fracas_marteau_can_kill_caster() {
-        use crate::{
-            character_mod::{attack_type::AttackType, buffers::BufKinds, effect::EffectParam},
-            common::constants::{
-                all_target_const::TARGET_HIMSELF, reach_const::INDIVIDUAL, stats_const::HP,
-            },
+        use crate::character_mod::{
+            attack_type::AttackType,
+            buffers::BufKinds,
+            effect::EffectParam,
+            target::{Reach, Target},
         };
 
         let (mut gm, hero_id_name, _) = testing_test_ally1_vs_test_boss1();
@@ -2629,17 +4357,17 @@ mod tests {
         use crate::character_mod::buffers::Buffer;
         let fracas_marteau = AttackType {
             name: "Fracas Marteau".to_owned(),
-            target: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target: Target::Himself,
+            reach: Reach::Individual,
             all_effects: vec![EffectParam {
                 nb_turns: 1,
-                target_kind: TARGET_HIMSELF.to_owned(),
-                reach: INDIVIDUAL.to_owned(),
+                target_kind: Target::Himself,
+                reach: Reach::Individual,
                 buffer: Buffer {
                     kind: BufKinds::ChangeCurrentStatByValue,
                     value: -50,
                     is_percent: false,
-                    stats_name: HP.to_owned(),
+                    stats_name: Some(StatKind::Hp),
                     is_passive_enabled: false,
                     is_passive: false,
                 },
@@ -2651,14 +4379,14 @@ mod tests {
         // Set hero HP to 10 so self-damage is lethal
         for hero in gm.pm.active_heroes.iter_mut() {
             if hero.id_name == hero_id_name {
-                hero.stats.get_mut_value(HP).current = 10;
+                hero.stats[StatKind::Hp].current = 10;
                 hero.attacks_list
                     .insert(fracas_marteau.name.clone(), fracas_marteau.clone());
             }
         }
         // Also update current_player (shadow copy)
         if gm.pm.current_player.id_name == hero_id_name {
-            gm.pm.current_player.stats.get_mut_value(HP).current = 10;
+            gm.pm.current_player.stats[StatKind::Hp].current = 10;
             gm.pm
                 .current_player
                 .attacks_list
@@ -2686,9 +4414,9 @@ mod tests {
             .find(|h| h.id_name == hero_id_name);
         if let Some(hero) = hero_after {
             assert!(
-                hero.stats.is_dead() == Some(true) || hero.stats.all_stats[HP].current == 0,
+                hero.stats.is_dead() || hero.stats[StatKind::Hp].current == 0,
                 "Fracas Marteau should kill the hero at 10 HP, but HP is {}",
-                hero.stats.all_stats[HP].current
+                hero.stats[StatKind::Hp].current
             );
         }
     }
@@ -2698,25 +4426,22 @@ mod tests {
     /// After a damage attack the launcher's aggro should be strictly greater than its initial value.
     #[test]
     fn unit_aggro_increases_after_damage_attack() {
-        use crate::common::constants::stats_const::AGGRO;
-
         let (mut gm, hero_launcher_id_name, target_id_name) = testing_test_ally1_vs_test_boss1();
 
         // Disable dodge & crit so the attack lands cleanly.
         gm.pm
             .get_mut_active_boss_character(&target_id_name)
             .unwrap()
-            .stats
-            .all_stats[DODGE]
+            .stats[StatKind::Dodge]
             .current = 0;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         gm.pm
             .get_mut_active_boss_character(&target_id_name)
             .unwrap()
             .character_rounds_info
             .is_current_target = true;
 
-        let aggro_before = gm.pm.current_player.stats.all_stats[AGGRO].current;
+        let aggro_before = gm.pm.current_player.stats[StatKind::Aggro].current;
 
         let ra = gm.launch_attack(Some("SimpleAtk"));
 
@@ -2733,7 +4458,7 @@ mod tests {
             .active_heroes
             .iter()
             .find(|h| h.id_name == hero_launcher_id_name)
-            .map(|h| h.stats.all_stats[AGGRO].current)
+            .map(|h| h.stats[StatKind::Aggro].current)
             .unwrap_or(0);
 
         assert!(
@@ -2754,10 +4479,9 @@ mod tests {
         gm.pm
             .get_mut_active_boss_character(&target_id_name)
             .unwrap()
-            .stats
-            .all_stats[DODGE]
+            .stats[StatKind::Dodge]
             .current = 0;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         gm.pm
             .get_mut_active_boss_character(&target_id_name)
             .unwrap()
@@ -2797,17 +4521,14 @@ mod tests {
     /// Aggro from two consecutive attacks accumulates (not reset to base each time).
     #[test]
     fn unit_aggro_accumulates_across_attacks() {
-        use crate::common::constants::stats_const::AGGRO;
-
         let (mut gm, hero_launcher_id_name, target_id_name) = testing_test_ally1_vs_test_boss1();
 
         gm.pm
             .get_mut_active_boss_character(&target_id_name)
             .unwrap()
-            .stats
-            .all_stats[DODGE]
+            .stats[StatKind::Dodge]
             .current = 0;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         gm.pm
             .get_mut_active_boss_character(&target_id_name)
             .unwrap()
@@ -2839,7 +4560,7 @@ mod tests {
             .active_heroes
             .iter()
             .find(|h| h.id_name == hero_launcher_id_name)
-            .map(|h| h.stats.all_stats[AGGRO].current)
+            .map(|h| h.stats[StatKind::Aggro].current)
             .unwrap_or(0);
 
         // Second attack.
@@ -2850,7 +4571,7 @@ mod tests {
             .active_heroes
             .iter()
             .find(|h| h.id_name == hero_launcher_id_name)
-            .map(|h| h.stats.all_stats[AGGRO].current)
+            .map(|h| h.stats[StatKind::Aggro].current)
             .unwrap_or(0);
 
         assert!(
@@ -2863,20 +4584,19 @@ mod tests {
     /// This verifies the real game flow where eval_end_of_round advances all other characters.
     #[test]
     fn unit_aggro_accumulates_across_full_turns() {
-        use crate::common::constants::stats_const::AGGRO;
         use crate::server::game_state::GameStatus;
 
         let (mut gm, hero_launcher_id_name, target_id_name) = testing_test_ally1_vs_test_boss1();
 
         // Disable dodge and critical strike variance for determinism.
-        gm.pm.current_player.stats.all_stats[DODGE].current = 0;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::Dodge].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         if let Some(boss) = gm.pm.get_mut_active_boss_character(&target_id_name) {
             boss.character_rounds_info.is_current_target = true;
         }
         for h in gm.pm.active_heroes.iter_mut() {
-            h.stats.all_stats[DODGE].current = 0;
-            h.stats.all_stats[CRITICAL_STRIKE].current = 0;
+            h.stats[StatKind::Dodge].current = 0;
+            h.stats[StatKind::CriticalStrike].current = 0;
         }
 
         // --- Turn 1: hero attacks ---
@@ -2886,7 +4606,7 @@ mod tests {
             .active_heroes
             .iter()
             .find(|h| h.id_name == hero_launcher_id_name)
-            .map(|h| h.stats.all_stats[AGGRO].current)
+            .map(|h| h.stats[StatKind::Aggro].current)
             .unwrap_or(0);
 
         // Advance through remaining rounds of turn 1 (all non-hero players auto-attack),
@@ -2920,7 +4640,7 @@ mod tests {
             .active_heroes
             .iter()
             .find(|h| h.id_name == hero_launcher_id_name)
-            .map(|h| h.stats.all_stats[AGGRO].current)
+            .map(|h| h.stats[StatKind::Aggro].current)
             .unwrap_or(0);
 
         assert!(
@@ -2937,7 +4657,6 @@ mod tests {
     /// across two consecutive turns.  Uses dxrpg_game_manager() so actual hero data is tested.
     #[test]
     fn unit_aggro_thrain_frappe_cinglante_accumulates() {
-        use crate::common::constants::stats_const::AGGRO;
         use crate::server::game_state::GameStatus;
         use crate::testing::testing_all_characters::dxrpg_game_manager;
 
@@ -2964,14 +4683,9 @@ mod tests {
 
         let thrain_id = gm.pm.current_player.id_name.clone();
         // Disable dodge & critical variance for determinism
-        gm.pm.current_player.stats.all_stats[DODGE].current = 0;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
-        if let Some(boss) = gm
-            .pm
-            .active_bosses
-            .iter_mut()
-            .find(|b| !b.stats.is_dead().unwrap_or(false))
-        {
+        gm.pm.current_player.stats[StatKind::Dodge].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
+        if let Some(boss) = gm.pm.active_bosses.iter_mut().find(|b| !b.stats.is_dead()) {
             boss.character_rounds_info.is_current_target = true;
         }
 
@@ -2982,7 +4696,7 @@ mod tests {
             .active_heroes
             .iter()
             .find(|h| h.id_name == thrain_id)
-            .map(|h| h.stats.all_stats[AGGRO].current)
+            .map(|h| h.stats[StatKind::Aggro].current)
             .unwrap_or(0);
         assert!(
             !ra1.new_game_atk_effects.is_empty(),
@@ -3006,14 +4720,9 @@ mod tests {
             return; // game ended, skip
         }
 
-        gm.pm.current_player.stats.all_stats[DODGE].current = 0;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
-        if let Some(boss) = gm
-            .pm
-            .active_bosses
-            .iter_mut()
-            .find(|b| !b.stats.is_dead().unwrap_or(false))
-        {
+        gm.pm.current_player.stats[StatKind::Dodge].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
+        if let Some(boss) = gm.pm.active_bosses.iter_mut().find(|b| !b.stats.is_dead()) {
             boss.character_rounds_info.is_current_target = true;
         }
 
@@ -3024,7 +4733,7 @@ mod tests {
             .active_heroes
             .iter()
             .find(|h| h.id_name == thrain_id)
-            .map(|h| h.stats.all_stats[AGGRO].current)
+            .map(|h| h.stats[StatKind::Aggro].current)
             .unwrap_or(0);
 
         assert!(
@@ -3064,7 +4773,7 @@ mod tests {
     #[test]
     fn unit_rameau_guerisseur_initial_heal_range() {
         let (mut gm, hero_id, _) = testing_test_ally1_vs_test_boss1();
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         if let Some(buf) = gm
             .pm
             .current_player
@@ -3074,13 +4783,8 @@ mod tests {
             buf.is_passive_enabled = false;
         }
         gm.pm.set_targeted_characters(&hero_id, "Rameau Guérisseur");
-        let old_hp = gm
-            .pm
-            .get_active_hero_character("test2_#1")
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
+        let old_hp =
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::Hp].current;
 
         let ra = gm.launch_attack(Some("Rameau Guérisseur"));
 
@@ -3095,7 +4799,7 @@ mod tests {
                     .input_effect_param
                     .buffer
                     .stats_name
-                    == HP
+                    == Some(StatKind::Hp)
             })
             .expect("HP effect missing");
         let per_tick = hot.effect_outcome.full_amount_tx;
@@ -3111,20 +4815,9 @@ mod tests {
         );
 
         // HP was immediately increased on launch
-        let new_hp = gm
-            .pm
-            .get_active_hero_character("test2_#1")
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
-        let hp_max = gm
-            .pm
-            .get_active_hero_character("test2_#1")
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .max;
+        let new_hp =
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::Hp].current;
+        let hp_max = gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::Hp].max;
         assert_eq!(
             new_hp,
             (old_hp as i64 + per_tick).clamp(0, hp_max as i64) as u64
@@ -3138,7 +4831,7 @@ mod tests {
         // With old_magic_max = 30 (20 raw + 10 equipment):
         //   increase = 30 * (applies * 10) / 100 = applies * 3  → new ∈ [33, 39]
         let (mut gm, hero_id, _) = testing_test_ally1_vs_test_boss1();
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         if let Some(buf) = gm
             .pm
             .current_player
@@ -3148,13 +4841,8 @@ mod tests {
             buf.is_passive_enabled = false;
         }
 
-        let old_magic_max = gm
-            .pm
-            .get_active_hero_character("test2_#1")
-            .unwrap()
-            .stats
-            .all_stats[MAGICAL_POWER]
-            .max;
+        let old_magic_max =
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::MagicalPower].max;
 
         gm.pm.set_targeted_characters(&hero_id, "Rameau Guérisseur");
         let ra = gm.launch_attack(Some("Rameau Guérisseur"));
@@ -3169,20 +4857,15 @@ mod tests {
                     .input_effect_param
                     .buffer
                     .stats_name
-                    == HP
+                    == Some(StatKind::Hp)
             })
             .unwrap()
             .effect_outcome
             .full_amount_tx;
         let applies = per_tick / 10;
 
-        let new_magic_max = gm
-            .pm
-            .get_active_hero_character("test2_#1")
-            .unwrap()
-            .stats
-            .all_stats[MAGICAL_POWER]
-            .max;
+        let new_magic_max =
+            gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::MagicalPower].max;
 
         // full_amount for magic buf = applies * 10 → increase = old * full_amount / 100
         let full_amount = applies * 10;
@@ -3209,7 +4892,7 @@ mod tests {
         // The effect entry persists for exactly nb_turns=4 turns regardless of how many
         // ticks actually fired (which is probabilistic: 1–3). Expiry is always at T5.
         let (mut gm, hero_id, _) = testing_test_ally1_vs_test_boss1();
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         if let Some(buf) = gm
             .pm
             .current_player
@@ -3265,7 +4948,7 @@ mod tests {
         // exactly 3: the DecreasingRateOnTurn probability means T2=100%, T3=67%,
         // T4=33%. So the HOT fires 1–3 times depending on the random rolls.
         let (mut gm, hero_id, _) = testing_test_ally1_vs_test_boss1();
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         if let Some(buf) = gm
             .pm
             .current_player
@@ -3283,33 +4966,18 @@ mod tests {
         }
 
         // HP regen per turn for test2 (7); HOT tick ≥10 — any increase > regen means HOT fired
-        let regen = gm
-            .pm
-            .get_active_hero_character("test2_#1")
-            .unwrap()
-            .stats
-            .all_stats[HP_REGEN]
+        let regen = gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::HpRegen]
             .current as i64;
 
         let mut hot_ticks = 0u32;
         for _ in 2..=4 {
             // Capture HP before start_new_turn because test2_#1 is first in new order:
             // start_new_turn processes round=1 (test2_#1) which applies HOT immediately.
-            let hp_before = gm
-                .pm
-                .get_active_hero_character("test2_#1")
-                .unwrap()
-                .stats
-                .all_stats[HP]
+            let hp_before = gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::Hp]
                 .current as i64;
             gm.start_new_turn();
             // After start_new_turn, test2_#1 is current (round=1) with HOT+regen applied.
-            let hp_after = gm
-                .pm
-                .get_active_hero_character("test2_#1")
-                .unwrap()
-                .stats
-                .all_stats[HP]
+            let hp_after = gm.pm.get_active_hero_character("test2_#1").unwrap().stats[StatKind::Hp]
                 .current as i64;
             // HOT (≥10) + regen (7) >> regen alone (7)
             if hp_after - hp_before > regen {
@@ -3333,7 +5001,7 @@ mod tests {
         gm.start_game();
         // Kill ALL heroes
         for hero in &mut gm.pm.active_heroes {
-            hero.stats.all_stats[HP].current = 0;
+            hero.stats[StatKind::Hp].current = 0;
         }
         // Make round 1 point to the first hero (who is dead)
         gm.game_state.current_round = 0;
@@ -3367,9 +5035,9 @@ mod tests {
             return;
         }
 
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
-        let mana_max = gm.pm.current_player.stats.all_stats[MANA].max;
-        gm.pm.current_player.stats.all_stats[MANA].current = mana_max;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
+        let mana_max = gm.pm.current_player.stats[StatKind::Mana].max;
+        gm.pm.current_player.stats[StatKind::Mana].current = mana_max;
 
         let thrain_id = gm
             .pm
@@ -3379,20 +5047,10 @@ mod tests {
             .map(|h| h.id_name.clone())
             .expect("Thraïn must be present");
 
-        let old_mag_armor = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[MAGICAL_ARMOR]
-            .max;
-        let old_phy_armor = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[PHYSICAL_ARMOR]
-            .max;
+        let old_mag_armor =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::MagicalArmor].max;
+        let old_phy_armor =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::PhysicalArmor].max;
 
         // Offrande vitale targets a single ally — manually mark Thraïn as the current target.
         gm.pm
@@ -3403,20 +5061,10 @@ mod tests {
 
         gm.launch_attack(Some("Offrande vitale"));
 
-        let new_mag_armor = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[MAGICAL_ARMOR]
-            .max;
-        let new_phy_armor = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[PHYSICAL_ARMOR]
-            .max;
+        let new_mag_armor =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::MagicalArmor].max;
+        let new_phy_armor =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::PhysicalArmor].max;
 
         assert_eq!(
             old_mag_armor + old_mag_armor * 50 / 100,
@@ -3448,21 +5096,16 @@ mod tests {
             return;
         }
 
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
 
         let thrain_id = gm.pm.current_player.id_name.clone();
-        let aggro_before = gm.pm.current_player.stats.all_stats[AGGRO].current;
+        let aggro_before = gm.pm.current_player.stats[StatKind::Aggro].current;
 
         // Bouclier Défensif targets Self — no explicit target setting needed.
         gm.launch_attack(Some("Bouclier Défensif "));
 
-        let aggro_after = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[AGGRO]
-            .current;
+        let aggro_after =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::Aggro].current;
 
         assert_eq!(
             aggro_before + 40,
@@ -3491,33 +5134,23 @@ mod tests {
         }
 
         // No crit so the result is deterministic.
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
         // Ensure enough Berserk for the attack (cost = 12).
-        gm.pm.current_player.stats.all_stats[BERSERK].current = 50;
+        gm.pm.current_player.stats[StatKind::Berserk].current = 50;
 
         let thrain_id = gm.pm.current_player.id_name.clone();
 
-        let old_phy_pow_max = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[PHYSICAL_POWER]
-            .max;
-        let old_aggro = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[AGGRO]
-            .current;
+        let old_phy_pow_max =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::PhysicalPower].max;
+        let old_aggro =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::Aggro].current;
 
         // Record every boss HP before the attack.
         let boss_hp_before: Vec<(String, u64)> = gm
             .pm
             .active_bosses
             .iter()
-            .map(|b| (b.id_name.clone(), b.stats.all_stats[HP].current))
+            .map(|b| (b.id_name.clone(), b.stats[StatKind::Hp].current))
             .collect();
 
         // Launch Fureur Déchaînée (target: Self — attack name has two trailing spaces).
@@ -3537,7 +5170,7 @@ mod tests {
             let hp_after = gm
                 .pm
                 .get_active_boss_character(boss_id)
-                .map(|b| b.stats.all_stats[HP].current)
+                .map(|b| b.stats[StatKind::Hp].current)
                 .unwrap_or(*hp_before);
             assert_eq!(
                 *hp_before, hp_after,
@@ -3546,13 +5179,8 @@ mod tests {
         }
 
         // --- Self-buff: Physical power max must be +30 % ---
-        let new_phy_pow_max = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[PHYSICAL_POWER]
-            .max;
+        let new_phy_pow_max =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::PhysicalPower].max;
         assert_eq!(
             old_phy_pow_max + old_phy_pow_max * 30 / 100,
             new_phy_pow_max,
@@ -3560,13 +5188,8 @@ mod tests {
         );
 
         // --- Explicit aggro effect: +5 aggro on Thraïn ---
-        let new_aggro = gm
-            .pm
-            .get_active_hero_character(&thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[AGGRO]
-            .current;
+        let new_aggro =
+            gm.pm.get_active_hero_character(&thrain_id).unwrap().stats[StatKind::Aggro].current;
         assert_eq!(
             old_aggro + 5,
             new_aggro,
@@ -3591,9 +5214,9 @@ mod tests {
         }
         // If Thalia never became current player the test is a no-op (guard at call site).
         let thalia_id = gm.pm.current_player.id_name.clone();
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
-        let mana_max = gm.pm.current_player.stats.all_stats[MANA].max;
-        gm.pm.current_player.stats.all_stats[MANA].current = mana_max;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
+        let mana_max = gm.pm.current_player.stats[StatKind::Mana].max;
+        gm.pm.current_player.stats[StatKind::Mana].current = mana_max;
         (gm, thalia_id)
     }
 
@@ -3610,15 +5233,15 @@ mod tests {
             .active_heroes
             .iter()
             .filter(|h| h.id_name != thalia_id)
-            .map(|h| (h.id_name.clone(), h.stats.all_stats[MAGICAL_POWER].max))
+            .map(|h| (h.id_name.clone(), h.stats[StatKind::MagicalPower].max))
             .collect();
-        let old_thalia_mag_pow = gm.pm.current_player.stats.all_stats[MAGICAL_POWER].max;
+        let old_thalia_mag_pow = gm.pm.current_player.stats[StatKind::MagicalPower].max;
 
         gm.launch_attack(Some("Eveil de la forêt"));
 
         for (id, old_val) in &old_mag_pow {
             let new_val =
-                gm.pm.get_active_hero_character(id).unwrap().stats.all_stats[MAGICAL_POWER].max;
+                gm.pm.get_active_hero_character(id).unwrap().stats[StatKind::MagicalPower].max;
             assert_eq!(
                 old_val + old_val * 10 / 100,
                 new_val,
@@ -3626,13 +5249,8 @@ mod tests {
             );
         }
         // Also applies to the caster herself (All allies target includes self)
-        let new_thalia_mag_pow = gm
-            .pm
-            .get_active_hero_character(&thalia_id)
-            .unwrap()
-            .stats
-            .all_stats[MAGICAL_POWER]
-            .max;
+        let new_thalia_mag_pow =
+            gm.pm.get_active_hero_character(&thalia_id).unwrap().stats[StatKind::MagicalPower].max;
         assert_eq!(
             old_thalia_mag_pow + old_thalia_mag_pow * 10 / 100,
             new_thalia_mag_pow,
@@ -3666,7 +5284,7 @@ mod tests {
                 .filter(|gae| {
                     is_hot(
                         &gae.processed_effect_param.input_effect_param.buffer.kind,
-                        &gae.processed_effect_param
+                        gae.processed_effect_param
                             .input_effect_param
                             .buffer
                             .stats_name,
@@ -3687,7 +5305,7 @@ mod tests {
                 .find(|gae| {
                     is_hot(
                         &gae.processed_effect_param.input_effect_param.buffer.kind,
-                        &gae.processed_effect_param
+                        gae.processed_effect_param
                             .input_effect_param
                             .buffer
                             .stats_name,
@@ -3731,7 +5349,7 @@ mod tests {
                         kind: BufKinds::ChangeCurrentStatByValue,
                         value: -20,
                         is_percent: false,
-                        stats_name: HP.to_owned(),
+                        stats_name: Some(StatKind::Hp),
                         is_passive_enabled: false,
                         is_passive: false,
                     },
@@ -3743,6 +5361,7 @@ mod tests {
             let gae = crate::server::players_manager::GameAtkEffect {
                 processed_effect_param: dot,
                 atk_type: Default::default(),
+                launcher_id_name: String::new(),
                 launching_turn: 1,
                 launching_round: 1,
                 effect_outcome: Default::default(),
@@ -3857,7 +5476,7 @@ mod tests {
                     kind: BufKinds::ChangeCurrentStatByValue,
                     value: 60,
                     is_percent: false,
-                    stats_name: HP.to_owned(),
+                    stats_name: Some(StatKind::Hp),
                     is_passive_enabled: false,
                     is_passive: false,
                 },
@@ -3869,6 +5488,7 @@ mod tests {
         let pre_hot_gae = crate::server::players_manager::GameAtkEffect {
             processed_effect_param: pre_hot_ep,
             atk_type: Default::default(),
+            launcher_id_name: String::new(),
             launching_turn: 1,
             launching_round: 1,
             effect_outcome: Default::default(),
@@ -3903,7 +5523,7 @@ mod tests {
                 .filter(|gae| {
                     is_hot(
                         &gae.processed_effect_param.input_effect_param.buffer.kind,
-                        &gae.processed_effect_param
+                        gae.processed_effect_param
                             .input_effect_param
                             .buffer
                             .stats_name,
@@ -3942,7 +5562,7 @@ mod tests {
                     kind: BufKinds::ChangeCurrentStatByValue,
                     value: 50,
                     is_percent: false,
-                    stats_name: HP.to_owned(),
+                    stats_name: Some(StatKind::Hp),
                     is_passive_enabled: false,
                     is_passive: false,
                 },
@@ -3955,6 +5575,7 @@ mod tests {
         let aged_hot_gae = crate::server::players_manager::GameAtkEffect {
             processed_effect_param: aged_hot_ep,
             atk_type: Default::default(),
+            launcher_id_name: String::new(),
             launching_turn: 1,
             launching_round: 1,
             effect_outcome: Default::default(),
@@ -3982,7 +5603,7 @@ mod tests {
                         .input_effect_param
                         .buffer
                         .stats_name
-                        == HP
+                        == Some(StatKind::Hp)
                     && gae.processed_effect_param.input_effect_param.buffer.value > 0
                     && gae.processed_effect_param.counter_turn == 0
             });
@@ -4015,7 +5636,7 @@ mod tests {
             .filter(|gae| {
                 is_hot(
                     &gae.processed_effect_param.input_effect_param.buffer.kind,
-                    &gae.processed_effect_param
+                    gae.processed_effect_param
                         .input_effect_param
                         .buffer
                         .stats_name,
@@ -4073,7 +5694,7 @@ mod tests {
                     kind: BufKinds::ChangeCurrentStatByValue,
                     value: hot_value,
                     is_percent: false,
-                    stats_name: HP.to_owned(),
+                    stats_name: Some(StatKind::Hp),
                     is_passive_enabled: false,
                     is_passive: false,
                 },
@@ -4085,6 +5706,7 @@ mod tests {
         let hot_gae = crate::server::players_manager::GameAtkEffect {
             processed_effect_param: hot_ep,
             atk_type: Default::default(),
+            launcher_id_name: String::new(),
             launching_turn: 1,
             launching_round: 1,
             effect_outcome: crate::character_mod::effect::EffectOutcome {
@@ -4112,7 +5734,7 @@ mod tests {
             .find(|gae| {
                 is_hot(
                     &gae.processed_effect_param.input_effect_param.buffer.kind,
-                    &gae.processed_effect_param
+                    gae.processed_effect_param
                         .input_effect_param
                         .buffer
                         .stats_name,
@@ -4170,26 +5792,26 @@ mod tests {
             .insert(prev_turn, vec!["Boss_A".to_owned(), "Boss_B".to_owned()]);
 
         // Snapshot max stats and Physical power base (raw + equip, no buf_effect) before the attack.
-        let hp_max = gm.pm.current_player.stats.all_stats[HP].max;
-        let vigor_max = gm.pm.current_player.stats.all_stats[VIGOR].max;
-        let mana_max = gm.pm.current_player.stats.all_stats[MANA].max;
-        let phy_pow_max_before = gm.pm.current_player.stats.all_stats[PHYSICAL_POWER].max;
+        let hp_max = gm.pm.current_player.stats[StatKind::Hp].max;
+        let vigor_max = gm.pm.current_player.stats[StatKind::Vigor].max;
+        let mana_max = gm.pm.current_player.stats[StatKind::Mana].max;
+        let phy_pow_max_before = gm.pm.current_player.stats[StatKind::PhysicalPower].max;
         // base_value = raw + equip (without existing buf_effect_percent, which the % is applied on top of)
-        let phy_pow_attr = &gm.pm.current_player.stats.all_stats[PHYSICAL_POWER];
+        let phy_pow_attr = &gm.pm.current_player.stats[StatKind::PhysicalPower];
         let phy_base = phy_pow_attr.max_raw as i64
             + phy_pow_attr.buf_equip_value
             + phy_pow_attr.buf_equip_percent * phy_pow_attr.max_raw as i64 / 100;
 
         // Drain resources to ~50% so recovery is clearly visible and stays below cap.
-        gm.pm.current_player.stats.all_stats[HP].current = hp_max / 2;
-        gm.pm.current_player.stats.all_stats[VIGOR].current = vigor_max / 2;
-        gm.pm.current_player.stats.all_stats[MANA].current = mana_max / 2;
-        let hp_before = gm.pm.current_player.stats.all_stats[HP].current;
-        let vigor_before = gm.pm.current_player.stats.all_stats[VIGOR].current;
-        let mana_before = gm.pm.current_player.stats.all_stats[MANA].current;
+        gm.pm.current_player.stats[StatKind::Hp].current = hp_max / 2;
+        gm.pm.current_player.stats[StatKind::Vigor].current = vigor_max / 2;
+        gm.pm.current_player.stats[StatKind::Mana].current = mana_max / 2;
+        let hp_before = gm.pm.current_player.stats[StatKind::Hp].current;
+        let vigor_before = gm.pm.current_player.stats[StatKind::Vigor].current;
+        let mana_before = gm.pm.current_player.stats[StatKind::Mana].current;
 
         // Disable crits for determinism.
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
 
         gm.launch_attack(Some("Récupération Mordorienne"));
 
@@ -4197,7 +5819,7 @@ mod tests {
 
         // 2 kills × 10% = +20% Physical power max for 2 turns.
         // ChangeMaxStatByPercentage applies to base_value (raw + equip), not current max.
-        let phy_pow_max_after = azrak.stats.all_stats[PHYSICAL_POWER].max;
+        let phy_pow_max_after = azrak.stats[StatKind::PhysicalPower].max;
         let expected_pow_max = phy_pow_max_before + (phy_base * 20 / 100) as u64;
         assert_eq!(
             expected_pow_max, phy_pow_max_after,
@@ -4209,21 +5831,21 @@ mod tests {
         let expected_hp_gain = hp_max * 20 / 100;
         assert_eq!(
             hp_before + expected_hp_gain,
-            azrak.stats.all_stats[HP].current,
+            azrak.stats[StatKind::Hp].current,
             "HP must recover by 20% of max HP"
         );
 
         let expected_vigor_gain = vigor_max * 20 / 100;
         assert_eq!(
             vigor_before + expected_vigor_gain,
-            azrak.stats.all_stats[VIGOR].current,
+            azrak.stats[StatKind::Vigor].current,
             "Vigor must recover by 20% of max Vigor"
         );
 
         let expected_mana_gain = mana_max * 20 / 100;
         assert_eq!(
             mana_before + expected_mana_gain,
-            azrak.stats.all_stats[MANA].current,
+            azrak.stats[StatKind::Mana].current,
             "Mana must recover by 20% of max Mana"
         );
     }
@@ -4246,31 +5868,19 @@ mod tests {
         let azrak_id = "Azrak_Ombresang_#1";
 
         // Drain Azrak to 10 HP — lowest ratio → most needy
-        gm.pm
-            .get_mut_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats
-            .get_mut(HP)
-            .unwrap()
-            .current = 10;
-        let azrak_hp_max = gm
-            .pm
-            .get_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .max;
+        gm.pm.get_mut_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].current = 10;
+        let azrak_hp_max =
+            gm.pm.get_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].max;
 
         // Set Elara as current player, no crit
         let elara = gm.pm.get_active_hero_character(elara_id).unwrap().clone();
         gm.pm.current_player = elara;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
 
         // Boss: no dodge, no armor bonus, no DamageRxPercent
         let boss_id = gm.pm.active_bosses[0].id_name.clone();
-        gm.pm.active_bosses[0].stats.all_stats[DODGE].current = 0;
-        gm.pm.active_bosses[0].stats.all_stats[MAGICAL_ARMOR].current = 0;
+        gm.pm.active_bosses[0].stats[StatKind::Dodge].current = 0;
+        gm.pm.active_bosses[0].stats[StatKind::MagicalArmor].current = 0;
         gm.pm.active_bosses[0]
             .character_rounds_info
             .is_current_target = true;
@@ -4281,30 +5891,20 @@ mod tests {
             buf.value = 0;
         }
 
-        let old_boss_hp = gm.pm.active_bosses[0].stats.all_stats[HP].current;
+        let old_boss_hp = gm.pm.active_bosses[0].stats[StatKind::Hp].current;
 
         let result = gm.launch_attack(Some("Frappe élémentaire"));
 
         // Derive actual damage from boss HP delta (avoids hardcoding hero magic power)
-        let new_boss_hp = gm
-            .pm
-            .get_active_boss_character(&boss_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
+        let new_boss_hp =
+            gm.pm.get_active_boss_character(&boss_id).unwrap().stats[StatKind::Hp].current;
         let actual_damage = old_boss_hp as i64 - new_boss_hp as i64;
         assert!(actual_damage > 0, "SimpleAtk must deal damage to the boss");
 
         // Passive fires in the same turn: 25% of damage heals Azrak (most needy)
         let expected_heal = (actual_damage as u64 * 25 / 100).min(azrak_hp_max - 10);
-        let new_azrak_hp = gm
-            .pm
-            .get_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
+        let new_azrak_hp =
+            gm.pm.get_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].current;
         assert_eq!(
             new_azrak_hp,
             10 + expected_heal,
@@ -4340,20 +5940,15 @@ mod tests {
 
         // At load: base_value = max_raw(5) + equip(24: amulet+4, cape+10, shoes+10) = 29
         // passive: +10% of 29 = 2 (integer) → total = 31
-        let dodge_after_load = gm
-            .pm
-            .get_active_hero_character(thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[DODGE]
-            .current;
+        let dodge_after_load =
+            gm.pm.get_active_hero_character(thrain_id).unwrap().stats[StatKind::Dodge].current;
         assert_eq!(31, dodge_after_load, "passive must be included at load");
 
         // After removing the starting amulet (Dodge +4), base_value drops to 25.
         // passive: +10% of 25 = 2 (integer) → total = 27
         let thrain = gm.pm.get_mut_active_hero_character(thrain_id).unwrap();
         thrain.toggle_equipment("starting amulet", &testing_all_equipment());
-        let dodge_after_toggle = thrain.stats.all_stats[DODGE].current;
+        let dodge_after_toggle = thrain.stats[StatKind::Dodge].current;
         assert_eq!(
             27, dodge_after_toggle,
             "Dodge must be 27 after removing amulet (passive still applies)"
@@ -4377,11 +5972,11 @@ mod tests {
         let elara = gm.pm.get_active_hero_character(elara_id).unwrap().clone();
         gm.pm.current_player = elara;
         gm.pm.current_player.level = 100;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
 
         // Set target: first boss, no dodge
         let boss_id = gm.pm.active_bosses[0].id_name.clone();
-        gm.pm.active_bosses[0].stats.all_stats[DODGE].current = 0;
+        gm.pm.active_bosses[0].stats[StatKind::Dodge].current = 0;
         gm.pm.active_bosses[0]
             .character_rounds_info
             .is_current_target = true;
@@ -4394,9 +5989,9 @@ mod tests {
             buf.value = 0;
         }
 
-        let old_boss_hp = gm.pm.active_bosses[0].stats.all_stats[HP].current;
-        let mana_max = gm.pm.current_player.stats.all_stats[MANA].max;
-        let old_mana = gm.pm.current_player.stats.all_stats[MANA].current;
+        let old_boss_hp = gm.pm.active_bosses[0].stats[StatKind::Hp].current;
+        let mana_max = gm.pm.current_player.stats[StatKind::Mana].max;
+        let old_mana = gm.pm.current_player.stats[StatKind::Mana].current;
 
         // Expected magic damage: rebalanced base value=76, Elara mag power, boss mag armor+pow
         let hero_mag_pow = gm.pm.current_player.stats.get_power_stat(true);
@@ -4420,23 +6015,13 @@ mod tests {
         // Mana cost: 15% of mana_max
         assert_eq!(
             old_mana - 15 * mana_max / 100,
-            gm.pm
-                .get_active_hero_character(elara_id)
-                .unwrap()
-                .stats
-                .all_stats[MANA]
-                .current,
+            gm.pm.get_active_hero_character(elara_id).unwrap().stats[StatKind::Mana].current,
             "Elara mana cost: 15% of max"
         );
 
         // Boss HP reduced by expected magic damage
-        let new_boss_hp = gm
-            .pm
-            .get_active_boss_character(&boss_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
+        let new_boss_hp =
+            gm.pm.get_active_boss_character(&boss_id).unwrap().stats[StatKind::Hp].current;
         assert_eq!(
             old_boss_hp as i64 - expected_dmg,
             new_boss_hp as i64,
@@ -4463,53 +6048,28 @@ mod tests {
         gm.pm.current_player.level = 100;
 
         // Drain Azrak to 50 HP and mark as ally target
-        gm.pm
-            .get_mut_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current = 50;
+        gm.pm.get_mut_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].current = 50;
         gm.pm
             .get_mut_active_hero_character(azrak_id)
             .unwrap()
             .character_rounds_info
             .is_current_target = true;
 
-        let azrak_hp_max = gm
-            .pm
-            .get_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .max;
-        let old_elara_hp = gm.pm.current_player.stats.all_stats[HP].current;
-        let elara_hp_max = gm.pm.current_player.stats.all_stats[HP].max;
-        let mana_max = gm.pm.current_player.stats.all_stats[MANA].max;
-        let old_mana = gm.pm.current_player.stats.all_stats[MANA].current;
+        let azrak_hp_max =
+            gm.pm.get_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].max;
+        let old_elara_hp = gm.pm.current_player.stats[StatKind::Hp].current;
+        let elara_hp_max = gm.pm.current_player.stats[StatKind::Hp].max;
+        let mana_max = gm.pm.current_player.stats[StatKind::Mana].max;
+        let old_mana = gm.pm.current_player.stats[StatKind::Mana].current;
 
         gm.launch_attack(Some("Don de vie"));
 
-        let new_azrak_hp = gm
-            .pm
-            .get_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
-        let new_elara_hp = gm
-            .pm
-            .get_active_hero_character(elara_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
-        let new_mana = gm
-            .pm
-            .get_active_hero_character(elara_id)
-            .unwrap()
-            .stats
-            .all_stats[MANA]
-            .current;
+        let new_azrak_hp =
+            gm.pm.get_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].current;
+        let new_elara_hp =
+            gm.pm.get_active_hero_character(elara_id).unwrap().stats[StatKind::Hp].current;
+        let new_mana =
+            gm.pm.get_active_hero_character(elara_id).unwrap().stats[StatKind::Mana].current;
 
         // DecreasingRateOnTurn(3): minimum 1 apply (first roll is always 100%)
         let min_heal_one_apply = 30 * azrak_hp_max / 100;
@@ -4558,6 +6118,11 @@ mod tests {
         let elara = gm.pm.get_active_hero_character(elara_id).unwrap().clone();
         gm.pm.current_player = elara;
         gm.pm.current_player.level = 100;
+        // Elara has a loadout: the attack must be learnt to be equipped
+        gm.pm
+            .current_player
+            .learn_attack("Lumiere curative")
+            .unwrap();
 
         // Sub-case A: condition not met (turn 0, no DamageTx on previous turn)
         let lumiere_atk = gm
@@ -4586,48 +6151,28 @@ mod tests {
         );
 
         // Drain Azrak to 10 HP and mark as ally target
-        gm.pm
-            .get_mut_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current = 10;
+        gm.pm.get_mut_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].current = 10;
         gm.pm
             .get_mut_active_hero_character(azrak_id)
             .unwrap()
             .character_rounds_info
             .is_current_target = true;
 
-        let azrak_hp_max = gm
-            .pm
-            .get_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .max;
-        let mana_max = gm.pm.current_player.stats.all_stats[MANA].max;
-        let old_mana = gm.pm.current_player.stats.all_stats[MANA].current;
-        let mana_regen = gm.pm.current_player.stats.all_stats[MANA_REGEN].current;
+        let azrak_hp_max =
+            gm.pm.get_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].max;
+        let mana_max = gm.pm.current_player.stats[StatKind::Mana].max;
+        let old_mana = gm.pm.current_player.stats[StatKind::Mana].current;
+        let mana_regen = gm.pm.current_player.stats[StatKind::ManaRegen].current;
         // Heal formula adds launcher magical power: full_amount = (buffer.value + pow_current)
         let elara_mag_pow = gm.pm.current_player.stats.get_power_stat(true);
         let heal_amount = (130 + elara_mag_pow) as u64;
 
         gm.launch_attack(Some("Lumiere curative"));
 
-        let new_azrak_hp = gm
-            .pm
-            .get_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
-        let new_mana = gm
-            .pm
-            .get_active_hero_character(elara_id)
-            .unwrap()
-            .stats
-            .all_stats[MANA]
-            .current;
+        let new_azrak_hp =
+            gm.pm.get_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].current;
+        let new_mana =
+            gm.pm.get_active_hero_character(elara_id).unwrap().stats[StatKind::Mana].current;
 
         // Heal = 130 + Elara's magical power (heal formula: buffer.value + pow_current), capped at max
         let expected_hp = std::cmp::min(10 + heal_amount, azrak_hp_max);
@@ -4663,53 +6208,28 @@ mod tests {
         gm.pm.current_player.level = 100;
 
         // Drain all heroes to 10 HP (zone heal must restore all to max)
-        gm.pm.current_player.stats.all_stats[HP].current = 10;
+        gm.pm.current_player.stats[StatKind::Hp].current = 10;
         for h in gm.pm.active_heroes.iter_mut() {
-            h.stats.all_stats[HP].current = 10;
+            h.stats[StatKind::Hp].current = 10;
         }
 
-        let elara_hp_max = gm.pm.current_player.stats.all_stats[HP].max;
-        let azrak_hp_max = gm
-            .pm
-            .get_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .max;
-        let thalia_hp_max = gm
-            .pm
-            .get_active_hero_character(thalia_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .max;
-        let mana_max = gm.pm.current_player.stats.all_stats[MANA].max;
-        let old_mana = gm.pm.current_player.stats.all_stats[MANA].current;
+        let elara_hp_max = gm.pm.current_player.stats[StatKind::Hp].max;
+        let azrak_hp_max =
+            gm.pm.get_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].max;
+        let thalia_hp_max =
+            gm.pm.get_active_hero_character(thalia_id).unwrap().stats[StatKind::Hp].max;
+        let mana_max = gm.pm.current_player.stats[StatKind::Mana].max;
+        let old_mana = gm.pm.current_player.stats[StatKind::Mana].current;
 
         gm.launch_attack(Some("Non sans raison"));
 
         // All heroes healed to max HP (100% of HP max heal > remaining 90% deficit)
-        let new_elara_hp = gm
-            .pm
-            .get_active_hero_character(elara_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
-        let new_azrak_hp = gm
-            .pm
-            .get_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
-        let new_thalia_hp = gm
-            .pm
-            .get_active_hero_character(thalia_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
+        let new_elara_hp =
+            gm.pm.get_active_hero_character(elara_id).unwrap().stats[StatKind::Hp].current;
+        let new_azrak_hp =
+            gm.pm.get_active_hero_character(azrak_id).unwrap().stats[StatKind::Hp].current;
+        let new_thalia_hp =
+            gm.pm.get_active_hero_character(thalia_id).unwrap().stats[StatKind::Hp].current;
         assert_eq!(elara_hp_max, new_elara_hp, "Elara must be healed to max HP");
         assert_eq!(azrak_hp_max, new_azrak_hp, "Azrak must be healed to max HP");
         assert_eq!(
@@ -4730,12 +6250,7 @@ mod tests {
         // Mana cost: 24% of max mana
         assert_eq!(
             old_mana - 24 * mana_max / 100,
-            gm.pm
-                .get_active_hero_character(elara_id)
-                .unwrap()
-                .stats
-                .all_stats[MANA]
-                .current,
+            gm.pm.get_active_hero_character(elara_id).unwrap().stats[StatKind::Mana].current,
             "Non sans raison costs 24% of max mana"
         );
     }
@@ -4755,21 +6270,21 @@ mod tests {
         let thrain = gm.pm.get_active_hero_character(thrain_id).unwrap().clone();
         gm.pm.current_player = thrain;
         gm.pm.current_player.level = 100;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
 
         // Set berserk to 60; the attack costs 20% of max berserk per apply and fires as many
         // times as possible (RepeatAsManyAsPossible) until rage is exhausted.
         // actual_cost = raw_cost * berserk_max / 100; nb_applies = floor(60 / actual_cost).
-        gm.pm.current_player.stats.all_stats[BERSERK].current = 60;
-        let berserk_max = gm.pm.current_player.stats.all_stats[BERSERK].max;
+        gm.pm.current_player.stats[StatKind::Berserk].current = 60;
+        let berserk_max = gm.pm.current_player.stats[StatKind::Berserk].max;
         let berseck_cost = 20u64;
         let actual_cost = (berseck_cost * berserk_max / 100).max(1);
         let nb_applies = (60u64 / actual_cost).max(1);
 
         // Target: first boss, zero dodge and DamageRxPercent for clean formula
         let boss_id = gm.pm.active_bosses[0].id_name.clone();
-        let old_boss_hp = gm.pm.active_bosses[0].stats.all_stats[HP].current;
-        gm.pm.active_bosses[0].stats.all_stats[DODGE].current = 0;
+        let old_boss_hp = gm.pm.active_bosses[0].stats[StatKind::Hp].current;
+        gm.pm.active_bosses[0].stats[StatKind::Dodge].current = 0;
         gm.pm.active_bosses[0]
             .character_rounds_info
             .is_current_target = true;
@@ -4784,13 +6299,8 @@ mod tests {
 
         // RepeatAsManyAsPossible bypasses armor; each apply deals the raw 50 damage.
         let expected_dmg = nb_applies * 50;
-        let new_boss_hp = gm
-            .pm
-            .get_active_boss_character(&boss_id)
-            .unwrap()
-            .stats
-            .all_stats[HP]
-            .current;
+        let new_boss_hp =
+            gm.pm.get_active_boss_character(&boss_id).unwrap().stats[StatKind::Hp].current;
         assert_eq!(
             old_boss_hp - expected_dmg,
             new_boss_hp,
@@ -4798,13 +6308,8 @@ mod tests {
         );
 
         // Every apply drains rage: total cost = nb_applies × actual_cost.
-        let new_berserk = gm
-            .pm
-            .get_active_hero_character(thrain_id)
-            .unwrap()
-            .stats
-            .all_stats[BERSERK]
-            .current;
+        let new_berserk =
+            gm.pm.get_active_hero_character(thrain_id).unwrap().stats[StatKind::Berserk].current;
         let expected_berserk = 60u64.saturating_sub(nb_applies * actual_cost);
         assert_eq!(
             expected_berserk, new_berserk,
@@ -4828,9 +6333,9 @@ mod tests {
         gm.pm.current_player = thrain;
         gm.pm.current_player.level = 100;
 
-        let old_berserk = gm.pm.current_player.stats.all_stats[BERSERK].current;
-        let old_crit_max = gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].max;
-        let old_aggro = gm.pm.current_player.stats.all_stats[AGGRO].current;
+        let old_berserk = gm.pm.current_player.stats[StatKind::Berserk].current;
+        let old_crit_max = gm.pm.current_player.stats[StatKind::CriticalStrike].max;
+        let old_aggro = gm.pm.current_player.stats[StatKind::Aggro].current;
 
         // Init aggro tx_rx slot so process_aggro can update the stat
         gm.pm
@@ -4844,21 +6349,21 @@ mod tests {
         // +12 Berserk (free attack, no cost)
         assert_eq!(
             old_berserk + 12,
-            thrain_after.stats.all_stats[BERSERK].current,
+            thrain_after.stats[StatKind::Berserk].current,
             "Thraïn berserk must increase by 12 (no cost)"
         );
 
         // +10 Aggro on self
         assert_eq!(
             old_aggro + 10,
-            thrain_after.stats.all_stats[AGGRO].current,
+            thrain_after.stats[StatKind::Aggro].current,
             "Thraïn aggro must increase by 10"
         );
 
         // +40 max Critical strike for 3 turns
         assert_eq!(
             old_crit_max + 40,
-            thrain_after.stats.all_stats[CRITICAL_STRIKE].max,
+            thrain_after.stats[StatKind::CriticalStrike].max,
             "Thraïn critical strike max must increase by 40"
         );
 
@@ -4893,23 +6398,23 @@ mod tests {
         let thrain = gm.pm.get_active_hero_character(thrain_id).unwrap().clone();
         gm.pm.current_player = thrain;
         gm.pm.current_player.level = 100;
-        gm.pm.current_player.stats.all_stats[CRITICAL_STRIKE].current = 0;
+        gm.pm.current_player.stats[StatKind::CriticalStrike].current = 0;
 
         let thrain_phy_pow = gm.pm.current_player.stats.get_power_stat(false);
-        let old_berserk = gm.pm.current_player.stats.all_stats[BERSERK].current;
-        let berserk_max = gm.pm.current_player.stats.all_stats[BERSERK].max;
+        let old_berserk = gm.pm.current_player.stats[StatKind::Berserk].current;
+        let berserk_max = gm.pm.current_player.stats[StatKind::Berserk].max;
         let berseck_cost = 15u64;
         let cost_deducted = berseck_cost * berserk_max / 100;
 
         let old_berserk_rate_pct =
-            gm.pm.current_player.stats.all_stats[BERSECK_RATE].buf_effect_percent;
+            gm.pm.current_player.stats[StatKind::BerserkRate].buf_effect_percent;
 
         // Zero out dodge and DamageRxPercent on all 3 bosses for clean damage formula
         let old_boss_hps: Vec<u64> = gm
             .pm
             .active_bosses
             .iter()
-            .map(|b| b.stats.all_stats[HP].current)
+            .map(|b| b.stats[StatKind::Hp].current)
             .collect();
         let boss_phy_armors: Vec<i64> = gm
             .pm
@@ -4924,7 +6429,7 @@ mod tests {
             .map(|b| b.stats.get_power_stat(false))
             .collect();
         for boss in gm.pm.active_bosses.iter_mut() {
-            boss.stats.all_stats[DODGE].current = 0;
+            boss.stats[StatKind::Dodge].current = 0;
             if let Some(buf) = boss
                 .character_rounds_info
                 .get_mut_buffer_by_type(&BufKinds::DamageRxPercent)
@@ -4937,7 +6442,7 @@ mod tests {
         gm.pm
             .current_player
             .init_aggro_on_turn(gm.game_state.current_turn_nb);
-        let old_aggro = gm.pm.current_player.stats.all_stats[AGGRO].current;
+        let old_aggro = gm.pm.current_player.stats[StatKind::Aggro].current;
 
         gm.launch_attack(Some("Tourbillon Destructeur "));
 
@@ -4949,13 +6454,8 @@ mod tests {
             .enumerate()
         {
             let boss_id = gm.pm.active_bosses[i].id_name.clone();
-            let new_hp = gm
-                .pm
-                .get_active_boss_character(&boss_id)
-                .unwrap()
-                .stats
-                .all_stats[HP]
-                .current;
+            let new_hp =
+                gm.pm.get_active_boss_character(&boss_id).unwrap().stats[StatKind::Hp].current;
             let power_factor = 1.0 + thrain_phy_pow as f64 / AttackType::POWER_SCALE;
             let raw_dmg = (67_f64 * power_factor).round() as i64;
             let defense = *phy_armor as f64 + *boss_phy_pow as f64 / AttackType::DEFENSE_DIVISOR;
@@ -4974,20 +6474,20 @@ mod tests {
         // Berserk cost: 15% of max
         assert_eq!(
             old_berserk - cost_deducted,
-            thrain_after.stats.all_stats[BERSERK].current,
+            thrain_after.stats[StatKind::Berserk].current,
             "Thraïn berserk: 15% of max deducted"
         );
 
         // +5 explicit Aggro on self; zone damage also generates implicit aggro
         assert!(
-            thrain_after.stats.all_stats[AGGRO].current >= old_aggro + 5,
+            thrain_after.stats[StatKind::Aggro].current >= old_aggro + 5,
             "Thraïn aggro must increase by at least 5 (explicit self effect)"
         );
 
         // +100% max Berserk rate for 4 turns: buf_effect_percent increased by 100
         assert_eq!(
             old_berserk_rate_pct + 100,
-            thrain_after.stats.all_stats[BERSECK_RATE].buf_effect_percent,
+            thrain_after.stats[StatKind::BerserkRate].buf_effect_percent,
             "Berserk rate buf_effect_percent must increase by 100"
         );
     }
@@ -3,7 +3,9 @@ use std::collections::HashMap;
 
 use crate::{
     character_mod::stats_in_game::StatsInGame,
-    server::{game_manager::ResultLaunchAttack, players_manager::GameAtkEffect},
+    server::{
+        game_manager::ResultLaunchAttack, objective::ObjectiveState, players_manager::GameAtkEffect,
+    },
 };
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -45,6 +47,12 @@ pub struct GameState {
     /// Accumulated boss kills across all completed scenarios (never reset between scenarios)
     #[serde(default)]
     pub accumulated_kills: usize,
+    /// Key: index of a reinforcement wave of the scenario, value: number of times it was summoned
+    #[serde(default)]
+    pub summoned_waves: HashMap<usize, usize>,
+    /// States of the objectives of the scenario, in the order of `Scenario::all_objectives`
+    #[serde(default)]
+    pub objective_states: Vec<ObjectiveState>,
 }
 
 impl GameState {
@@ -64,6 +72,8 @@ impl GameState {
         self.current_turn_nb = 0;
         self.current_round = 0;
         self.died_ennemies.clear();
+        self.summoned_waves.clear();
+        self.objective_states.clear();
         self.order_to_play.clear();
         self.status = GameStatus::StartGame;
         self.last_result_atk = ResultLaunchAttack::default();
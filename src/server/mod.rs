@@ -1,10 +1,21 @@
+pub mod boss_ai;
+pub mod campaign;
+pub mod command_log;
 pub mod core_game_data;
 pub mod data_manager;
+pub mod difficulty;
 pub mod end_of_scenario;
 pub mod game_manager;
 pub mod game_paths;
 pub mod game_state;
+pub mod objective;
 pub mod overworld_manager;
 pub mod players_manager;
+pub mod procgen;
+pub mod run;
+pub mod save_game;
 pub mod scenario;
 pub mod server_manager;
+pub mod simulator;
+pub mod threat;
+pub mod validator;
@@ -0,0 +1,196 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{character_mod::loot::Loot, server::players_manager::PlayerManager};
+
+/// Objective or failure condition of a scenario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectiveKind {
+    /// All the bosses are dead
+    KillAllBosses,
+    /// All the bosses with that name are dead
+    KillBoss(String),
+    /// At least one hero is alive at the start of the turn following the value, or all the bosses are dead
+    SurviveTurns(usize),
+    /// The hero with that id_name or name stays alive
+    ProtectAlly(String),
+    /// The scenario is won before the end of the turn of the value
+    WinWithinTurns(usize),
+    /// No hero dies during the fight
+    NoHeroDies,
+}
+
+impl fmt::Display for ObjectiveKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectiveKind::KillAllBosses => write!(f, "Kill all the bosses"),
+            ObjectiveKind::KillBoss(name) => write!(f, "Kill {}", name),
+            ObjectiveKind::SurviveTurns(n) => write!(f, "Survive {} turns", n),
+            ObjectiveKind::ProtectAlly(name) => write!(f, "Protect {}", name),
+            ObjectiveKind::WinWithinTurns(n) => write!(f, "Win within {} turns", n),
+            ObjectiveKind::NoHeroDies => write!(f, "No hero dies"),
+        }
+    }
+}
+
+impl ObjectiveKind {
+    /// A goal must be completed to win, the other objectives are conditions
+    /// which are completed at the victory if they did not fail before.
+    pub fn is_goal(&self) -> bool {
+        matches!(
+            self,
+            ObjectiveKind::KillAllBosses
+                | ObjectiveKind::KillBoss(_)
+                | ObjectiveKind::SurviveTurns(_)
+        )
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ObjectiveState {
+    #[default]
+    InProgress,
+    Completed,
+    Failed,
+}
+
+/// Objective declared by a scenario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Objective {
+    pub kind: ObjectiveKind,
+    /// A secondary objective does not decide the end of the scenario,
+    /// its bonus loots are given if it is completed at the victory
+    #[serde(default)]
+    pub is_secondary: bool,
+    #[serde(default)]
+    pub bonus_loots: Vec<Loot>,
+}
+
+impl Objective {
+    pub fn new(kind: ObjectiveKind) -> Objective {
+        Objective {
+            kind,
+            is_secondary: false,
+            bonus_loots: Vec::new(),
+        }
+    }
+
+    /// State of the objective at the current turn of the fight
+    pub fn evaluate(&self, pm: &PlayerManager, turn_nb: usize) -> ObjectiveState {
+        let all_bosses_dead = |name: Option<&str>| {
+            let mut bosses = pm
+                .active_bosses
+                .iter()
+                .filter(|b| name.is_none_or(|n| b.db_full_name == n))
+                .peekable();
+            bosses.peek().is_some() && bosses.all(|b| b.stats.is_dead())
+        };
+        match &self.kind {
+            ObjectiveKind::KillAllBosses if all_bosses_dead(None) => ObjectiveState::Completed,
+            ObjectiveKind::KillBoss(name) if all_bosses_dead(Some(name)) => {
+                ObjectiveState::Completed
+            }
+            ObjectiveKind::SurviveTurns(n) if turn_nb > *n || all_bosses_dead(None) => {
+                ObjectiveState::Completed
+            }
+            ObjectiveKind::ProtectAlly(name)
+                if pm.active_heroes.iter().any(|h| {
+                    (h.id_name == *name || h.db_full_name == *name) && h.stats.is_dead()
+                }) =>
+            {
+                ObjectiveState::Failed
+            }
+            ObjectiveKind::WinWithinTurns(n) if turn_nb > *n => ObjectiveState::Failed,
+            ObjectiveKind::NoHeroDies if pm.active_heroes.iter().any(|h| h.stats.is_dead()) => {
+                ObjectiveState::Failed
+            }
+            _ => ObjectiveState::InProgress,
+        }
+    }
+}
+
+/// Objective of the scenario and its state, reported at the end of the scenario
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectiveResult {
+    pub description: String,
+    pub is_secondary: bool,
+    pub state: ObjectiveState,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{character_mod::stats::StatKind, testing::testing_all_characters::testing_pm};
+
+    #[test]
+    fn unit_objective_evaluate() {
+        let mut pm = testing_pm();
+        let boss_name = pm.active_bosses[0].db_full_name.clone();
+        let hero_id_name = pm.active_heroes[0].id_name.clone();
+        let evaluate = |kind: ObjectiveKind, pm: &PlayerManager, turn_nb: usize| {
+            Objective::new(kind).evaluate(pm, turn_nb)
+        };
+
+        assert_eq!(
+            evaluate(ObjectiveKind::KillBoss(boss_name.clone()), &pm, 1),
+            ObjectiveState::InProgress
+        );
+        assert_eq!(
+            evaluate(ObjectiveKind::SurviveTurns(3), &pm, 3),
+            ObjectiveState::InProgress
+        );
+        assert_eq!(
+            evaluate(ObjectiveKind::SurviveTurns(3), &pm, 4),
+            ObjectiveState::Completed
+        );
+        assert_eq!(
+            evaluate(ObjectiveKind::WinWithinTurns(3), &pm, 3),
+            ObjectiveState::InProgress
+        );
+        assert_eq!(
+            evaluate(ObjectiveKind::WinWithinTurns(3), &pm, 4),
+            ObjectiveState::Failed
+        );
+        assert_eq!(
+            evaluate(ObjectiveKind::NoHeroDies, &pm, 1),
+            ObjectiveState::InProgress
+        );
+        // an unknown boss is never killed
+        assert_eq!(
+            evaluate(ObjectiveKind::KillBoss("unknown".to_owned()), &pm, 1),
+            ObjectiveState::InProgress
+        );
+
+        pm.active_bosses
+            .iter_mut()
+            .filter(|b| b.db_full_name == boss_name)
+            .for_each(|b| b.stats[StatKind::Hp].current = 0);
+        assert_eq!(
+            evaluate(ObjectiveKind::KillBoss(boss_name), &pm, 1),
+            ObjectiveState::Completed
+        );
+        assert_eq!(
+            evaluate(ObjectiveKind::KillAllBosses, &pm, 1),
+            ObjectiveState::InProgress
+        );
+
+        pm.active_heroes[0].stats[StatKind::Hp].current = 0;
+        assert_eq!(
+            evaluate(ObjectiveKind::ProtectAlly(hero_id_name), &pm, 1),
+            ObjectiveState::Failed
+        );
+        assert_eq!(
+            evaluate(ObjectiveKind::NoHeroDies, &pm, 1),
+            ObjectiveState::Failed
+        );
+
+        assert!(ObjectiveKind::SurviveTurns(3).is_goal());
+        assert!(!ObjectiveKind::NoHeroDies.is_goal());
+        assert_eq!(
+            ObjectiveKind::WinWithinTurns(5).to_string(),
+            "Win within 5 turns"
+        );
+    }
+}
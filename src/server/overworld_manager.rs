@@ -7,7 +7,10 @@ use anyhow::Result;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-use crate::common::overworld::{Direction, Position, TileKind};
+use crate::common::{
+    game_rng::GameRng,
+    overworld::{Direction, Position, TileKind},
+};
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NpcState {
@@ -184,7 +187,9 @@ impl OverworldManager {
     /// - `Moved` — free tile or grass with no encounter roll
     /// - `Encounter(scenario_id)` — grass tile triggered a fight (50 % chance)
     /// - `MapTransition(map_id, spawn)` — hero stepped on a door
-    pub fn move_player(&mut self, hero_id: &str, dir: Direction) -> MoveResult {
+    ///
+    /// The encounter roll draws from the game `rng`.
+    pub fn move_player(&mut self, hero_id: &str, dir: Direction, rng: &mut GameRng) -> MoveResult {
         let Some(current_pos) = self.state.player_positions.get(hero_id).cloned() else {
             return MoveResult::Blocked;
         };
@@ -231,14 +236,11 @@ impl OverworldManager {
                 self.state
                     .player_positions
                     .insert(hero_id.to_string(), new_pos);
-                if !self.state.encounters.is_empty() {
-                    let mut rng = rand::rng();
-                    if rng.random_bool(0.5) {
-                        let idx = rng.random_range(0..self.state.encounters.len());
-                        let scenario_id = self.state.encounters[idx].clone();
-                        self.state.pending_encounter = Some(scenario_id.clone());
-                        return MoveResult::Encounter(scenario_id);
-                    }
+                if !self.state.encounters.is_empty() && rng.random_bool(0.5) {
+                    let idx = rng.random_range(0..self.state.encounters.len());
+                    let scenario_id = self.state.encounters[idx].clone();
+                    self.state.pending_encounter = Some(scenario_id.clone());
+                    return MoveResult::Encounter(scenario_id);
                 }
                 MoveResult::Moved
             }
@@ -388,7 +390,7 @@ mod tests {
         mgr.place_hero_at_spawn("hero_1");
 
         // spawn is (2,1) — move right to (3,1) which is floor
-        let result = mgr.move_player("hero_1", Direction::Right);
+        let result = mgr.move_player("hero_1", Direction::Right, &mut GameRng::default());
         assert_eq!(result, MoveResult::Moved);
         assert_eq!(
             *mgr.state.player_positions.get("hero_1").unwrap(),
@@ -403,7 +405,7 @@ mod tests {
         mgr.place_hero_at_spawn("hero_1");
 
         // spawn (2,1) — move up to (2,0) which is wall
-        let result = mgr.move_player("hero_1", Direction::Up);
+        let result = mgr.move_player("hero_1", Direction::Up, &mut GameRng::default());
         assert_eq!(result, MoveResult::Blocked);
         assert_eq!(
             *mgr.state.player_positions.get("hero_1").unwrap(),
@@ -419,7 +421,7 @@ mod tests {
         mgr.state
             .player_positions
             .insert("hero_1".to_string(), Position::new(2, 3));
-        let result = mgr.move_player("hero_1", Direction::Left);
+        let result = mgr.move_player("hero_1", Direction::Left, &mut GameRng::default());
         assert_eq!(result, MoveResult::Blocked);
     }
 
@@ -431,7 +433,7 @@ mod tests {
         mgr.state
             .player_positions
             .insert("hero_1".to_string(), Position::new(0, 2));
-        let result = mgr.move_player("hero_1", Direction::Left);
+        let result = mgr.move_player("hero_1", Direction::Left, &mut GameRng::default());
         assert_eq!(result, MoveResult::Blocked);
     }
 
@@ -441,7 +443,7 @@ mod tests {
         let mut mgr = OverworldManager::load_map("test_map_grass", &root).unwrap();
         // place hero at (2,1); move down to (2,2) = grass
         mgr.place_hero_at_spawn("hero_1");
-        let result = mgr.move_player("hero_1", Direction::Down);
+        let result = mgr.move_player("hero_1", Direction::Down, &mut GameRng::default());
         assert!(
             matches!(result, MoveResult::Moved | MoveResult::Encounter(_)),
             "grass must give Moved or Encounter, got {result:?}"
@@ -466,7 +468,7 @@ mod tests {
         let mut mgr = OverworldManager::load_map("t", &root).unwrap();
         mgr.place_hero_at_spawn("h");
         // With empty encounters list, grass always returns Moved
-        let result = mgr.move_player("h", Direction::Down);
+        let result = mgr.move_player("h", Direction::Down, &mut GameRng::default());
         assert_eq!(result, MoveResult::Moved);
     }
 
@@ -478,7 +480,7 @@ mod tests {
         mgr.state
             .player_positions
             .insert("hero_1".to_string(), Position::new(2, 3));
-        let result = mgr.move_player("hero_1", Direction::Right);
+        let result = mgr.move_player("hero_1", Direction::Right, &mut GameRng::default());
         assert!(
             matches!(result, MoveResult::MapTransition(ref map, _) if map == "route_1"),
             "expected MapTransition to route_1, got {result:?}"
@@ -489,7 +491,7 @@ mod tests {
     fn unit_move_player_unknown_hero() {
         let root = write_temp_map(small_map_json(), "test_map_unk");
         let mut mgr = OverworldManager::load_map("test_map_unk", &root).unwrap();
-        let result = mgr.move_player("ghost", Direction::Up);
+        let result = mgr.move_player("ghost", Direction::Up, &mut GameRng::default());
         assert_eq!(result, MoveResult::Blocked);
     }
 
@@ -608,7 +610,7 @@ mod tests {
             .insert("hero_2".to_string(), Position::new(3, 1));
         // hero_1 tries to move right onto hero_2 — must be blocked.
         assert_eq!(
-            mgr.move_player("hero_1", Direction::Right),
+            mgr.move_player("hero_1", Direction::Right, &mut GameRng::default()),
             MoveResult::Blocked
         );
         // Position must be unchanged.
@@ -625,7 +627,7 @@ mod tests {
         let mut mgr = OverworldManager::load_map("test_map_occ_npc_block", &root).unwrap();
         mgr.place_hero_at_spawn("hero_1"); // spawn = (2,1)
         assert_eq!(
-            mgr.move_player("hero_1", Direction::Left),
+            mgr.move_player("hero_1", Direction::Left, &mut GameRng::default()),
             MoveResult::Blocked
         );
         assert_eq!(
@@ -755,14 +757,14 @@ mod tests {
 
         // Initially blocked by living NPC at (1,1).
         assert_eq!(
-            mgr.move_player("hero_1", Direction::Left),
+            mgr.move_player("hero_1", Direction::Left, &mut GameRng::default()),
             MoveResult::Blocked
         );
 
         // Mark NPC defeated — hero should now be able to step onto (1,1).
         mgr.state.npcs.iter_mut().for_each(|n| n.defeated = true);
         assert_eq!(
-            mgr.move_player("hero_1", Direction::Left),
+            mgr.move_player("hero_1", Direction::Left, &mut GameRng::default()),
             MoveResult::Moved
         );
         assert_eq!(
@@ -816,7 +818,7 @@ mod tests {
             .player_positions
             .insert("hero".to_string(), Position::new(2, 1));
         assert_eq!(
-            mgr.move_player("hero", Direction::Left),
+            mgr.move_player("hero", Direction::Left, &mut GameRng::default()),
             MoveResult::Blocked,
             "(1,1) should be a wall – movement must be blocked"
         );
@@ -837,7 +839,7 @@ mod tests {
             .player_positions
             .insert("hero".to_string(), Position::new(3, 2));
         assert_eq!(
-            mgr.move_player("hero", Direction::Right),
+            mgr.move_player("hero", Direction::Right, &mut GameRng::default()),
             MoveResult::Moved,
             "(4,2) should be floor – movement must succeed"
         );
@@ -895,7 +897,7 @@ mod tests {
                 .player_positions
                 .insert("hero".to_string(), Position::new(sx, sy));
             assert_eq!(
-                mgr.move_player("hero", dir.clone()),
+                mgr.move_player("hero", dir.clone(), &mut GameRng::default()),
                 MoveResult::Blocked,
                 "Diagonal wall {} should block movement",
                 label
@@ -2,32 +2,39 @@ use std::collections::HashMap;
 
 use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
 
 use crate::{
     character_mod::{
+        ailment::AilmentKind,
         attack_type::AttackType,
-        buffers::BufKinds,
+        buffers::{BufKinds, Buffer},
         character::{Character, CharacterKind},
-        effect::{EffectOutcome, ProcessedEffectParam},
+        effect::{EffectOutcome, EffectParam, ProcessedEffectParam},
         equipment::{Equipment, EquipmentJsonKey},
         inventory::Consumable,
+        stats::StatKind,
+        target::{Reach, Target, TargetingStrategy},
     },
     common::{
-        constants::{
-            all_target_const::{TARGET_ALL_ALLIES, TARGET_ALLY, TARGET_ENNEMY, TARGET_HIMSELF},
-            character_const::*,
-            reach_const::{INDIVIDUAL, ZONE},
-            stats_const::*,
-        },
+        constants::character_const::*,
+        game_rng::GameRng,
         log_data::{LogData, const_colors::LIGHT_GREEN},
     },
-    server::game_state::GameState,
+    server::{
+        difficulty::Difficulty,
+        game_state::GameState,
+        threat::{ThreatEntry, ThreatTable},
+    },
 };
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameAtkEffect {
     pub processed_effect_param: ProcessedEffectParam,
     pub atk_type: AttackType,
+    /// id_name of the character who launched the atk
+    #[serde(default)]
+    pub launcher_id_name: String,
     pub launching_turn: usize,
     pub launching_round: usize,
     pub effect_outcome: EffectOutcome,
@@ -44,11 +51,11 @@ impl GameAtkEffect {
         let real = self.effect_outcome.real_amount_tx;
         let full = self.effect_outcome.full_amount_tx;
         let pre = self.effect_outcome.pre_armor_amount_tx;
-        let stat = &self
+        let stat = self
             .processed_effect_param
             .input_effect_param
             .buffer
-            .stats_name;
+            .stats_label();
         let number_of_applies = self.processed_effect_param.number_of_applies;
         let buf_value = self.processed_effect_param.input_effect_param.buffer.value;
 
@@ -66,6 +73,19 @@ impl GameAtkEffect {
                 }
             }
             BufKinds::MultiValue => Some(format!("{target} ← Heal ×{buf_value}")),
+            BufKinds::AbsorbShield => Some(format!("{target} ← Shield absorbing {full} HP")),
+            BufKinds::InflictAilment => {
+                let ep = &self.processed_effect_param.input_effect_param;
+                let ailment = ep.ailment?;
+                if full < 0 {
+                    Some(format!(
+                        "{target} ← {ailment} {full} HP/turn ({} turns)",
+                        ep.nb_turns
+                    ))
+                } else {
+                    Some(format!("{target} ← {ailment} ({} turns)", ep.nb_turns))
+                }
+            }
             BufKinds::RemoveOneDebuf => {
                 if self.effect_outcome.debuff_removed {
                     Some(format!("{target} ← debuff removed"))
@@ -91,12 +111,22 @@ impl GameAtkEffect {
                 "{target} ← +{buf_value}% heal boost per active HOT"
             )),
             _ => {
-                let is_hp = stat == HP
+                let is_hp = self
+                    .processed_effect_param
+                    .input_effect_param
+                    .buffer
+                    .stats_name
+                    == Some(StatKind::Hp)
                     && *kind != BufKinds::ChangeMaxStatByPercentage
                     && *kind != BufKinds::ChangeMaxStatByValue;
                 let is_damage = is_hp && (real < 0 || full < 0);
+                let absorbed = self.effect_outcome.absorbed_amount;
                 if is_hp {
-                    if is_damage {
+                    if is_damage && absorbed > 0 {
+                        Some(format!(
+                            "{target} ← {real} HP (full: {pre}, real: {real}, absorbed: {absorbed})"
+                        ))
+                    } else if is_damage {
                         if pre == real {
                             Some(format!("{target} ← {real} HP"))
                         } else {
@@ -126,6 +156,16 @@ pub struct DodgeInfo {
     pub is_blocking: bool,
 }
 
+/// Outcome of `PlayerManager::whatif_set_targeted_characters`
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WhatIfTargets {
+    /// Number of potential targets
+    pub nb: u64,
+    /// Boss atk on heroes: hero the boss would choose with its targeting strategy.
+    /// None for a random strategy.
+    pub boss_target: Option<String>,
+}
+
 /// Define all the parameters of a playerManager
 /// Should store all the relative data to all the playABLE characters
 /// /// Should store all the relative data to all the playING characters
@@ -145,6 +185,12 @@ pub struct PlayerManager {
     /// Shared party consumables pool — available to any hero, consumed when used
     #[serde(default)]
     pub party_consumables: Vec<Consumable>,
+    /// Threat generated by the heroes against each boss, used by the bosses to choose their targets
+    #[serde(default)]
+    pub threat_table: ThreatTable,
+    /// Dead heroes are not revived at the end of a scenario, e.g. during a run
+    #[serde(default)]
+    pub is_permadeath: bool,
 }
 
 impl PlayerManager {
@@ -161,32 +207,40 @@ impl PlayerManager {
             current_player: Character::default(),
             equipment_table,
             party_consumables: Vec::new(),
+            threat_table: ThreatTable::default(),
+            is_permadeath: false,
         }
     }
 
     pub fn clear_scenario(&mut self) {
         self.active_bosses.clear();
         self.current_player = Character::default();
+        self.threat_table.clear();
+        let is_permadeath = self.is_permadeath;
         self.active_heroes.iter_mut().for_each(|c| {
+            let is_dead = c.stats.is_dead();
             // Reverse active ChangeMaxStat* effects before clearing so buf_effect_*
             // fields are reset to zero and the next scenario starts from the correct base.
             c.reset_all_effects_on_player()
                 .expect("failed to reset all effects");
             // Clamp any stat inflated above its max by uncapped passive boosts
             // (e.g. OverHealBoostStat writes directly to stat.current without a cap).
-            for stat in c.stats.all_stats.values_mut() {
+            for kind in StatKind::iter() {
+                let stat = &mut c.stats[kind];
                 stat.current = stat.current.min(stat.max);
             }
             c.character_rounds_info.clear();
-            c.stats.get_mut_value(HP).current = c.stats.all_stats[HP].max;
-            c.stats.get_mut_value(MANA).current = c.stats.all_stats[MANA].max;
-            c.stats.get_mut_value(VIGOR).current = c.stats.all_stats[VIGOR].max;
-            c.stats.get_mut_value(BERSERK).current = 0;
-            c.stats.get_mut_value(SPEED).current = 0;
+            c.stats[StatKind::Hp].current = if is_permadeath && is_dead {
+                0
+            } else {
+                c.stats[StatKind::Hp].max
+            };
+            c.stats[StatKind::Mana].current = c.stats[StatKind::Mana].max;
+            c.stats[StatKind::Vigor].current = c.stats[StatKind::Vigor].max;
+            c.stats[StatKind::Berserk].current = 0;
+            c.stats[StatKind::Speed].current = 0;
             // Reset displayed aggro so the new scenario starts from 0.
-            if let Some(aggro) = c.stats.all_stats.get_mut(AGGRO) {
-                aggro.current = 0;
-            }
+            c.stats[StatKind::Aggro].current = 0;
         });
     }
 
@@ -206,6 +260,24 @@ impl PlayerManager {
             .count()
     }
 
+    /// Add a boss of `all_bosses` to the active bosses during a fight,
+    /// with a unique id_name (`"<name>_#<n>"`). Return its id_name.
+    pub fn summon_boss(&mut self, db_full_name: &str) -> Option<String> {
+        let mut boss = self
+            .all_bosses
+            .iter()
+            .find(|b| b.db_full_name == db_full_name)?
+            .clone();
+        boss.id_name = format!(
+            "{}_#{}",
+            boss.db_full_name,
+            1 + self.get_nb_of_active_bosses_by_name(&boss.db_full_name)
+        );
+        let id_name = boss.id_name.clone();
+        self.active_bosses.push(boss);
+        Some(id_name)
+    }
+
     pub fn increment_counter_effect(&mut self) {
         for c in self.active_heroes.iter_mut() {
             c.character_rounds_info.increment_counter_effect();
@@ -232,7 +304,7 @@ impl PlayerManager {
             &mut self.active_bosses
         };
         for pl in player_list {
-            if pl.stats.is_dead().unwrap_or(false) {
+            if pl.stats.is_dead() {
                 continue;
             }
 
@@ -292,6 +364,7 @@ impl PlayerManager {
         hero_id_name: &str,
         potion_name: &str,
         game_state: &GameState,
+        rng: &mut GameRng,
     ) -> Result<()> {
         let idx = self
             .party_consumables
@@ -303,7 +376,7 @@ impl PlayerManager {
             .get_mut_active_hero_character(hero_id_name)
             .ok_or_else(|| anyhow::anyhow!("Hero '{}' not found", hero_id_name))?;
         let launcher_stats = hero.stats.clone();
-        hero.apply_consumable_effects(&consumable, game_state, &launcher_stats)
+        hero.apply_consumable_effects(&consumable, game_state, &launcher_stats, rng)
             .map_err(|e| anyhow::anyhow!("{}", e))?;
         Ok(())
     }
@@ -324,6 +397,7 @@ impl PlayerManager {
         &mut self,
         game_state: &GameState,
         id_name: &str,
+        rng: &mut GameRng,
     ) -> Result<Vec<LogData>> {
         let logs;
         match self.get_mut_active_character(id_name) {
@@ -334,8 +408,12 @@ impl PlayerManager {
                 logs = self.current_player.new_round(
                     game_state.current_turn_nb,
                     self.process_launchable_atks(game_state.current_turn_nb),
+                    rng,
                 );
 
+                // the launchers of the hots and dots get the threat of their ticks
+                self.process_tick_threat();
+
                 // update the active character
                 self.modify_active_character(id_name);
 
@@ -386,14 +464,10 @@ impl PlayerManager {
         let target_id = self
             .active_heroes
             .iter()
-            .filter(|c| c.stats.is_dead() == Some(false))
+            .filter(|c| !c.stats.is_dead())
             .min_by_key(|c| {
-                c.stats
-                    .all_stats
-                    .get(HP)
-                    .filter(|s| s.max > 0)
-                    .map(|s| s.current * 10000 / s.max)
-                    .unwrap_or(u64::MAX)
+                let hp = &c.stats[StatKind::Hp];
+                (hp.current * 10000).checked_div(hp.max).unwrap_or(u64::MAX)
             })
             .map(|c| c.id_name.clone());
 
@@ -409,9 +483,7 @@ impl PlayerManager {
             return logs;
         };
         let short_name = target.short_name.clone();
-        let Some(hp) = target.stats.all_stats.get_mut(HP) else {
-            return logs;
-        };
+        let hp = &mut target.stats[StatKind::Hp];
         let new_hp = (hp.current + heal_amount).min(hp.max);
         let real_heal = new_hp - hp.current;
         hp.current = new_hp;
@@ -428,7 +500,157 @@ impl PlayerManager {
         logs
     }
 
-    /// Process the start of a new turn by incrementing counter effects, resetting first round booleans and applying regen stats.
+    /// Heal the current player by the lifesteal percent of the affixes of its equipments
+    pub(crate) fn apply_lifesteal(&mut self, damage_tx: i64) -> Vec<LogData> {
+        let launcher = &mut self.current_player;
+        let pct = launcher.inventory.lifesteal_percent();
+        let heal_amount = (damage_tx * pct / 100).max(0) as u64;
+        if heal_amount == 0 || launcher.stats.is_dead() {
+            return Vec::new();
+        }
+        let hp = &mut launcher.stats[StatKind::Hp];
+        let new_hp = (hp.current + heal_amount).min(hp.max);
+        let real_heal = new_hp - hp.current;
+        hp.current = new_hp;
+        if real_heal == 0 {
+            return Vec::new();
+        }
+        vec![LogData {
+            message: format!(
+                "\u{26a1} Lifesteal({}): +{} HP ({}% of {} damage TX)",
+                launcher.short_name, real_heal, pct, damage_tx
+            ),
+            color: LIGHT_GREEN.to_string(),
+        }]
+    }
+
+    /// Fire the innate class passives triggered by the effects of an attack of the current player:
+    /// - `OverHealToShield` of the launcher: the overheal becomes a shield on the healed target
+    /// - `CritManaRefundPercent` of the launcher: a part of the mana cost is refunded on a crit
+    /// - `BerserkOnDamageRx` of a target: a part of the HP damages received is gained as berserk
+    pub(crate) fn apply_class_passives(
+        &mut self,
+        atk: &AttackType,
+        gaes: &[GameAtkEffect],
+        is_crit: bool,
+        game_state: &GameState,
+    ) -> Vec<LogData> {
+        let mut logs = Vec::new();
+        let launcher_id_name = self.current_player.id_name.clone();
+        let rounds_info = &self.current_player.character_rounds_info;
+        let shield_pct = rounds_info
+            .get_passive_buffer(&BufKinds::OverHealToShield)
+            .map_or(0, |b| b.value);
+        let refund_pct = rounds_info
+            .get_passive_buffer(&BufKinds::CritManaRefundPercent)
+            .map_or(0, |b| b.value);
+
+        for gae in gaes {
+            let ep = &gae.processed_effect_param.input_effect_param;
+            if ep.buffer.stats_name != Some(StatKind::Hp) {
+                continue;
+            }
+            let outcome = &gae.effect_outcome;
+            let Some(target) = self.get_mut_fighter(&outcome.target_id_name) else {
+                continue;
+            };
+            // overheal of a heal
+            let overheal = outcome.full_amount_tx - outcome.real_amount_tx;
+            let shield = overheal * shield_pct / 100;
+            if outcome.full_amount_tx > 0 && shield > 0 {
+                target.character_rounds_info.add_shield(GameAtkEffect {
+                    processed_effect_param: ProcessedEffectParam {
+                        input_effect_param: EffectParam {
+                            nb_turns: OVERHEAL_SHIELD_NB_TURNS,
+                            buffer: Buffer {
+                                kind: BufKinds::AbsorbShield,
+                                value: shield,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    atk_type: atk.clone(),
+                    launcher_id_name: launcher_id_name.clone(),
+                    launching_turn: game_state.current_turn_nb,
+                    launching_round: game_state.current_round,
+                    effect_outcome: EffectOutcome {
+                        full_amount_tx: shield,
+                        real_amount_tx: shield,
+                        shield_remaining: shield,
+                        target_id_name: outcome.target_id_name.clone(),
+                        ..Default::default()
+                    },
+                });
+                logs.push(LogData {
+                    message: format!(
+                        "\u{26a1} Passive({}): {} \u{2190} Shield absorbing {} HP ({}% of {} overheal)",
+                        launcher_id_name, target.short_name, shield, shield_pct, overheal
+                    ),
+                    color: LIGHT_GREEN.to_string(),
+                });
+            }
+            // damages received
+            let berserk_pct = target
+                .character_rounds_info
+                .get_passive_buffer(&BufKinds::BerserkOnDamageRx)
+                .map_or(0, |b| b.value);
+            let berserk = outcome.real_amount_tx.abs() * berserk_pct / 100;
+            if outcome.real_amount_tx < 0 && berserk > 0 {
+                let overhead = target.stats.modify_stat_current(StatKind::Berserk, berserk);
+                let gain = berserk - overhead.max(0);
+                if gain > 0 {
+                    logs.push(LogData {
+                        message: format!(
+                            "\u{26a1} Passive({}): +{} Berserk ({}% of {} damage RX)",
+                            target.id_name,
+                            gain,
+                            berserk_pct,
+                            outcome.real_amount_tx.abs()
+                        ),
+                        color: LIGHT_GREEN.to_string(),
+                    });
+                }
+            }
+        }
+
+        // the cost is a percentage of the max mana, as in `Stats::apply_cost_on_stats`
+        let mana_spent = atk
+            .mana_cost
+            .saturating_mul(self.current_player.stats[StatKind::Mana].max)
+            / 100;
+        let refund = mana_spent as i64 * refund_pct / 100;
+        if is_crit && refund > 0 {
+            let overhead = self
+                .current_player
+                .stats
+                .modify_stat_current(StatKind::Mana, refund);
+            let gain = refund - overhead.max(0);
+            if gain > 0 {
+                logs.push(LogData {
+                    message: format!(
+                        "\u{26a1} Passive({}): +{} Mana ({}% of the cost refunded on crit)",
+                        launcher_id_name, gain, refund_pct
+                    ),
+                    color: LIGHT_GREEN.to_string(),
+                });
+            }
+        }
+
+        logs
+    }
+
+    /// Character fighting in the current round: the current player itself or an active character
+    fn get_mut_fighter(&mut self, id_name: &str) -> Option<&mut Character> {
+        if self.current_player.id_name == id_name {
+            return Some(&mut self.current_player);
+        }
+        self.get_mut_active_character(id_name)
+    }
+
+    /// Process the start of a new turn by incrementing counter effects, resetting first round booleans,
+    /// applying regen stats and decaying the threat against the bosses.
     pub fn start_new_turn(&mut self, is_first_turn: bool) {
         // Increment turn effects
         self.increment_counter_effect();
@@ -438,9 +660,91 @@ impl PlayerManager {
         if !is_first_turn {
             self.apply_regen_stats(CharacterKind::Boss);
             self.apply_regen_stats(CharacterKind::Hero);
+            self.threat_table.decay(THREAT_DECAY_PERCENT);
         }
     }
 
+    /// Add the threat generated by the effects of an atk launched by a hero.
+    /// An effect on a boss generates threat against that boss only, an effect on a hero
+    /// (heal, buf) generates threat against all the living bosses.
+    /// A taunt inflicted on a boss puts the launcher on top of its threat.
+    pub fn process_threat(&mut self, launcher_id_name: &str, gaes: &[GameAtkEffect]) {
+        if self.get_active_boss_character(launcher_id_name).is_some() {
+            return;
+        }
+        let alive_bosses: Vec<String> = self
+            .active_bosses
+            .iter()
+            .filter(|b| !b.stats.is_dead())
+            .map(|b| b.id_name.clone())
+            .collect();
+        for gae in gaes {
+            let target_id_name = &gae.effect_outcome.target_id_name;
+            if gae.processed_effect_param.input_effect_param.ailment == Some(AilmentKind::Taunt)
+                && alive_bosses.contains(target_id_name)
+            {
+                self.threat_table.taunt(target_id_name, launcher_id_name);
+            }
+            self.add_threat(
+                launcher_id_name,
+                target_id_name,
+                gae.effect_outcome.aggro_generated,
+                &alive_bosses,
+            );
+        }
+    }
+
+    /// Add the threat of the hots, dots and dot ailments ticked on the current player
+    /// to their launchers, normalized as the HP changes of the atks.
+    fn process_tick_threat(&mut self) {
+        let ticks =
+            std::mem::take(&mut self.current_player.character_rounds_info.hot_and_dot_ticks);
+        let target_id_name = self.current_player.id_name.clone();
+        let alive_bosses: Vec<String> = self
+            .active_bosses
+            .iter()
+            .filter(|b| !b.stats.is_dead())
+            .map(|b| b.id_name.clone())
+            .collect();
+        for (launcher_id_name, amount) in ticks {
+            if self.get_active_boss_character(&launcher_id_name).is_some() {
+                continue;
+            }
+            let threat = (amount.abs() as f64 / AGGRO_NORM).round() as u64;
+            self.add_threat(&launcher_id_name, &target_id_name, threat, &alive_bosses);
+        }
+    }
+
+    /// Threat of an effect of the hero on the target: against the target if it is a living boss,
+    /// against all the living bosses if it is a hero.
+    fn add_threat(
+        &mut self,
+        launcher_id_name: &str,
+        target_id_name: &str,
+        threat: u64,
+        alive_bosses: &[String],
+    ) {
+        if launcher_id_name.is_empty() {
+            return;
+        }
+        if self.get_active_boss_character(target_id_name).is_some() {
+            if alive_bosses.iter().any(|b| b == target_id_name) {
+                self.threat_table
+                    .add(target_id_name, launcher_id_name, threat);
+            }
+        } else {
+            for boss_id_name in alive_bosses {
+                self.threat_table
+                    .add(boss_id_name, launcher_id_name, threat);
+            }
+        }
+    }
+
+    /// Threat meter of the boss: threat of each hero against it, highest first
+    pub fn threat_meter(&self, boss_id_name: &str) -> Vec<ThreatEntry> {
+        self.threat_table.meter(boss_id_name)
+    }
+
     pub fn process_sup_atk_turn(&mut self, launcher_type: CharacterKind) -> Vec<String> {
         let player_list = if launcher_type == CharacterKind::Hero {
             &mut self.active_heroes
@@ -448,15 +752,10 @@ impl PlayerManager {
             &mut self.active_bosses
         };
         for pl in player_list {
-            if pl.stats.is_dead().unwrap_or(false) {
+            if pl.stats.is_dead() {
                 continue;
             }
-            let speed = pl
-                .stats
-                .all_stats
-                .get(SPEED)
-                .map(|s| s.current)
-                .unwrap_or(0);
+            let speed = pl.stats[StatKind::Speed].current;
             if speed >= SPEED_THRESHOLD {
                 pl.stats.reset_speed();
                 return vec![pl.id_name.clone()];
@@ -470,12 +769,14 @@ impl PlayerManager {
         all_targets: &Vec<String>,
         atk_level: u64,
         kind: &CharacterKind,
+        difficulty: Difficulty,
+        rng: &mut GameRng,
     ) {
         for t in all_targets {
             match self.get_mut_active_character(t) {
                 Some(c) => {
                     if c.kind != *kind {
-                        c.process_dodging(atk_level);
+                        c.process_dodging(atk_level, difficulty, rng);
                     }
                 }
                 _ => continue,
@@ -486,7 +787,7 @@ impl PlayerManager {
     pub fn process_died_players(&mut self) -> Result<()> {
         // heroes
         for c in self.active_heroes.iter_mut() {
-            if c.stats.is_dead() == Some(true) {
+            if c.stats.is_dead() {
                 c.reset_all_effects_on_player()?; // now ? works
                 c.character_rounds_info.reset_all_buffers();
             }
@@ -494,26 +795,92 @@ impl PlayerManager {
         Ok(())
     }
 
-    /// Process the boss target at the start of the turn by setting the hero with the highest aggro as current target.
+    /// Process the boss target at the start of the turn by setting the hero chosen by
+    /// the targeting strategy of the boss as current target.
+    /// `focus` is the strategy of a boss AI rule, used instead of the boss one.
     /// If all heroes are dead, no target is set.
     /// If the current player is a hero, no target is set.
-    pub fn process_boss_target(&mut self) {
+    pub fn process_boss_target(&mut self, focus: Option<&TargetingStrategy>, rng: &mut GameRng) {
         if self.current_player.kind == CharacterKind::Hero {
             return;
         }
 
         self.reset_targeted_character();
-        if let Some((max_index, _)) = self
+        let strategy = focus.unwrap_or(&self.current_player.targeting_strategy);
+        let forced_target = self.forced_target_id_name(rng);
+        let boss_id_name = &self.current_player.id_name;
+        let Some(target_id_name) = forced_target.or_else(|| {
+            strategy.choose_target(
+                &self.active_heroes,
+                &|id_name| self.threat_table.get(boss_id_name, id_name),
+                &self
+                    .current_player
+                    .character_rounds_info
+                    .last_target_id_name,
+                Some(rng),
+            )
+        }) else {
+            return;
+        };
+        if let Some(hero) = self
             .active_heroes
-            .iter()
-            .enumerate()
-            .filter(|(_, c)| c.stats.is_dead() == Some(false))
-            .max_by_key(|&(_, c)| c.stats.all_stats[AGGRO].current)
+            .iter_mut()
+            .find(|c| c.id_name == target_id_name)
         {
-            self.active_heroes[max_index]
-                .character_rounds_info
-                .is_current_target = true;
+            hero.character_rounds_info.is_current_target = true;
         }
+        self.current_player
+            .character_rounds_info
+            .last_target_id_name = target_id_name;
+    }
+
+    /// Apply the taunt and the confusion of the current hero on the target of an individual
+    /// atk on enemies, instead of the target chosen from UI.
+    pub fn process_hero_forced_target(&mut self, atk: &AttackType, rng: &mut GameRng) {
+        if self.current_player.kind != CharacterKind::Hero
+            || atk.target != Target::Enemy
+            || atk.reach != Reach::Individual
+        {
+            return;
+        }
+        let Some(target_id_name) = self.forced_target_id_name(rng) else {
+            return;
+        };
+        self.reset_targeted_character();
+        if let Some(boss) = self
+            .active_bosses
+            .iter_mut()
+            .find(|c| c.id_name == target_id_name)
+        {
+            boss.character_rounds_info.is_current_target = true;
+        }
+    }
+
+    /// Target imposed by the ailments of the current player among its enemies:
+    /// a taunt forces its source while it is alive, a confusion chooses a random alive enemy.
+    fn forced_target_id_name(&self, rng: &mut GameRng) -> Option<String> {
+        let enemies = if self.current_player.kind == CharacterKind::Hero {
+            &self.active_bosses
+        } else {
+            &self.active_heroes
+        };
+        let cri = &self.current_player.character_rounds_info;
+        if let Some(taunt) = cri.get_ailment(AilmentKind::Taunt)
+            && let Some(taunter) = enemies
+                .iter()
+                .find(|c| c.id_name == taunt.source_id_name && !c.stats.is_dead())
+        {
+            return Some(taunter.id_name.clone());
+        }
+        if cri.has_ailment(AilmentKind::Confusion) {
+            let alive: Vec<&Character> = enemies.iter().filter(|c| !c.stats.is_dead()).collect();
+            if alive.is_empty() {
+                return None;
+            }
+            let index = rng.random_nb(0, alive.len() as i64 - 1) as usize;
+            return Some(alive[index].id_name.clone());
+        }
+        None
     }
 
     /// Apply target choice from UI
@@ -522,7 +889,7 @@ impl PlayerManager {
             let Some(atk) = h.attacks_list.iter().find(|a| a.0 == atk_name) else {
                 return;
             };
-            if atk.1.reach == ZONE {
+            if atk.1.reach == Reach::Zone {
                 return;
             }
             self.reset_targeted_character();
@@ -545,7 +912,13 @@ impl PlayerManager {
                 .count()
     }
 
-    pub fn whatif_set_targeted_characters(&self, launcher_id_name: &str, atk_name: &str) -> u64 {
+    /// Count the potential targets of an atk without changing the targets.
+    /// For a boss atk on heroes, also report the hero the boss would choose.
+    pub fn whatif_set_targeted_characters(
+        &self,
+        launcher_id_name: &str,
+        atk_name: &str,
+    ) -> WhatIfTargets {
         if let Some(launcher) = self.get_active_character(launcher_id_name) {
             let Some(atk) = launcher
                 .attacks_list
@@ -553,54 +926,74 @@ impl PlayerManager {
                 .find(|a| a.0 == atk_name)
                 .map(|a| a.1.clone())
             else {
-                return 0;
+                return WhatIfTargets::default();
             };
 
-            let is_hero_ally = launcher.kind == CharacterKind::Hero && atk.target == TARGET_ALLY;
-            let is_boss_ally = launcher.kind == CharacterKind::Boss && atk.target == TARGET_ALLY;
+            let is_hero_ally = launcher.kind == CharacterKind::Hero && atk.target == Target::Ally;
+            let is_boss_ally = launcher.kind == CharacterKind::Boss && atk.target == Target::Ally;
             let is_boss_ennemy =
-                launcher.kind == CharacterKind::Boss && atk.target == TARGET_ENNEMY;
+                launcher.kind == CharacterKind::Boss && atk.target == Target::Enemy;
             let is_hero_ennemy =
-                launcher.kind == CharacterKind::Hero && atk.target == TARGET_ENNEMY;
+                launcher.kind == CharacterKind::Hero && atk.target == Target::Enemy;
 
             // self - atk
-            if atk.target == TARGET_HIMSELF {
-                return 1;
+            if atk.target == Target::Himself {
+                return WhatIfTargets {
+                    nb: 1,
+                    ..Default::default()
+                };
             }
             // all heroes - atk
-            if atk.target == TARGET_ALL_ALLIES {
+            if atk.target == Target::AllAllies {
                 let mut nb = 0;
                 self.active_heroes.iter().for_each(|c| {
-                    if c.stats.is_dead() == Some(false) {
+                    if !c.stats.is_dead() {
                         nb += 1;
                     }
                 });
-                return nb;
+                return WhatIfTargets {
+                    nb,
+                    ..Default::default()
+                };
             }
             // atk on heroes
             if is_boss_ennemy || is_hero_ally {
-                return Self::whatif_targets_for_collection(
+                let nb = Self::whatif_targets_for_collection(
                     &self.active_heroes,
                     launcher_id_name,
                     &atk,
                     is_hero_ally,
                     is_boss_ennemy,
                 );
+                let boss_target = if is_boss_ennemy {
+                    launcher.targeting_strategy.choose_target(
+                        &self.active_heroes,
+                        &|id_name| self.threat_table.get(launcher_id_name, id_name),
+                        &launcher.character_rounds_info.last_target_id_name,
+                        None,
+                    )
+                } else {
+                    None
+                };
+                return WhatIfTargets { nb, boss_target };
             }
 
             // atk on ennemies
             if is_boss_ally || is_hero_ennemy {
-                return Self::whatif_targets_for_collection(
-                    &self.active_bosses,
-                    launcher_id_name,
-                    &atk,
-                    is_boss_ally,
-                    is_hero_ennemy,
-                );
+                return WhatIfTargets {
+                    nb: Self::whatif_targets_for_collection(
+                        &self.active_bosses,
+                        launcher_id_name,
+                        &atk,
+                        is_boss_ally,
+                        is_hero_ennemy,
+                    ),
+                    ..Default::default()
+                };
             }
         }
 
-        0
+        WhatIfTargets::default()
     }
 
     /// Apply potential target choice for UI
@@ -618,23 +1011,23 @@ impl PlayerManager {
                 return;
             };
 
-            let is_hero_ally = launcher.kind == CharacterKind::Hero && atk.target == TARGET_ALLY;
-            let is_boss_ally = launcher.kind == CharacterKind::Boss && atk.target == TARGET_ALLY;
+            let is_hero_ally = launcher.kind == CharacterKind::Hero && atk.target == Target::Ally;
+            let is_boss_ally = launcher.kind == CharacterKind::Boss && atk.target == Target::Ally;
             let is_boss_ennemy =
-                launcher.kind == CharacterKind::Boss && atk.target == TARGET_ENNEMY;
+                launcher.kind == CharacterKind::Boss && atk.target == Target::Enemy;
             let is_hero_ennemy =
-                launcher.kind == CharacterKind::Hero && atk.target == TARGET_ENNEMY;
+                launcher.kind == CharacterKind::Hero && atk.target == Target::Enemy;
 
             // self - atk
-            if atk.target == TARGET_HIMSELF {
+            if atk.target == Target::Himself {
                 launcher.character_rounds_info.is_current_target = true;
                 launcher.character_rounds_info.is_potential_target = true;
                 return;
             }
             // all heroes - atk
-            if atk.target == TARGET_ALL_ALLIES {
+            if atk.target == Target::AllAllies {
                 self.active_heroes.iter_mut().for_each(|c| {
-                    if c.stats.is_dead() == Some(false) {
+                    if !c.stats.is_dead() {
                         c.character_rounds_info.is_potential_target = true;
                         c.character_rounds_info.is_current_target = true;
                     }
@@ -689,9 +1082,9 @@ impl PlayerManager {
 
         for atk in self.current_player.attacks_list.values() {
             let can_be_launched = self.current_player.can_be_launched(atk, current_turn_nb);
-            let whatif_nb =
+            let whatif =
                 self.whatif_set_targeted_characters(&self.current_player.id_name, &atk.name);
-            if can_be_launched && whatif_nb > 0 {
+            if can_be_launched && whatif.nb > 0 {
                 launchable_attacks.push(atk.clone());
             }
         }
@@ -718,13 +1111,15 @@ impl PlayerManager {
         characters
             .iter_mut()
             .filter(|c| {
-                let alive_or_targetable = c.stats.is_dead() == Some(false)
-                    || (can_target_dead && c.stats.is_dead() == Some(true));
+                let alive_or_targetable =
+                    !c.stats.is_dead() || (can_target_dead && c.stats.is_dead());
                 alive_or_targetable
                     && ((is_ally_condition && c.id_name != launcher_id_name) || is_ennemy_condition)
             })
             .for_each(|c| {
-                if !has_at_least_one_target && atk.reach == INDIVIDUAL || atk.reach == ZONE {
+                if !has_at_least_one_target && atk.reach == Reach::Individual
+                    || atk.reach == Reach::Zone
+                {
                     c.character_rounds_info.is_current_target = true;
                     c.character_rounds_info.is_potential_target = true;
                     has_at_least_one_target = true;
@@ -749,13 +1144,15 @@ impl PlayerManager {
         characters
             .iter()
             .filter(|c| {
-                let alive_or_targetable = c.stats.is_dead() == Some(false)
-                    || (can_target_dead && c.stats.is_dead() == Some(true));
+                let alive_or_targetable =
+                    !c.stats.is_dead() || (can_target_dead && c.stats.is_dead());
                 alive_or_targetable
                     && ((is_ally_condition && c.id_name != launcher_id_name) || is_ennemy_condition)
             })
             .for_each(|_c| {
-                if !has_at_least_one_target && atk.reach == INDIVIDUAL || atk.reach == ZONE {
+                if !has_at_least_one_target && atk.reach == Reach::Individual
+                    || atk.reach == Reach::Zone
+                {
                     nb += 1;
                     has_at_least_one_target = true;
                 } else {
@@ -779,13 +1176,13 @@ impl PlayerManager {
         let Some(first_effect) = consumable.effects.first() else {
             return;
         };
-        let target_kind = &first_effect.target_kind;
+        let target_kind = first_effect.target_kind;
         let can_target_dead = consumable
             .effects
             .iter()
             .any(|e| e.buffer.kind == BufKinds::Resurrect);
 
-        if target_kind == TARGET_HIMSELF {
+        if target_kind == Target::Himself {
             if let Some(launcher) = self.get_mut_active_character(launcher_id_name) {
                 launcher.character_rounds_info.is_current_target = true;
                 launcher.character_rounds_info.is_potential_target = true;
@@ -793,13 +1190,13 @@ impl PlayerManager {
             return;
         }
 
-        if target_kind == TARGET_ALLY {
+        if target_kind == Target::Ally {
             let mut has_first = false;
             for c in self.active_heroes.iter_mut() {
                 let is_targetable = if can_target_dead {
-                    c.stats.is_dead() == Some(true)
+                    c.stats.is_dead()
                 } else {
-                    c.stats.is_dead() == Some(false)
+                    !c.stats.is_dead()
                 };
                 if is_targetable {
                     if !has_first {
@@ -812,10 +1209,10 @@ impl PlayerManager {
             return;
         }
 
-        if target_kind == TARGET_ENNEMY {
+        if target_kind == Target::Enemy {
             let mut has_first = false;
             for c in self.active_bosses.iter_mut() {
-                if c.stats.is_dead() == Some(false) {
+                if !c.stats.is_dead() {
                     if !has_first {
                         c.character_rounds_info.is_current_target = true;
                         has_first = true;
@@ -833,6 +1230,7 @@ impl PlayerManager {
         potion_name: &str,
         target_id_name: &str,
         game_state: &crate::server::game_state::GameState,
+        rng: &mut GameRng,
     ) -> Result<Vec<EffectOutcome>> {
         if !self.current_player.inventory.contains_potion(potion_name) {
             bail!("no {} in inventory", potion_name);
@@ -853,19 +1251,20 @@ impl PlayerManager {
                 &consumable,
                 game_state,
                 &launcher_stats,
+                rng,
             )?
         } else if let Some(target) = self
             .active_heroes
             .iter_mut()
             .find(|c| c.id_name == target_id_name)
         {
-            target.apply_consumable_effects(&consumable, game_state, &launcher_stats)?
+            target.apply_consumable_effects(&consumable, game_state, &launcher_stats, rng)?
         } else if let Some(target) = self
             .active_bosses
             .iter_mut()
             .find(|c| c.id_name == target_id_name)
         {
-            target.apply_consumable_effects(&consumable, game_state, &launcher_stats)?
+            target.apply_consumable_effects(&consumable, game_state, &launcher_stats, rng)?
         } else {
             bail!("target {} not found", target_id_name);
         };
@@ -880,6 +1279,7 @@ impl PlayerManager {
         potion_name: &str,
         target_id_name: &str,
         game_state: &crate::server::game_state::GameState,
+        rng: &mut GameRng,
     ) -> Result<Vec<EffectOutcome>> {
         let idx = self
             .party_consumables
@@ -897,6 +1297,7 @@ impl PlayerManager {
                 &consumable,
                 game_state,
                 &launcher_stats,
+                rng,
             )?;
             return Ok(outcomes);
         }
@@ -907,7 +1308,7 @@ impl PlayerManager {
             .find(|c| c.id_name == target_id_name)
         {
             let outcomes =
-                target.apply_consumable_effects(&consumable, game_state, &launcher_stats)?;
+                target.apply_consumable_effects(&consumable, game_state, &launcher_stats, rng)?;
             Ok(outcomes)
         } else {
             self.party_consumables.insert(idx, consumable);
@@ -918,29 +1319,31 @@ impl PlayerManager {
     /// Check if the game is over by checking if all heroes or all bosses are dead.
     /// Returns a tuple of booleans (all_heroes_dead, all_bosses_dead).
     pub fn check_end_of_game(&self) -> (bool, bool) {
-        let all_heroes_dead = self
-            .active_heroes
-            .iter()
-            .all(|c| c.stats.is_dead() == Some(true));
-        let all_bosses_dead = self
-            .active_bosses
-            .iter()
-            .all(|c| c.stats.is_dead() == Some(true));
+        let all_heroes_dead = self.active_heroes.iter().all(|c| c.stats.is_dead());
+        let all_bosses_dead = self.active_bosses.iter().all(|c| c.stats.is_dead());
         (all_heroes_dead, all_bosses_dead)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use strum::IntoEnumIterator;
+
     use crate::{
-        character_mod::{effect::EffectOutcome, equipment::EquipmentJsonKey},
-        common::constants::stats_const::*,
+        character_mod::{
+            ailment::{Ailment, AilmentKind},
+            attack_type::AttackType,
+            effect::{EffectOutcome, EffectParam, ProcessedEffectParam},
+            equipment::EquipmentJsonKey,
+            stats::StatKind,
+            target::{Reach, Target, TargetingStrategy},
+        },
+        common::game_rng::GameRng,
         server::game_state::GameState,
         server::players_manager::GameAtkEffect,
         testing::testing_all_characters::{self, testing_pm},
         testing::testing_effect::*,
     };
-    use strum::IntoEnumIterator;
 
     #[test]
     fn unit_try_new() {
@@ -997,57 +1400,57 @@ mod tests {
     #[test]
     fn unit_apply_regen_stats() {
         let mut pl = testing_all_characters::testing_pm();
-        let old_hp = pl.active_heroes[0].stats.all_stats[HP].current;
-        let hp_regen = pl.active_heroes[0].stats.all_stats[HP_REGEN].current;
-        let old_mana = pl.active_heroes[0].stats.all_stats[MANA].current;
-        let mana_regen = pl.active_heroes[0].stats.all_stats[MANA_REGEN].current;
-        let old_berseck = pl.active_heroes[0].stats.all_stats[BERSERK].current;
-        let berseck_regen = pl.active_heroes[0].stats.all_stats[BERSECK_RATE].current;
-        let old_vigor = pl.active_heroes[0].stats.all_stats[VIGOR].current;
-        let vigor_regen = pl.active_heroes[0].stats.all_stats[VIGOR_REGEN].current;
-        let old_speed = pl.active_heroes[0].stats.all_stats[SPEED].current;
-        let speed_regen = pl.active_heroes[0].stats.all_stats[SPEED_REGEN].current;
+        let old_hp = pl.active_heroes[0].stats[StatKind::Hp].current;
+        let hp_regen = pl.active_heroes[0].stats[StatKind::HpRegen].current;
+        let old_mana = pl.active_heroes[0].stats[StatKind::Mana].current;
+        let mana_regen = pl.active_heroes[0].stats[StatKind::ManaRegen].current;
+        let old_berseck = pl.active_heroes[0].stats[StatKind::Berserk].current;
+        let berseck_regen = pl.active_heroes[0].stats[StatKind::BerserkRate].current;
+        let old_vigor = pl.active_heroes[0].stats[StatKind::Vigor].current;
+        let vigor_regen = pl.active_heroes[0].stats[StatKind::VigorRegen].current;
+        let old_speed = pl.active_heroes[0].stats[StatKind::Speed].current;
+        let speed_regen = pl.active_heroes[0].stats[StatKind::SpeedRegen].current;
         pl.apply_regen_stats(crate::character_mod::character::CharacterKind::Hero);
         assert_eq!(
             old_hp + hp_regen,
-            pl.active_heroes[0].stats.all_stats[HP].current
+            pl.active_heroes[0].stats[StatKind::Hp].current
         );
         assert_eq!(
             std::cmp::min(
                 old_mana + mana_regen,
-                pl.active_heroes[0].stats.all_stats[MANA].max
+                pl.active_heroes[0].stats[StatKind::Mana].max
             ),
-            pl.active_heroes[0].stats.all_stats[MANA].current
+            pl.active_heroes[0].stats[StatKind::Mana].current
         );
         assert_eq!(
             std::cmp::min(
                 old_berseck + berseck_regen,
-                pl.active_heroes[0].stats.all_stats[BERSERK].max
+                pl.active_heroes[0].stats[StatKind::Berserk].max
             ),
-            pl.active_heroes[0].stats.all_stats[BERSERK].current
+            pl.active_heroes[0].stats[StatKind::Berserk].current
         );
         assert_eq!(
             std::cmp::min(
                 old_vigor + vigor_regen,
-                pl.active_heroes[0].stats.all_stats[VIGOR].max
+                pl.active_heroes[0].stats[StatKind::Vigor].max
             ),
-            pl.active_heroes[0].stats.all_stats[VIGOR].current
+            pl.active_heroes[0].stats[StatKind::Vigor].current
         );
         assert_eq!(
             old_speed + speed_regen,
-            pl.active_heroes[0].stats.all_stats[SPEED].current
+            pl.active_heroes[0].stats[StatKind::Speed].current
         );
 
-        let old_hp = pl.active_bosses[0].stats.all_stats[HP].current;
-        let hp_regen = pl.active_bosses[0].stats.all_stats[HP_REGEN].current;
+        let old_hp = pl.active_bosses[0].stats[StatKind::Hp].current;
+        let hp_regen = pl.active_bosses[0].stats[StatKind::HpRegen].current;
         pl.apply_regen_stats(crate::character_mod::character::CharacterKind::Boss);
         // max is topped
         assert_eq!(
             std::cmp::min(
-                pl.active_bosses[0].stats.all_stats[HP].max,
+                pl.active_bosses[0].stats[StatKind::Hp].max,
                 old_hp + hp_regen
             ),
-            pl.active_bosses[0].stats.all_stats[HP].current
+            pl.active_bosses[0].stats[StatKind::Hp].current
         );
     }
 
@@ -1079,7 +1482,7 @@ mod tests {
             .character_rounds_info
             .actions_done_in_round = 100;
         let gs = GameState::default();
-        pl.update_current_player_on_new_round(&gs, "test_#1")
+        pl.update_current_player_on_new_round(&gs, "test_#1", &mut GameRng::default())
             .unwrap();
         assert_eq!(
             0,
@@ -1102,7 +1505,7 @@ mod tests {
         let (logs, hot_and_dot) = pl
             .current_player
             .character_rounds_info
-            .process_hot_and_dot(gs.current_turn_nb);
+            .process_hot_and_dot(gs.current_turn_nb, &mut GameRng::default());
         assert_eq!(0, logs.len());
         assert_eq!(0, hot_and_dot);
         // test cooldown effect
@@ -1116,7 +1519,7 @@ mod tests {
         let (logs, hot_and_dot) = pl
             .current_player
             .character_rounds_info
-            .process_hot_and_dot(gs.current_turn_nb);
+            .process_hot_and_dot(gs.current_turn_nb, &mut GameRng::default());
         assert_eq!(0, logs.len());
         assert_eq!(0, hot_and_dot);
         // add test HOT but on same turn
@@ -1134,7 +1537,7 @@ mod tests {
         let (logs, hot_and_dot) = pl
             .current_player
             .character_rounds_info
-            .process_hot_and_dot(gs.current_turn_nb);
+            .process_hot_and_dot(gs.current_turn_nb, &mut GameRng::default());
         assert_eq!(0, logs.len());
         assert_eq!(0, hot_and_dot);
         // add test HOT on different turn
@@ -1142,7 +1545,7 @@ mod tests {
         let (logs, hot_and_dot) = pl
             .current_player
             .character_rounds_info
-            .process_hot_and_dot(gs.current_turn_nb);
+            .process_hot_and_dot(gs.current_turn_nb, &mut GameRng::default());
         assert_eq!(1, logs.len());
         assert_eq!(30, hot_and_dot);
         // add test DOT on different turn
@@ -1160,7 +1563,7 @@ mod tests {
         let (logs, hot_and_dot) = pl
             .current_player
             .character_rounds_info
-            .process_hot_and_dot(gs.current_turn_nb);
+            .process_hot_and_dot(gs.current_turn_nb, &mut GameRng::default());
         assert_eq!(2, logs.len()); // hot + dot
         assert_eq!(10, hot_and_dot); // 30(hot) - 20 (dot)
     }
@@ -1564,10 +1967,7 @@ mod tests {
         pl.get_active_character(test_ally_id_name).expect("no hero");
         pl.get_mut_active_character(boss_id_name)
             .expect("no boss")
-            .stats
-            .all_stats
-            .get_mut(HP)
-            .unwrap()
+            .stats[StatKind::Hp]
             .current = 0; // boss is dead
         pl.set_targeted_characters(test_ally_id_name, "SimpleAtk");
         assert!(
@@ -1632,16 +2032,189 @@ mod tests {
         // atk to ennemy - effect dmg indiv
         let test_ally_id_name = "test_#1";
         pl.get_active_character(test_ally_id_name).expect("no hero");
-        let potential_target_nb = pl.whatif_set_targeted_characters(test_ally_id_name, "SimpleAtk");
-        assert_eq!(2, potential_target_nb);
+        let whatif = pl.whatif_set_targeted_characters(test_ally_id_name, "SimpleAtk");
+        assert_eq!(2, whatif.nb);
+        assert_eq!(None, whatif.boss_target);
         // atk to ennemy - effect dmg zone
-        let potential_target_nb =
-            pl.whatif_set_targeted_characters(test_ally_id_name, "simple-atk-zone");
-        assert_eq!(2, potential_target_nb);
+        let whatif = pl.whatif_set_targeted_characters(test_ally_id_name, "simple-atk-zone");
+        assert_eq!(2, whatif.nb);
         // atk to ally(himself in this example) - effect heal indiv, test -> test2
-        let potential_target_nb =
-            pl.whatif_set_targeted_characters(test_ally_id_name, "simple-atk-himself");
-        assert_eq!(1, potential_target_nb);
+        let whatif = pl.whatif_set_targeted_characters(test_ally_id_name, "simple-atk-himself");
+        assert_eq!(1, whatif.nb);
+    }
+
+    #[test]
+    fn unit_whatif_reports_boss_target() {
+        let mut pl = testing_all_characters::testing_pm();
+        let boss_id_name = pl.active_bosses[0].id_name.clone();
+        let atk_name = pl.active_bosses[0]
+            .attacks_list
+            .values()
+            .find(|a| a.target == Target::Enemy)
+            .map(|a| a.name.clone())
+            .unwrap();
+        pl.active_heroes[0].stats[StatKind::Hp].current = 100;
+        pl.active_heroes[1].stats[StatKind::Hp].current = 50;
+        pl.active_bosses[0].targeting_strategy = TargetingStrategy::LowestHp;
+        let whatif = pl.whatif_set_targeted_characters(&boss_id_name, &atk_name);
+        assert_eq!(
+            whatif.boss_target,
+            Some(pl.active_heroes[1].id_name.clone())
+        );
+
+        // the boss targets the reported hero and remembers it
+        pl.current_player = pl.active_bosses[0].clone();
+        pl.process_boss_target(None, &mut GameRng::new(1));
+        assert!(pl.active_heroes[1].character_rounds_info.is_current_target);
+        assert!(!pl.active_heroes[0].character_rounds_info.is_current_target);
+        assert_eq!(
+            pl.current_player.character_rounds_info.last_target_id_name,
+            pl.active_heroes[1].id_name
+        );
+        // a rule focus replaces the boss strategy
+        pl.process_boss_target(Some(&TargetingStrategy::Spread), &mut GameRng::new(1));
+        assert!(pl.active_heroes[0].character_rounds_info.is_current_target);
+    }
+
+    #[test]
+    fn unit_ailments_force_the_target() {
+        let mut pl = testing_pm();
+        pl.active_heroes[0].stats[StatKind::Hp].current = 100;
+        pl.active_heroes[1].stats[StatKind::Hp].current = 50;
+        pl.active_bosses[0].targeting_strategy = TargetingStrategy::LowestHp;
+
+        // a taunted boss targets the taunter instead of the hero of its strategy
+        pl.current_player = pl.active_bosses[0].clone();
+        pl.current_player
+            .character_rounds_info
+            .add_ailment(Ailment {
+                kind: AilmentKind::Taunt,
+                turns_left: 1,
+                source_id_name: pl.active_heroes[0].id_name.clone(),
+                ..Default::default()
+            });
+        pl.process_boss_target(None, &mut GameRng::new(1));
+        assert!(pl.active_heroes[0].character_rounds_info.is_current_target);
+        assert!(!pl.active_heroes[1].character_rounds_info.is_current_target);
+        // a dead taunter has no effect
+        pl.active_heroes[0].stats[StatKind::Hp].current = 0;
+        pl.process_boss_target(None, &mut GameRng::new(1));
+        assert!(pl.active_heroes[1].character_rounds_info.is_current_target);
+
+        // a confused hero hits an alive enemy, whatever the target chosen from UI
+        let atk = AttackType {
+            target: Target::Enemy,
+            reach: Reach::Individual,
+            ..Default::default()
+        };
+        pl.current_player = pl.active_heroes[1].clone();
+        pl.reset_targeted_character();
+        pl.process_hero_forced_target(&atk, &mut GameRng::new(1));
+        assert!(
+            pl.active_bosses
+                .iter()
+                .all(|c| !c.character_rounds_info.is_current_target)
+        );
+        pl.current_player
+            .character_rounds_info
+            .add_ailment(Ailment {
+                kind: AilmentKind::Confusion,
+                turns_left: 1,
+                ..Default::default()
+            });
+        pl.process_hero_forced_target(&atk, &mut GameRng::new(1));
+        assert_eq!(
+            1,
+            pl.active_bosses
+                .iter()
+                .filter(|c| c.character_rounds_info.is_current_target && !c.stats.is_dead())
+                .count()
+        );
+    }
+
+    #[test]
+    fn unit_process_threat() {
+        let mut pl = testing_pm();
+        let (hero0, hero1) = (
+            pl.active_heroes[0].id_name.clone(),
+            pl.active_heroes[1].id_name.clone(),
+        );
+        let boss = pl.active_bosses[0].id_name.clone();
+        let gae = |target: &str, aggro: u64, ailment: Option<AilmentKind>| GameAtkEffect {
+            processed_effect_param: ProcessedEffectParam {
+                input_effect_param: EffectParam {
+                    ailment,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            effect_outcome: EffectOutcome {
+                target_id_name: target.to_owned(),
+                aggro_generated: aggro,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // damages on a boss, heal on a hero: threat against all the bosses
+        pl.process_threat(&hero0, &[gae(&boss, 30, None)]);
+        pl.process_threat(&hero1, &[gae(&hero0, 20, None)]);
+        assert_eq!(30, pl.threat_table.get(&boss, &hero0));
+        assert_eq!(20, pl.threat_table.get(&boss, &hero1));
+        for b in pl.active_bosses.iter().skip(1) {
+            assert_eq!(0, pl.threat_table.get(&b.id_name, &hero0));
+            assert_eq!(20, pl.threat_table.get(&b.id_name, &hero1));
+        }
+        // bosses do not generate threat
+        pl.process_threat(&boss, &[gae(&hero0, 50, None)]);
+        assert_eq!(20, pl.threat_table.get(&boss, &hero1));
+
+        pl.active_bosses[0].targeting_strategy = TargetingStrategy::HighestAggro;
+        pl.current_player = pl.active_bosses[0].clone();
+        pl.process_boss_target(None, &mut GameRng::new(1));
+        assert!(pl.active_heroes[0].character_rounds_info.is_current_target);
+
+        // a taunt puts the launcher on top of the threat
+        pl.process_threat(&hero1, &[gae(&boss, 0, Some(AilmentKind::Taunt))]);
+        assert_eq!(31, pl.threat_table.get(&boss, &hero1));
+        pl.process_boss_target(None, &mut GameRng::new(1));
+        assert!(pl.active_heroes[1].character_rounds_info.is_current_target);
+        let meter = pl.threat_meter(&boss);
+        assert_eq!(meter[0].hero_id_name, hero1);
+        assert_eq!(meter[1].hero_id_name, hero0);
+
+        // decay at each new turn, except the first one
+        pl.start_new_turn(true);
+        assert_eq!(31, pl.threat_table.get(&boss, &hero1));
+        pl.start_new_turn(false);
+        assert_eq!(27, pl.threat_table.get(&boss, &hero1));
+        assert_eq!(27, pl.threat_table.get(&boss, &hero0));
+
+        pl.clear_scenario();
+        assert!(pl.threat_meter(&boss).is_empty());
+
+        // a dot ticking on a boss gives threat against it, a hot on a hero against all the bosses
+        let mut pl = testing_pm();
+        pl.active_bosses[0].character_rounds_info.hot_and_dot_ticks = vec![
+            (hero0.clone(), -40),
+            (pl.active_bosses[1].id_name.clone(), -40),
+        ];
+        pl.update_current_player_on_new_round(&GameState::default(), &boss, &mut GameRng::new(1))
+            .unwrap();
+        assert_eq!(2, pl.threat_table.get(&boss, &hero0));
+        assert_eq!(0, pl.threat_table.get(&pl.active_bosses[1].id_name, &hero0));
+        assert!(
+            pl.current_player
+                .character_rounds_info
+                .hot_and_dot_ticks
+                .is_empty()
+        );
+        pl.active_heroes[0].character_rounds_info.hot_and_dot_ticks = vec![(hero1.clone(), 30)];
+        pl.update_current_player_on_new_round(&GameState::default(), &hero0, &mut GameRng::new(1))
+            .unwrap();
+        for b in pl.active_bosses.iter() {
+            assert_eq!(2, pl.threat_table.get(&b.id_name, &hero1));
+        }
     }
 
     #[test]
@@ -1651,8 +2224,13 @@ mod tests {
 
         // error: consumable not found
         assert!(
-            pl.use_party_consumable("test_#1", "NoSuchPotion", &GameState::default())
-                .is_err()
+            pl.use_party_consumable(
+                "test_#1",
+                "NoSuchPotion",
+                &GameState::default(),
+                &mut GameRng::default()
+            )
+            .is_err()
         );
 
         // success: valid hero + valid potion
@@ -1661,8 +2239,13 @@ mod tests {
             ..Default::default()
         });
         assert!(
-            pl.use_party_consumable("test_#1", "TestPotion", &GameState::default())
-                .is_ok()
+            pl.use_party_consumable(
+                "test_#1",
+                "TestPotion",
+                &GameState::default(),
+                &mut GameRng::default()
+            )
+            .is_ok()
         );
         // consumable removed after use
         assert!(pl.party_consumables.is_empty());
@@ -1673,16 +2256,20 @@ mod tests {
             ..Default::default()
         });
         assert!(
-            pl.use_party_consumable("no_hero", "TestPotion2", &GameState::default())
-                .is_err()
+            pl.use_party_consumable(
+                "no_hero",
+                "TestPotion2",
+                &GameState::default(),
+                &mut GameRng::default()
+            )
+            .is_err()
         );
     }
 
     #[test]
     fn unit_use_consumable_on_target_self() {
-        use crate::common::constants::stats_const::HP;
         let mut pm = testing_all_characters::testing_pm();
-        pm.current_player.stats.all_stats[HP].current = 10;
+        pm.current_player.stats[StatKind::Hp].current = 10;
         // sync reduced HP back to active_heroes so is_dead check is consistent
         let id = pm.current_player.id_name.clone();
         pm.modify_active_character(&id);
@@ -1692,13 +2279,13 @@ mod tests {
         pm.modify_active_character(&id);
 
         let gs = GameState::default();
-        let result = pm.use_consumable_on_target("potion", &id, &gs);
+        let result = pm.use_consumable_on_target("potion", &id, &gs, &mut GameRng::default());
         assert!(
             result.is_ok(),
             "use_consumable_on_target(self) must succeed"
         );
         assert!(
-            pm.current_player.stats.all_stats[HP].current > 10,
+            pm.current_player.stats[StatKind::Hp].current > 10,
             "current_player HP must increase after self-potion"
         );
         assert!(
@@ -1712,7 +2299,7 @@ mod tests {
             pm.active_heroes
                 .iter()
                 .find(|c| c.id_name == id)
-                .map(|c| c.stats.all_stats[HP].current)
+                .map(|c| c.stats[StatKind::Hp].current)
                 .unwrap_or(0)
                 > 10,
             "active_heroes HP must reflect the heal after modify_active_character"
@@ -1723,10 +2310,9 @@ mod tests {
     fn unit_use_party_consumable_on_target_self() {
         use crate::character_mod::effect::build_hp_effect;
         use crate::character_mod::inventory::Consumable;
-        use crate::common::constants::stats_const::HP;
 
         let mut pm = testing_all_characters::testing_pm();
-        pm.current_player.stats.all_stats[HP].current = 10;
+        pm.current_player.stats[StatKind::Hp].current = 10;
         let id = pm.current_player.id_name.clone();
         pm.modify_active_character(&id);
 
@@ -1737,7 +2323,8 @@ mod tests {
         });
 
         let gs = GameState::default();
-        let result = pm.use_party_consumable_on_target("PartyHealPotion", &id, &gs);
+        let result =
+            pm.use_party_consumable_on_target("PartyHealPotion", &id, &gs, &mut GameRng::default());
         assert!(
             result.is_ok(),
             "use_party_consumable_on_target(self) must succeed"
@@ -1747,7 +2334,7 @@ mod tests {
             "party consumable must be removed after use"
         );
         assert!(
-            pm.current_player.stats.all_stats[HP].current > 10,
+            pm.current_player.stats[StatKind::Hp].current > 10,
             "current_player HP must increase after party self-potion"
         );
 
@@ -1757,7 +2344,7 @@ mod tests {
             pm.active_heroes
                 .iter()
                 .find(|c| c.id_name == id)
-                .map(|c| c.stats.all_stats[HP].current)
+                .map(|c| c.stats[StatKind::Hp].current)
                 .unwrap_or(0)
                 > 10,
             "active_heroes HP must reflect the heal after modify_active_character"
@@ -1794,14 +2381,13 @@ mod tests {
             buffers::{BufKinds, Buffer},
             effect::{EffectParam, ProcessedEffectParam},
         };
-        use crate::common::constants::{all_target_const::TARGET_HIMSELF, reach_const::INDIVIDUAL};
 
         let mut pm = testing_pm();
 
-        let old_dodge_max = pm.active_heroes[0].stats.all_stats[DODGE].max;
-        let old_speed_regen_max = pm.active_heroes[0].stats.all_stats[SPEED_REGEN].max;
-        let old_hp_max = pm.active_heroes[0].stats.all_stats[HP].max;
-        let old_mana_max = pm.active_heroes[0].stats.all_stats[MANA].max;
+        let old_dodge_max = pm.active_heroes[0].stats[StatKind::Dodge].max;
+        let old_speed_regen_max = pm.active_heroes[0].stats[StatKind::SpeedRegen].max;
+        let old_hp_max = pm.active_heroes[0].stats[StatKind::Hp].max;
+        let old_mana_max = pm.active_heroes[0].stats[StatKind::Mana].max;
 
         // Inject ChangeMaxStatByValue +20 on Dodge (simulates an active mid-scenario buff).
         let dodge_ep = EffectParam {
@@ -1809,12 +2395,12 @@ mod tests {
                 kind: BufKinds::ChangeMaxStatByValue,
                 value: 20,
                 is_percent: false,
-                stats_name: DODGE.to_string(),
+                stats_name: Some(StatKind::Dodge),
                 ..Default::default()
             },
             nb_turns: 3,
-            target_kind: TARGET_HIMSELF.to_string(),
-            reach: INDIVIDUAL.to_string(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             ..Default::default()
         };
         pm.active_heroes[0]
@@ -1830,7 +2416,7 @@ mod tests {
             });
         pm.active_heroes[0]
             .stats
-            .set_stats_on_effect(DODGE, 20, false, true);
+            .set_stats_on_effect(StatKind::Dodge, 20, false, true);
 
         // Inject ChangeMaxStatByValue +5 on SPEED_REGEN (simulates a speed-regen buff).
         let sr_ep = EffectParam {
@@ -1838,12 +2424,12 @@ mod tests {
                 kind: BufKinds::ChangeMaxStatByValue,
                 value: 5,
                 is_percent: false,
-                stats_name: SPEED_REGEN.to_string(),
+                stats_name: Some(StatKind::SpeedRegen),
                 ..Default::default()
             },
             nb_turns: 3,
-            target_kind: TARGET_HIMSELF.to_string(),
-            reach: INDIVIDUAL.to_string(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             ..Default::default()
         };
         pm.active_heroes[0]
@@ -1859,25 +2445,25 @@ mod tests {
             });
         pm.active_heroes[0]
             .stats
-            .set_stats_on_effect(SPEED_REGEN, 5, false, true);
+            .set_stats_on_effect(StatKind::SpeedRegen, 5, false, true);
 
         // Confirm effects are live before the clear.
         assert_eq!(
             old_dodge_max + 20,
-            pm.active_heroes[0].stats.all_stats[DODGE].max,
+            pm.active_heroes[0].stats[StatKind::Dodge].max,
             "pre-check: Dodge should be buffed"
         );
         assert_eq!(
             old_speed_regen_max + 5,
-            pm.active_heroes[0].stats.all_stats[SPEED_REGEN].max,
+            pm.active_heroes[0].stats[StatKind::SpeedRegen].max,
             "pre-check: Speed regen should be buffed"
         );
 
         // Simulate accumulated speed (e.g. after a supplementary-attack reset_speed call).
-        pm.active_heroes[0].stats.get_mut_value(SPEED).current = 50;
+        pm.active_heroes[0].stats[StatKind::Speed].current = 50;
         // Partially drain HP and Mana.
-        pm.active_heroes[0].stats.get_mut_value(HP).current = 10;
-        pm.active_heroes[0].stats.get_mut_value(MANA).current = 5;
+        pm.active_heroes[0].stats[StatKind::Hp].current = 10;
+        pm.active_heroes[0].stats[StatKind::Mana].current = 5;
 
         // --- Act ---
         pm.clear_scenario();
@@ -1886,31 +2472,38 @@ mod tests {
         let hero = &pm.active_heroes[0];
 
         assert_eq!(
-            0, hero.stats.all_stats[SPEED].current,
+            0,
+            hero.stats[StatKind::Speed].current,
             "Speed must be 0 after clear_scenario"
         );
         assert_eq!(
-            old_dodge_max, hero.stats.all_stats[DODGE].max,
+            old_dodge_max,
+            hero.stats[StatKind::Dodge].max,
             "Dodge max must be restored after clear_scenario"
         );
         assert_eq!(
-            old_speed_regen_max, hero.stats.all_stats[SPEED_REGEN].max,
+            old_speed_regen_max,
+            hero.stats[StatKind::SpeedRegen].max,
             "Speed regen max must be restored after clear_scenario"
         );
         assert_eq!(
-            old_hp_max, hero.stats.all_stats[HP].current,
+            old_hp_max,
+            hero.stats[StatKind::Hp].current,
             "HP must be restored to max after clear_scenario"
         );
         assert_eq!(
-            old_mana_max, hero.stats.all_stats[MANA].current,
+            old_mana_max,
+            hero.stats[StatKind::Mana].current,
             "Mana must be restored to max after clear_scenario"
         );
         assert_eq!(
-            0, hero.stats.all_stats[BERSERK].current,
+            0,
+            hero.stats[StatKind::Berserk].current,
             "Berserk must be 0 after clear_scenario"
         );
         assert_eq!(
-            0, hero.stats.all_stats[AGGRO].current,
+            0,
+            hero.stats[StatKind::Aggro].current,
             "Aggro must be 0 after clear_scenario"
         );
     }
@@ -1918,33 +2511,20 @@ mod tests {
     /// clear_scenario must clamp any stat inflated above max by the OverHealBoostStat passive.
     #[test]
     fn unit_clear_scenario_resets_overheal_passive_stat_boost() {
-        use crate::common::constants::stats_const::PHYSICAL_POWER;
-
         let mut pm = testing_all_characters::dxrpg_pm();
         // Use Azrak who carries the OverHealBoostStat passive on Physical power.
         let azrak_id = "Azrak_Ombresang_#1";
-        let phys_pow_max = pm
-            .get_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[PHYSICAL_POWER]
-            .max;
+        let phys_pow_max =
+            pm.get_active_hero_character(azrak_id).unwrap().stats[StatKind::PhysicalPower].max;
 
         // Simulate the passive having accumulated a large uncapped boost across a scenario.
-        pm.get_mut_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .get_mut_value(PHYSICAL_POWER)
+        pm.get_mut_active_hero_character(azrak_id).unwrap().stats[StatKind::PhysicalPower]
             .current = phys_pow_max + 200;
 
         pm.clear_scenario();
 
-        let after = pm
-            .get_active_hero_character(azrak_id)
-            .unwrap()
-            .stats
-            .all_stats[PHYSICAL_POWER]
-            .current;
+        let after =
+            pm.get_active_hero_character(azrak_id).unwrap().stats[StatKind::PhysicalPower].current;
         assert_eq!(
             after, phys_pow_max,
             "Physical power current must be clamped back to max after clear_scenario"
@@ -1970,21 +2550,16 @@ mod tests {
             });
 
         // Set launcher (hero[0]) to full HP so hero[1] (at HP=1) is the most needy
-        let max_hp_launcher = pm.active_heroes[0].stats.all_stats[HP].max;
-        pm.active_heroes[0]
-            .stats
-            .all_stats
-            .get_mut(HP)
-            .unwrap()
-            .current = max_hp_launcher;
+        let max_hp_launcher = pm.active_heroes[0].stats[StatKind::Hp].max;
+        pm.active_heroes[0].stats[StatKind::Hp].current = max_hp_launcher;
 
         // hero[1] starts at HP=1 (from test JSON) — lowest ratio → most needy
-        let hero1_hp_before = pm.active_heroes[1].stats.all_stats[HP].current; // 1
+        let hero1_hp_before = pm.active_heroes[1].stats[StatKind::Hp].current; // 1
 
         // Passive fires immediately with damage_tx=200 (simulates an attack dealing 200 damage)
         pm.apply_damage_tx_heal_passive(&launcher_id, 200);
 
-        let hero1_hp_after = pm.active_heroes[1].stats.all_stats[HP].current;
+        let hero1_hp_after = pm.active_heroes[1].stats[StatKind::Hp].current;
         // 200 * 25 / 100 = 50
         assert_eq!(
             hero1_hp_after,
@@ -2010,13 +2585,14 @@ mod tests {
                 ..Default::default()
             });
 
-        let hero1_hp_before = pm.active_heroes[1].stats.all_stats[HP].current;
+        let hero1_hp_before = pm.active_heroes[1].stats[StatKind::Hp].current;
 
         // damage_tx=0 → passive must be a no-op
         pm.apply_damage_tx_heal_passive(&launcher_id, 0);
 
         assert_eq!(
-            pm.active_heroes[1].stats.all_stats[HP].current, hero1_hp_before,
+            pm.active_heroes[1].stats[StatKind::Hp].current,
+            hero1_hp_before,
             "zero damage TX means no passive heal"
         );
     }
@@ -2039,12 +2615,13 @@ mod tests {
                 ..Default::default()
             });
 
-        let hero1_hp_before = pm.active_heroes[1].stats.all_stats[HP].current;
+        let hero1_hp_before = pm.active_heroes[1].stats[StatKind::Hp].current;
 
         pm.apply_damage_tx_heal_passive(&launcher_id, 200);
 
         assert_eq!(
-            pm.active_heroes[1].stats.all_stats[HP].current, hero1_hp_before,
+            pm.active_heroes[1].stats[StatKind::Hp].current,
+            hero1_hp_before,
             "disabled passive must not heal"
         );
     }
@@ -2067,22 +2644,18 @@ mod tests {
             });
 
         // Set launcher (hero[0]) to full HP so hero[1] (at HP=1) is the most needy
-        let max_hp_launcher = pm.active_heroes[0].stats.all_stats[HP].max;
-        pm.active_heroes[0]
-            .stats
-            .all_stats
-            .get_mut(HP)
-            .unwrap()
-            .current = max_hp_launcher;
+        let max_hp_launcher = pm.active_heroes[0].stats[StatKind::Hp].max;
+        pm.active_heroes[0].stats[StatKind::Hp].current = max_hp_launcher;
 
         // hero[1] starts at HP=1 (most needy); track its max for the cap assertion
-        let hp_max = pm.active_heroes[1].stats.all_stats[HP].max; // 135
+        let hp_max = pm.active_heroes[1].stats[StatKind::Hp].max; // 135
 
         // Huge damage TX so heal would overflow HP max
         pm.apply_damage_tx_heal_passive(&launcher_id, 10_000);
 
         assert_eq!(
-            pm.active_heroes[1].stats.all_stats[HP].current, hp_max,
+            pm.active_heroes[1].stats[StatKind::Hp].current,
+            hp_max,
             "heal must not exceed HP max"
         );
     }
@@ -2099,13 +2672,12 @@ mod tests {
             buffers::Buffer,
             effect::{EffectOutcome, EffectParam, ProcessedEffectParam},
         };
-        use crate::common::constants::stats_const::HP;
         GameAtkEffect {
             processed_effect_param: ProcessedEffectParam {
                 input_effect_param: EffectParam {
                     buffer: Buffer {
                         kind: kind.clone(),
-                        stats_name: HP.to_string(),
+                        stats_name: Some(StatKind::Hp),
                         ..Default::default()
                     },
                     ..Default::default()
@@ -2255,4 +2827,93 @@ mod tests {
         };
         assert_eq!(gae.log_text(), None, "no debuff removed → hidden from log");
     }
+
+    #[test]
+    fn unit_apply_lifesteal() {
+        use crate::character_mod::{
+            affix::{Affix, AffixKind, Rarity},
+            equipment::Equipment,
+        };
+        let mut pm = testing_pm();
+        assert!(pm.apply_lifesteal(100).is_empty());
+
+        let ring = Equipment {
+            unique_name: "ring".to_owned(),
+            ..Default::default()
+        }
+        .with_affixes(
+            Rarity::Uncommon,
+            &[Affix {
+                kind: AffixKind::Lifesteal,
+                value: 10,
+            }],
+        );
+        let id = pm.current_player.inventory.add_equipment(&ring, false);
+        assert!(pm.apply_lifesteal(100).is_empty());
+        pm.current_player
+            .inventory
+            .toggle_equipment_by_id(id)
+            .unwrap();
+        let hp = &mut pm.current_player.stats[StatKind::Hp];
+        hp.current = hp.max - 5;
+        let logs = pm.apply_lifesteal(100);
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].message.contains("+5 HP (10% of 100 damage TX)"));
+        let hp = &pm.current_player.stats[StatKind::Hp];
+        assert_eq!(hp.current, hp.max);
+    }
+
+    #[test]
+    fn unit_apply_class_passives() {
+        use crate::character_mod::{buffers::Buffer, class::Class};
+        let dm = testing_all_characters::testing_dm();
+        let passive = |class: Class| dm.class_passives.iter().find(|p| p.class == class).unwrap();
+        let mut pm = testing_pm();
+        let target_id = pm.active_heroes[1].id_name.clone();
+        pm.active_heroes[1].apply_class_passive(passive(Class::Berserker));
+        pm.active_heroes[1].stats[StatKind::Berserk].current = 0;
+        pm.current_player = pm.active_heroes[0].clone();
+        pm.current_player
+            .apply_class_passive(passive(Class::Healer));
+        pm.current_player.apply_class_passive(passive(Class::Mage));
+        pm.current_player.stats[StatKind::Mana].max = 500;
+        pm.current_player.stats[StatKind::Mana].current = 100;
+        let hp_gae = |full_amount_tx, real_amount_tx| GameAtkEffect {
+            processed_effect_param: ProcessedEffectParam {
+                input_effect_param: EffectParam {
+                    buffer: Buffer {
+                        stats_name: Some(StatKind::Hp),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            effect_outcome: EffectOutcome {
+                full_amount_tx,
+                real_amount_tx,
+                target_id_name: target_id.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let atk = AttackType {
+            mana_cost: 20,
+            ..Default::default()
+        };
+        let gaes = [hp_gae(100, 40), hp_gae(-50, -50)];
+
+        // 30% of 60 overheal as shield, 20% of 50 damages as berserk
+        let logs = pm.apply_class_passives(&atk, &gaes, false, &GameState::default());
+        assert_eq!(logs.len(), 2);
+        let target = pm.get_active_hero_character(&target_id).unwrap();
+        assert_eq!(target.character_rounds_info.shield_total(), 18);
+        assert_eq!(target.stats[StatKind::Berserk].current, 10);
+        assert_eq!(pm.current_player.stats[StatKind::Mana].current, 100);
+
+        // 50% of the mana cost refunded on a crit: the cost is 20% of 500 max mana
+        let logs = pm.apply_class_passives(&atk, &[], true, &GameState::default());
+        assert_eq!(logs.len(), 1);
+        assert_eq!(pm.current_player.stats[StatKind::Mana].current, 150);
+    }
 }
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use indexmap::IndexMap;
+
+use crate::{
+    character_mod::{
+        character::Character,
+        class::Class,
+        loot::{Loot, LootType},
+        rank::Rank,
+    },
+    common::{
+        constants::procgen_const::{
+            PROCGEN_ADVANCED_LEVEL, PROCGEN_BASE_BUDGET, PROCGEN_GOLD_PER_LEVEL,
+            PROCGEN_INTERMEDIATE_LEVEL, PROCGEN_LEVELS_PER_BUDGET, PROCGEN_MAX_BOSSES,
+        },
+        game_rng::GameRng,
+    },
+    server::{campaign::ScenarioEdge, data_manager::DataManager, scenario::Scenario},
+};
+
+/// Cost of a boss in the difficulty budget of a generated scenario
+pub fn boss_cost(rank: &Rank) -> u64 {
+    match rank {
+        Rank::Common => 1,
+        Rank::Intermediate => 2,
+        Rank::Advanced => 3,
+    }
+}
+
+/// Difficulty budget of a generated scenario of that level
+pub fn difficulty_budget(level: u64) -> u64 {
+    PROCGEN_BASE_BUDGET + level.saturating_sub(1) / PROCGEN_LEVELS_PER_BUDGET
+}
+
+/// Rank of the equipment loot of a generated scenario of that level
+pub fn loot_rank(level: u64) -> Rank {
+    if level >= PROCGEN_ADVANCED_LEVEL {
+        Rank::Advanced
+    } else if level >= PROCGEN_INTERMEDIATE_LEVEL {
+        Rank::Intermediate
+    } else {
+        Rank::Common
+    }
+}
+
+/// Name of the generated scenario of that level in the universe
+pub fn endless_scenario_name(universe: &str, level: u64) -> String {
+    if universe.is_empty() {
+        format!("Endless {}", level)
+    } else {
+        format!("Endless {} {}", universe, level)
+    }
+}
+
+/// Build a scenario of the target level from the bosses and the equipments of `dm`.
+/// Bosses of the universe (all the bosses if it is empty) are drawn within the difficulty budget,
+/// raised to the target level, and the loots are rolled from the equipments of the loot rank.
+pub fn generate_scenario(
+    dm: &DataManager,
+    universe: &str,
+    level: u64,
+    rng: &mut GameRng,
+) -> Result<Scenario> {
+    let mut candidates: Vec<&Character> = dm
+        .all_bosses
+        .iter()
+        .filter(|b| universe.is_empty() || b.universe == universe)
+        .collect();
+    let mut budget = difficulty_budget(level);
+    let mut bosses: Vec<&Character> = Vec::new();
+    while bosses.len() < PROCGEN_MAX_BOSSES {
+        candidates.retain(|b| boss_cost(&b.rank) <= budget);
+        if candidates.is_empty() {
+            break;
+        }
+        let index = rng.random_nb(0, candidates.len() as i64 - 1) as usize;
+        let boss = candidates.swap_remove(index);
+        budget -= boss_cost(&boss.rank);
+        bosses.push(boss);
+    }
+    if bosses.is_empty() {
+        bail!(
+            "No boss fits the budget of level {} in universe '{}'",
+            level,
+            universe
+        );
+    }
+
+    let mut boss_patterns = IndexMap::new();
+    let mut boss_levels = HashMap::new();
+    for boss in &bosses {
+        boss_patterns.insert(
+            boss.db_full_name.clone(),
+            (0..boss.attacks_list.len() as u64).collect(),
+        );
+        if boss.level < level {
+            boss_levels.insert(boss.db_full_name.clone(), level);
+        }
+    }
+    let boss_names: Vec<&str> = bosses.iter().map(|b| b.db_full_name.as_str()).collect();
+
+    Ok(Scenario {
+        name: endless_scenario_name(universe, level),
+        description: format!(
+            "Endless fight of level {}: {}",
+            level,
+            boss_names.join(", ")
+        ),
+        boss_patterns,
+        boss_levels,
+        loots: roll_loots(dm, level, rng),
+        level,
+        universe: universe.to_owned(),
+        ..Default::default()
+    })
+}
+
+/// Currency loot of the level and one equipment of the highest rank available up to the loot rank
+fn roll_loots(dm: &DataManager, level: u64, rng: &mut GameRng) -> Vec<Loot> {
+    let mut loots = vec![Loot {
+        name: "gold".to_owned(),
+        kind: LootType::Currency,
+        rank: Rank::Common,
+        level: PROCGEN_GOLD_PER_LEVEL * level as i64,
+        classes: vec![Class::Standard],
+    }];
+    let max_rank = loot_rank(level);
+    let equipments: Vec<_> = dm
+        .equipment_table
+        .values()
+        .flatten()
+        .filter(|e| e.rank <= max_rank)
+        .collect();
+    let Some(best_rank) = equipments
+        .iter()
+        .map(|e| e.rank.clone())
+        .reduce(|a, b| if b > a { b } else { a })
+    else {
+        return loots;
+    };
+    let mut equipments: Vec<_> = equipments
+        .into_iter()
+        .filter(|e| e.rank == best_rank)
+        .collect();
+    // the equipment table is a hash map: sort to roll the same loot from the same seed
+    equipments.sort_by(|a, b| a.unique_name.cmp(&b.unique_name));
+    let equipment = equipments[rng.random_nb(0, equipments.len() as i64 - 1) as usize];
+    loots.push(Loot {
+        name: equipment.unique_name.clone(),
+        kind: LootType::Equipment,
+        rank: equipment.rank.clone(),
+        level: level as i64,
+        classes: vec![Class::Standard],
+    });
+    loots
+}
+
+/// Build `nb` scenarios of consecutive levels from `first_level`, chained by the campaign graph.
+/// The same seed always builds the same scenarios.
+pub fn generate_scenarios(
+    dm: &DataManager,
+    universe: &str,
+    first_level: u64,
+    nb: u64,
+    seed: u64,
+) -> Result<Vec<Scenario>> {
+    let mut rng = GameRng::new(seed);
+    let mut scenarios = Vec::new();
+    for level in first_level..first_level + nb {
+        let mut scenario = generate_scenario(dm, universe, level, &mut rng)?;
+        if level + 1 < first_level + nb {
+            scenario.next = vec![ScenarioEdge {
+                scenario: endless_scenario_name(universe, level + 1),
+                conditions: Vec::new(),
+            }];
+        }
+        scenarios.push(scenario);
+    }
+    Ok(scenarios)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::testing_all_characters::dxrpg_dm;
+
+    #[test]
+    fn unit_generate_scenarios() {
+        let dm = dxrpg_dm();
+        let scenarios = generate_scenarios(&dm, "lotr", 11, 5, 42).unwrap();
+        assert_eq!(scenarios.len(), 5);
+        // same seed, same scenarios
+        assert_eq!(
+            scenarios,
+            generate_scenarios(&dm, "lotr", 11, 5, 42).unwrap()
+        );
+
+        for (i, scenario) in scenarios.iter().enumerate() {
+            let level = 11 + i as u64;
+            assert_eq!(scenario.level, level);
+            assert_eq!(scenario.name, endless_scenario_name("lotr", level));
+            assert!(!scenario.boss_patterns.is_empty());
+            assert!(scenario.boss_patterns.len() <= PROCGEN_MAX_BOSSES);
+            let cost: u64 = scenario
+                .boss_patterns
+                .keys()
+                .map(|name| {
+                    let boss = dm.all_bosses.iter().find(|b| b.db_full_name == *name);
+                    assert_eq!(boss.unwrap().universe, "lotr");
+                    boss_cost(&boss.unwrap().rank)
+                })
+                .sum();
+            assert!(cost <= difficulty_budget(level));
+            assert!(scenario.boss_levels.values().all(|l| *l == level));
+            // gold and the best equipment available up to the loot rank
+            assert_eq!(scenario.loots.len(), 2);
+            assert_eq!(
+                scenario.loots[0].level,
+                PROCGEN_GOLD_PER_LEVEL * level as i64
+            );
+            assert_eq!(scenario.loots[1].rank, Rank::Intermediate);
+            if i + 1 < scenarios.len() {
+                assert_eq!(scenario.next[0].scenario, scenarios[i + 1].name);
+            } else {
+                assert!(scenario.next.is_empty());
+            }
+        }
+
+        assert!(generate_scenario(&dm, "unknown", 1, &mut GameRng::new(1)).is_err());
+        assert_eq!(difficulty_budget(1), PROCGEN_BASE_BUDGET);
+        assert_eq!(loot_rank(1), Rank::Common);
+    }
+}
@@ -0,0 +1,270 @@
+use std::fmt;
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    character_mod::{
+        character::Character, equipment::EquipmentJsonKey, loot::LootType, stats::StatKind,
+    },
+    common::{
+        constants::run_const::{
+            RUN_BLESSING_PERCENT, RUN_NB_OFFERS, RUN_SCORE_PER_HERO_ALIVE, RUN_SCORE_PER_KILL,
+            RUN_SCORE_PER_SCENARIO,
+        },
+        game_rng::GameRng,
+    },
+    server::players_manager::PlayerManager,
+    shop::{build_consumable_by_name, build_shop_catalog},
+};
+
+/// Stats which can be blessed between two fights of a run
+pub const RUN_BLESSING_STATS: &[StatKind] = &[
+    StatKind::Hp,
+    StatKind::PhysicalPower,
+    StatKind::MagicalPower,
+    StatKind::PhysicalArmor,
+    StatKind::MagicalArmor,
+];
+
+/// Reward offered between two fights of a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunRewardKind {
+    /// Equipment of that unique name, added to the inventory of the hero
+    Equipment(String),
+    /// Consumable of that name, added to the party bag
+    Consumable(String),
+    /// The stat of the hero is raised by `RUN_BLESSING_PERCENT` percent
+    StatBlessing(StatKind),
+    /// The attack of that name can be launched without waiting for its level
+    NewAttack(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunReward {
+    pub hero_id_name: String,
+    pub kind: RunRewardKind,
+}
+
+impl fmt::Display for RunReward {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            RunRewardKind::Equipment(name) => write!(f, "{}: {}", self.hero_id_name, name),
+            RunRewardKind::Consumable(name) => write!(f, "Party bag: {}", name),
+            RunRewardKind::StatBlessing(kind) => write!(
+                f,
+                "{}: {} +{}%",
+                self.hero_id_name, kind, RUN_BLESSING_PERCENT
+            ),
+            RunRewardKind::NewAttack(name) => {
+                write!(f, "{}: new attack {}", self.hero_id_name, name)
+            }
+        }
+    }
+}
+
+/// Score summary of a run, at its end
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub scenarios_won: usize,
+    pub nb_scenarios: usize,
+    pub kills: usize,
+    pub heroes_alive: usize,
+    pub is_victory: bool,
+    pub score: u64,
+}
+
+/// State of a roguelike run: generated scenarios, permadeath and rewards between the fights
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunState {
+    /// Seed of the generated scenarios and of the reward offers
+    pub seed: u64,
+    pub scenarios_won: usize,
+    /// Rewards chosen since the start of the run
+    pub rewards: Vec<RunReward>,
+}
+
+impl RunState {
+    pub fn new(seed: u64) -> RunState {
+        RunState {
+            seed,
+            ..Default::default()
+        }
+    }
+
+    /// Rewards offered after the last won scenario.
+    /// They only depend on the seed, the number of won scenarios and the heroes,
+    /// so the same offers are computed again until one is chosen.
+    pub fn roll_offers(&self, pm: &PlayerManager) -> Vec<RunReward> {
+        let mut rng = GameRng::new(self.seed.wrapping_add(self.scenarios_won as u64 + 1));
+        let heroes: Vec<_> = pm
+            .active_heroes
+            .iter()
+            .filter(|h| !h.stats.is_dead())
+            .collect();
+        if heroes.is_empty() {
+            return Vec::new();
+        }
+        let consumables: Vec<String> = build_shop_catalog(&Default::default())
+            .into_iter()
+            .filter(|item| item.kind == LootType::Consumable)
+            .map(|item| item.name)
+            .collect();
+        let mut offers = Vec::new();
+        for _ in 0..RUN_NB_OFFERS {
+            let hero = heroes[rng.random_nb(0, heroes.len() as i64 - 1) as usize];
+            let mut equipments: Vec<String> = pm
+                .equipment_table
+                .iter()
+                .filter(|(category, _)| **category != EquipmentJsonKey::Tattoes)
+                .flat_map(|(_, equipments)| equipments)
+                .filter(|e| {
+                    !hero
+                        .inventory
+                        .equipments
+                        .values()
+                        .flatten()
+                        .any(|owned| owned.unique_name == e.unique_name)
+                })
+                .map(|e| e.unique_name.clone())
+                .collect();
+            // the equipment table is a hash map: sort to offer the same rewards from the same seed
+            equipments.sort();
+            let locked_atks: Vec<String> = hero
+                .attacks_list
+                .values()
+                .filter(|atk| !hero.is_atk_known(atk))
+                .map(|atk| atk.name.clone())
+                .collect();
+            let pick = |rng: &mut GameRng, list: &[String]| {
+                list[rng.random_nb(0, list.len() as i64 - 1) as usize].clone()
+            };
+            let kind = match rng.random_nb(0, 3) {
+                0 if !equipments.is_empty() => {
+                    RunRewardKind::Equipment(pick(&mut rng, &equipments))
+                }
+                1 if !consumables.is_empty() => {
+                    RunRewardKind::Consumable(pick(&mut rng, &consumables))
+                }
+                3 if !locked_atks.is_empty() => {
+                    RunRewardKind::NewAttack(pick(&mut rng, &locked_atks))
+                }
+                _ => RunRewardKind::StatBlessing(
+                    RUN_BLESSING_STATS
+                        [rng.random_nb(0, RUN_BLESSING_STATS.len() as i64 - 1) as usize],
+                ),
+            };
+            offers.push(RunReward {
+                hero_id_name: hero.id_name.clone(),
+                kind,
+            });
+        }
+        offers
+    }
+
+    /// Give the reward to its hero
+    pub fn apply_reward(&mut self, reward: &RunReward, pm: &mut PlayerManager) -> Result<()> {
+        match &reward.kind {
+            RunRewardKind::Consumable(name) => {
+                let consumable = build_consumable_by_name(name)
+                    .ok_or_else(|| anyhow!("Unknown consumable {}", name))?;
+                pm.party_consumables.push(consumable);
+            }
+            RunRewardKind::Equipment(name) => {
+                let equipment = pm
+                    .equipment_table
+                    .values()
+                    .flatten()
+                    .find(|e| e.unique_name == *name)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Unknown equipment {}", name))?;
+                rewarded_hero(pm, reward)?
+                    .inventory
+                    .add_equipment(&equipment, false);
+            }
+            RunRewardKind::StatBlessing(kind) => {
+                rewarded_hero(pm, reward)?
+                    .stats
+                    .scale_by_percent(&[*kind], 100 + RUN_BLESSING_PERCENT);
+            }
+            RunRewardKind::NewAttack(name) => {
+                let hero = rewarded_hero(pm, reward)?;
+                let level = hero.level;
+                let atk = hero
+                    .attacks_list
+                    .get_mut(name)
+                    .ok_or_else(|| anyhow!("Unknown attack {}", name))?;
+                atk.level = atk.level.min(level);
+                if hero.loadout.is_enabled() {
+                    hero.loadout.learn(name);
+                }
+            }
+        }
+        tracing::info!("Run reward: {}", reward);
+        self.rewards.push(reward.clone());
+        Ok(())
+    }
+}
+
+fn rewarded_hero<'a>(pm: &'a mut PlayerManager, reward: &RunReward) -> Result<&'a mut Character> {
+    pm.get_mut_active_hero_character(&reward.hero_id_name)
+        .ok_or_else(|| anyhow!("Unknown hero {}", reward.hero_id_name))
+}
+
+/// Score of a run from its results
+pub fn run_score(scenarios_won: usize, kills: usize, heroes_alive: usize) -> u64 {
+    scenarios_won as u64 * RUN_SCORE_PER_SCENARIO
+        + kills as u64 * RUN_SCORE_PER_KILL
+        + heroes_alive as u64 * RUN_SCORE_PER_HERO_ALIVE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::testing_all_characters::testing_pm;
+
+    #[test]
+    fn unit_run_rewards() {
+        let mut pm = testing_pm();
+        let mut run = RunState::new(3);
+        let offers = run.roll_offers(&pm);
+        assert_eq!(offers.len(), RUN_NB_OFFERS);
+        // the offers do not change until a reward is chosen
+        assert_eq!(offers, run.roll_offers(&pm));
+
+        let hero_id_name = pm.active_heroes[0].id_name.clone();
+        let hp = pm.active_heroes[0].stats[StatKind::Hp].max;
+        let blessing = RunReward {
+            hero_id_name: hero_id_name.clone(),
+            kind: RunRewardKind::StatBlessing(StatKind::Hp),
+        };
+        run.apply_reward(&blessing, &mut pm).unwrap();
+        assert_eq!(
+            pm.active_heroes[0].stats[StatKind::Hp].max,
+            hp * (100 + RUN_BLESSING_PERCENT) / 100
+        );
+
+        let nb_consumables = pm.party_consumables.len();
+        let potion = RunReward {
+            hero_id_name: hero_id_name.clone(),
+            kind: RunRewardKind::Consumable("potion".to_owned()),
+        };
+        run.apply_reward(&potion, &mut pm).unwrap();
+        assert_eq!(pm.party_consumables.len(), nb_consumables + 1);
+
+        let unknown = RunReward {
+            hero_id_name,
+            kind: RunRewardKind::Equipment("unknown".to_owned()),
+        };
+        assert!(run.apply_reward(&unknown, &mut pm).is_err());
+        assert_eq!(run.rewards, vec![blessing, potion]);
+
+        // no offer when all the heroes are dead
+        pm.active_heroes
+            .iter_mut()
+            .for_each(|h| h.stats[StatKind::Hp].current = 0);
+        assert!(run.roll_offers(&pm).is_empty());
+        assert_eq!(run_score(2, 5, 1), 2 * 100 + 5 * 10 + 50);
+    }
+}
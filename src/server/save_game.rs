@@ -0,0 +1,377 @@
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    character_mod::class::Class,
+    common::constants::core_game_data_const::SAVE_SCHEMA_VERSION,
+    server::{core_game_data::CoreGameData, difficulty::Difficulty},
+};
+
+/// Hero of the party, as displayed on the load game page
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartyMemberSummary {
+    pub id_name: String,
+    pub class: Class,
+    pub level: u64,
+}
+
+/// Header of a save file, readable without loading the whole game
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveHeader {
+    pub schema_version: u32,
+    /// Local time of the save
+    pub timestamp: String,
+    pub universe: String,
+    /// Name of the current scenario
+    pub scenario: String,
+    pub difficulty: Difficulty,
+    pub party: Vec<PartyMemberSummary>,
+}
+
+/// Versioned envelope written on disk
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveFile {
+    #[serde(flatten)]
+    pub header: SaveHeader,
+    pub core_game_data: CoreGameData,
+}
+
+impl SaveHeader {
+    pub fn new(core: &CoreGameData, timestamp: &str) -> SaveHeader {
+        SaveHeader {
+            schema_version: SAVE_SCHEMA_VERSION,
+            timestamp: timestamp.to_owned(),
+            universe: core.universe.clone(),
+            scenario: core.game_manager.current_scenario.name.clone(),
+            difficulty: core.game_manager.difficulty,
+            party: core
+                .game_manager
+                .pm
+                .active_heroes
+                .iter()
+                .map(|h| PartyMemberSummary {
+                    id_name: h.id_name.clone(),
+                    class: h.class.clone(),
+                    level: h.level,
+                })
+                .collect(),
+        }
+    }
+}
+
+type Migration = fn(&mut Value) -> Result<()>;
+
+/// `MIGRATIONS[n]` upgrades a save from version `n` to version `n + 1`
+const MIGRATIONS: [Migration; SAVE_SCHEMA_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+];
+
+/// Version 0 is a bare `CoreGameData` without envelope.
+fn migrate_v0_to_v1(save: &mut Value) -> Result<()> {
+    let core = save.take();
+    let universe = core.get("universe").cloned().unwrap_or_default();
+    let mut envelope = serde_json::Map::new();
+    envelope.insert("timestamp".to_owned(), Value::String(String::new()));
+    envelope.insert("universe".to_owned(), universe);
+    envelope.insert("core_game_data".to_owned(), core);
+    *save = Value::Object(envelope);
+    Ok(())
+}
+
+/// Version 2 decodes `Cible` and `Portée` as `Target` and `Reach` enums:
+/// empty values get the defaults and the values fixed in the offline data are renamed.
+fn migrate_v1_to_v2(save: &mut Value) -> Result<()> {
+    match save {
+        Value::Object(obj) => {
+            for (key, value) in obj.iter_mut() {
+                let fixed = match (key.as_str(), value.as_str()) {
+                    ("Cible", Some("")) => Some("Ally"),
+                    ("Portée", Some("") | Some("Self")) => Some("Individual"),
+                    ("Portée", Some("Aleatoire Individuel")) => Some("Zone"),
+                    _ => None,
+                };
+                match fixed {
+                    Some(fixed) => *value = Value::from(fixed),
+                    None => migrate_v1_to_v2(value)?,
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                migrate_v1_to_v2(value)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Version 3 drops the `all_stats` map of the stats: its values were the up-to-date ones,
+/// so they replace the stat fields, whose keys are "Magical armor" and "Magical power".
+fn migrate_v2_to_v3(save: &mut Value) -> Result<()> {
+    match save {
+        Value::Object(obj) => {
+            if let Some(Value::Object(all_stats)) = obj.remove("all_stats") {
+                for (name, attribute) in all_stats {
+                    let name = match name.as_str() {
+                        "Magic armor" => "Magical armor".to_owned(),
+                        "Magic power" => "Magical power".to_owned(),
+                        _ => name,
+                    };
+                    obj.insert(name, attribute);
+                }
+            }
+            for value in obj.values_mut() {
+                migrate_v2_to_v3(value)?;
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                migrate_v2_to_v3(value)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Version 4 gives each equipment instance of an inventory its own id:
+/// the items saved before the ids all had id 0, they get a new id after the highest one.
+fn migrate_v3_to_v4(save: &mut Value) -> Result<()> {
+    match save {
+        Value::Object(obj) => {
+            if let Some(Value::Object(inventory)) = obj.get_mut("inventory") {
+                let mut items = match inventory.get_mut("equipments") {
+                    Some(Value::Object(equipments)) => equipments
+                        .values_mut()
+                        .filter_map(Value::as_array_mut)
+                        .flatten()
+                        .filter_map(Value::as_object_mut)
+                        .collect::<Vec<_>>(),
+                    _ => Vec::new(),
+                };
+                let id_of = |item: &serde_json::Map<String, Value>| {
+                    item.get("id").and_then(Value::as_u64).unwrap_or(0)
+                };
+                let mut last_id = items.iter().map(|item| id_of(item)).max().unwrap_or(0);
+                let mut seen = std::collections::HashSet::new();
+                for item in items.iter_mut() {
+                    let id = id_of(item);
+                    if id == 0 || !seen.insert(id) {
+                        last_id += 1;
+                        seen.insert(last_id);
+                        item.insert("id".to_owned(), Value::from(last_id));
+                    }
+                }
+                inventory.insert("last_equipment_id".to_owned(), Value::from(last_id));
+            }
+            for value in obj.values_mut() {
+                migrate_v3_to_v4(value)?;
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                migrate_v3_to_v4(value)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Version 5 stores the remaining amount of an absorb shield in `shield_remaining`
+/// instead of `full_amount_tx` of its effect outcome.
+fn migrate_v4_to_v5(save: &mut Value) -> Result<()> {
+    match save {
+        Value::Object(obj) => {
+            let is_shield = obj
+                .get("processed_effect_param")
+                .and_then(|p| p.pointer("/input_effect_param/Buffer/kind"))
+                .and_then(Value::as_str)
+                == Some("AbsorbShield");
+            if is_shield && let Some(Value::Object(outcome)) = obj.get_mut("effect_outcome") {
+                let remaining = outcome.get("full_amount_tx").cloned().unwrap_or_default();
+                outcome.insert("shield_remaining".to_owned(), remaining);
+            }
+            for value in obj.values_mut() {
+                migrate_v4_to_v5(value)?;
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                migrate_v4_to_v5(value)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Version of a raw save: files without `schema_version` are version 0.
+pub fn get_schema_version(save: &Value) -> Result<u32> {
+    match save.get("schema_version") {
+        None => Ok(0),
+        Some(v) => v
+            .as_u64()
+            .map(|v| v as u32)
+            .ok_or_else(|| anyhow!("invalid schema_version: {}", v)),
+    }
+}
+
+/// Run the migration chain on a raw save up to `SAVE_SCHEMA_VERSION`.
+pub fn migrate(save: &mut Value) -> Result<()> {
+    let version = get_schema_version(save)?;
+    if version > SAVE_SCHEMA_VERSION {
+        bail!(
+            "save schema version {} is newer than the supported version {}",
+            version,
+            SAVE_SCHEMA_VERSION
+        );
+    }
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        tracing::info!("Migrating save from version {} to {}", from, from + 1);
+        migration(save)?;
+        if let Some(obj) = save.as_object_mut() {
+            obj.insert("schema_version".to_owned(), Value::from(from as u32 + 1));
+        }
+    }
+    Ok(())
+}
+
+/// Parse a save file content of any supported version.
+pub fn parse_save(content: &str) -> Result<SaveFile> {
+    let mut save: Value = serde_json::from_str(content)?;
+    migrate(&mut save)?;
+    Ok(serde_json::from_value(save)?)
+}
+
+/// Parse only the header of a save file content, the game itself is not deserialized.
+pub fn parse_save_header(content: &str) -> Result<SaveHeader> {
+    let mut save: Value = serde_json::from_str(content)?;
+    migrate(&mut save)?;
+    if let Some(obj) = save.as_object_mut() {
+        obj.remove("core_game_data");
+    }
+    Ok(serde_json::from_value(save)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character_mod::{
+        inventory::Inventory,
+        stats::{StatKind, Stats},
+    };
+
+    #[test]
+    fn unit_get_schema_version() {
+        assert_eq!(get_schema_version(&serde_json::json!({})).unwrap(), 0);
+        assert_eq!(
+            get_schema_version(&serde_json::json!({"schema_version": 3})).unwrap(),
+            3
+        );
+        assert!(get_schema_version(&serde_json::json!({"schema_version": "a"})).is_err());
+    }
+
+    #[test]
+    fn unit_migrate_v0_wraps_core_game_data() {
+        let mut save = serde_json::json!({"universe": "lotr", "server_name": "srv"});
+        migrate(&mut save).unwrap();
+        assert_eq!(save["schema_version"], SAVE_SCHEMA_VERSION);
+        assert_eq!(save["universe"], "lotr");
+        assert_eq!(save["core_game_data"]["server_name"], "srv");
+    }
+
+    #[test]
+    fn unit_migrate_v1_fixes_targets_and_reaches() {
+        let mut save = serde_json::json!({
+            "schema_version": 1,
+            "core_game_data": {"atk": {"Cible": "", "Portée": "", "Effet": [
+                {"Cible": "Enemy", "Portée": "Aleatoire Individuel"},
+                {"Cible": "Self", "Portée": "Self"}
+            ]}}
+        });
+        migrate(&mut save).unwrap();
+        let atk = &save["core_game_data"]["atk"];
+        assert_eq!(atk["Cible"], "Ally");
+        assert_eq!(atk["Portée"], "Individual");
+        assert_eq!(atk["Effet"][0]["Cible"], "Enemy");
+        assert_eq!(atk["Effet"][0]["Portée"], "Zone");
+        assert_eq!(atk["Effet"][1]["Cible"], "Self");
+        assert_eq!(atk["Effet"][1]["Portée"], "Individual");
+    }
+
+    #[test]
+    fn unit_migrate_v2_moves_all_stats() {
+        let mut save = serde_json::json!({
+            "schema_version": 2,
+            "core_game_data": {"stats": {
+                "HP": {"Current": 1},
+                "all_stats": {"HP": {"Current": 80}, "Magic power": {"Current": 12}}
+            }}
+        });
+        migrate(&mut save).unwrap();
+        let stats = &save["core_game_data"]["stats"];
+        assert!(stats.get("all_stats").is_none());
+        assert_eq!(stats["HP"]["Current"], 80);
+        assert_eq!(stats["Magical power"]["Current"], 12);
+        let stats: Stats = serde_json::from_value(stats.clone()).unwrap();
+        assert_eq!(stats[StatKind::MagicalPower].current, 12);
+    }
+
+    #[test]
+    fn unit_migrate_v3_renumbers_equipment_ids() {
+        let mut save = serde_json::json!({
+            "schema_version": 3,
+            "core_game_data": {"heroes": [{"inventory": {"equipments": {
+                "Head": [{"unique_name": "helm"}],
+                "Tattoes": [{"unique_name": "t1", "id": 0}, {"unique_name": "t2", "id": 0}],
+                "Belt": [{"unique_name": "belt", "id": 7}]
+            }}}]}
+        });
+        migrate(&mut save).unwrap();
+        let inventory = &save["core_game_data"]["heroes"][0]["inventory"];
+        // unique ids are kept, the others follow the highest one
+        assert_eq!(inventory["equipments"]["Belt"][0]["id"], 7);
+        assert_eq!(inventory["equipments"]["Head"][0]["id"], 8);
+        assert_eq!(inventory["equipments"]["Tattoes"][0]["id"], 9);
+        assert_eq!(inventory["equipments"]["Tattoes"][1]["id"], 10);
+        assert_eq!(inventory["last_equipment_id"], 10);
+        let inventory: Inventory = serde_json::from_value(inventory.clone()).unwrap();
+        assert_eq!(inventory.last_equipment_id, 10);
+    }
+
+    #[test]
+    fn unit_migrate_v4_moves_shield_remaining() {
+        let shield = |kind: &str| {
+            serde_json::json!({
+                "processed_effect_param": {"input_effect_param": {"Buffer": {"kind": kind}}},
+                "effect_outcome": {"full_amount_tx": 30}
+            })
+        };
+        let mut save = serde_json::json!({
+            "schema_version": 4,
+            "core_game_data": {"all_effects": [shield("AbsorbShield"), shield("ChangeCurrentStatByValue")]}
+        });
+        migrate(&mut save).unwrap();
+        let effects = &save["core_game_data"]["all_effects"];
+        assert_eq!(effects[0]["effect_outcome"]["shield_remaining"], 30);
+        assert!(
+            effects[1]["effect_outcome"]
+                .get("shield_remaining")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn unit_migrate_rejects_newer_version() {
+        let mut save = serde_json::json!({"schema_version": SAVE_SCHEMA_VERSION + 1});
+        assert!(migrate(&mut save).is_err());
+    }
+}
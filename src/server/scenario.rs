@@ -1,8 +1,17 @@
 use std::collections::HashMap;
 
 use anyhow::{Result, bail};
+use indexmap::IndexMap;
 
-use crate::{character_mod::loot::Loot, utils};
+use crate::{
+    character_mod::{loot::Loot, target::TargetingStrategy},
+    server::{
+        boss_ai::{BossPhase, BossRule},
+        campaign::ScenarioEdge,
+        objective::{Objective, ObjectiveKind},
+    },
+    utils,
+};
 
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Scenario {
@@ -12,7 +21,33 @@ pub struct Scenario {
     pub description: String,
     /// Boss patterns, used for boss to adapt the behavior of the fight
     /// The key is the name of the boss, and the value is a list of pattern indexes that the boss can use
-    pub boss_patterns: HashMap<String, Vec<u64>>,
+    /// The bosses are set active in the order of this map
+    pub boss_patterns: IndexMap<String, Vec<u64>>,
+    /// Boss AI rules, evaluated before the patterns
+    /// The key is the name of the boss, and the value is the ordered list of its rules
+    #[serde(default)]
+    pub boss_rules: HashMap<String, Vec<BossRule>>,
+    /// Targeting strategy of a boss in this scenario, overriding the one of its character file
+    #[serde(default)]
+    pub boss_targeting: HashMap<String, TargetingStrategy>,
+    /// Phases of a multi-phase boss fight, ordered by decreasing HP threshold
+    /// The key is the name of the boss
+    #[serde(default)]
+    pub boss_phases: HashMap<String, Vec<BossPhase>>,
+    /// Level of a boss in this scenario, when higher than the one of its character file
+    /// The key is the name of the boss
+    #[serde(default)]
+    pub boss_levels: HashMap<String, u64>,
+    /// Bosses joining the fight during the scenario
+    #[serde(default)]
+    pub waves: Vec<ReinforcementWave>,
+    /// Objectives and failure conditions of the scenario, killing all the bosses by default
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+    /// Edges of the campaign graph toward the next scenarios.
+    /// Without edges, the next scenarios are the ones of the next level in the same universe.
+    #[serde(default)]
+    pub next: Vec<ScenarioEdge>,
     /// Loots to give to the heroes at the end of the scenario, if they win
     #[serde(default)]
     pub loots: Vec<Loot>,
@@ -24,15 +59,77 @@ pub struct Scenario {
     pub universe: String,
 }
 
+/// Trigger of a reinforcement wave
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaveTrigger {
+    /// At the start of each turn multiple of the value
+    TurnEvery(usize),
+    /// When a boss with that name dies
+    BossDied(String),
+}
+
+/// Bosses of `PlayerManager::all_bosses` joining the fight when the trigger occurs.
+/// They play from the next turn.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ReinforcementWave {
+    pub trigger: WaveTrigger,
+    pub bosses: Vec<String>,
+    /// Maximum number of times the wave is summoned in the fight, 0 for no limit
+    #[serde(default)]
+    pub max_nb: usize,
+}
+
+impl ReinforcementWave {
+    /// The wave can still be summoned after `nb_summoned` waves
+    pub fn is_available(&self, nb_summoned: usize) -> bool {
+        self.max_nb == 0 || nb_summoned < self.max_nb
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum ScenarioState {
+    /// Locked until an edge of the campaign graph leads to it
     #[default]
     NotStarted = 0,
     InProgress,
     Completed,
+    /// Unlocked, the players can choose it
+    Available,
 }
 
 impl Scenario {
+    /// Objectives of the scenario. Without a primary goal, killing all the bosses is the goal.
+    pub fn all_objectives(&self) -> Vec<Objective> {
+        let mut objectives = self.objectives.clone();
+        if !objectives
+            .iter()
+            .any(|o| !o.is_secondary && o.kind.is_goal())
+        {
+            objectives.insert(0, Objective::new(ObjectiveKind::KillAllBosses));
+        }
+        objectives
+    }
+
+    /// Edges toward the next scenarios: the declared ones, or else
+    /// all the scenarios of the next level in the same universe
+    pub fn next_edges(&self, all_scenarios: &[Scenario]) -> Vec<ScenarioEdge> {
+        if !self.next.is_empty() {
+            return self.next.clone();
+        }
+        all_scenarios
+            .iter()
+            .filter(|s| {
+                s.level == self.level + 1
+                    && (self.universe.is_empty() || s.universe == self.universe)
+            })
+            .map(|s| ScenarioEdge {
+                scenario: s.name.clone(),
+                conditions: Vec::new(),
+            })
+            .collect()
+    }
+
     pub fn try_new_from_json<P: AsRef<std::path::Path>>(path: P) -> Result<Scenario> {
         if let Ok(value) = utils::read_from_json::<_, Scenario>(&path) {
             // check if the scenario is valid
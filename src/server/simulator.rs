@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use indexmap::IndexMap;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    character_mod::{
+        attack_type::AttackType,
+        character::Character,
+        element::TypeChart,
+        stats::StatKind,
+        target::{Reach, Target},
+    },
+    common::game_rng::GameRng,
+    server::{
+        data_manager::DataManager,
+        game_manager::GameManager,
+        game_state::GameStatus,
+        scenario::{Scenario, ScenarioState},
+    },
+};
+
+/// How the simulated heroes choose their attack and its target
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeroPolicy {
+    /// Random launchable attack on a random potential target
+    #[default]
+    Random,
+    /// Attack with the highest estimated damage on the enemy with the lowest HP,
+    /// heal the ally with the lowest HP percentage when no damage attack is launchable
+    GreedyDamage,
+    /// Key: hero name or id_name, value: attacks played in order and cycled.
+    /// Attacks of the script which are not launchable are skipped, random if none is.
+    Scripted(HashMap<String, Vec<String>>),
+}
+
+/// Parameters of a simulation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    /// Heroes of the party, by name or id_name
+    pub party: Vec<String>,
+    /// Name of the scenario to fight
+    pub scenario: String,
+    pub policy: HeroPolicy,
+    pub nb_runs: usize,
+    /// Run `n` is played with the seed `seed + n`
+    pub seed: u64,
+    /// A fight still running after this turn is stopped and counted as a loss
+    pub max_turns: usize,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            party: Vec::new(),
+            scenario: String::new(),
+            policy: HeroPolicy::default(),
+            nb_runs: 100,
+            seed: 0,
+            max_turns: 100,
+        }
+    }
+}
+
+/// Damage and heal of one attack of one character, summed over all the runs
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AtkSimStats {
+    pub launcher_id_name: String,
+    pub atk_name: String,
+    pub nb_use: i64,
+    /// Damage after armor, as a positive value
+    pub total_real_dmg: i64,
+    pub total_real_heal: i64,
+}
+
+impl AtkSimStats {
+    pub fn average_dmg(&self) -> f64 {
+        if self.nb_use == 0 {
+            return 0.0;
+        }
+        self.total_real_dmg as f64 / self.nb_use as f64
+    }
+}
+
+/// Outcome of a simulation
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub scenario: String,
+    pub nb_runs: usize,
+    pub nb_wins: usize,
+    /// Fights stopped by `max_turns`
+    pub nb_timeouts: usize,
+    pub win_rate: f64,
+    /// Average number of turns of a fight
+    pub average_turns: f64,
+    /// Sorted by launcher then attack
+    pub atk_stats: Vec<AtkSimStats>,
+    /// Key: character id_name, value: number of runs in which it died
+    pub deaths: IndexMap<String, usize>,
+}
+
+/// Outcome of one fight
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct RunOutcome {
+    pub status: GameStatus,
+    pub nb_turns: usize,
+    pub is_timeout: bool,
+    pub game_manager: GameManager,
+}
+
+/// Run `config.nb_runs` fights of the party against the scenario and aggregate the outcomes.
+pub fn simulate(dm: &DataManager, config: &SimulationConfig) -> Result<SimulationReport> {
+    let mut report = SimulationReport {
+        scenario: config.scenario.clone(),
+        nb_runs: config.nb_runs,
+        ..Default::default()
+    };
+    let mut total_turns = 0;
+    let mut atk_stats: IndexMap<(String, String), AtkSimStats> = IndexMap::new();
+    for run in 0..config.nb_runs {
+        let outcome = run_one(dm, config, config.seed.wrapping_add(run as u64))?;
+        total_turns += outcome.nb_turns;
+        if outcome.status == GameStatus::EndOfScenario {
+            report.nb_wins += 1;
+        }
+        if outcome.is_timeout {
+            report.nb_timeouts += 1;
+        }
+        let pm = &outcome.game_manager.pm;
+        for c in pm.active_heroes.iter().chain(pm.active_bosses.iter()) {
+            let deaths = report.deaths.entry(c.id_name.clone()).or_default();
+            if c.stats.is_dead() {
+                *deaths += 1;
+            }
+        }
+        for (launcher_id_name, stats) in &outcome.game_manager.game_state.stats_in_game {
+            for atk_info in &stats.all_atk_info {
+                let entry = atk_stats
+                    .entry((launcher_id_name.clone(), atk_info.atk_name.clone()))
+                    .or_insert_with(|| AtkSimStats {
+                        launcher_id_name: launcher_id_name.clone(),
+                        atk_name: atk_info.atk_name.clone(),
+                        ..Default::default()
+                    });
+                entry.nb_use += atk_info.nb_use;
+                for totals in atk_info.totals_by_target.values() {
+                    entry.total_real_dmg += totals.total_real_dmg.abs();
+                    entry.total_real_heal += totals.total_real_heal;
+                }
+            }
+        }
+    }
+    if config.nb_runs > 0 {
+        report.win_rate = report.nb_wins as f64 / config.nb_runs as f64;
+        report.average_turns = total_turns as f64 / config.nb_runs as f64;
+    }
+    report.atk_stats = atk_stats.into_values().collect();
+    report.atk_stats.sort_by(|a, b| {
+        (&a.launcher_id_name, &a.atk_name).cmp(&(&b.launcher_id_name, &b.atk_name))
+    });
+    Ok(report)
+}
+
+/// Build a game with the party against the scenario of the config.
+pub fn build_game(dm: &DataManager, config: &SimulationConfig, seed: u64) -> Result<GameManager> {
+    if config.party.is_empty() {
+        bail!("the party is empty");
+    }
+    let Some(scenario) = dm.all_scenarios.iter().find(|s| s.name == config.scenario) else {
+        bail!("scenario {} not found", config.scenario);
+    };
+    let mut gm = GameManager::new(
+        &dm.offline_root,
+        dm.equipment_table.clone(),
+        dm.all_scenarios.clone(),
+    );
+    gm.set_seed(seed);
+    gm.type_chart = dm.type_chart.clone();
+    for name in &config.party {
+        let Some(hero) = dm
+            .all_heroes
+            .iter()
+            .find(|h| h.db_full_name == *name || h.id_name == *name)
+        else {
+            bail!("hero {} not found", name);
+        };
+        gm.pm.active_heroes.push(hero.clone());
+    }
+    gm.pm.all_bosses = dm.all_bosses.clone();
+    start_scenario(&mut gm, scenario);
+    Ok(gm)
+}
+
+fn start_scenario(gm: &mut GameManager, scenario: &Scenario) {
+    gm.current_scenario = scenario.clone();
+    gm.states_scenarios
+        .insert(scenario.name.clone(), ScenarioState::InProgress);
+    let all_bosses = gm.pm.all_bosses.clone();
+    gm.set_active_bosses(&all_bosses);
+}
+
+/// Play one fight until `EndOfGame`, `EndOfScenario` or `config.max_turns`.
+pub fn run_one(dm: &DataManager, config: &SimulationConfig, seed: u64) -> Result<RunOutcome> {
+    let mut gm = build_game(dm, config, seed)?;
+    // the policy has its own generator so that it does not shift the rolls of the fight
+    let mut policy_rng = GameRng::new(seed.rotate_left(32));
+    let mut script_indexes: HashMap<String, usize> = HashMap::new();
+    gm.start_game();
+    while !matches!(
+        gm.game_state.status,
+        GameStatus::EndOfGame | GameStatus::EndOfScenario
+    ) && gm.game_state.current_turn_nb <= config.max_turns
+    {
+        if gm.is_round_auto() {
+            gm.launch_attack(None);
+            continue;
+        }
+        let atk = choose_hero_atk(
+            &gm.pm.current_player,
+            &gm.pm.active_bosses,
+            &gm.type_chart,
+            &config.policy,
+            &mut script_indexes,
+            &mut policy_rng,
+        );
+        match atk {
+            Some(atk) => {
+                let launcher_id_name = gm.pm.current_player.id_name.clone();
+                gm.pm.set_targeted_characters(&launcher_id_name, &atk.name);
+                if let Some(target_id_name) =
+                    choose_hero_target(&gm, &atk, &config.policy, &mut policy_rng)
+                {
+                    gm.set_one_target(&launcher_id_name, &atk.name, &target_id_name);
+                }
+                gm.launch_attack(Some(&atk.name));
+            }
+            None => {
+                gm.launch_attack(None);
+            }
+        }
+    }
+    let is_timeout = gm.game_state.current_turn_nb > config.max_turns;
+    Ok(RunOutcome {
+        status: gm.game_state.status.clone(),
+        nb_turns: gm.game_state.current_turn_nb.min(config.max_turns),
+        is_timeout,
+        game_manager: gm,
+    })
+}
+
+/// Estimated damage of an atk on a target, as a positive value
+fn estimate_damage(
+    atk: &AttackType,
+    launcher: &Character,
+    target: &Character,
+    type_chart: &TypeChart,
+) -> i64 {
+    let type_multiplier = type_chart.multiplier(atk.element, &target.elements);
+    atk.all_effects
+        .iter()
+        .filter(|e| {
+            e.target_kind == Target::Enemy
+                && e.buffer.stats_name == Some(StatKind::Hp)
+                && e.buffer.value < 0
+        })
+        .map(|e| {
+            let (_, effective) = AttackType::damage_by_atk(
+                &target.stats,
+                &launcher.stats,
+                e.is_magic_atk,
+                e.buffer.value,
+                e.nb_turns.max(1),
+                type_multiplier,
+            );
+            effective.abs() * e.nb_turns.max(1)
+        })
+        .sum()
+}
+
+fn choose_hero_atk(
+    hero: &Character,
+    enemies: &[Character],
+    type_chart: &TypeChart,
+    policy: &HeroPolicy,
+    script_indexes: &mut HashMap<String, usize>,
+    rng: &mut GameRng,
+) -> Option<AttackType> {
+    let launchable = &hero.character_rounds_info.launchable_atks;
+    match policy {
+        HeroPolicy::Random => launchable.choose(rng).cloned(),
+        HeroPolicy::GreedyDamage => {
+            let alive: Vec<&Character> = enemies.iter().filter(|c| !c.stats.is_dead()).collect();
+            let damage = |atk: &AttackType| {
+                alive
+                    .iter()
+                    .min_by_key(|c| c.stats[StatKind::Hp].current)
+                    .map(|target| {
+                        let dmg = estimate_damage(atk, hero, target, type_chart);
+                        if atk.reach == Reach::Zone {
+                            dmg * alive.len() as i64
+                        } else {
+                            dmg
+                        }
+                    })
+                    .unwrap_or(0)
+            };
+            launchable
+                .iter()
+                .filter(|atk| damage(atk) > 0)
+                .max_by_key(|atk| damage(atk))
+                .or_else(|| launchable.iter().find(|atk| atk.target == Target::Ally))
+                .or_else(|| launchable.first())
+                .cloned()
+        }
+        HeroPolicy::Scripted(scripts) => {
+            let script = scripts
+                .get(&hero.db_full_name)
+                .or_else(|| scripts.get(&hero.id_name));
+            if let Some(script) = script.filter(|s| !s.is_empty()) {
+                let index = script_indexes.entry(hero.id_name.clone()).or_default();
+                for i in 0..script.len() {
+                    let atk_name = &script[(*index + i) % script.len()];
+                    if let Some(atk) = launchable.iter().find(|a| a.name == *atk_name) {
+                        *index = (*index + i + 1) % script.len();
+                        return Some(atk.clone());
+                    }
+                }
+            }
+            launchable.choose(rng).cloned()
+        }
+    }
+}
+
+fn choose_hero_target(
+    gm: &GameManager,
+    atk: &AttackType,
+    policy: &HeroPolicy,
+    rng: &mut GameRng,
+) -> Option<String> {
+    if atk.reach != Reach::Individual {
+        return None;
+    }
+    let potential_targets: Vec<&Character> = gm
+        .pm
+        .active_heroes
+        .iter()
+        .chain(gm.pm.active_bosses.iter())
+        .filter(|c| c.character_rounds_info.is_potential_target)
+        .collect();
+    let target = match policy {
+        HeroPolicy::GreedyDamage if atk.target == Target::Enemy => potential_targets
+            .iter()
+            .min_by_key(|c| c.stats[StatKind::Hp].current)
+            .copied(),
+        HeroPolicy::GreedyDamage => potential_targets
+            .iter()
+            .min_by_key(|c| {
+                let hp = &c.stats[StatKind::Hp];
+                hp.current * 100 / hp.max.max(1)
+            })
+            .copied(),
+        _ => potential_targets.choose(rng).copied(),
+    };
+    target.map(|c| c.id_name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::testing_all_characters::dxrpg_dm;
+
+    fn lotr_config(policy: HeroPolicy) -> (DataManager, SimulationConfig) {
+        let dm = dxrpg_dm();
+        let party = dm
+            .heroes_by_universe("lotr")
+            .iter()
+            .map(|h| h.db_full_name.clone())
+            .collect();
+        let config = SimulationConfig {
+            party,
+            scenario: "Patrouille Gobeline".to_owned(),
+            policy,
+            nb_runs: 5,
+            seed: 7,
+            max_turns: 30,
+        };
+        (dm, config)
+    }
+
+    #[test]
+    fn unit_simulate_report() {
+        let (dm, config) = lotr_config(HeroPolicy::GreedyDamage);
+        let report = simulate(&dm, &config).unwrap();
+        assert_eq!(report.nb_runs, 5);
+        assert!((0.0..=1.0).contains(&report.win_rate));
+        assert!(report.average_turns >= 1.0);
+        assert!(report.atk_stats.iter().any(|s| s.total_real_dmg > 0));
+        // every hero and boss of the fight is listed
+        assert_eq!(report.deaths.len(), config.party.len() + 1);
+        // same seed, same report
+        assert_eq!(report, simulate(&dm, &config).unwrap());
+    }
+
+    #[test]
+    fn unit_run_one_ends_the_fight() {
+        let (dm, config) = lotr_config(HeroPolicy::Random);
+        let outcome = run_one(&dm, &config, 1).unwrap();
+        assert!(
+            outcome.is_timeout
+                || matches!(
+                    outcome.status,
+                    GameStatus::EndOfGame | GameStatus::EndOfScenario
+                )
+        );
+    }
+
+    #[test]
+    fn unit_build_game_errors() {
+        let (dm, config) = lotr_config(HeroPolicy::Random);
+        let unknown_hero = SimulationConfig {
+            party: vec!["nobody".to_owned()],
+            ..config.clone()
+        };
+        assert!(build_game(&dm, &unknown_hero, 0).is_err());
+        let unknown_scenario = SimulationConfig {
+            scenario: "nowhere".to_owned(),
+            ..config.clone()
+        };
+        assert!(build_game(&dm, &unknown_scenario, 0).is_err());
+        let empty_party = SimulationConfig {
+            party: vec![],
+            ..config
+        };
+        assert!(build_game(&dm, &empty_party, 0).is_err());
+    }
+
+    #[test]
+    fn unit_scripted_policy_cycles() {
+        let (dm, _) = lotr_config(HeroPolicy::Random);
+        let mut hero = dm.heroes_by_universe("lotr")[0].clone();
+        hero.character_rounds_info.launchable_atks = hero.attacks_list.values().cloned().collect();
+        let names: Vec<String> = hero.attacks_list.keys().take(2).cloned().collect();
+        let policy = HeroPolicy::Scripted(HashMap::from([(
+            hero.db_full_name.clone(),
+            vec![names[1].clone(), "unknown".to_owned(), names[0].clone()],
+        )]));
+        let mut indexes = HashMap::new();
+        let mut rng = GameRng::new(0);
+        let played: Vec<String> = (0..4)
+            .filter_map(|_| {
+                choose_hero_atk(
+                    &hero,
+                    &[],
+                    &TypeChart::default(),
+                    &policy,
+                    &mut indexes,
+                    &mut rng,
+                )
+            })
+            .map(|atk| atk.name)
+            .collect();
+        assert_eq!(
+            played,
+            vec![
+                names[1].clone(),
+                names[0].clone(),
+                names[1].clone(),
+                names[0].clone()
+            ]
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Threat of one hero against one boss, as displayed by the threat meter
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreatEntry {
+    pub hero_id_name: String,
+    pub threat: u64,
+    /// Percentage of the highest threat against the boss
+    pub percent: u64,
+}
+
+/// Threat generated by each hero against each boss.
+/// Key: boss id_name, value: threat of each hero id_name against that boss.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreatTable(pub IndexMap<String, IndexMap<String, u64>>);
+
+impl ThreatTable {
+    /// Add `amount` of threat of the hero against the boss
+    pub fn add(&mut self, boss_id_name: &str, hero_id_name: &str, amount: u64) {
+        if amount == 0 {
+            return;
+        }
+        *self
+            .0
+            .entry(boss_id_name.to_owned())
+            .or_default()
+            .entry(hero_id_name.to_owned())
+            .or_insert(0) += amount;
+    }
+
+    /// Threat of the hero against the boss, 0 if unknown
+    pub fn get(&self, boss_id_name: &str, hero_id_name: &str) -> u64 {
+        self.0
+            .get(boss_id_name)
+            .and_then(|t| t.get(hero_id_name))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// A taunt puts the hero on top of the threat of the boss
+    pub fn taunt(&mut self, boss_id_name: &str, hero_id_name: &str) {
+        let table = self.0.entry(boss_id_name.to_owned()).or_default();
+        let highest_other = table
+            .iter()
+            .filter(|(id_name, _)| *id_name != hero_id_name)
+            .map(|(_, threat)| *threat)
+            .max()
+            .unwrap_or(0);
+        let threat = table.entry(hero_id_name.to_owned()).or_insert(0);
+        *threat = (*threat).max(highest_other + 1);
+    }
+
+    /// Decrease all the threats by `percent` percent (rounded up), at each new turn
+    pub fn decay(&mut self, percent: u64) {
+        let percent = percent.min(100);
+        for table in self.0.values_mut() {
+            for threat in table.values_mut() {
+                *threat -= (*threat * percent).div_ceil(100);
+            }
+            table.retain(|_, threat| *threat > 0);
+        }
+        self.0.retain(|_, table| !table.is_empty());
+    }
+
+    /// Remove the threat against a boss, e.g. when it dies
+    pub fn remove_boss(&mut self, boss_id_name: &str) {
+        self.0.shift_remove(boss_id_name);
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Threat meter of the boss: threats of the heroes, highest first
+    pub fn meter(&self, boss_id_name: &str) -> Vec<ThreatEntry> {
+        let Some(table) = self.0.get(boss_id_name) else {
+            return Vec::new();
+        };
+        let highest = table.values().copied().max().unwrap_or(0).max(1);
+        let mut entries: Vec<ThreatEntry> = table
+            .iter()
+            .map(|(hero_id_name, threat)| ThreatEntry {
+                hero_id_name: hero_id_name.clone(),
+                threat: *threat,
+                percent: *threat * 100 / highest,
+            })
+            .collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.threat));
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_threat_table() {
+        let mut table = ThreatTable::default();
+        table.add("boss1", "hero1", 100);
+        table.add("boss1", "hero2", 50);
+        table.add("boss1", "hero2", 150);
+        table.add("boss2", "hero1", 10);
+        table.add("boss2", "hero2", 0);
+        assert_eq!(table.get("boss1", "hero1"), 100);
+        assert_eq!(table.get("boss1", "hero2"), 200);
+        assert_eq!(table.get("boss2", "hero1"), 10);
+        assert_eq!(table.get("boss2", "hero2"), 0);
+        assert_eq!(table.get("unknown", "hero1"), 0);
+
+        let meter = table.meter("boss1");
+        assert_eq!(meter.len(), 2);
+        assert_eq!(meter[0].hero_id_name, "hero2");
+        assert_eq!(meter[0].percent, 100);
+        assert_eq!(meter[1].hero_id_name, "hero1");
+        assert_eq!(meter[1].percent, 50);
+        assert!(table.meter("unknown").is_empty());
+
+        // taunt
+        table.taunt("boss1", "hero1");
+        assert_eq!(table.get("boss1", "hero1"), 201);
+        table.taunt("boss3", "hero2");
+        assert_eq!(table.get("boss3", "hero2"), 1);
+
+        // decay
+        table.decay(50);
+        assert_eq!(table.get("boss1", "hero1"), 100);
+        assert_eq!(table.get("boss1", "hero2"), 100);
+        assert_eq!(table.get("boss2", "hero1"), 5);
+        // threats decayed to 0 are removed
+        assert!(!table.0.contains_key("boss3"));
+
+        table.remove_boss("boss2");
+        assert_eq!(table.get("boss2", "hero1"), 0);
+        table.clear();
+        assert!(table.0.is_empty());
+    }
+}
@@ -0,0 +1,824 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    character_mod::{
+        attack_type::AttackType,
+        character::Character,
+        element::TypeChart,
+        equipment::{Equipment, EquipmentJsonKey},
+        loot::LootType,
+        talent::{TalentKind, TalentTree},
+    },
+    common::{constants::paths_const::*, overworld::TileKind},
+    server::{
+        campaign::CampaignCondition,
+        data_manager::DataManager,
+        objective::ObjectiveKind,
+        overworld_manager::OverworldManager,
+        scenario::{Scenario, WaveTrigger},
+    },
+    shop::build_consumable_by_name,
+    utils::{self, list_dirs_in_dir, list_files_in_dir},
+};
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+/// Problem found in the offline data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// A file cannot be decoded, the game skips it
+    DecodeError { error: String },
+    /// A scenario references a boss which does not exist
+    UnknownBoss { scenario: String, boss: String },
+    /// A boss pattern index is not an index of the boss attacks
+    AtkIndexOutOfRange {
+        scenario: String,
+        boss: String,
+        index: u64,
+        nb_atks: usize,
+    },
+    /// A boss rule uses an attack the boss does not have
+    UnknownRuleAtk {
+        scenario: String,
+        boss: String,
+        atk_name: String,
+    },
+    /// A loot matches no equipment or no consumable
+    UnknownLoot {
+        scenario: String,
+        loot: String,
+        kind: LootType,
+    },
+    /// A wave without limit is summoned by the death of a boss it summons, the fight never ends
+    EndlessWave { scenario: String, boss: String },
+    /// An edge of the campaign graph leads to a scenario which does not exist
+    UnknownNextScenario { scenario: String, next: String },
+    /// An edge condition refers to an objective the scenario does not have
+    ObjectiveIndexOutOfRange {
+        scenario: String,
+        index: usize,
+        nb_objectives: usize,
+    },
+    /// No scenario of that level in the universe, while a higher level exists
+    ScenarioLevelGap { universe: String, level: u64 },
+    /// A door leads to a map file which does not exist
+    MissingDoorTarget { map: String, target_map: String },
+    /// A talent requires a talent which is not in the tree of its class
+    UnknownTalentPrerequisite {
+        class: String,
+        talent: String,
+        prerequisite: String,
+    },
+    /// A talent unlocks an attack no hero of its class has
+    UnknownTalentAtk {
+        class: String,
+        talent: String,
+        atk_name: String,
+    },
+    /// An attack requires a specialisation the class of the hero does not have
+    UnknownAtkSpecialisation {
+        hero: String,
+        atk_name: String,
+        class: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// File in which the problem was found, if known
+    pub path: Option<PathBuf>,
+    pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    pub fn error(kind: DiagnosticKind) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            path: None,
+            kind,
+        }
+    }
+
+    pub fn with_path<P: AsRef<Path>>(mut self, path: P) -> Diagnostic {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::DecodeError { error } => write!(f, "cannot be decoded: {}", error),
+            DiagnosticKind::UnknownBoss { scenario, boss } => {
+                write!(f, "scenario '{}': unknown boss '{}'", scenario, boss)
+            }
+            DiagnosticKind::AtkIndexOutOfRange {
+                scenario,
+                boss,
+                index,
+                nb_atks,
+            } => write!(
+                f,
+                "scenario '{}': pattern index {} of boss '{}' is out of range ({} attacks)",
+                scenario, index, boss, nb_atks
+            ),
+            DiagnosticKind::UnknownRuleAtk {
+                scenario,
+                boss,
+                atk_name,
+            } => write!(
+                f,
+                "scenario '{}': boss '{}' has no attack '{}'",
+                scenario, boss, atk_name
+            ),
+            DiagnosticKind::UnknownLoot {
+                scenario,
+                loot,
+                kind,
+            } => write!(
+                f,
+                "scenario '{}': unknown {:?} loot '{}'",
+                scenario, kind, loot
+            ),
+            DiagnosticKind::EndlessWave { scenario, boss } => write!(
+                f,
+                "scenario '{}': the death of boss '{}' summons it again without limit",
+                scenario, boss
+            ),
+            DiagnosticKind::UnknownNextScenario { scenario, next } => {
+                write!(
+                    f,
+                    "scenario '{}': unknown next scenario '{}'",
+                    scenario, next
+                )
+            }
+            DiagnosticKind::ObjectiveIndexOutOfRange {
+                scenario,
+                index,
+                nb_objectives,
+            } => write!(
+                f,
+                "scenario '{}': objective index {} is out of range ({} objectives)",
+                scenario, index, nb_objectives
+            ),
+            DiagnosticKind::ScenarioLevelGap { universe, level } => {
+                write!(f, "universe '{}': no scenario of level {}", universe, level)
+            }
+            DiagnosticKind::MissingDoorTarget { map, target_map } => {
+                write!(f, "map '{}': door to missing map '{}'", map, target_map)
+            }
+            DiagnosticKind::UnknownTalentPrerequisite {
+                class,
+                talent,
+                prerequisite,
+            } => write!(
+                f,
+                "talent tree '{}': talent '{}' requires unknown talent '{}'",
+                class, talent, prerequisite
+            ),
+            DiagnosticKind::UnknownTalentAtk {
+                class,
+                talent,
+                atk_name,
+            } => write!(
+                f,
+                "talent tree '{}': talent '{}' unlocks unknown attack '{}'",
+                class, talent, atk_name
+            ),
+            DiagnosticKind::UnknownAtkSpecialisation {
+                hero,
+                atk_name,
+                class,
+            } => write!(
+                f,
+                "hero '{}': attack '{}' requires the specialisation '{}' of another class",
+                hero, atk_name, class
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:?}] ", self.severity)?;
+        if let Some(path) = &self.path {
+            write!(f, "{}: ", path.display())?;
+        }
+        write!(f, "{}", self.kind)
+    }
+}
+
+/// Validate the whole offline tree: files which cannot be decoded, then the loaded data and the maps.
+pub fn validate_offline_root<P: AsRef<Path>>(root: P) -> Vec<Diagnostic> {
+    let root = root.as_ref();
+    let dm = match DataManager::try_new(root) {
+        Ok(dm) => dm,
+        Err(e) => {
+            return vec![
+                Diagnostic::error(DiagnosticKind::DecodeError {
+                    error: e.to_string(),
+                })
+                .with_path(root),
+            ];
+        }
+    };
+    let mut diagnostics = validate_files(root, &dm);
+    diagnostics.extend(validate_data(&dm));
+    diagnostics.extend(validate_maps(root));
+    diagnostics
+}
+
+/// Files of a directory and of its sub-directories (universes)
+fn list_json_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = list_files_in_dir(dir).unwrap_or_default();
+    for sub_dir in list_dirs_in_dir(dir).unwrap_or_default() {
+        files.extend(list_files_in_dir(sub_dir).unwrap_or_default());
+    }
+    files
+}
+
+fn decode_error<P: AsRef<Path>>(path: P, error: impl fmt::Display) -> Diagnostic {
+    Diagnostic::error(DiagnosticKind::DecodeError {
+        error: error.to_string(),
+    })
+    .with_path(path)
+}
+
+/// Report the files the `DataManager` skips because they cannot be decoded.
+pub fn validate_files(root: &Path, dm: &DataManager) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for part in EquipmentJsonKey::iter() {
+        let dir = root.join(*OFFLINE_LOOT_EQUIPMENT).join(part.to_string());
+        for path in list_files_in_dir(&dir).unwrap_or_default() {
+            if let Err(e) = Equipment::try_new_from_json(&path) {
+                diagnostics.push(decode_error(&path, e));
+            }
+        }
+    }
+    for path in list_json_files(&root.join(*OFFLINE_CHARACTERS)) {
+        if let Err(e) = Character::try_new_from_json(&path, root, false, &dm.equipment_table) {
+            diagnostics.push(decode_error(&path, e));
+        }
+    }
+    for path in list_json_files(&root.join(*OFFLINE_ATTACKS)) {
+        if let Err(e) = utils::read_from_json::<_, AttackType>(&path) {
+            diagnostics.push(decode_error(&path, e));
+        }
+    }
+    for path in list_json_files(&root.join(*OFFLINE_SCENARIOS)) {
+        if let Err(e) = utils::read_from_json::<_, Scenario>(&path) {
+            diagnostics.push(decode_error(&path, e));
+        } else if let Err(e) = Scenario::try_new_from_json(&path) {
+            diagnostics.push(decode_error(&path, e));
+        }
+    }
+    let type_chart_path = root.join(*OFFLINE_TYPE_CHART);
+    if type_chart_path.exists()
+        && let Err(e) = TypeChart::try_new_from_json(&type_chart_path)
+    {
+        diagnostics.push(decode_error(&type_chart_path, e));
+    }
+    for path in list_files_in_dir(root.join(*OFFLINE_TALENTS)).unwrap_or_default() {
+        if let Err(e) = TalentTree::try_new_from_json(&path) {
+            diagnostics.push(decode_error(&path, e));
+        }
+    }
+    diagnostics
+}
+
+/// Check the references between the loaded scenarios, characters, attacks and equipments.
+pub fn validate_data(dm: &DataManager) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for scenario in &dm.all_scenarios {
+        diagnostics.extend(validate_scenario(scenario, dm));
+    }
+    diagnostics.extend(validate_scenario_levels(&dm.all_scenarios));
+    for tree in &dm.talent_trees {
+        diagnostics.extend(validate_talent_tree(tree, &dm.all_heroes));
+    }
+    for hero in &dm.all_heroes {
+        diagnostics.extend(validate_hero_atks(hero));
+    }
+    diagnostics
+}
+
+/// Check that the attacks of a specialisation can be learnt by the hero
+fn validate_hero_atks(hero: &Character) -> Vec<Diagnostic> {
+    hero.attacks_list
+        .values()
+        .filter_map(|atk| {
+            let class = atk.specialisation.as_ref()?;
+            if *class == hero.class || hero.class.specialisations().contains(class) {
+                return None;
+            }
+            Some(Diagnostic::error(
+                DiagnosticKind::UnknownAtkSpecialisation {
+                    hero: hero.id_name.clone(),
+                    atk_name: atk.name.clone(),
+                    class: class.to_str().to_owned(),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Check the prerequisites of the talents and the attacks they unlock
+fn validate_talent_tree(tree: &TalentTree, heroes: &[Character]) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let class = format!("{:?}", tree.class);
+    for talent in &tree.talents {
+        for prerequisite in &talent.prerequisites {
+            if tree.get_talent(prerequisite).is_none() {
+                diagnostics.push(Diagnostic::error(
+                    DiagnosticKind::UnknownTalentPrerequisite {
+                        class: class.clone(),
+                        talent: talent.name.clone(),
+                        prerequisite: prerequisite.clone(),
+                    },
+                ));
+            }
+        }
+        if let TalentKind::AttackUnlock(atk_name) = &talent.kind
+            && !heroes
+                .iter()
+                .any(|h| tree.class.is_a(&h.class) && h.attacks_list.contains_key(atk_name))
+        {
+            diagnostics.push(Diagnostic::error(DiagnosticKind::UnknownTalentAtk {
+                class: class.clone(),
+                talent: talent.name.clone(),
+                atk_name: atk_name.clone(),
+            }));
+        }
+    }
+    diagnostics
+}
+
+fn validate_scenario(scenario: &Scenario, dm: &DataManager) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let find_boss = |boss: &str| dm.all_bosses.iter().find(|b| b.db_full_name == boss);
+    let unknown_boss = |boss: &str| {
+        Diagnostic::error(DiagnosticKind::UnknownBoss {
+            scenario: scenario.name.clone(),
+            boss: boss.to_owned(),
+        })
+    };
+    for (boss_name, pattern) in &scenario.boss_patterns {
+        let Some(boss) = find_boss(boss_name) else {
+            diagnostics.push(unknown_boss(boss_name));
+            continue;
+        };
+        let nb_atks = boss.attacks_list.len();
+        for index in pattern.iter().filter(|i| **i as usize >= nb_atks) {
+            diagnostics.push(Diagnostic::error(DiagnosticKind::AtkIndexOutOfRange {
+                scenario: scenario.name.clone(),
+                boss: boss_name.clone(),
+                index: *index,
+                nb_atks,
+            }));
+        }
+    }
+    for (boss_name, rules) in &scenario.boss_rules {
+        let Some(boss) = find_boss(boss_name) else {
+            diagnostics.push(unknown_boss(boss_name));
+            continue;
+        };
+        for rule in rules
+            .iter()
+            .filter(|r| !boss.attacks_list.contains_key(&r.atk_name))
+        {
+            diagnostics.push(Diagnostic::error(DiagnosticKind::UnknownRuleAtk {
+                scenario: scenario.name.clone(),
+                boss: boss_name.clone(),
+                atk_name: rule.atk_name.clone(),
+            }));
+        }
+    }
+    for boss_name in scenario.boss_targeting.keys() {
+        if find_boss(boss_name).is_none() {
+            diagnostics.push(unknown_boss(boss_name));
+        }
+    }
+    for (boss_name, phases) in &scenario.boss_phases {
+        let Some(boss) = find_boss(boss_name) else {
+            diagnostics.push(unknown_boss(boss_name));
+            continue;
+        };
+        let nb_atks = boss.attacks_list.len();
+        for phase in phases {
+            for index in phase.pattern.iter().filter(|i| **i as usize >= nb_atks) {
+                diagnostics.push(Diagnostic::error(DiagnosticKind::AtkIndexOutOfRange {
+                    scenario: scenario.name.clone(),
+                    boss: boss_name.clone(),
+                    index: *index,
+                    nb_atks,
+                }));
+            }
+            for add in phase.adds.iter().filter(|a| find_boss(a).is_none()) {
+                diagnostics.push(unknown_boss(add));
+            }
+        }
+    }
+    for wave in &scenario.waves {
+        let trigger_boss = match &wave.trigger {
+            WaveTrigger::BossDied(boss_name) => Some(boss_name),
+            WaveTrigger::TurnEvery(_) => None,
+        };
+        for boss_name in wave.bosses.iter().chain(trigger_boss) {
+            if find_boss(boss_name).is_none() {
+                diagnostics.push(unknown_boss(boss_name));
+            }
+        }
+        if let Some(boss_name) = trigger_boss
+            && wave.max_nb == 0
+            && wave.bosses.contains(boss_name)
+        {
+            diagnostics.push(Diagnostic::error(DiagnosticKind::EndlessWave {
+                scenario: scenario.name.clone(),
+                boss: boss_name.clone(),
+            }));
+        }
+    }
+    for boss_name in scenario.boss_levels.keys() {
+        if find_boss(boss_name).is_none() {
+            diagnostics.push(unknown_boss(boss_name));
+        }
+    }
+    for objective in &scenario.objectives {
+        if let ObjectiveKind::KillBoss(boss_name) = &objective.kind
+            && find_boss(boss_name).is_none()
+        {
+            diagnostics.push(unknown_boss(boss_name));
+        }
+    }
+    for edge in &scenario.next {
+        if !dm.all_scenarios.iter().any(|s| s.name == edge.scenario) {
+            diagnostics.push(Diagnostic::error(DiagnosticKind::UnknownNextScenario {
+                scenario: scenario.name.clone(),
+                next: edge.scenario.clone(),
+            }));
+        }
+        for condition in &edge.conditions {
+            if let CampaignCondition::ObjectiveCompleted(index) = condition
+                && *index >= scenario.objectives.len()
+            {
+                diagnostics.push(Diagnostic::error(
+                    DiagnosticKind::ObjectiveIndexOutOfRange {
+                        scenario: scenario.name.clone(),
+                        index: *index,
+                        nb_objectives: scenario.objectives.len(),
+                    },
+                ));
+            }
+        }
+    }
+    let bonus_loots = scenario.objectives.iter().flat_map(|o| &o.bonus_loots);
+    for loot in scenario.loots.iter().chain(bonus_loots) {
+        let is_known = match loot.kind {
+            LootType::Equipment => dm
+                .equipment_table
+                .values()
+                .flatten()
+                .any(|e| e.unique_name == loot.name),
+            LootType::Consumable => build_consumable_by_name(&loot.name).is_some(),
+            LootType::Material | LootType::Currency => true,
+        };
+        if !is_known {
+            diagnostics.push(Diagnostic::error(DiagnosticKind::UnknownLoot {
+                scenario: scenario.name.clone(),
+                loot: loot.name.clone(),
+                kind: loot.kind.clone(),
+            }));
+        }
+    }
+    diagnostics
+}
+
+/// Levels of a universe must go from 1 to the max level without gap,
+/// otherwise `load_next_scenario` stops at the gap.
+fn validate_scenario_levels(scenarios: &[Scenario]) -> Vec<Diagnostic> {
+    let mut levels: HashMap<&str, BTreeSet<u64>> = HashMap::new();
+    for s in scenarios {
+        levels.entry(&s.universe).or_default().insert(s.level);
+    }
+    let mut universes: Vec<_> = levels.into_iter().collect();
+    universes.sort();
+    let mut diagnostics = vec![];
+    for (universe, levels) in universes {
+        let max_level = levels.last().copied().unwrap_or_default();
+        for level in (1..max_level).filter(|l| !levels.contains(l)) {
+            diagnostics.push(Diagnostic::error(DiagnosticKind::ScenarioLevelGap {
+                universe: universe.to_owned(),
+                level,
+            }));
+        }
+    }
+    diagnostics
+}
+
+/// Check that the maps can be loaded and that their doors lead to existing maps.
+pub fn validate_maps(root: &Path) -> Vec<Diagnostic> {
+    let maps_dir = root.join(*OFFLINE_MAPS);
+    let mut diagnostics = vec![];
+    for path in list_files_in_dir(&maps_dir).unwrap_or_default() {
+        let Some(map_id) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let manager = match OverworldManager::load_map(&map_id, root) {
+            Ok(manager) => manager,
+            Err(e) => {
+                diagnostics.push(decode_error(&path, e));
+                continue;
+            }
+        };
+        let mut targets = BTreeSet::new();
+        for tile in manager.state.tiles.iter().flatten() {
+            if let TileKind::Door { target_map, .. } = tile {
+                targets.insert(target_map.clone());
+            }
+        }
+        for target_map in targets {
+            if !maps_dir.join(format!("{target_map}.json")).is_file() {
+                diagnostics.push(
+                    Diagnostic::error(DiagnosticKind::MissingDoorTarget {
+                        map: map_id.clone(),
+                        target_map,
+                    })
+                    .with_path(&path),
+                );
+            }
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        character_mod::{class::Class, loot::Loot},
+        common::constants::paths_const::{OFFLINE_ROOT, TEST_OFFLINE_ROOT},
+        server::{
+            boss_ai::{BossPhase, BossRule},
+            campaign::ScenarioEdge,
+            objective::Objective,
+            scenario::ReinforcementWave,
+        },
+        testing::testing_all_characters::{testing_dm, testing_pm},
+    };
+
+    #[test]
+    fn unit_validate_test_offline_root() {
+        // the test data only has known references
+        let diagnostics = validate_offline_root(*TEST_OFFLINE_ROOT);
+        assert!(
+            !diagnostics.iter().any(|d| matches!(
+                d.kind,
+                DiagnosticKind::UnknownBoss { .. } | DiagnosticKind::AtkIndexOutOfRange { .. }
+            )),
+            "{:?}",
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn unit_validate_scenario() {
+        let dm = testing_dm();
+        let mut scenario = Scenario {
+            name: "s".to_owned(),
+            level: 1,
+            ..Default::default()
+        };
+        scenario
+            .boss_patterns
+            .insert("test_boss1".to_owned(), vec![0, 99]);
+        scenario.boss_patterns.insert("nobody".to_owned(), vec![0]);
+        scenario.boss_rules.insert(
+            "test_boss1".to_owned(),
+            vec![BossRule {
+                atk_name: "unknown atk".to_owned(),
+                ..Default::default()
+            }],
+        );
+        scenario.boss_phases.insert(
+            "test_boss1".to_owned(),
+            vec![BossPhase {
+                pattern: vec![0, 42],
+                adds: vec!["test_boss1".to_owned(), "unknown add".to_owned()],
+                ..Default::default()
+            }],
+        );
+        scenario.objectives = vec![Objective {
+            kind: ObjectiveKind::KillBoss("unknown target".to_owned()),
+            is_secondary: true,
+            bonus_loots: vec![Loot {
+                name: "unknown bonus".to_owned(),
+                kind: LootType::Consumable,
+                ..Default::default()
+            }],
+        }];
+        scenario.next = vec![ScenarioEdge {
+            scenario: "nowhere".to_owned(),
+            conditions: vec![CampaignCondition::ObjectiveCompleted(1)],
+        }];
+        scenario.waves = vec![
+            ReinforcementWave {
+                trigger: WaveTrigger::BossDied("unknown trigger".to_owned()),
+                bosses: vec!["test_boss1".to_owned()],
+                max_nb: 1,
+            },
+            ReinforcementWave {
+                trigger: WaveTrigger::BossDied("test_boss1".to_owned()),
+                bosses: vec!["test_boss1".to_owned()],
+                max_nb: 0,
+            },
+            // a limited wave ends
+            ReinforcementWave {
+                trigger: WaveTrigger::BossDied("test_boss1".to_owned()),
+                bosses: vec!["test_boss1".to_owned()],
+                max_nb: 2,
+            },
+        ];
+        scenario.loots = vec![
+            Loot {
+                name: "unknown equipment".to_owned(),
+                kind: LootType::Equipment,
+                ..Default::default()
+            },
+            Loot {
+                name: "potion".to_owned(),
+                kind: LootType::Consumable,
+                ..Default::default()
+            },
+            Loot {
+                name: "unknown potion".to_owned(),
+                kind: LootType::Consumable,
+                ..Default::default()
+            },
+        ];
+        let kinds: Vec<DiagnosticKind> = validate_scenario(&scenario, &dm)
+            .into_iter()
+            .map(|d| d.kind)
+            .collect();
+        assert_eq!(kinds.len(), 13, "{:?}", kinds);
+        assert!(kinds.contains(&DiagnosticKind::EndlessWave {
+            scenario: "s".to_owned(),
+            boss: "test_boss1".to_owned()
+        }));
+        assert!(kinds.contains(&DiagnosticKind::UnknownNextScenario {
+            scenario: "s".to_owned(),
+            next: "nowhere".to_owned()
+        }));
+        assert!(kinds.contains(&DiagnosticKind::ObjectiveIndexOutOfRange {
+            scenario: "s".to_owned(),
+            index: 1,
+            nb_objectives: 1
+        }));
+        assert!(kinds.contains(&DiagnosticKind::UnknownBoss {
+            scenario: "s".to_owned(),
+            boss: "unknown target".to_owned()
+        }));
+        assert!(kinds.contains(&DiagnosticKind::UnknownLoot {
+            scenario: "s".to_owned(),
+            loot: "unknown bonus".to_owned(),
+            kind: LootType::Consumable
+        }));
+        assert!(kinds.contains(&DiagnosticKind::UnknownBoss {
+            scenario: "s".to_owned(),
+            boss: "unknown trigger".to_owned()
+        }));
+        assert!(kinds.contains(&DiagnosticKind::UnknownBoss {
+            scenario: "s".to_owned(),
+            boss: "nobody".to_owned()
+        }));
+        assert!(
+            kinds
+                .iter()
+                .any(|k| matches!(k, DiagnosticKind::AtkIndexOutOfRange { index: 99, .. }))
+        );
+        assert!(
+            kinds
+                .iter()
+                .any(|k| matches!(k, DiagnosticKind::AtkIndexOutOfRange { index: 42, .. }))
+        );
+        assert!(kinds.contains(&DiagnosticKind::UnknownBoss {
+            scenario: "s".to_owned(),
+            boss: "unknown add".to_owned()
+        }));
+        assert!(
+            kinds
+                .iter()
+                .any(|k| matches!(k, DiagnosticKind::UnknownRuleAtk { .. }))
+        );
+        assert!(kinds.contains(&DiagnosticKind::UnknownLoot {
+            scenario: "s".to_owned(),
+            loot: "unknown potion".to_owned(),
+            kind: LootType::Consumable
+        }));
+    }
+
+    #[test]
+    fn unit_validate_scenario_levels() {
+        let scenario = |universe: &str, level| Scenario {
+            universe: universe.to_owned(),
+            level,
+            ..Default::default()
+        };
+        let scenarios = vec![
+            scenario("lotr", 1),
+            scenario("lotr", 2),
+            scenario("lotr", 4),
+            scenario("pokemon", 1),
+        ];
+        let kinds: Vec<DiagnosticKind> = validate_scenario_levels(&scenarios)
+            .into_iter()
+            .map(|d| d.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![DiagnosticKind::ScenarioLevelGap {
+                universe: "lotr".to_owned(),
+                level: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn unit_validate_talent_tree() {
+        let dm = testing_dm();
+        assert!(validate_talent_tree(&dm.talent_trees[0], &dm.all_heroes).is_empty());
+
+        let mut tree = dm.talent_trees[0].clone();
+        tree.talents[0].prerequisites.push("unknown".to_owned());
+        tree.talents[2].kind = TalentKind::AttackUnlock("unknown".to_owned());
+        let kinds: Vec<DiagnosticKind> = validate_talent_tree(&tree, &dm.all_heroes)
+            .into_iter()
+            .map(|d| d.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DiagnosticKind::UnknownTalentPrerequisite {
+                    class: "Standard".to_owned(),
+                    talent: "Vitality".to_owned(),
+                    prerequisite: "unknown".to_owned(),
+                },
+                DiagnosticKind::UnknownTalentAtk {
+                    class: "Standard".to_owned(),
+                    talent: "Early offering".to_owned(),
+                    atk_name: "unknown".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unit_validate_hero_atks() {
+        let mut hero = testing_pm().active_heroes[0].clone();
+        assert!(validate_hero_atks(&hero).is_empty());
+
+        hero.class = Class::Warrior;
+        let atk_name = hero.attacks_list.keys().next().unwrap().clone();
+        hero.attacks_list[&atk_name].specialisation = Some(Class::Paladin);
+        assert!(validate_hero_atks(&hero).is_empty());
+        hero.attacks_list[&atk_name].specialisation = Some(Class::Druid);
+        let kinds: Vec<DiagnosticKind> = validate_hero_atks(&hero)
+            .into_iter()
+            .map(|d| d.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![DiagnosticKind::UnknownAtkSpecialisation {
+                hero: hero.id_name.clone(),
+                atk_name,
+                class: "Druid".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn unit_validate_maps() {
+        // pallet_town has a door to route_1, which is not written yet
+        let diagnostics = validate_maps(&OFFLINE_ROOT);
+        assert!(diagnostics.iter().any(|d| d.kind
+            == DiagnosticKind::MissingDoorTarget {
+                map: "pallet_town".to_owned(),
+                target_map: "route_1".to_owned()
+            }));
+        let text = diagnostics[0].to_string();
+        assert!(text.starts_with("[Error]"), "{text}");
+    }
+}
@@ -2,15 +2,15 @@ use std::collections::HashMap;
 
 use crate::character_mod::effect::{build_energy_effect, build_hp_effect, build_resurrect_effect};
 use crate::character_mod::inventory::ConsumableKind;
-use crate::{
-    character_mod::{
-        equipment::{Equipment, EquipmentJsonKey},
-        inventory::Consumable,
-        loot::LootType,
-        rank::Rank,
-    },
-    common::constants::stats_const::{BERSERK, MANA, VIGOR},
+use crate::character_mod::{
+    affix::{Affix, Rarity},
+    equipment::{Equipment, EquipmentJsonKey},
+    inventory::Consumable,
+    loot::LootType,
+    rank::Rank,
+    stats::StatKind,
 };
+use crate::common::game_rng::GameRng;
 
 /// A single item available for purchase in the shop.
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -23,6 +23,10 @@ pub struct ShopCatalogItem {
     /// `None` for consumables; set for equipment items.
     pub category: Option<EquipmentJsonKey>,
     pub description: String,
+    /// Rarity rolled at the restock, `Common` for consumables
+    pub rarity: Rarity,
+    /// Affixes rolled at the restock, empty for consumables
+    pub affixes: Vec<Affix>,
 }
 
 impl Default for ShopCatalogItem {
@@ -34,10 +38,26 @@ impl Default for ShopCatalogItem {
             rank: Rank::Common,
             category: None,
             description: String::new(),
+            rarity: Rarity::Common,
+            affixes: Vec::new(),
         }
     }
 }
 
+impl ShopCatalogItem {
+    /// Equipment instance sold by this item: its template with the rolled rarity and affixes
+    pub fn build_equipment(
+        &self,
+        equipment_table: &HashMap<EquipmentJsonKey, Vec<Equipment>>,
+    ) -> Option<Equipment> {
+        equipment_table
+            .values()
+            .flatten()
+            .find(|e| self.kind == LootType::Equipment && e.unique_name == self.name)
+            .map(|e| e.with_affixes(self.rarity, &self.affixes))
+    }
+}
+
 /// Sell price is half the buy price.
 pub fn sell_price(buy_price: u64) -> u64 {
     buy_price / 2
@@ -57,6 +77,22 @@ fn equipment_price(unique_name: &str) -> u64 {
 /// Tattoos are character-specific and are excluded.
 pub fn build_shop_catalog(
     equipment_table: &HashMap<EquipmentJsonKey, Vec<Equipment>>,
+) -> Vec<ShopCatalogItem> {
+    build_catalog(equipment_table, None)
+}
+
+/// Restock the shop: like `build_shop_catalog`, with a rarity and affixes rolled for each equipment.
+/// The price scales with the rarity.
+pub fn restock_shop_catalog(
+    equipment_table: &HashMap<EquipmentJsonKey, Vec<Equipment>>,
+    rng: &mut GameRng,
+) -> Vec<ShopCatalogItem> {
+    build_catalog(equipment_table, Some(rng))
+}
+
+fn build_catalog(
+    equipment_table: &HashMap<EquipmentJsonKey, Vec<Equipment>>,
+    mut rng: Option<&mut GameRng>,
 ) -> Vec<ShopCatalogItem> {
     let mut items: Vec<ShopCatalogItem> = Vec::new();
 
@@ -65,12 +101,18 @@ pub fn build_shop_catalog(
         if *category == EquipmentJsonKey::Tattoes {
             continue;
         }
-        for equip in equipments {
-            let price = equipment_price(&equip.unique_name);
-            // Collect non-zero stat bonuses for description
-            let stat_lines: Vec<String> = equip
+        // sorted so a seeded restock rolls the same items
+        let mut equipments: Vec<&Equipment> = equipments.iter().collect();
+        equipments.sort_by(|a, b| a.unique_name.cmp(&b.unique_name));
+        for template in equipments {
+            let equip = match rng.as_deref_mut() {
+                Some(rng) => template.roll(rng),
+                None => template.clone(),
+            };
+            let price = equipment_price(&equip.unique_name) * equip.rarity.price_percent() / 100;
+            // Collect non-zero stat bonuses of the template for description, then the affixes
+            let stat_lines: Vec<String> = template
                 .stats
-                .all_stats
                 .iter()
                 .filter_map(|(k, v)| {
                     let has_value = v.buf_equip_value != 0;
@@ -89,6 +131,10 @@ pub fn build_shop_catalog(
                     }
                 })
                 .collect();
+            let stat_lines: Vec<String> = stat_lines
+                .into_iter()
+                .chain(equip.affixes.iter().map(|a| a.to_string()))
+                .collect();
             let description = if stat_lines.is_empty() {
                 "No stat bonuses.".to_owned()
             } else {
@@ -98,9 +144,11 @@ pub fn build_shop_catalog(
                 name: equip.unique_name.clone(),
                 kind: LootType::Equipment,
                 price,
-                rank: Rank::Common,
+                rank: equip.rank.clone(),
                 category: Some(category.clone()),
                 description,
+                rarity: equip.rarity,
+                affixes: equip.affixes.clone(),
             });
         }
     }
@@ -128,6 +176,7 @@ pub fn build_shop_catalog(
             rank,
             category: None,
             description: desc.to_owned(),
+            ..Default::default()
         });
     }
 
@@ -164,19 +213,19 @@ pub fn build_consumable_by_name(name: &str) -> Option<Consumable> {
         }),
         "mana potion" => Some(Consumable {
             name: "mana potion".to_owned(),
-            effects: vec![build_energy_effect(MANA, 30)],
+            effects: vec![build_energy_effect(StatKind::Mana, 30)],
             consumable_kind: ConsumableKind::Potion,
             rank: Rank::Common,
         }),
         "vigor potion" => Some(Consumable {
             name: "vigor potion".to_owned(),
-            effects: vec![build_energy_effect(VIGOR, 30)],
+            effects: vec![build_energy_effect(StatKind::Vigor, 30)],
             consumable_kind: ConsumableKind::Potion,
             rank: Rank::Common,
         }),
         "berserk potion" => Some(Consumable {
             name: "berserk potion".to_owned(),
-            effects: vec![build_energy_effect(BERSERK, 30)],
+            effects: vec![build_energy_effect(StatKind::Berserk, 30)],
             consumable_kind: ConsumableKind::Potion,
             rank: Rank::Common,
         }),
@@ -241,6 +290,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn unit_restock_shop_catalog() {
+        let mut dm = DataManager::default();
+        dm.load_all_equipments(&*OFFLINE_ROOT).unwrap();
+        let catalog = restock_shop_catalog(&dm.equipment_table, &mut GameRng::new(9));
+        assert_eq!(
+            catalog,
+            restock_shop_catalog(&dm.equipment_table, &mut GameRng::new(9))
+        );
+        assert_eq!(catalog.len(), build_shop_catalog(&dm.equipment_table).len());
+
+        let item = catalog
+            .iter()
+            .find(|i| i.rarity > Rarity::Common)
+            .expect("a restock should roll some rare items");
+        assert_eq!(item.affixes.len(), item.rarity.nb_affixes());
+        assert_eq!(
+            item.price,
+            equipment_price(&item.name) * item.rarity.price_percent() / 100
+        );
+        for affix in &item.affixes {
+            assert!(item.description.contains(&affix.to_string()));
+        }
+        let equipment = item.build_equipment(&dm.equipment_table).unwrap();
+        assert_eq!(equipment.rarity, item.rarity);
+        assert_eq!(equipment.affixes, item.affixes);
+
+        let potion = catalog.iter().find(|i| i.name == "potion").unwrap();
+        assert_eq!(potion.rarity, Rarity::Common);
+        assert!(potion.build_equipment(&dm.equipment_table).is_none());
+    }
+
     #[test]
     fn unit_sell_price_is_half() {
         assert_eq!(sell_price(100), 50);
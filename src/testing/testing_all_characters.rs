@@ -46,6 +46,8 @@ pub fn testing_game_manager() -> GameManager {
         dm.equipment_table.clone(),
         dm.all_scenarios.clone(),
     );
+    gm.type_chart = dm.type_chart.clone();
+    gm.talent_trees = dm.talent_trees.clone();
     // All the bosses are active
     gm.pm = testing_pm();
     gm
@@ -112,6 +114,8 @@ pub fn dxrpg_game_manager() -> GameManager {
         dm.equipment_table.clone(),
         dm.all_scenarios.clone(),
     );
+    gm.type_chart = dm.type_chart.clone();
+    gm.talent_trees = dm.talent_trees.clone();
     // All the bosses are active
     gm.pm = dxrpg_pm();
     gm
@@ -3,7 +3,7 @@
 #[cfg(not(tarpaulin_include))]
 use crate::{
     character_mod::attack_type::AttackType,
-    common::constants::{all_target_const::*, reach_const::*},
+    character_mod::target::{Reach, Target},
     testing::testing_effect::{build_dmg_effect_individual, build_hot_effect_individual},
 };
 
@@ -14,8 +14,8 @@ pub fn build_atk_damage_indiv() -> AttackType {
         vigor_cost: 10,
         mana_cost: 10,
         berseck_cost: 10,
-        target: TARGET_ENNEMY.to_owned(),
-        reach: INDIVIDUAL.to_owned(),
+        target: Target::Enemy,
+        reach: Reach::Individual,
         all_effects: vec![build_dmg_effect_individual().input_effect_param],
         ..Default::default()
     }
@@ -29,8 +29,8 @@ pub fn build_atk_damage_zone() -> AttackType {
     AttackType {
         name: "atk1_zone".to_owned(),
         mana_cost: 10,
-        target: TARGET_ENNEMY.to_owned(),
-        reach: ZONE.to_owned(),
+        target: Target::Enemy,
+        reach: Reach::Zone,
         all_effects: vec![build_dmg_effect_zone().input_effect_param],
         ..Default::default()
     }
@@ -42,8 +42,8 @@ pub fn build_atk_berseck_damage1() -> AttackType {
     AttackType {
         name: "atk1".to_owned(),
         berseck_cost: 2,
-        target: TARGET_ENNEMY.to_owned(),
-        reach: INDIVIDUAL.to_owned(),
+        target: Target::Enemy,
+        reach: Reach::Individual,
         all_effects: vec![build_dmg_effect_individual().input_effect_param],
         ..Default::default()
     }
@@ -54,8 +54,8 @@ pub fn build_atk_heal1_indiv() -> AttackType {
     AttackType {
         name: "atk_heal1_indiv".to_owned(),
         berseck_cost: 2,
-        target: TARGET_ALLY.to_owned(),
-        reach: INDIVIDUAL.to_owned(),
+        target: Target::Ally,
+        reach: Reach::Individual,
         all_effects: vec![build_hot_effect_individual().input_effect_param],
         ..Default::default()
     }
@@ -68,8 +68,8 @@ pub fn build_atk_heal1_zone() -> AttackType {
     AttackType {
         name: "atk1".to_owned(),
         mana_cost: 10,
-        target: TARGET_ALLY.to_owned(),
-        reach: ZONE.to_owned(),
+        target: Target::Ally,
+        reach: Reach::Zone,
         all_effects: vec![build_hot_effect_zone().input_effect_param],
         ..Default::default()
     }
@@ -1,10 +1,11 @@
 #![allow(dead_code)]
+use crate::character_mod::stats::StatKind;
+use crate::character_mod::target::{Reach, Target};
 #[cfg(not(tarpaulin_include))]
 use crate::character_mod::{
     buffers::{BufKinds, Buffer},
     effect::{EffectParam, ProcessedEffectParam},
 };
-use crate::common::constants::{all_target_const::*, reach_const::*, stats_const::*};
 
 #[cfg(not(tarpaulin_include))]
 pub fn build_cooldown_effect() -> ProcessedEffectParam {
@@ -12,13 +13,13 @@ pub fn build_cooldown_effect() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 3,
             sub_value_effect: 0,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::CooldownTurnsNumber,
                 value: 3,
                 is_percent: false,
-                stats_name: String::new(),
+                stats_name: None,
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -35,13 +36,13 @@ pub fn build_heal_atk_blocked() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 1,
             sub_value_effect: 0,
-            target_kind: TARGET_ALL_ALLIES.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::AllAllies,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::BlockHealAtk,
                 value: 0,
                 is_percent: false,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -58,13 +59,13 @@ pub fn build_dot_effect_individual() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 3,
             sub_value_effect: 0,
-            target_kind: TARGET_ENNEMY.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Enemy,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ChangeCurrentStatByValue,
                 value: -20,
                 is_percent: false,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -81,13 +82,13 @@ pub fn build_dot_effect_zone() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 3,
             sub_value_effect: 0,
-            target_kind: TARGET_ENNEMY.to_owned(),
-            reach: ZONE.to_owned(),
+            target_kind: Target::Enemy,
+            reach: Reach::Zone,
             buffer: Buffer {
                 kind: BufKinds::ChangeCurrentStatByValue,
                 value: -20,
                 is_percent: false,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -104,19 +105,20 @@ pub fn build_dmg_effect_individual() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 1,
             sub_value_effect: 0,
-            target_kind: TARGET_ENNEMY.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Enemy,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ChangeCurrentStatByValue,
                 value: -30,
                 is_percent: false,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 is_passive_enabled: false,
                 is_passive: false,
             },
             is_magic_atk: false,
             conditions: vec![],
             is_passive: false,
+            ailment: None,
         },
         number_of_applies: 1,
         ..Default::default()
@@ -129,19 +131,20 @@ pub fn build_dmg_effect_zone() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 1,
             sub_value_effect: 0,
-            target_kind: TARGET_ENNEMY.to_owned(),
-            reach: ZONE.to_owned(),
+            target_kind: Target::Enemy,
+            reach: Reach::Zone,
             buffer: Buffer {
                 kind: BufKinds::ChangeCurrentStatByValue,
                 value: -30,
                 is_percent: false,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 is_passive_enabled: false,
                 is_passive: false,
             },
             is_magic_atk: false,
             conditions: vec![],
             is_passive: false,
+            ailment: None,
         },
         number_of_applies: 1,
         ..Default::default()
@@ -154,13 +157,13 @@ pub fn build_hot_effect_individual() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 2,
             sub_value_effect: 0,
-            target_kind: TARGET_ALLY.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Ally,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ChangeCurrentStatByValue,
                 value: 30,
                 is_percent: false,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -179,13 +182,13 @@ pub fn build_hot_effect_zone() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 3,
             sub_value_effect: 0,
-            target_kind: TARGET_ALLY.to_owned(),
-            reach: ZONE.to_owned(),
+            target_kind: Target::Ally,
+            reach: Reach::Zone,
             buffer: Buffer {
                 kind: BufKinds::ChangeCurrentStatByPercentage,
                 value: 30,
                 is_percent: false,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -204,13 +207,13 @@ pub fn build_hot_effect_all() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 3,
             sub_value_effect: 0,
-            target_kind: TARGET_ALL_ALLIES.to_owned(),
-            reach: ZONE.to_owned(),
+            target_kind: Target::AllAllies,
+            reach: Reach::Zone,
             buffer: Buffer {
                 kind: BufKinds::ChangeCurrentStatByValue,
                 value: 20,
                 is_percent: false,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -229,13 +232,13 @@ pub fn build_effect_max_stats() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 3,
             sub_value_effect: 0,
-            target_kind: TARGET_ENNEMY.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Enemy,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ChangeMaxStatByValue,
                 value: -20,
                 is_percent: false,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -254,13 +257,13 @@ pub fn build_debuf_effect_individual() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 3,
             sub_value_effect: 0,
-            target_kind: TARGET_ENNEMY.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Enemy,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ChangeCurrentStatByValue,
                 value: -20,
                 is_percent: false,
-                stats_name: MAGICAL_ARMOR.to_owned(),
+                stats_name: Some(StatKind::MagicalArmor),
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -279,13 +282,13 @@ pub fn build_buf_effect_individual() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 3,
             sub_value_effect: 0,
-            target_kind: TARGET_ENNEMY.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Enemy,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::ChangeCurrentStatByValue,
                 value: 20,
                 is_percent: false,
-                stats_name: MAGICAL_ARMOR.to_owned(),
+                stats_name: Some(StatKind::MagicalArmor),
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -304,13 +307,13 @@ pub fn build_change_max_hp_by_percent_effect() -> ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 2,
             sub_value_effect: 0,
-            target_kind: TARGET_ALL_ALLIES.to_owned(),
-            reach: ZONE.to_owned(),
+            target_kind: Target::AllAllies,
+            reach: Reach::Zone,
             buffer: Buffer {
                 kind: BufKinds::ChangeMaxStatByPercentage,
                 value: 25,
                 is_percent: true,
-                stats_name: HP.to_owned(),
+                stats_name: Some(StatKind::Hp),
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -331,14 +334,14 @@ pub fn build_buf_effect_individual_speed_regen() -> ProcessedEffectParam {
                 kind: BufKinds::ChangeMaxStatByValue,
                 value: 10,
                 is_percent: false,
-                stats_name: SPEED_REGEN.to_owned(),
+                stats_name: Some(StatKind::SpeedRegen),
                 is_passive_enabled: false,
                 is_passive: false,
             },
             nb_turns: 3,
             sub_value_effect: 0,
-            target_kind: TARGET_HIMSELF.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Himself,
+            reach: Reach::Individual,
             ..Default::default()
         },
         number_of_applies: 6,
@@ -351,13 +354,13 @@ pub fn build_remove_one_debuf_effect() -> ProcessedEffectParam {
     ProcessedEffectParam {
         input_effect_param: EffectParam {
             nb_turns: 1,
-            target_kind: TARGET_ALLY.to_owned(),
-            reach: INDIVIDUAL.to_owned(),
+            target_kind: Target::Ally,
+            reach: Reach::Individual,
             buffer: Buffer {
                 kind: BufKinds::RemoveOneDebuf,
                 value: 0,
                 is_percent: false,
-                stats_name: String::new(),
+                stats_name: None,
                 is_passive_enabled: false,
                 is_passive: false,
             },
@@ -1,5 +1,4 @@
 use anyhow::Result;
-use rand::Rng;
 use serde::{Serialize, de::DeserializeOwned};
 use std::{
     fs, io,
@@ -99,11 +98,6 @@ pub fn calc_ratio(val1: i64, val2: i64) -> f64 {
     }
 }
 
-pub fn get_random_nb(min: i64, max: i64) -> i64 {
-    let mut rng = rand::rng();
-    rng.random_range(min..=max)
-}
-
 /// Applies a hyperbolic softcap to a raw stat value, returning a percentage in [0, 100).
 /// Formula: P = raw / (100 + raw) * 100
 /// This provides diminishing returns instead of a hard cap:
@@ -199,14 +193,6 @@ mod tests {
         assert_eq!(super::softcap_percent(200), 67);
     }
 
-    #[test]
-    fn unit_get_random_nb() {
-        for _ in 0..20 {
-            let n = super::get_random_nb(1, 10);
-            assert!((1..=10).contains(&n));
-        }
-    }
-
     #[test]
     fn unit_format_string_with_timestamp() {
         let s = super::format_string_with_timestamp("test");